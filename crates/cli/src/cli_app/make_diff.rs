@@ -0,0 +1,321 @@
+//! Generation of differential update patches for hosted filters.
+//!
+//! The patch from the old version to the new one is written into the file, referred
+//! by `! Diff-Path` of the old version. The new version gets `! Diff-Path` of the next,
+//! yet empty, patch file.
+use adguard_flm::{make_batch_diff_patch, make_diff_patch, set_diff_path, BatchDiffPatchList};
+use clap::builder::PossibleValuesParser;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use std::error::Error;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::exit;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LIST_ARG: &str = "LIST";
+const PATCHES_DIR_ARG: &str = "PATCHES_DIR";
+const NAME_ARG: &str = "NAME";
+const RESOLUTION_ARG: &str = "RESOLUTION";
+const EXPIRATION_ARG: &str = "EXPIRATION";
+
+const DIFF_PATH_LINE_PREFIX: &str = "! Diff-Path:";
+
+/// Default name of the batch patch files
+const DEFAULT_BATCH_NAME: &str = "batch";
+
+type CommandResult = Result<(), Box<dyn Error>>;
+
+/// Versions of one filter list
+struct ListVersions {
+    new_path: PathBuf,
+    old_contents: String,
+    new_contents: String,
+    /// Fragment of the old `! Diff-Path` or the file stem
+    resource_name: String,
+    /// Patch file from the old `! Diff-Path`
+    current_patch_path: Option<PathBuf>,
+}
+
+/// `make-diff` subcommand
+pub(super) fn command() -> Command {
+    Command::new("make-diff")
+        .about("Make differential update patch from old to new filter versions")
+        .args([
+            Arg::new(LIST_ARG)
+                .long("list")
+                .num_args(2)
+                .value_names(["OLD", "NEW"])
+                .action(ArgAction::Append)
+                .help("Old and new versions of the filter. New version is updated in place, so it must be in the hosted folder. Repeat for batch patch")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+            Arg::new(PATCHES_DIR_ARG)
+                .long("patches")
+                .short('p')
+                .action(ArgAction::Set)
+                .help("Folder of the next patch file")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+            Arg::new(NAME_ARG)
+                .long("name")
+                .short('n')
+                .action(ArgAction::Set)
+                .help("Name of the next patch file. Must not contain \"-\". Defaults to the file name of the list, or \"batch\" for several lists"),
+            Arg::new(RESOLUTION_ARG)
+                .long("resolution")
+                .short('r')
+                .action(ArgAction::Set)
+                .help("Time resolution of the next patch name: hours, minutes or seconds")
+                .value_parser(PossibleValuesParser::new(["h", "m", "s"]))
+                .default_value("s"),
+            Arg::new(EXPIRATION_ARG)
+                .long("expiration")
+                .short('e')
+                .action(ArgAction::Set)
+                .help("Period after which the next patch may be available, in units of resolution")
+                .value_parser(value_parser!(u64).range(1..))
+                .default_value("3600"),
+        ])
+}
+
+/// Entry for `make-diff` subcommand. Exits with code 1 on failure
+pub(super) fn entry(matches: &ArgMatches) {
+    if let Err(why) = make_diff(matches) {
+        eprintln!("Error: {}", why);
+        exit(1);
+    }
+}
+
+fn make_diff(matches: &ArgMatches) -> CommandResult {
+    let paths: Vec<&PathBuf> = matches.get_many::<PathBuf>(LIST_ARG).unwrap().collect();
+    let lists = paths
+        .chunks(2)
+        .map(|pair| read_list(pair[0], pair[1]))
+        .collect::<Result<Vec<ListVersions>, Box<dyn Error>>>()?;
+
+    // Clients look for their chunk by fragment of the Diff-Path
+    let is_batch = lists.len() > 1
+        || lists.iter().any(|list| {
+            find_diff_path(&list.old_contents).is_some_and(|diff_path| diff_path.contains('#'))
+        });
+
+    let current_patch_path = get_current_patch_path(&lists)?;
+
+    let patches_dir = matches.get_one::<PathBuf>(PATCHES_DIR_ARG).unwrap();
+    fs::create_dir_all(patches_dir)?;
+    let patches_dir = patches_dir.canonicalize()?;
+
+    let next_patch_file_name = make_next_patch_file_name(matches, &lists, is_batch)?;
+    let next_patch_path = patches_dir.join(&next_patch_file_name);
+    if current_patch_path.as_ref() == Some(&next_patch_path) {
+        return Err(format!(
+            "Next patch {} is the current one. Use finer resolution or another name",
+            next_patch_path.display()
+        )
+        .into());
+    }
+
+    let lists = lists
+        .into_iter()
+        .map(|mut list| {
+            let mut diff_path = relative_path(list_dir(&list.new_path)?.as_path(), &patches_dir)
+                .join(&next_patch_file_name)
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if is_batch {
+                diff_path = format!("{}#{}", diff_path, list.resource_name);
+            }
+
+            list.new_contents = set_diff_path(&list.new_contents, &diff_path)?;
+
+            Ok(list)
+        })
+        .collect::<Result<Vec<ListVersions>, Box<dyn Error>>>()?;
+
+    if let Some(current_patch_path) = current_patch_path.as_ref() {
+        let patch = if is_batch {
+            make_batch_diff_patch(
+                &lists
+                    .iter()
+                    .map(|list| BatchDiffPatchList {
+                        resource_name: &list.resource_name,
+                        old_contents: &list.old_contents,
+                        new_contents: &list.new_contents,
+                    })
+                    .collect::<Vec<_>>(),
+            )?
+        } else {
+            make_diff_patch(&lists[0].old_contents, &lists[0].new_contents)?
+        };
+
+        if fs::metadata(current_patch_path).is_ok_and(|metadata| metadata.len() > 0) {
+            return Err(format!(
+                "Patch file {} already contains a patch",
+                current_patch_path.display()
+            )
+            .into());
+        }
+
+        if let Some(parent) = current_patch_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(current_patch_path, patch)?;
+        println!("Patch: {}", current_patch_path.display());
+    } else {
+        println!("Old versions have no Diff-Path, so there is nothing to patch");
+    }
+
+    for list in lists.iter() {
+        fs::write(&list.new_path, &list.new_contents)?;
+        println!("Updated Diff-Path: {}", list.new_path.display());
+    }
+
+    // Empty file means, that the next patch is not available yet
+    if !next_patch_path.exists() {
+        fs::write(&next_patch_path, "")?;
+    }
+    println!("Next patch: {}", next_patch_path.display());
+
+    Ok(())
+}
+
+fn read_list(old_path: &Path, new_path: &Path) -> Result<ListVersions, Box<dyn Error>> {
+    let old_contents = fs::read_to_string(old_path)?;
+    let new_contents = fs::read_to_string(new_path)?;
+
+    let (resource_name, current_patch_path) = match find_diff_path(&old_contents) {
+        Some(diff_path) => {
+            let (path, fragment) = match diff_path.split_once('#') {
+                Some((path, fragment)) => (path, Some(fragment)),
+                None => (diff_path, None),
+            };
+
+            (
+                fragment.map(String::from),
+                Some(normalize_path(&list_dir(new_path)?.join(path))),
+            )
+        }
+        None => (None, None),
+    };
+
+    let resource_name = match resource_name {
+        Some(name) => name,
+        None => new_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .ok_or_else(|| format!("{} is not a file", new_path.display()))?,
+    };
+
+    Ok(ListVersions {
+        new_path: new_path.to_path_buf(),
+        old_contents,
+        new_contents,
+        resource_name,
+        current_patch_path,
+    })
+}
+
+/// All lists must refer to one patch file, or none of them
+fn get_current_patch_path(lists: &[ListVersions]) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let current_patch_path = lists[0].current_patch_path.clone();
+
+    if lists
+        .iter()
+        .any(|list| list.current_patch_path != current_patch_path)
+    {
+        return Err("Old versions of the lists refer to different patch files".into());
+    }
+
+    Ok(current_patch_path)
+}
+
+/// `<name>-<resolution>-<epoch>-<expiration>.patch`
+fn make_next_patch_file_name(
+    matches: &ArgMatches,
+    lists: &[ListVersions],
+    is_batch: bool,
+) -> Result<String, Box<dyn Error>> {
+    let name = match matches.get_one::<String>(NAME_ARG) {
+        Some(name) => name.to_owned(),
+        None if is_batch => DEFAULT_BATCH_NAME.to_string(),
+        None => lists[0].resource_name.clone(),
+    };
+    if name.is_empty() || name.contains(['-', '/', '\\', '#']) {
+        return Err(format!(
+            "Invalid patch name \"{}\". It must not contain \"-\", use --name to set another one",
+            name
+        )
+        .into());
+    }
+
+    let resolution = matches.get_one::<String>(RESOLUTION_ARG).unwrap();
+    let expiration = matches.get_one::<u64>(EXPIRATION_ARG).unwrap();
+
+    let divider = match resolution.as_str() {
+        "h" => 3600,
+        "m" => 60,
+        _ => 1,
+    };
+    let epoch = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() / divider;
+
+    Ok(format!(
+        "{}-{}-{}-{}.patch",
+        name, resolution, epoch, expiration
+    ))
+}
+
+/// Finds `! Diff-Path` value
+fn find_diff_path(contents: &str) -> Option<&str> {
+    contents.lines().find_map(|line| {
+        line.strip_prefix(DIFF_PATH_LINE_PREFIX)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// Absolute folder of the list file
+fn list_dir(list_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    Ok(list_path
+        .canonicalize()?
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default())
+}
+
+/// Resolves `.` and `..` components without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    path.components()
+        .fold(PathBuf::new(), |mut out, component| {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    out.pop();
+                }
+                component => out.push(component),
+            }
+
+            out
+        })
+}
+
+/// Path of `to` relative to `from`. Both paths must be absolute
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(left, right)| left == right)
+        .count();
+
+    let mut out = PathBuf::new();
+    from[common..].iter().for_each(|_| out.push(".."));
+    to[common..]
+        .iter()
+        .for_each(|component| out.push(component));
+
+    out
+}
@@ -0,0 +1,444 @@
+//! Filters management subcommands, backed by [`FilterListManager`]
+use super::output;
+use super::{FILL_DNS_DATABASE, FILL_STANDARD_DATABASE};
+use adguard_flm::{
+    Configuration, FilterId, FilterListManager, FilterListManagerImpl, FilterListRules,
+    FilterListType, USER_RULES_FILTER_LIST_ID,
+};
+use clap::builder::PossibleValuesParser;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use std::error::Error;
+use std::fs::File;
+use std::io::{stdin, stdout, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::exit;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DB_FOLDER_PATH_ARG: &str = "DB_FOLDER_PATH";
+const DB_TYPE_ARG: &str = "DB_TYPE";
+const INDEX_URL_ARG: &str = "INDEX_URL";
+const I18N_URL_ARG: &str = "I18N_URL";
+const JSON_ARG: &str = "JSON";
+const IDS_ARG: &str = "IDS";
+const ID_ARG: &str = "ID";
+const URL_ARG: &str = "URL";
+const FILE_ARG: &str = "FILE";
+const TITLE_ARG: &str = "TITLE";
+const DESCRIPTION_ARG: &str = "DESCRIPTION";
+const TRUSTED_ARG: &str = "TRUSTED";
+const SET_ARG: &str = "SET";
+const FORCE_ARG: &str = "FORCE";
+const LOOSE_TIMEOUT_ARG: &str = "LOOSE_TIMEOUT";
+const OUTPUT_ARG: &str = "OUTPUT";
+
+/// Value of file arguments, which means standard input
+const STDIN_PATH: &str = "-";
+
+type CommandResult = Result<(), Box<dyn Error>>;
+
+/// Arguments, shared by all management subcommands
+fn common_args() -> [Arg; 5] {
+    [
+        Arg::new(DB_FOLDER_PATH_ARG)
+            .long("database")
+            .short('d')
+            .action(ArgAction::Set)
+            .help("Folder where the database file is stored")
+            .value_parser(value_parser!(PathBuf))
+            .required(true),
+        Arg::new(DB_TYPE_ARG)
+            .long("db-type")
+            .short('t')
+            .action(ArgAction::Set)
+            .help("Database type")
+            .value_parser(PossibleValuesParser::new([
+                FILL_STANDARD_DATABASE,
+                FILL_DNS_DATABASE,
+            ]))
+            .default_value(FILL_STANDARD_DATABASE),
+        Arg::new(INDEX_URL_ARG)
+            .long("index_url")
+            .short('i')
+            .action(ArgAction::Set)
+            .help("Main index URL. Required for pull_metadata and updates of index filters"),
+        Arg::new(I18N_URL_ARG)
+            .long("index_locales_url")
+            .short('l')
+            .action(ArgAction::Set)
+            .help("Index locales URL"),
+        Arg::new(JSON_ARG)
+            .long("json")
+            .action(ArgAction::SetTrue)
+            .help("Print results as JSON"),
+    ]
+}
+
+/// Positional list of filter ids
+fn ids_arg() -> Arg {
+    Arg::new(IDS_ARG)
+        .help("Filter ids")
+        .num_args(1..)
+        .value_parser(value_parser!(FilterId))
+        .allow_negative_numbers(true)
+        .required(true)
+}
+
+/// Positional filter id
+fn id_arg() -> Arg {
+    Arg::new(ID_ARG)
+        .help("Filter id")
+        .value_parser(value_parser!(FilterId))
+        .allow_negative_numbers(true)
+        .required(true)
+}
+
+/// Optional file, which replaces the stored value
+fn set_arg(help: &'static str) -> Arg {
+    Arg::new(SET_ARG)
+        .long("set")
+        .action(ArgAction::Set)
+        .help(help)
+        .value_parser(value_parser!(PathBuf))
+}
+
+/// Management subcommands
+pub(super) fn commands() -> Vec<Command> {
+    [
+        Command::new("filters").about("List stored filters"),
+        Command::new("groups").about("List filter groups"),
+        Command::new("tags").about("List filter tags"),
+        Command::new("enable")
+            .about("Enable filters")
+            .arg(ids_arg()),
+        Command::new("disable")
+            .about("Disable filters")
+            .arg(ids_arg()),
+        Command::new("install")
+            .about("Mark filters as installed")
+            .arg(ids_arg()),
+        Command::new("uninstall")
+            .about("Mark filters as not installed")
+            .arg(ids_arg()),
+        Command::new("add_custom")
+            .about("Install a custom filter from URL or local file")
+            .args([
+                Arg::new(URL_ARG)
+                    .long("url")
+                    .short('u')
+                    .action(ArgAction::Set)
+                    .help("Download URL of the filter")
+                    .conflicts_with(FILE_ARG)
+                    .required_unless_present(FILE_ARG),
+                Arg::new(FILE_ARG)
+                    .long("file")
+                    .short('f')
+                    .action(ArgAction::Set)
+                    .help("Local file with filter contents. The filter won't be updated. Use - for stdin")
+                    .value_parser(value_parser!(PathBuf)),
+                Arg::new(TITLE_ARG)
+                    .long("title")
+                    .action(ArgAction::Set)
+                    .help("Custom title"),
+                Arg::new(DESCRIPTION_ARG)
+                    .long("description")
+                    .action(ArgAction::Set)
+                    .help("Custom description"),
+                Arg::new(TRUSTED_ARG)
+                    .long("trusted")
+                    .action(ArgAction::SetTrue)
+                    .help("Mark the filter as trusted"),
+            ]),
+        Command::new("delete_custom")
+            .about("Delete custom filters")
+            .arg(ids_arg()),
+        Command::new("user_rules")
+            .about("Print user rules or replace them")
+            .arg(set_arg("File with new user rules. Use - for stdin")),
+        Command::new("disabled_rules")
+            .about("Print disabled rules of the filter or replace them")
+            .args([
+                id_arg(),
+                set_arg("File with new disabled rules. Use - for stdin"),
+            ]),
+        Command::new("pull_metadata").about("Pull filters metadata from the index"),
+        Command::new("update")
+            .about("Update filters")
+            .args([
+                Arg::new(IDS_ARG)
+                    .long("ids")
+                    .help("Update only these filters")
+                    .num_args(1..)
+                    .value_parser(value_parser!(FilterId))
+                    .allow_negative_numbers(true),
+                Arg::new(FORCE_ARG)
+                    .long("force")
+                    .action(ArgAction::SetTrue)
+                    .help("Ignore filters expiration"),
+                Arg::new(LOOSE_TIMEOUT_ARG)
+                    .long("loose-timeout")
+                    .action(ArgAction::Set)
+                    .help("Stop dispatching updates after this number of milliseconds. 0 means no timeout")
+                    .value_parser(value_parser!(i32))
+                    .default_value("0"),
+            ]),
+        Command::new("dump_rules")
+            .about("Print rules of enabled filters or of the chosen ones")
+            .args([
+                Arg::new(IDS_ARG)
+                    .long("ids")
+                    .help("Dump rules of these filters, including disabled ones")
+                    .num_args(1..)
+                    .value_parser(value_parser!(FilterId))
+                    .allow_negative_numbers(true),
+                Arg::new(OUTPUT_ARG)
+                    .long("output")
+                    .short('o')
+                    .action(ArgAction::Set)
+                    .help("Write rules to this file instead of stdout")
+                    .value_parser(value_parser!(PathBuf)),
+            ]),
+    ]
+    .into_iter()
+    .map(|command| command.args(common_args()))
+    .collect()
+}
+
+/// Entry for management subcommands. Exits with code 1 on failure
+pub(super) fn entry(command: &str, matches: &ArgMatches) {
+    if let Err(why) = run_command(command, matches) {
+        eprintln!("Error: {}", why);
+        exit(1);
+    }
+}
+
+fn run_command(command: &str, matches: &ArgMatches) -> CommandResult {
+    let flm = make_manager(matches)?;
+    let is_json = matches.get_flag(JSON_ARG);
+
+    match command {
+        "filters" => output::print_filters(&flm.get_stored_filters_metadata()?, is_json),
+        "groups" => output::print_groups(&flm.get_all_groups()?, is_json),
+        "tags" => output::print_tags(&flm.get_all_tags()?, is_json),
+        "enable" | "disable" => {
+            let count = flm.enable_filter_lists(get_ids(matches), command == "enable")?;
+            output::print_affected_count(command, count, is_json);
+        }
+        "install" | "uninstall" => {
+            let count = flm.install_filter_lists(get_ids(matches), command == "install")?;
+            output::print_affected_count(command, count, is_json);
+        }
+        "add_custom" => add_custom(&flm, matches, is_json)?,
+        "delete_custom" => {
+            let count = flm.delete_custom_filter_lists(get_ids(matches))?;
+            output::print_affected_count(command, count, is_json);
+        }
+        "user_rules" => user_rules(&flm, matches, is_json)?,
+        "disabled_rules" => disabled_rules(&flm, matches, is_json)?,
+        "pull_metadata" => output::print_pull_metadata_result(&flm.pull_metadata()?, is_json),
+        "update" => update(&flm, matches, is_json)?,
+        "dump_rules" => dump_rules(&flm, matches, is_json)?,
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::field_reassign_with_default)]
+fn make_manager(matches: &ArgMatches) -> Result<Box<FilterListManagerImpl>, Box<dyn Error>> {
+    let db_path = matches.get_one::<PathBuf>(DB_FOLDER_PATH_ARG).unwrap();
+
+    let mut configuration = Configuration::default();
+    configuration.filter_list_type = match matches.get_one::<String>(DB_TYPE_ARG).unwrap().as_str()
+    {
+        FILL_DNS_DATABASE => FilterListType::DNS,
+        _ => FilterListType::STANDARD,
+    };
+    configuration.app_name = env!("CARGO_PKG_NAME").to_string();
+    configuration.version = env!("CARGO_PKG_VERSION").to_string();
+    configuration.working_directory = Some(
+        db_path
+            .to_str()
+            .ok_or("Database path is not valid UTF-8")?
+            .to_string(),
+    );
+
+    if let Some(index_url) = matches.get_one::<String>(INDEX_URL_ARG) {
+        configuration.metadata_url = index_url.to_owned();
+    }
+    if let Some(index_i18n_url) = matches.get_one::<String>(I18N_URL_ARG) {
+        configuration.metadata_locales_url = index_i18n_url.to_owned();
+    }
+
+    Ok(FilterListManagerImpl::new(configuration)?)
+}
+
+fn get_ids(matches: &ArgMatches) -> Vec<FilterId> {
+    matches
+        .get_many::<FilterId>(IDS_ARG)
+        .map(|ids| ids.copied().collect())
+        .unwrap_or_default()
+}
+
+/// Reads file contents. [`STDIN_PATH`] reads standard input
+fn read_input(path: &PathBuf) -> Result<String, Box<dyn Error>> {
+    if path.as_os_str() == STDIN_PATH {
+        let mut contents = String::new();
+        stdin().read_to_string(&mut contents)?;
+
+        return Ok(contents);
+    }
+
+    Ok(std::fs::read_to_string(path)?)
+}
+
+/// Non-empty lines of the input
+fn read_lines(path: &PathBuf) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(read_input(path)?
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+fn add_custom(flm: &FilterListManagerImpl, matches: &ArgMatches, is_json: bool) -> CommandResult {
+    let title = matches.get_one::<String>(TITLE_ARG).cloned();
+    let description = matches.get_one::<String>(DESCRIPTION_ARG).cloned();
+    let is_trusted = matches.get_flag(TRUSTED_ARG);
+
+    let filter = match matches.get_one::<String>(URL_ARG) {
+        Some(url) => {
+            flm.install_custom_filter_list(url.to_owned(), is_trusted, title, description)?
+        }
+        None => {
+            let path = matches.get_one::<PathBuf>(FILE_ARG).unwrap();
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+            flm.install_custom_filter_from_string(
+                String::new(),
+                now,
+                true,
+                is_trusted,
+                read_input(path)?,
+                title,
+                description,
+            )?
+        }
+    };
+
+    output::print_installed_filter(filter.id, &filter.title, is_json);
+
+    Ok(())
+}
+
+fn user_rules(flm: &FilterListManagerImpl, matches: &ArgMatches, is_json: bool) -> CommandResult {
+    if let Some(path) = matches.get_one::<PathBuf>(SET_ARG) {
+        let rules = read_lines(path)?;
+        let count = rules.len();
+
+        // Saving replaces disabled rules too, so the current ones are passed through
+        let disabled_rules = flm
+            .get_disabled_rules(vec![USER_RULES_FILTER_LIST_ID])?
+            .into_iter()
+            .next()
+            .map(|raw| raw.text.lines().map(String::from).collect())
+            .unwrap_or_default();
+
+        flm.save_custom_filter_rules(FilterListRules {
+            filter_id: USER_RULES_FILTER_LIST_ID,
+            rules,
+            disabled_rules,
+            rules_count: 0,
+        })?;
+        output::print_saved("user rules", USER_RULES_FILTER_LIST_ID, count, is_json);
+
+        return Ok(());
+    }
+
+    let rules = flm
+        .get_filter_rules_as_strings(vec![USER_RULES_FILTER_LIST_ID])?
+        .into_iter()
+        .next()
+        .map(|raw| raw.rules)
+        .unwrap_or_default();
+    output::print_rules(USER_RULES_FILTER_LIST_ID, &rules, is_json);
+
+    Ok(())
+}
+
+fn disabled_rules(
+    flm: &FilterListManagerImpl,
+    matches: &ArgMatches,
+    is_json: bool,
+) -> CommandResult {
+    let filter_id = *matches.get_one::<FilterId>(ID_ARG).unwrap();
+
+    if let Some(path) = matches.get_one::<PathBuf>(SET_ARG) {
+        let rules = read_lines(path)?;
+        let count = rules.len();
+
+        flm.save_disabled_rules(filter_id, rules)?;
+        output::print_saved("disabled rules", filter_id, count, is_json);
+
+        return Ok(());
+    }
+
+    let rules = flm
+        .get_disabled_rules(vec![filter_id])?
+        .into_iter()
+        .next()
+        .map(|raw| raw.text)
+        .unwrap_or_default();
+    output::print_rules(filter_id, &rules, is_json);
+
+    Ok(())
+}
+
+fn update(flm: &FilterListManagerImpl, matches: &ArgMatches, is_json: bool) -> CommandResult {
+    let ignore_filters_expiration = matches.get_flag(FORCE_ARG);
+    let loose_timeout = *matches.get_one::<i32>(LOOSE_TIMEOUT_ARG).unwrap();
+
+    let result = if matches.contains_id(IDS_ARG) {
+        flm.update_filters_by_ids(
+            get_ids(matches),
+            ignore_filters_expiration,
+            loose_timeout,
+            false,
+        )?
+    } else {
+        flm.update_filters(ignore_filters_expiration, loose_timeout, false)?
+    };
+
+    output::print_update_result(result.as_ref(), is_json);
+
+    Ok(())
+}
+
+fn dump_rules(flm: &FilterListManagerImpl, matches: &ArgMatches, is_json: bool) -> CommandResult {
+    let rules_by_filter: Vec<(FilterId, Vec<String>)> = if matches.contains_id(IDS_ARG) {
+        flm.get_filter_rules_as_strings(get_ids(matches))?
+            .into_iter()
+            .map(|raw| {
+                let rules = raw.rules.lines().map(String::from).collect();
+
+                (raw.filter_id, rules)
+            })
+            .collect()
+    } else {
+        flm.get_active_rules()?
+            .into_iter()
+            .map(|info| (info.filter_id, info.rules))
+            .collect()
+    };
+
+    match matches.get_one::<PathBuf>(OUTPUT_ARG) {
+        Some(path) => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            output::write_dumped_rules(&mut writer, &rules_by_filter, is_json)?;
+            writer.flush()?;
+        }
+        None => output::write_dumped_rules(&mut stdout().lock(), &rules_by_filter, is_json)?,
+    }
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
 mod fill_database;
+mod make_diff;
+mod manage;
+mod output;
 mod test;
 
 use crate::cli_app::test::test;
@@ -61,6 +64,8 @@ fn cli_setup() -> Command {
                 ]),
         )
         .subcommand(Command::new("test"))
+        .subcommand(make_diff::command())
+        .subcommands(manage::commands())
 }
 
 /// Entrypoint of a CLI application
@@ -88,6 +93,10 @@ pub(super) fn run_app() {
             test();
         }
 
+        Some(("make-diff", sub_matches)) => make_diff::entry(sub_matches),
+
+        Some((name, sub_matches)) if name != "migrate" => manage::entry(name, sub_matches),
+
         _ => unreachable!(),
     }
 }
@@ -0,0 +1,229 @@
+//! Printing of management commands results as text or JSON
+use adguard_flm::{
+    FilterGroup, FilterId, FilterTag, FilterUpdateOutcome, PullMetadataResult,
+    StoredFilterMetadata, UpdateResult,
+};
+use serde_json::{json, Value};
+use std::io::Write;
+
+/// Prints `value` as pretty JSON
+fn print_json(value: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).unwrap_or_default()
+    );
+}
+
+fn filter_to_json(filter: &StoredFilterMetadata) -> Value {
+    json!({
+        "id": filter.id,
+        "group_id": filter.group_id,
+        "title": filter.title,
+        "description": filter.description,
+        "version": filter.version,
+        "download_url": filter.download_url,
+        "homepage": filter.homepage,
+        "time_updated": filter.time_updated,
+        "last_download_time": filter.last_download_time,
+        "expires": filter.expires,
+        "is_enabled": filter.is_enabled,
+        "is_installed": filter.is_installed,
+        "is_custom": filter.is_custom,
+        "is_trusted": filter.is_trusted,
+        "trust_level": filter.trust_level.map(|level| format!("{:?}", level)),
+        "registry": filter.registry,
+        "tags": filter.tags.iter().map(|tag| tag.id).collect::<Vec<i32>>(),
+        "languages": filter.languages,
+    })
+}
+
+/// Name and optional failure kind of the update outcome
+fn outcome_to_json(outcome: &FilterUpdateOutcome) -> Value {
+    match outcome {
+        FilterUpdateOutcome::Failed { kind } => json!({
+            "outcome": "Failed",
+            "failure_kind": format!("{:?}", kind),
+        }),
+        outcome => json!({ "outcome": format!("{:?}", outcome) }),
+    }
+}
+
+/// Prints stored filters
+pub(super) fn print_filters(filters: &[StoredFilterMetadata], is_json: bool) {
+    if is_json {
+        return print_json(&Value::Array(filters.iter().map(filter_to_json).collect()));
+    }
+
+    println!("id\tgroup\tenabled\tinstalled\ttitle");
+    for filter in filters {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            filter.id, filter.group_id, filter.is_enabled, filter.is_installed, filter.title
+        );
+    }
+}
+
+/// Prints all groups
+pub(super) fn print_groups(groups: &[FilterGroup], is_json: bool) {
+    if is_json {
+        return print_json(&json!(groups));
+    }
+
+    println!("id\tname");
+    for group in groups {
+        println!("{}\t{}", group.id, group.name);
+    }
+}
+
+/// Prints all tags
+pub(super) fn print_tags(tags: &[FilterTag], is_json: bool) {
+    if is_json {
+        return print_json(&json!(tags));
+    }
+
+    println!("id\tkeyword");
+    for tag in tags {
+        println!("{}\t{}", tag.id, tag.keyword);
+    }
+}
+
+/// Prints the number of filters, changed by the command
+pub(super) fn print_affected_count(action: &str, count: usize, is_json: bool) {
+    if is_json {
+        return print_json(&json!({ "action": action, "affected": count }));
+    }
+
+    println!("{}: {} filter(s)", action, count);
+}
+
+/// Prints the installed custom filter
+pub(super) fn print_installed_filter(filter_id: FilterId, title: &str, is_json: bool) {
+    if is_json {
+        return print_json(&json!({ "id": filter_id, "title": title }));
+    }
+
+    println!("Installed filter {}: {}", filter_id, title);
+}
+
+/// Prints result of the saving command
+pub(super) fn print_saved(what: &str, filter_id: FilterId, count: usize, is_json: bool) {
+    if is_json {
+        return print_json(&json!({ "saved": what, "filter_id": filter_id, "count": count }));
+    }
+
+    println!("Saved {} {} for filter {}", count, what, filter_id);
+}
+
+/// Prints rules of the filter, one per line
+pub(super) fn print_rules(filter_id: FilterId, rules: &str, is_json: bool) {
+    if is_json {
+        return print_json(&json!({
+            "filter_id": filter_id,
+            "rules": rules.lines().collect::<Vec<&str>>(),
+        }));
+    }
+
+    if !rules.is_empty() {
+        println!("{}", rules);
+    }
+}
+
+/// Prints result of `pull_metadata`
+pub(super) fn print_pull_metadata_result(result: &PullMetadataResult, is_json: bool) {
+    if is_json {
+        return print_json(&json!({
+            "added_filters": result.added_filters,
+            "removed_filters": result.removed_filters,
+            "moved_filters": result.moved_filters.iter().map(|moved| json!({
+                "previous_id": moved.previous_id,
+                "new_id": moved.new_id,
+            })).collect::<Vec<Value>>(),
+            "failed_registries": result.failed_registries.iter().map(|failed| json!({
+                "name": failed.name,
+                "message": failed.message,
+            })).collect::<Vec<Value>>(),
+        }));
+    }
+
+    println!("Added filters: {:?}", result.added_filters);
+    println!("Removed filters: {:?}", result.removed_filters);
+    for moved in result.moved_filters.iter() {
+        println!("Moved filter: {} -> {}", moved.previous_id, moved.new_id);
+    }
+    for failed in result.failed_registries.iter() {
+        println!("Failed registry {}: {}", failed.name, failed.message);
+    }
+}
+
+/// Prints result of the filters update. [`None`] means that there was nothing to update
+pub(super) fn print_update_result(result: Option<&UpdateResult>, is_json: bool) {
+    let Some(result) = result else {
+        if is_json {
+            return print_json(&Value::Null);
+        }
+
+        return println!("There are no filters to update");
+    };
+
+    if is_json {
+        return print_json(&json!({
+            "updated_filters": result.updated_list.iter().map(|filter| filter.id).collect::<Vec<FilterId>>(),
+            "remaining_filters_count": result.remaining_filters_count,
+            "errors": result.filters_errors.iter().map(|error| json!({
+                "filter_id": error.filter_id,
+                "message": error.message,
+                "http_status": error.http_status,
+            })).collect::<Vec<Value>>(),
+            "reports": result.filters_reports.iter().map(|report| {
+                let mut value = outcome_to_json(&report.outcome);
+                value["filter_id"] = json!(report.filter_id);
+                value["downloaded_bytes"] = json!(report.downloaded_bytes);
+
+                value
+            }).collect::<Vec<Value>>(),
+        }));
+    }
+
+    for filter in result.updated_list.iter() {
+        println!("Updated filter {}: {}", filter.id, filter.title);
+    }
+    for error in result.filters_errors.iter() {
+        println!("Failed filter {}: {}", error.filter_id, error.message);
+    }
+    println!(
+        "Updated: {}, failed: {}, remaining: {}",
+        result.updated_list.len(),
+        result.filters_errors.len(),
+        result.remaining_filters_count
+    );
+}
+
+/// Writes rules of filters into `writer`: JSON array or plain rules, one per line
+pub(super) fn write_dumped_rules(
+    writer: &mut dyn Write,
+    rules_by_filter: &[(FilterId, Vec<String>)],
+    is_json: bool,
+) -> std::io::Result<()> {
+    if is_json {
+        let value = Value::Array(
+            rules_by_filter
+                .iter()
+                .map(|(filter_id, rules)| json!({ "filter_id": filter_id, "rules": rules }))
+                .collect(),
+        );
+
+        return writeln!(
+            writer,
+            "{}",
+            serde_json::to_string_pretty(&value).unwrap_or_default()
+        );
+    }
+
+    for (_, rules) in rules_by_filter {
+        for rule in rules {
+            writeln!(writer, "{}", rule)?;
+        }
+    }
+
+    Ok(())
+}
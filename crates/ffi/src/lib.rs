@@ -9,20 +9,25 @@ use crate::outer_error::AGOuterError;
 use crate::result::AGResult;
 use adguard_flm::FilterListManager as IFilterListManager;
 pub use adguard_flm::*;
-use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 // Re-export native structs and functions
 pub use crate::native_interface::*;
 
 pub struct FilterListManager {
     flm: RwLock<FilterListManagerImpl>,
+    /// Kept outside the lock, so running update can be cancelled without waiting for it
+    update_cancellation_token: UpdateCancellationToken,
 }
 
 impl FilterListManager {
     pub fn new(configuration: Configuration) -> AGResult<Self> {
         let flm = FilterListManagerImpl::new(configuration).map_err(AGOuterError::from)?;
+        let update_cancellation_token = flm.get_update_cancellation_token();
+
         Ok(Self {
             flm: RwLock::new(*flm),
+            update_cancellation_token,
         })
     }
 
@@ -80,6 +85,14 @@ impl FilterListManager {
         self.wrap(|flm| flm.save_disabled_rules(filter_id, disabled_rules))
     }
 
+    pub fn save_disabled_rule_patterns(
+        &self,
+        filter_id: FilterId,
+        patterns: Vec<DisabledRulePattern>,
+    ) -> AGResult<()> {
+        self.wrap(move |flm| flm.save_disabled_rule_patterns(filter_id, patterns))
+    }
+
     pub fn update_filters(
         &self,
         ignore_filters_expiration: bool,
@@ -202,6 +215,17 @@ impl FilterListManager {
         self.wrap(|flm| flm.get_active_rules_raw(filter_by))
     }
 
+    pub fn get_active_rules_deduplicated(&self) -> AGResult<DeduplicatedActiveRules> {
+        self.wrap(|flm| flm.get_active_rules_deduplicated())
+    }
+
+    pub fn get_active_rules_raw_deduplicated(
+        &self,
+        filter_by: Vec<FilterId>,
+    ) -> AGResult<DeduplicatedActiveRulesRaw> {
+        self.wrap(move |flm| flm.get_active_rules_raw_deduplicated(filter_by))
+    }
+
     pub fn get_filter_rules_as_strings(
         &self,
         ids: Vec<FilterId>,
@@ -213,10 +237,25 @@ impl FilterListManager {
         self.wrap(|flm| flm.save_rules_to_file_blob(filter_id, file_path))
     }
 
+    pub fn save_active_rules_to_file(
+        &self,
+        file_path: String,
+        options: ActiveRulesExportOptions,
+    ) -> AGResult<ActiveRulesExportManifest> {
+        self.wrap(move |flm| flm.save_active_rules_to_file(file_path, options))
+    }
+
     pub fn get_disabled_rules(&self, ids: Vec<FilterId>) -> AGResult<Vec<DisabledRulesRaw>> {
         self.wrap(|flm| flm.get_disabled_rules(ids))
     }
 
+    pub fn get_disabled_rule_patterns(
+        &self,
+        ids: Vec<FilterId>,
+    ) -> AGResult<Vec<DisabledRulePatterns>> {
+        self.wrap(move |flm| flm.get_disabled_rule_patterns(ids))
+    }
+
     pub fn set_proxy_mode(&self, request_proxy_mode: RequestProxyMode) -> AGResult<()> {
         self.wrap_mut(|mut flm| {
             flm.set_proxy_mode(request_proxy_mode);
@@ -224,6 +263,39 @@ impl FilterListManager {
         })
     }
 
+    pub fn set_update_observer(&self, observer: Option<Arc<dyn UpdateObserver>>) -> AGResult<()> {
+        self.wrap_mut(|mut flm| {
+            flm.set_update_observer(observer);
+            Ok(())
+        })
+    }
+
+    pub fn set_http_transport(&self, transport: Option<Arc<dyn HttpTransport>>) -> AGResult<()> {
+        self.wrap_mut(|mut flm| {
+            flm.set_http_transport(transport);
+            Ok(())
+        })
+    }
+
+    pub fn cancel_update(&self) {
+        self.update_cancellation_token.cancel()
+    }
+
+    pub fn start_update_scheduler(
+        &self,
+        settings: UpdateSchedulerSettings,
+        observer: Arc<dyn UpdateSchedulerObserver>,
+    ) -> AGResult<()> {
+        self.wrap_mut(move |mut flm| flm.start_update_scheduler(settings, observer))
+    }
+
+    pub fn stop_update_scheduler(&self) -> AGResult<()> {
+        self.wrap_mut(|mut flm| {
+            flm.stop_update_scheduler();
+            Ok(())
+        })
+    }
+
     pub fn get_rules_count(&self, ids: Vec<FilterId>) -> AGResult<Vec<RulesCountByFilter>> {
         self.wrap(move |flm| flm.get_rules_count(ids))
     }
@@ -236,9 +308,58 @@ impl FilterListManager {
         self.wrap_mut(|mut flm| flm.sign_all_data_with_new_key(integrity_key))
     }
 
+    pub fn change_database_encryption_key(&self, encryption_key: Option<String>) -> AGResult<()> {
+        self.wrap_mut(|mut flm| flm.change_database_encryption_key(encryption_key))
+    }
+
     pub fn verify_integrity(&self) -> AGResult<()> {
         self.wrap(|flm| flm.verify_integrity())
     }
+
+    pub fn export_user_state(&self) -> AGResult<String> {
+        self.wrap(|flm| flm.export_user_state())
+    }
+
+    pub fn import_user_state(&self, document: String) -> AGResult<ImportUserStateResult> {
+        self.wrap(move |flm| flm.import_user_state(document))
+    }
+
+    pub fn search_rules(
+        &self,
+        query: String,
+        filter_ids: Vec<FilterId>,
+        options: RuleSearchOptions,
+    ) -> AGResult<Vec<RuleSearchMatch>> {
+        self.wrap(move |flm| flm.search_rules(query, filter_ids, options))
+    }
+
+    pub fn get_rule_provenance(&self, rule: String) -> AGResult<Vec<RuleProvenance>> {
+        self.wrap(move |flm| flm.get_rule_provenance(rule))
+    }
+
+    pub fn get_filter_update_failures(&self) -> AGResult<Vec<FilterUpdateFailure>> {
+        self.wrap(|flm| flm.get_filter_update_failures())
+    }
+
+    pub fn preview_filter_update(&self, filter_id: FilterId) -> AGResult<FilterUpdatePreview> {
+        self.wrap(move |flm| flm.preview_filter_update(filter_id))
+    }
+
+    pub fn list_filter_versions(&self, filter_id: FilterId) -> AGResult<Vec<FilterVersion>> {
+        self.wrap(move |flm| flm.list_filter_versions(filter_id))
+    }
+
+    pub fn rollback_filter(&self, filter_id: FilterId, version_id: i64) -> AGResult<()> {
+        self.wrap(move |flm| flm.rollback_filter(filter_id, version_id))
+    }
+
+    pub fn unpin_filter(&self, filter_id: FilterId) -> AGResult<()> {
+        self.wrap(move |flm| flm.unpin_filter(filter_id))
+    }
+
+    pub fn lint_filter(&self, body: String, base_url: String) -> AGResult<Vec<FilterDiagnostic>> {
+        self.wrap(move |flm| flm.lint_filter(body, base_url))
+    }
 }
 
 impl FilterListManager {
@@ -3,30 +3,44 @@
 use crate::native_interface::{build_rust_response_error, FLMHandle, RustResponse};
 use crate::outer_error::AGOuterError;
 use crate::protobuf_generated::filter_list_manager;
+use crate::protobuf_generated::filter_list_manager::http_transport_result;
 use crate::protobuf_generated::filter_list_manager::{
-    ChangeLocaleRequest, ChangeLocaleResponse, DeleteCustomFilterListsRequest,
-    DeleteCustomFilterListsResponse, EmptyResponse, EnableFilterListsRequest,
-    EnableFilterListsResponse, FetchFilterListMetadataRequest, FetchFilterListMetadataResponse,
+    ChangeDatabaseEncryptionKeyRequest, ChangeLocaleRequest, ChangeLocaleResponse,
+    DeleteCustomFilterListsRequest, DeleteCustomFilterListsResponse, EmptyResponse,
+    EnableFilterListsRequest, EnableFilterListsResponse, ExportUserStateResponse,
+    FetchFilterListMetadataRequest, FetchFilterListMetadataResponse,
     FetchFilterListMetadataWithBodyRequest, FetchFilterListMetadataWithBodyResponse,
-    ForceUpdateFiltersByIdsRequest, ForceUpdateFiltersByIdsResponse, GetActiveRulesRawRequest,
-    GetActiveRulesRawResponse, GetActiveRulesResponse, GetAllGroupsResponse, GetAllTagsResponse,
-    GetDatabasePathResponse, GetDatabaseVersionResponse, GetDisabledRulesRequest,
-    GetDisabledRulesResponse, GetFilterRulesAsStringsRequest, GetFilterRulesAsStringsResponse,
-    GetFullFilterListByIdRequest, GetRulesCountRequest, GetRulesCountResponse,
-    GetStoredFilterMetadataByIdRequest, GetStoredFilterMetadataByIdResponse,
-    GetStoredFiltersMetadataResponse, InstallCustomFilterFromStringRequest,
-    InstallCustomFilterFromStringResponse, InstallCustomFilterListRequest,
-    InstallCustomFilterListResponse, InstallFilterListsRequest, InstallFilterListsResponse,
-    PullMetadataResponse, SaveCustomFilterRulesRequest, SaveDisabledRulesRequest,
-    SaveRulesToFileBlobRequest, SetProxyModeRequest, SignAllDataWithNewKeyRequest,
+    ForceUpdateFiltersByIdsRequest, ForceUpdateFiltersByIdsResponse,
+    GetActiveRulesDeduplicatedResponse, GetActiveRulesRawDeduplicatedRequest,
+    GetActiveRulesRawDeduplicatedResponse, GetActiveRulesRawRequest, GetActiveRulesRawResponse,
+    GetActiveRulesResponse, GetAllGroupsResponse, GetAllTagsResponse, GetDatabasePathResponse,
+    GetDatabaseVersionResponse, GetDisabledRulePatternsRequest, GetDisabledRulePatternsResponse,
+    GetDisabledRulesRequest, GetDisabledRulesResponse, GetFilterRulesAsStringsRequest,
+    GetFilterRulesAsStringsResponse, GetFilterUpdateFailuresResponse, GetFullFilterListByIdRequest,
+    GetRuleProvenanceRequest, GetRuleProvenanceResponse, GetRulesCountRequest,
+    GetRulesCountResponse, GetStoredFilterMetadataByIdRequest, GetStoredFilterMetadataByIdResponse,
+    GetStoredFiltersMetadataResponse, ImportUserStateRequest, ImportUserStateResponse,
+    InstallCustomFilterFromStringRequest, InstallCustomFilterFromStringResponse,
+    InstallCustomFilterListRequest, InstallCustomFilterListResponse, InstallFilterListsRequest,
+    InstallFilterListsResponse, LintFilterRequest, LintFilterResponse, ListFilterVersionsRequest,
+    ListFilterVersionsResponse, PreviewFilterUpdateRequest, PreviewFilterUpdateResponse,
+    PullMetadataResponse, RollbackFilterRequest, SaveActiveRulesToFileRequest,
+    SaveActiveRulesToFileResponse, SaveCustomFilterRulesRequest, SaveDisabledRulePatternsRequest,
+    SaveDisabledRulesRequest, SaveRulesToFileBlobRequest, SearchRulesRequest, SearchRulesResponse,
+    SetProxyModeRequest, SignAllDataWithNewKeyRequest, UnpinFilterRequest,
     UpdateCustomFilterMetadataRequest, UpdateCustomFilterMetadataResponse,
     UpdateFiltersByIdsRequest, UpdateFiltersByIdsResponse, UpdateFiltersRequest,
     UpdateFiltersResponse,
 };
-use adguard_flm::RequestProxyMode;
+use adguard_flm::{
+    ActiveRulesExportOptions, HttpClientError, HttpRequest, HttpResponse, HttpTransport,
+    RequestProxyMode, UpdateObserver, UpdateProgressEvent, UpdateSchedulerEvent,
+    UpdateSchedulerObserver,
+};
 use enum_stringify::EnumStringify;
 use prost::Message;
 use std::ffi::c_void;
+use std::sync::Arc;
 
 /// Representation of method handle for [`flm_call_protobuf`]
 #[repr(C)]
@@ -65,6 +79,366 @@ pub enum FFIMethod {
     VerifyIntegrity,
     SignAllData,
     SignAllDataWithNewKey,
+    CancelUpdate,
+    ExportUserState,
+    ImportUserState,
+    SearchRules,
+    GetRuleProvenance,
+    GetFilterUpdateFailures,
+    LintFilter,
+    PreviewFilterUpdate,
+    ListFilterVersions,
+    RollbackFilter,
+    UnpinFilter,
+    SaveDisabledRulePatterns,
+    GetDisabledRulePatterns,
+    SaveActiveRulesToFile,
+    GetActiveRulesDeduplicated,
+    GetActiveRulesRawDeduplicated,
+    ChangeDatabaseEncryptionKey,
+    StopUpdateScheduler,
+}
+
+/// Callback for update progress events.
+/// `event_buffer` contains protobuf-encoded `UpdateProgressEvent` and is valid only during the call.
+/// `context` is the pointer, passed to [`flm_set_update_progress_callback`]
+pub type FLMUpdateProgressCallback =
+    extern "C" fn(context: *mut c_void, event_buffer: *const u8, event_buffer_len: usize);
+
+/// [`UpdateObserver`], which passes events to native callback
+struct NativeUpdateObserver {
+    callback: FLMUpdateProgressCallback,
+    context: *mut c_void,
+}
+
+// Caller of `flm_set_update_progress_callback` guarantees that callback and context
+// can be used from any thread
+unsafe impl Send for NativeUpdateObserver {}
+unsafe impl Sync for NativeUpdateObserver {}
+
+impl UpdateObserver for NativeUpdateObserver {
+    fn on_update_progress(&self, event: UpdateProgressEvent) {
+        let event = filter_list_manager::UpdateProgressEvent::from(event);
+
+        let mut buffer = vec![];
+        if event.encode(&mut buffer).is_ok() {
+            (self.callback)(self.context, buffer.as_ptr(), buffer.len());
+        }
+    }
+}
+
+/// Sets callback for progress events of filters update methods and `pull_metadata`.
+/// Pass `NULL` callback to remove it.
+/// Running update can be cancelled with [`FFIMethod::CancelUpdate`] method.
+///
+/// # Safety
+///
+/// 1. `handle.is_null()` is safe and returns error result
+/// 2. `callback` will be called from worker threads, so it and `context` must be thread-safe
+/// 3. `context` must be valid until callback is removed or handle is freed
+#[no_mangle]
+pub unsafe extern "C" fn flm_set_update_progress_callback(
+    handle: *mut FLMHandle,
+    callback: Option<FLMUpdateProgressCallback>,
+    context: *mut c_void,
+) -> *mut RustResponse {
+    let mut rust_response = Box::<RustResponse>::default();
+
+    if handle.is_null() {
+        return build_rust_response_error(
+            Box::new(AGOuterError::Other(String::from(
+                "Got empty handle, while setting update progress callback",
+            ))),
+            rust_response,
+            "",
+        );
+    }
+
+    let flm_handle = &*handle;
+
+    let observer = callback.map(|callback| {
+        Arc::new(NativeUpdateObserver { callback, context }) as Arc<dyn UpdateObserver>
+    });
+
+    let mut out_bytes_buffer = vec![];
+    let encode_result = EmptyResponse {
+        error: flm_handle
+            .flm
+            .set_update_observer(observer)
+            .err()
+            .map(Into::into),
+    }
+    .encode(&mut out_bytes_buffer);
+
+    if let Err(encode_error) = encode_result {
+        return build_rust_response_error(
+            Box::new(encode_error),
+            rust_response,
+            "Cannot encode output data for update progress callback",
+        );
+    }
+
+    rust_response.result_data_capacity = out_bytes_buffer.capacity();
+    rust_response.result_data_len = out_bytes_buffer.len();
+    rust_response.result_data = Box::into_raw(out_bytes_buffer.into_boxed_slice()) as *mut c_void;
+
+    Box::leak(rust_response)
+}
+
+/// Callback for results of the update scheduler tasks.
+/// `event_buffer` contains protobuf-encoded `UpdateSchedulerEvent` and is valid only during the call.
+/// `context` is the pointer, passed to [`flm_start_update_scheduler`]
+pub type FLMUpdateSchedulerCallback =
+    extern "C" fn(context: *mut c_void, event_buffer: *const u8, event_buffer_len: usize);
+
+/// [`UpdateSchedulerObserver`], which passes events to native callback
+struct NativeUpdateSchedulerObserver {
+    callback: FLMUpdateSchedulerCallback,
+    context: *mut c_void,
+}
+
+// Caller of `flm_start_update_scheduler` guarantees that callback and context
+// can be used from any thread
+unsafe impl Send for NativeUpdateSchedulerObserver {}
+unsafe impl Sync for NativeUpdateSchedulerObserver {}
+
+impl UpdateSchedulerObserver for NativeUpdateSchedulerObserver {
+    fn on_scheduled_task_finished(&self, event: UpdateSchedulerEvent) {
+        let event = filter_list_manager::UpdateSchedulerEvent::from(event);
+
+        let mut buffer = vec![];
+        if event.encode(&mut buffer).is_ok() {
+            (self.callback)(self.context, buffer.as_ptr(), buffer.len());
+        }
+    }
+}
+
+/// Starts the built-in update scheduler, which calls `pull_metadata` and `update_filters`
+/// on a background thread and passes their results to `callback`.
+/// `settings_buffer` contains protobuf-encoded `UpdateSchedulerSettings`.
+/// Running scheduler is restarted with the new settings.
+/// Scheduler can be stopped with [`FFIMethod::StopUpdateScheduler`] method, and it is stopped on handle free.
+///
+/// # Safety
+///
+/// 1. `handle.is_null()` is safe and returns error result
+/// 2. `settings_buffer` must point to `settings_buffer_len` bytes
+/// 3. `callback` will be called from the scheduler thread, so it and `context` must be thread-safe.
+///    Callback must not wait for the calls of this handle
+/// 4. `context` must be valid until scheduler is stopped or handle is freed
+#[no_mangle]
+pub unsafe extern "C" fn flm_start_update_scheduler(
+    handle: *mut FLMHandle,
+    settings_buffer: *const u8,
+    settings_buffer_len: usize,
+    callback: FLMUpdateSchedulerCallback,
+    context: *mut c_void,
+) -> *mut RustResponse {
+    let mut rust_response = Box::<RustResponse>::default();
+
+    if handle.is_null() {
+        return build_rust_response_error(
+            Box::new(AGOuterError::Other(String::from(
+                "Got empty handle, while starting update scheduler",
+            ))),
+            rust_response,
+            "",
+        );
+    }
+
+    let flm_handle = &*handle;
+
+    let settings_bytes = if settings_buffer.is_null() {
+        &[]
+    } else {
+        std::slice::from_raw_parts(settings_buffer, settings_buffer_len)
+    };
+
+    let settings = match filter_list_manager::UpdateSchedulerSettings::decode(settings_bytes) {
+        Ok(settings) => settings,
+        Err(decode_error) => {
+            return build_rust_response_error(
+                Box::new(decode_error),
+                rust_response,
+                "Cannot decode update scheduler settings",
+            )
+        }
+    };
+
+    let observer = Arc::new(NativeUpdateSchedulerObserver { callback, context });
+
+    let mut out_bytes_buffer = vec![];
+    let encode_result = EmptyResponse {
+        error: flm_handle
+            .flm
+            .start_update_scheduler(settings.into(), observer)
+            .err()
+            .map(Into::into),
+    }
+    .encode(&mut out_bytes_buffer);
+
+    if let Err(encode_error) = encode_result {
+        return build_rust_response_error(
+            Box::new(encode_error),
+            rust_response,
+            "Cannot encode output data for update scheduler start",
+        );
+    }
+
+    rust_response.result_data_capacity = out_bytes_buffer.capacity();
+    rust_response.result_data_len = out_bytes_buffer.len();
+    rust_response.result_data = Box::into_raw(out_bytes_buffer.into_boxed_slice()) as *mut c_void;
+
+    Box::leak(rust_response)
+}
+
+/// Callback for HTTP GET requests of the library.
+/// `request_buffer` contains protobuf-encoded `HttpRequest` and is valid only during the call.
+/// Callback must perform the request synchronously and pass protobuf-encoded `HttpTransportResult`
+/// to [`flm_http_transport_set_result`] with `result_sink` before returning.
+/// `context` is the pointer, passed to [`flm_set_http_transport_callback`]
+pub type FLMHttpTransportCallback = extern "C" fn(
+    context: *mut c_void,
+    request_buffer: *const u8,
+    request_buffer_len: usize,
+    result_sink: *mut c_void,
+);
+
+/// [`HttpTransport`], which passes requests to native callback
+struct NativeHttpTransport {
+    callback: FLMHttpTransportCallback,
+    context: *mut c_void,
+}
+
+// Caller of `flm_set_http_transport_callback` guarantees that callback and context
+// can be used from any thread
+unsafe impl Send for NativeHttpTransport {}
+unsafe impl Sync for NativeHttpTransport {}
+
+impl HttpTransport for NativeHttpTransport {
+    fn get(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+        let request = filter_list_manager::HttpRequest::from(request);
+
+        let mut buffer = vec![];
+        request
+            .encode(&mut buffer)
+            .map_err(|why| HttpClientError::NetworkError(why.to_string()))?;
+
+        // Sink lives on the stack until callback returns
+        let mut result_sink: Option<Vec<u8>> = None;
+        (self.callback)(
+            self.context,
+            buffer.as_ptr(),
+            buffer.len(),
+            &mut result_sink as *mut Option<Vec<u8>> as *mut c_void,
+        );
+
+        let Some(result_bytes) = result_sink else {
+            return Err(HttpClientError::NetworkError(String::from(
+                "Native HTTP transport hasn't set the result",
+            )));
+        };
+
+        let result = filter_list_manager::HttpTransportResult::decode(result_bytes.as_slice())
+            .map_err(|why| HttpClientError::NetworkError(why.to_string()))?;
+
+        match result.result {
+            Some(http_transport_result::Result::Response(response)) => Ok(response.into()),
+            Some(http_transport_result::Result::Error(error)) if error.is_timed_out => {
+                Err(HttpClientError::TimedOut(error.message))
+            }
+            Some(http_transport_result::Result::Error(error)) => {
+                Err(HttpClientError::NetworkError(error.message))
+            }
+            None => Err(HttpClientError::NetworkError(String::from(
+                "Native HTTP transport has returned empty result",
+            ))),
+        }
+    }
+}
+
+/// Sets callback, which performs all HTTP(S) requests of the library instead of the built-in client.
+/// Pass `NULL` callback to use the built-in client again.
+/// Custom transport ignores timeout, proxy mode and user agent settings of the configuration.
+///
+/// # Safety
+///
+/// 1. `handle.is_null()` is safe and returns error result
+/// 2. `callback` will be called from worker threads, so it and `context` must be thread-safe
+/// 3. `context` must be valid until callback is removed or handle is freed
+#[no_mangle]
+pub unsafe extern "C" fn flm_set_http_transport_callback(
+    handle: *mut FLMHandle,
+    callback: Option<FLMHttpTransportCallback>,
+    context: *mut c_void,
+) -> *mut RustResponse {
+    let mut rust_response = Box::<RustResponse>::default();
+
+    if handle.is_null() {
+        return build_rust_response_error(
+            Box::new(AGOuterError::Other(String::from(
+                "Got empty handle, while setting http transport callback",
+            ))),
+            rust_response,
+            "",
+        );
+    }
+
+    let flm_handle = &*handle;
+
+    let transport = callback.map(|callback| {
+        Arc::new(NativeHttpTransport { callback, context }) as Arc<dyn HttpTransport>
+    });
+
+    let mut out_bytes_buffer = vec![];
+    let encode_result = EmptyResponse {
+        error: flm_handle
+            .flm
+            .set_http_transport(transport)
+            .err()
+            .map(Into::into),
+    }
+    .encode(&mut out_bytes_buffer);
+
+    if let Err(encode_error) = encode_result {
+        return build_rust_response_error(
+            Box::new(encode_error),
+            rust_response,
+            "Cannot encode output data for http transport callback",
+        );
+    }
+
+    rust_response.result_data_capacity = out_bytes_buffer.capacity();
+    rust_response.result_data_len = out_bytes_buffer.len();
+    rust_response.result_data = Box::into_raw(out_bytes_buffer.into_boxed_slice()) as *mut c_void;
+
+    Box::leak(rust_response)
+}
+
+/// Passes protobuf-encoded `HttpTransportResult` of the request back to the library.
+/// Bytes are copied, so `result_buffer` may be freed right after the call.
+///
+/// # Safety
+///
+/// 1. `result_sink` must be the pointer, passed to [`FLMHttpTransportCallback`], and may be used only during its call
+/// 2. `result_buffer` must point to `result_buffer_len` bytes. `NULL` is treated as empty buffer
+#[no_mangle]
+pub unsafe extern "C" fn flm_http_transport_set_result(
+    result_sink: *mut c_void,
+    result_buffer: *const u8,
+    result_buffer_len: usize,
+) {
+    if result_sink.is_null() {
+        return;
+    }
+
+    let bytes = if result_buffer.is_null() || result_buffer_len == 0 {
+        vec![]
+    } else {
+        std::slice::from_raw_parts(result_buffer, result_buffer_len).to_vec()
+    };
+
+    *(result_sink as *mut Option<Vec<u8>>) = Some(bytes);
 }
 
 /// Calls FLM method described as [`FFIMethod`] for object behind [`FLMHandle`]
@@ -564,6 +938,253 @@ pub unsafe extern "C" fn flm_call_protobuf(
             }
         }
         .encode(&mut out_bytes_buffer),
+        FFIMethod::CancelUpdate => {
+            flm_handle.flm.cancel_update();
+
+            EmptyResponse { error: None }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::ExportUserState => {
+            do_simple_match!(
+                flm_handle.flm.export_user_state(),
+                ExportUserStateResponse,
+                document
+            )
+        }
+        FFIMethod::ImportUserState => {
+            let request = decode_input_request!(ImportUserStateRequest);
+
+            match flm_handle.flm.import_user_state(request.document) {
+                Ok(value) => ImportUserStateResponse {
+                    result: Some(value.into()),
+                    error: None,
+                },
+                Err(why) => ImportUserStateResponse {
+                    result: None,
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::SearchRules => {
+            let request = decode_input_request!(SearchRulesRequest);
+
+            match flm_handle.flm.search_rules(
+                request.query,
+                request.filter_ids,
+                request.options.map(Into::into).unwrap_or_default(),
+            ) {
+                Ok(value) => SearchRulesResponse {
+                    matches: value.into_iter().map(Into::into).collect(),
+                    error: None,
+                },
+                Err(why) => SearchRulesResponse {
+                    matches: vec![],
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::GetRuleProvenance => {
+            let request = decode_input_request!(GetRuleProvenanceRequest);
+
+            match flm_handle.flm.get_rule_provenance(request.rule) {
+                Ok(value) => GetRuleProvenanceResponse {
+                    locations: value.into_iter().map(Into::into).collect(),
+                    error: None,
+                },
+                Err(why) => GetRuleProvenanceResponse {
+                    locations: vec![],
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::GetFilterUpdateFailures => match flm_handle.flm.get_filter_update_failures() {
+            Ok(value) => GetFilterUpdateFailuresResponse {
+                failures: value.into_iter().map(Into::into).collect(),
+                error: None,
+            },
+            Err(why) => GetFilterUpdateFailuresResponse {
+                failures: vec![],
+                error: Some(why.into()),
+            },
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::LintFilter => {
+            let request = decode_input_request!(LintFilterRequest);
+
+            match flm_handle.flm.lint_filter(request.body, request.base_url) {
+                Ok(value) => LintFilterResponse {
+                    diagnostics: value.into_iter().map(Into::into).collect(),
+                    error: None,
+                },
+                Err(why) => LintFilterResponse {
+                    diagnostics: vec![],
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::PreviewFilterUpdate => {
+            let request = decode_input_request!(PreviewFilterUpdateRequest);
+
+            match flm_handle.flm.preview_filter_update(request.filter_id) {
+                Ok(value) => PreviewFilterUpdateResponse {
+                    preview: Some(value.into()),
+                    error: None,
+                },
+                Err(why) => PreviewFilterUpdateResponse {
+                    preview: None,
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::ListFilterVersions => {
+            let request = decode_input_request!(ListFilterVersionsRequest);
+
+            match flm_handle.flm.list_filter_versions(request.filter_id) {
+                Ok(versions) => ListFilterVersionsResponse {
+                    versions: versions.into_iter().map(Into::into).collect(),
+                    error: None,
+                },
+                Err(why) => ListFilterVersionsResponse {
+                    versions: vec![],
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::RollbackFilter => {
+            let request = decode_input_request!(RollbackFilterRequest);
+
+            EmptyResponse {
+                error: flm_handle
+                    .flm
+                    .rollback_filter(request.filter_id, request.version_id)
+                    .err()
+                    .map(Into::into),
+            }
+            .encode(&mut out_bytes_buffer)
+        }
+        FFIMethod::UnpinFilter => {
+            let request = decode_input_request!(UnpinFilterRequest);
+
+            EmptyResponse {
+                error: flm_handle
+                    .flm
+                    .unpin_filter(request.filter_id)
+                    .err()
+                    .map(Into::into),
+            }
+            .encode(&mut out_bytes_buffer)
+        }
+        FFIMethod::SaveDisabledRulePatterns => {
+            let request = decode_input_request!(SaveDisabledRulePatternsRequest);
+
+            EmptyResponse {
+                error: flm_handle
+                    .flm
+                    .save_disabled_rule_patterns(
+                        request.filter_id,
+                        request.patterns.into_iter().map(Into::into).collect(),
+                    )
+                    .err()
+                    .map(Into::into),
+            }
+            .encode(&mut out_bytes_buffer)
+        }
+        FFIMethod::GetDisabledRulePatterns => {
+            let request = decode_input_request!(GetDisabledRulePatternsRequest);
+
+            match flm_handle.flm.get_disabled_rule_patterns(request.ids) {
+                Ok(value) => GetDisabledRulePatternsResponse {
+                    patterns: value.into_iter().map(Into::into).collect(),
+                    error: None,
+                },
+                Err(why) => GetDisabledRulePatternsResponse {
+                    patterns: vec![],
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::SaveActiveRulesToFile => {
+            let request = decode_input_request!(SaveActiveRulesToFileRequest);
+
+            let options = ActiveRulesExportOptions {
+                write_filter_headers: request.write_filter_headers,
+                untrusted_file_path: request.untrusted_file_path.map(Into::into),
+                deduplicate_rules: request.deduplicate_rules,
+            };
+
+            match flm_handle
+                .flm
+                .save_active_rules_to_file(request.file_path, options)
+            {
+                Ok(value) => SaveActiveRulesToFileResponse {
+                    manifest: Some(value.into()),
+                    error: None,
+                },
+                Err(why) => SaveActiveRulesToFileResponse {
+                    manifest: None,
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::GetActiveRulesDeduplicated => {
+            match flm_handle.flm.get_active_rules_deduplicated() {
+                Ok(value) => GetActiveRulesDeduplicatedResponse {
+                    rules: value.rules.into_iter().map(Into::into).collect(),
+                    duplicates: value.duplicates.into_iter().map(Into::into).collect(),
+                    error: None,
+                },
+                Err(why) => GetActiveRulesDeduplicatedResponse {
+                    rules: vec![],
+                    duplicates: vec![],
+                    error: Some(why.into()),
+                },
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::GetActiveRulesRawDeduplicated => {
+            let request = decode_input_request!(GetActiveRulesRawDeduplicatedRequest);
+
+            match flm_handle
+                .flm
+                .get_active_rules_raw_deduplicated(request.filter_by)
+            {
+                Ok(value) => GetActiveRulesRawDeduplicatedResponse {
+                    rules: value.rules.into_iter().map(Into::into).collect(),
+                    duplicates: value.duplicates.into_iter().map(Into::into).collect(),
+                    error: None,
+                },
+                Err(why) => GetActiveRulesRawDeduplicatedResponse {
+                    rules: vec![],
+                    duplicates: vec![],
+                    error: Some(why.into()),
+                },
+            }
+            .encode(&mut out_bytes_buffer)
+        }
+        FFIMethod::ChangeDatabaseEncryptionKey => {
+            let request = decode_input_request!(ChangeDatabaseEncryptionKeyRequest);
+
+            EmptyResponse {
+                error: flm_handle
+                    .flm
+                    .change_database_encryption_key(request.encryption_key)
+                    .err()
+                    .map(Into::into),
+            }
+        }
+        .encode(&mut out_bytes_buffer),
+        FFIMethod::StopUpdateScheduler => EmptyResponse {
+            error: flm_handle.flm.stop_update_scheduler().err().map(Into::into),
+        }
+        .encode(&mut out_bytes_buffer),
     };
 
     if let Err(encode_error) = encode_result {
@@ -580,3 +1201,98 @@ pub unsafe extern "C" fn flm_call_protobuf(
 
     Box::leak(rust_response)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{flm_http_transport_set_result, NativeHttpTransport};
+    use crate::protobuf_generated::filter_list_manager;
+    use crate::protobuf_generated::filter_list_manager::http_transport_result;
+    use adguard_flm::{HttpClientError, HttpRequest, HttpTransport};
+    use prost::Message;
+    use std::ffi::c_void;
+
+    extern "C" fn echo_url_callback(
+        _context: *mut c_void,
+        request_buffer: *const u8,
+        request_buffer_len: usize,
+        result_sink: *mut c_void,
+    ) {
+        let request = filter_list_manager::HttpRequest::decode(unsafe {
+            std::slice::from_raw_parts(request_buffer, request_buffer_len)
+        })
+        .unwrap();
+
+        let result = if request.url.ends_with("timeout") {
+            http_transport_result::Result::Error(filter_list_manager::HttpTransportError {
+                message: request.url,
+                is_timed_out: true,
+            })
+        } else {
+            http_transport_result::Result::Response(filter_list_manager::HttpResponse {
+                status: 200,
+                headers: request.headers,
+                body: request.url.into_bytes(),
+            })
+        };
+
+        let mut buffer = vec![];
+        filter_list_manager::HttpTransportResult {
+            result: Some(result),
+        }
+        .encode(&mut buffer)
+        .unwrap();
+
+        unsafe { flm_http_transport_set_result(result_sink, buffer.as_ptr(), buffer.len()) };
+    }
+
+    extern "C" fn silent_callback(
+        _context: *mut c_void,
+        _request_buffer: *const u8,
+        _request_buffer_len: usize,
+        _result_sink: *mut c_void,
+    ) {
+    }
+
+    #[test]
+    fn test_native_http_transport() {
+        let transport = NativeHttpTransport {
+            callback: echo_url_callback,
+            context: std::ptr::null_mut(),
+        };
+
+        let response = transport
+            .get(HttpRequest {
+                url: String::from("https://example.org/filter.txt"),
+                headers: vec![(String::from("if-none-match"), String::from("\"v1\""))],
+            })
+            .unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"https://example.org/filter.txt");
+        assert_eq!(
+            response.headers,
+            vec![(String::from("if-none-match"), String::from("\"v1\""))]
+        );
+
+        assert!(matches!(
+            transport.get(HttpRequest {
+                url: String::from("https://example.org/timeout"),
+                headers: vec![],
+            }),
+            Err(HttpClientError::TimedOut(_))
+        ));
+
+        let transport = NativeHttpTransport {
+            callback: silent_callback,
+            context: std::ptr::null_mut(),
+        };
+
+        assert!(matches!(
+            transport.get(HttpRequest {
+                url: String::from("https://example.org/filter.txt"),
+                headers: vec![],
+            }),
+            Err(HttpClientError::NetworkError(_))
+        ));
+    }
+}
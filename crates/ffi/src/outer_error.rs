@@ -55,8 +55,17 @@ pub enum AGOuterError {
     FilterContentIsLikelyNotAFilter(String),
 
     /// Filter parser/compiler error
-    #[error("ParserError: {0}")]
-    FilterParserError(String),
+    #[error("ParserError: {message}")]
+    FilterParserError {
+        /// Parser error itself
+        error: FilterParserError,
+        /// Absolute url of the file, where the error has been encountered
+        file: String,
+        /// Line number
+        line: usize,
+        /// Error with its context converted to a string
+        message: String,
+    },
 
     #[error("Field is empty: {0}")]
     FieldIsEmpty(&'static str),
@@ -102,21 +111,38 @@ impl From<FLMError> for AGOuterError {
             FLMError::EntityNotFound(id) => Self::EntityNotFound(id),
             FLMError::FilterIntegrityCheckFailed(id) => Self::FilterIntegrityCheckFailed(id),
             FLMError::Other(msg) => Self::Other(msg),
-            FLMError::Network(variant) => match variant {
-                HttpClientError::NetworkError(err) => Self::HttpClientNetworkError(err),
-                HttpClientError::BodyRecoveryFailed(err) => Self::HttpClientBodyRecoveryFailed(err),
-                HttpClientError::Strict200Response(code, url) => {
-                    Self::HttpStrict200Response(code, url)
+            FLMError::Network(variant) => Self::from(variant),
+            FLMError::ParseFilterError(context) => {
+                let message = context.to_string();
+
+                match context.error {
+                    FilterParserError::FilterContentIsLikelyNotAFilter => {
+                        Self::FilterContentIsLikelyNotAFilter(message)
+                    }
+                    // Network errors are surfaced as is, so clients could decide on retrying
+                    FilterParserError::Network(variant) => Self::from(variant),
+                    error => Self::FilterParserError {
+                        error,
+                        file: context.file,
+                        line: context.line,
+                        message,
+                    },
                 }
-                _ => Self::Other(String::from("Unknown network error")),
-            },
-            FLMError::ParseFilterError(error) => match error.error {
-                FilterParserError::FilterContentIsLikelyNotAFilter => {
-                    Self::FilterContentIsLikelyNotAFilter(error.to_string())
-                }
-                _ => Self::FilterParserError(error.to_string()),
-            },
+            }
             _ => Self::Other(String::from("Unknown error")),
         }
     }
 }
+
+impl From<HttpClientError> for AGOuterError {
+    fn from(value: HttpClientError) -> Self {
+        match value {
+            HttpClientError::NetworkError(err) => Self::HttpClientNetworkError(err),
+            HttpClientError::TimedOut(err) => Self::TimedOut(err),
+            HttpClientError::BodyRecoveryFailed(err) => Self::HttpClientBodyRecoveryFailed(err),
+            HttpClientError::Strict200Response(code, url)
+            | HttpClientError::RetryAfter(code, _, url) => Self::HttpStrict200Response(code, url),
+            _ => Self::Other(String::from("Unknown network error")),
+        }
+    }
+}
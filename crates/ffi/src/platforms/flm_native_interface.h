@@ -45,6 +45,24 @@ typedef enum FFIMethod {
     VerifyIntegrity,
     SignAllData,
     SignAllDataWithNewKey,
+    CancelUpdate,
+    ExportUserState,
+    ImportUserState,
+    SearchRules,
+    GetRuleProvenance,
+    GetFilterUpdateFailures,
+    LintFilter,
+    PreviewFilterUpdate,
+    ListFilterVersions,
+    RollbackFilter,
+    UnpinFilter,
+    SaveDisabledRulePatterns,
+    GetDisabledRulePatterns,
+    SaveActiveRulesToFile,
+    GetActiveRulesDeduplicated,
+    GetActiveRulesRawDeduplicated,
+    ChangeDatabaseEncryptionKey,
+    StopUpdateScheduler,
 } FFIMethod;
 
 /**
@@ -126,6 +144,36 @@ typedef struct FilterListManagerConstants {
     int32_t smallest_filter_id;
 } FilterListManagerConstants;
 
+/**
+ * Callback for update progress events.
+ * `event_buffer` contains protobuf-encoded `UpdateProgressEvent` and is valid only during the call.
+ * `context` is the pointer, passed to [`flm_set_update_progress_callback`]
+ */
+typedef void (*FLMUpdateProgressCallback)(void *context,
+                                          const uint8_t *event_buffer,
+                                          size_t event_buffer_len);
+
+/**
+ * Callback for results of the update scheduler tasks.
+ * `event_buffer` contains protobuf-encoded `UpdateSchedulerEvent` and is valid only during the call.
+ * `context` is the pointer, passed to [`flm_start_update_scheduler`]
+ */
+typedef void (*FLMUpdateSchedulerCallback)(void *context,
+                                           const uint8_t *event_buffer,
+                                           size_t event_buffer_len);
+
+/**
+ * Callback for HTTP GET requests of the library.
+ * `request_buffer` contains protobuf-encoded `HttpRequest` and is valid only during the call.
+ * Callback must perform the request synchronously and pass protobuf-encoded `HttpTransportResult`
+ * to [`flm_http_transport_set_result`] with `result_sink` before returning.
+ * `context` is the pointer, passed to [`flm_set_http_transport_callback`]
+ */
+typedef void (*FLMHttpTransportCallback)(void *context,
+                                         const uint8_t *request_buffer,
+                                         size_t request_buffer_len,
+                                         void *result_sink);
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -177,6 +225,70 @@ void flm_free_handle(struct FLMHandle *handle);
  */
 struct FilterListManagerConstants flm_get_constants(void);
 
+/**
+ * Sets callback for progress events of filters update methods and `pull_metadata`.
+ * Pass `NULL` callback to remove it.
+ * Running update can be cancelled with [`FFIMethod::CancelUpdate`] method.
+ *
+ * # Safety
+ *
+ * 1. `handle.is_null()` is safe and returns error result
+ * 2. `callback` will be called from worker threads, so it and `context` must be thread-safe
+ * 3. `context` must be valid until callback is removed or handle is freed
+ */
+struct RustResponse *flm_set_update_progress_callback(struct FLMHandle *handle,
+                                                      FLMUpdateProgressCallback callback,
+                                                      void *context);
+
+/**
+ * Starts the built-in update scheduler, which calls `pull_metadata` and `update_filters`
+ * on a background thread and passes their results to `callback`.
+ * `settings_buffer` contains protobuf-encoded `UpdateSchedulerSettings`.
+ * Running scheduler is restarted with the new settings.
+ * Scheduler can be stopped with [`FFIMethod::StopUpdateScheduler`] method, and it is stopped on handle free.
+ *
+ * # Safety
+ *
+ * 1. `handle.is_null()` is safe and returns error result
+ * 2. `settings_buffer` must point to `settings_buffer_len` bytes
+ * 3. `callback` will be called from the scheduler thread, so it and `context` must be thread-safe.
+ *    Callback must not wait for the calls of this handle
+ * 4. `context` must be valid until scheduler is stopped or handle is freed
+ */
+struct RustResponse *flm_start_update_scheduler(struct FLMHandle *handle,
+                                                const uint8_t *settings_buffer,
+                                                size_t settings_buffer_len,
+                                                FLMUpdateSchedulerCallback callback,
+                                                void *context);
+
+/**
+ * Sets callback, which performs all HTTP(S) requests of the library instead of the built-in client.
+ * Pass `NULL` callback to use the built-in client again.
+ * Custom transport ignores timeout, proxy mode and user agent settings of the configuration.
+ *
+ * # Safety
+ *
+ * 1. `handle.is_null()` is safe and returns error result
+ * 2. `callback` will be called from worker threads, so it and `context` must be thread-safe
+ * 3. `context` must be valid until callback is removed or handle is freed
+ */
+struct RustResponse *flm_set_http_transport_callback(struct FLMHandle *handle,
+                                                     FLMHttpTransportCallback callback,
+                                                     void *context);
+
+/**
+ * Passes protobuf-encoded `HttpTransportResult` of the request back to the library.
+ * Bytes are copied, so `result_buffer` may be freed right after the call.
+ *
+ * # Safety
+ *
+ * 1. `result_sink` must be the pointer, passed to [`FLMHttpTransportCallback`], and may be used only during its call
+ * 2. `result_buffer` must point to `result_buffer_len` bytes. `NULL` is treated as empty buffer
+ */
+void flm_http_transport_set_result(void *result_sink,
+                                   const uint8_t *result_buffer,
+                                   size_t result_buffer_len);
+
 /**
  * Calls FLM method described as [`FFIMethod`] for object behind [`FLMHandle`]
  *
@@ -4,10 +4,18 @@ use crate::outer_error::AGOuterError;
 use crate::protobuf_generated::filter_list_manager;
 use adguard_flm::manager::models::configuration::FiltersCompilationPolicy;
 use adguard_flm::{
-    ActiveRulesInfo, ActiveRulesInfoRaw, Configuration, DisabledRulesRaw, FilterGroup,
-    FilterListMetadata, FilterListMetadataWithBody, FilterListRules, FilterListRulesRaw,
-    FilterListType, FilterTag, FullFilterList, MovedFilterInfo, PullMetadataResult,
-    RequestProxyMode, RulesCountByFilter, StoredFilterMetadata, UpdateFilterError, UpdateResult,
+    ActiveRulesExportManifest, ActiveRulesInfo, ActiveRulesInfoRaw, Configuration,
+    DisabledRulePattern, DisabledRulePatternKind, DisabledRulePatterns, DisabledRulesRaw,
+    DuplicatesByFilter, ExportedFilterInfo, FailedFilterRegistry, FilterDiagnostic,
+    FilterDiagnosticKind, FilterDiagnosticSeverity, FilterGroup, FilterListMetadata,
+    FilterListMetadataWithBody, FilterListRules, FilterListRulesRaw, FilterListType,
+    FilterParserError, FilterRegistry, FilterTag, FilterTrustLevel, FilterUpdateFailure,
+    FilterUpdateOutcome, FilterUpdatePreview, FilterUpdateReport, FilterVersion, FullFilterList,
+    HttpRequest, HttpResponse, ImportUserStateResult, MovedFilterInfo, PullMetadataResult,
+    RequestProxyMode, RuleProvenance, RuleSearchMatch, RuleSearchOptions, RulesCountByFilter,
+    RulesStorageCompression, StoredFilterMetadata, UpdateFailureKind, UpdateFilterError,
+    UpdateProgressEvent, UpdateProgressStage, UpdateResult, UpdateSchedulerEvent,
+    UpdateSchedulerSettings, UpdateSchedulerTask, UserStateConflict, UserStateConflictKind,
 };
 
 impl From<Vec<String>> for filter_list_manager::FiltersCompilationPolicy {
@@ -64,6 +72,29 @@ impl From<Configuration> for filter_list_manager::Configuration {
             integrity_key: value.integrity_key,
             filter_update_concurrency: value.filter_update_concurrency as i32,
             filter_update_dispatch_delay_ms: value.filter_update_dispatch_delay_ms,
+            filter_update_max_retries: value.filter_update_max_retries,
+            filter_update_retry_delay_ms: value.filter_update_retry_delay_ms,
+            filter_failure_backoff_sec: value.filter_failure_backoff_sec,
+            filter_update_max_concurrency_per_host: value.filter_update_max_concurrency_per_host,
+            filter_update_host_rate_limit_per_min: value.filter_update_host_rate_limit_per_min,
+            filter_update_host_rate_burst: value.filter_update_host_rate_burst,
+            rules_storage_compression: match value.rules_storage_compression {
+                RulesStorageCompression::None => {
+                    filter_list_manager::RulesStorageCompression::None as i32
+                }
+                RulesStorageCompression::Zstd => {
+                    filter_list_manager::RulesStorageCompression::Zstd as i32
+                }
+            },
+            filter_versions_retention: value.filter_versions_retention,
+            database_encryption_key: value.database_encryption_key,
+            metadata_mirror_urls: value.metadata_mirror_urls,
+            metadata_locales_mirror_urls: value.metadata_locales_mirror_urls,
+            filter_registries: value
+                .filter_registries
+                .into_iter()
+                .map(Into::into)
+                .collect(),
         }
     }
 }
@@ -103,6 +134,46 @@ impl From<filter_list_manager::Configuration> for Configuration {
             integrity_key: val.integrity_key,
             filter_update_concurrency: val.filter_update_concurrency as usize,
             filter_update_dispatch_delay_ms: val.filter_update_dispatch_delay_ms,
+            filter_update_max_retries: val.filter_update_max_retries,
+            filter_update_retry_delay_ms: val.filter_update_retry_delay_ms,
+            filter_failure_backoff_sec: val.filter_failure_backoff_sec,
+            filter_update_max_concurrency_per_host: val.filter_update_max_concurrency_per_host,
+            filter_update_host_rate_limit_per_min: val.filter_update_host_rate_limit_per_min,
+            filter_update_host_rate_burst: val.filter_update_host_rate_burst,
+            rules_storage_compression: match val.rules_storage_compression {
+                1 => RulesStorageCompression::Zstd,
+                _ => RulesStorageCompression::None,
+            },
+            filter_versions_retention: val.filter_versions_retention,
+            http_transport: None,
+            database_encryption_key: val.database_encryption_key,
+            metadata_mirror_urls: val.metadata_mirror_urls,
+            metadata_locales_mirror_urls: val.metadata_locales_mirror_urls,
+            filter_registries: val.filter_registries.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<FilterRegistry> for filter_list_manager::FilterRegistry {
+    fn from(value: FilterRegistry) -> Self {
+        Self {
+            name: value.name,
+            metadata_url: value.metadata_url,
+            metadata_locales_url: value.metadata_locales_url,
+            metadata_mirror_urls: value.metadata_mirror_urls,
+            metadata_locales_mirror_urls: value.metadata_locales_mirror_urls,
+        }
+    }
+}
+
+impl From<filter_list_manager::FilterRegistry> for FilterRegistry {
+    fn from(value: filter_list_manager::FilterRegistry) -> Self {
+        Self {
+            name: value.name,
+            metadata_url: value.metadata_url,
+            metadata_locales_url: value.metadata_locales_url,
+            metadata_mirror_urls: value.metadata_mirror_urls,
+            metadata_locales_mirror_urls: value.metadata_locales_mirror_urls,
         }
     }
 }
@@ -193,9 +264,13 @@ impl From<AGOuterError> for filter_list_manager::AgOuterError {
                 )),
                 message,
             },
-            AGOuterError::FilterParserError(message) => Self {
+            AGOuterError::FilterParserError { error, file, line, .. } => Self {
                 error: Some(crate::protobuf_generated::filter_list_manager::ag_outer_error::Error::FilterParserError(
-                    filter_list_manager::FilterParserError {},
+                    filter_list_manager::FilterParserError {
+                        kind: filter_list_manager::FilterParserErrorKind::from(error).into(),
+                        file,
+                        line: line as u32,
+                    },
                 )),
                 message,
             },
@@ -233,6 +308,27 @@ impl From<AGOuterError> for filter_list_manager::AgOuterError {
     }
 }
 
+impl From<FilterParserError> for filter_list_manager::FilterParserErrorKind {
+    fn from(value: FilterParserError) -> Self {
+        match value {
+            FilterParserError::Io(_) => Self::Io,
+            FilterParserError::EmptyIf => Self::EmptyIf,
+            FilterParserError::UnbalancedElse => Self::UnbalancedElse,
+            FilterParserError::UnbalancedEndIf => Self::UnbalancedEndIf,
+            FilterParserError::UnbalancedIf => Self::UnbalancedIf,
+            FilterParserError::RecursiveInclusion => Self::RecursiveInclusion,
+            FilterParserError::StackIsCorrupted => Self::StackIsCorrupted,
+            FilterParserError::SchemeIsIncorrect(_) => Self::SchemeIsIncorrect,
+            FilterParserError::InvalidBooleanExpression => Self::InvalidBooleanExpression,
+            FilterParserError::InvalidChecksum(_, _) => Self::InvalidChecksum,
+            FilterParserError::NoContent => Self::NoContent,
+            FilterParserError::NotModified => Self::NotModified,
+            FilterParserError::InvalidDiffPatch(_) => Self::InvalidDiffPatch,
+            _ => Self::Other,
+        }
+    }
+}
+
 impl From<FullFilterList> for filter_list_manager::FullFilterList {
     fn from(value: FullFilterList) -> Self {
         Self {
@@ -284,6 +380,92 @@ impl From<UpdateResult> for filter_list_manager::UpdateResult {
                 .into_iter()
                 .map(|error| error.into())
                 .collect(),
+            filters_reports: value
+                .filters_reports
+                .into_iter()
+                .map(|report| report.into())
+                .collect(),
+        }
+    }
+}
+
+impl From<UpdateFailureKind> for filter_list_manager::UpdateFailureKind {
+    fn from(value: UpdateFailureKind) -> Self {
+        match value {
+            UpdateFailureKind::Network => Self::Network,
+            UpdateFailureKind::Io => Self::Io,
+            UpdateFailureKind::InvalidChecksum => Self::InvalidChecksum,
+            UpdateFailureKind::InvalidContent => Self::InvalidContent,
+            UpdateFailureKind::Parser => Self::Parser,
+            UpdateFailureKind::Other => Self::Other,
+        }
+    }
+}
+
+impl From<FilterUpdateReport> for filter_list_manager::FilterUpdateReport {
+    fn from(value: FilterUpdateReport) -> Self {
+        let (outcome, failure_kind) = match value.outcome {
+            FilterUpdateOutcome::Updated => {
+                (filter_list_manager::FilterUpdateOutcome::Updated, None)
+            }
+            FilterUpdateOutcome::UpdatedViaDiff => (
+                filter_list_manager::FilterUpdateOutcome::UpdatedViaDiff,
+                None,
+            ),
+            FilterUpdateOutcome::NotExpired => {
+                (filter_list_manager::FilterUpdateOutcome::NotExpired, None)
+            }
+            FilterUpdateOutcome::SameVersion => {
+                (filter_list_manager::FilterUpdateOutcome::SameVersion, None)
+            }
+            FilterUpdateOutcome::SameContent => {
+                (filter_list_manager::FilterUpdateOutcome::SameContent, None)
+            }
+            FilterUpdateOutcome::Disabled => {
+                (filter_list_manager::FilterUpdateOutcome::Disabled, None)
+            }
+            FilterUpdateOutcome::NoDownloadUrl => (
+                filter_list_manager::FilterUpdateOutcome::NoDownloadUrl,
+                None,
+            ),
+            FilterUpdateOutcome::NotProcessed => {
+                (filter_list_manager::FilterUpdateOutcome::NotProcessed, None)
+            }
+            FilterUpdateOutcome::Failed { kind } => (
+                filter_list_manager::FilterUpdateOutcome::Failed,
+                Some(filter_list_manager::UpdateFailureKind::from(kind)),
+            ),
+            FilterUpdateOutcome::Postponed => {
+                (filter_list_manager::FilterUpdateOutcome::Postponed, None)
+            }
+            FilterUpdateOutcome::Pinned => (filter_list_manager::FilterUpdateOutcome::Pinned, None),
+            FilterUpdateOutcome::PatchNotReady => (
+                filter_list_manager::FilterUpdateOutcome::PatchNotReady,
+                None,
+            ),
+        };
+
+        Self {
+            filter_id: value.filter_id,
+            outcome: outcome.into(),
+            failure_kind: failure_kind.map(Into::into),
+            downloaded_bytes: value.downloaded_bytes,
+            http_status: value.http_status.map(Into::into),
+            compile_duration_ms: value.compile_duration_ms,
+            served_url: value.served_url,
+        }
+    }
+}
+
+impl From<FilterUpdateFailure> for filter_list_manager::FilterUpdateFailure {
+    fn from(value: FilterUpdateFailure) -> Self {
+        Self {
+            filter_id: value.filter_id,
+            consecutive_failures: value.consecutive_failures,
+            first_failure_time: value.first_failure_time,
+            last_failure_time: value.last_failure_time,
+            last_error: value.last_error,
+            next_retry_time: value.next_retry_time,
         }
     }
 }
@@ -295,6 +477,157 @@ impl From<UpdateFilterError> for filter_list_manager::UpdateFilterError {
             message: value.message,
             filter_url: value.filter_url,
             http_client_error: value.http_client_error,
+            error: value.error.map(|error| AGOuterError::from(error).into()),
+            http_status: value.http_status.map(Into::into),
+        }
+    }
+}
+
+impl From<UpdateProgressEvent> for filter_list_manager::UpdateProgressEvent {
+    fn from(value: UpdateProgressEvent) -> Self {
+        let stage = match value.stage {
+            UpdateProgressStage::Queued => filter_list_manager::UpdateProgressStage::Queued,
+            UpdateProgressStage::Downloading => {
+                filter_list_manager::UpdateProgressStage::Downloading
+            }
+            UpdateProgressStage::DiffPatched => {
+                filter_list_manager::UpdateProgressStage::DiffPatched
+            }
+            UpdateProgressStage::Compiled => filter_list_manager::UpdateProgressStage::Compiled,
+            UpdateProgressStage::Saved => filter_list_manager::UpdateProgressStage::Saved,
+            UpdateProgressStage::NotChanged => filter_list_manager::UpdateProgressStage::NotChanged,
+            UpdateProgressStage::Failed => filter_list_manager::UpdateProgressStage::Failed,
+        };
+
+        Self {
+            filter_id: value.filter_id,
+            url: value.url,
+            stage: stage.into(),
+            message: value.message,
+        }
+    }
+}
+
+impl From<filter_list_manager::UpdateSchedulerSettings> for UpdateSchedulerSettings {
+    fn from(value: filter_list_manager::UpdateSchedulerSettings) -> Self {
+        Self {
+            update_interval_sec: value.update_interval_sec,
+            pull_metadata_interval_sec: value.pull_metadata_interval_sec,
+            offline_backoff_min_sec: value.offline_backoff_min_sec,
+            offline_backoff_max_sec: value.offline_backoff_max_sec,
+            loose_timeout: value.loose_timeout,
+        }
+    }
+}
+
+impl From<UpdateSchedulerEvent> for filter_list_manager::UpdateSchedulerEvent {
+    fn from(value: UpdateSchedulerEvent) -> Self {
+        let task = match value.task {
+            UpdateSchedulerTask::UpdateFilters => {
+                filter_list_manager::UpdateSchedulerTask::UpdateFilters
+            }
+            UpdateSchedulerTask::PullMetadata => {
+                filter_list_manager::UpdateSchedulerTask::PullMetadata
+            }
+            UpdateSchedulerTask::Restart => filter_list_manager::UpdateSchedulerTask::Restart,
+        };
+
+        Self {
+            task: task.into(),
+            update_result: value.update_result.map(Into::into),
+            pull_metadata_result: value.pull_metadata_result.map(Into::into),
+            error: value.error.map(|why| AGOuterError::from(why).into()),
+            is_offline: value.is_offline,
+            next_run_time: value.next_run_time,
+        }
+    }
+}
+
+impl From<FilterVersion> for filter_list_manager::FilterVersion {
+    fn from(value: FilterVersion) -> Self {
+        Self {
+            version_id: value.version_id,
+            filter_id: value.filter_id,
+            version: value.version,
+            title: value.title,
+            last_update_time: value.last_update_time,
+            saved_time: value.saved_time,
+            rules_count: value.rules_count,
+        }
+    }
+}
+
+impl From<FilterUpdatePreview> for filter_list_manager::FilterUpdatePreview {
+    fn from(value: FilterUpdatePreview) -> Self {
+        Self {
+            filter_id: value.filter_id,
+            is_diff_update: value.is_diff_update,
+            added_rules: value.added_rules,
+            removed_rules: value.removed_rules,
+            old_version: value.old_version,
+            new_version: value.new_version,
+            old_title: value.old_title,
+            new_title: value.new_title,
+            old_expires: value.old_expires,
+            new_expires: value.new_expires,
+            rules_count_delta: value.rules_count_delta,
+            is_patch_not_ready: value.is_patch_not_ready,
+            served_url: value.served_url,
+        }
+    }
+}
+
+impl From<FilterDiagnostic> for filter_list_manager::FilterDiagnostic {
+    fn from(value: FilterDiagnostic) -> Self {
+        let severity = match value.severity {
+            FilterDiagnosticSeverity::Error => filter_list_manager::FilterDiagnosticSeverity::Error,
+            FilterDiagnosticSeverity::Warning => {
+                filter_list_manager::FilterDiagnosticSeverity::Warning
+            }
+        };
+
+        let kind = match value.kind {
+            FilterDiagnosticKind::EmptyIf => filter_list_manager::FilterDiagnosticKind::EmptyIf,
+            FilterDiagnosticKind::UnbalancedElse => {
+                filter_list_manager::FilterDiagnosticKind::UnbalancedElse
+            }
+            FilterDiagnosticKind::UnbalancedEndIf => {
+                filter_list_manager::FilterDiagnosticKind::UnbalancedEndIf
+            }
+            FilterDiagnosticKind::UnbalancedIf => {
+                filter_list_manager::FilterDiagnosticKind::UnbalancedIf
+            }
+            FilterDiagnosticKind::InvalidBooleanExpression => {
+                filter_list_manager::FilterDiagnosticKind::InvalidBooleanExpression
+            }
+            FilterDiagnosticKind::UnknownConstant => {
+                filter_list_manager::FilterDiagnosticKind::UnknownConstant
+            }
+            FilterDiagnosticKind::InvalidInclude => {
+                filter_list_manager::FilterDiagnosticKind::InvalidInclude
+            }
+            FilterDiagnosticKind::CrossOriginInclude => {
+                filter_list_manager::FilterDiagnosticKind::CrossOriginInclude
+            }
+            FilterDiagnosticKind::RecursiveInclude => {
+                filter_list_manager::FilterDiagnosticKind::RecursiveInclude
+            }
+            FilterDiagnosticKind::MalformedExpires => {
+                filter_list_manager::FilterDiagnosticKind::MalformedExpires
+            }
+            FilterDiagnosticKind::MalformedTimeUpdated => {
+                filter_list_manager::FilterDiagnosticKind::MalformedTimeUpdated
+            }
+            FilterDiagnosticKind::ChecksumMismatch => {
+                filter_list_manager::FilterDiagnosticKind::ChecksumMismatch
+            }
+        };
+
+        Self {
+            line_number: value.line_number,
+            severity: severity.into(),
+            kind: kind.into(),
+            message: value.message,
         }
     }
 }
@@ -328,6 +661,19 @@ impl From<ActiveRulesInfo> for filter_list_manager::ActiveRulesInfo {
             group_id: value.group_id,
             is_trusted: value.is_trusted,
             rules: value.rules,
+            trust_level: value
+                .trust_level
+                .map(|level| filter_list_manager::FilterTrustLevel::from(level) as i32),
+        }
+    }
+}
+
+impl From<FilterTrustLevel> for filter_list_manager::FilterTrustLevel {
+    fn from(value: FilterTrustLevel) -> Self {
+        match value {
+            FilterTrustLevel::Full => filter_list_manager::FilterTrustLevel::Full,
+            FilterTrustLevel::High => filter_list_manager::FilterTrustLevel::High,
+            FilterTrustLevel::Low => filter_list_manager::FilterTrustLevel::Low,
         }
     }
 }
@@ -392,6 +738,10 @@ impl From<StoredFilterMetadata> for filter_list_manager::StoredFilterMetadata {
             license: value.license,
             checksum: value.checksum,
             languages: value.languages,
+            registry: value.registry,
+            trust_level: value
+                .trust_level
+                .map(|level| filter_list_manager::FilterTrustLevel::from(level) as i32),
         }
     }
 }
@@ -440,6 +790,93 @@ impl From<PullMetadataResult> for filter_list_manager::PullMetadataResult {
             added_filters: value.added_filters,
             removed_filters: value.removed_filters,
             moved_filters: value.moved_filters.into_iter().map(Into::into).collect(),
+            failed_registries: value
+                .failed_registries
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+impl From<FailedFilterRegistry> for filter_list_manager::FailedFilterRegistry {
+    fn from(value: FailedFilterRegistry) -> Self {
+        Self {
+            name: value.name,
+            message: value.message,
+        }
+    }
+}
+
+impl From<UserStateConflict> for filter_list_manager::UserStateConflict {
+    fn from(value: UserStateConflict) -> Self {
+        let kind = match value.kind {
+            UserStateConflictKind::FilterNotFound => {
+                filter_list_manager::UserStateConflictKind::FilterNotFound
+            }
+            UserStateConflictKind::RulesListNotFound => {
+                filter_list_manager::UserStateConflictKind::RulesListNotFound
+            }
+            UserStateConflictKind::CustomFilterAlreadyInstalled => {
+                filter_list_manager::UserStateConflictKind::CustomFilterAlreadyInstalled
+            }
+            UserStateConflictKind::CustomFilterIdCollision => {
+                filter_list_manager::UserStateConflictKind::CustomFilterIdCollision
+            }
+        };
+
+        Self {
+            filter_id: value.filter_id,
+            kind: kind.into(),
+            message: value.message,
+        }
+    }
+}
+
+impl From<ImportUserStateResult> for filter_list_manager::ImportUserStateResult {
+    fn from(value: ImportUserStateResult) -> Self {
+        Self {
+            restored_filters: value.restored_filters,
+            installed_custom_filters: value
+                .installed_custom_filters
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            conflicts: value.conflicts.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<filter_list_manager::RuleSearchOptions> for RuleSearchOptions {
+    fn from(value: filter_list_manager::RuleSearchOptions) -> Self {
+        Self {
+            is_regex: value.is_regex,
+            is_case_sensitive: value.is_case_sensitive,
+            include_disabled_rules: value.include_disabled_rules,
+            limit: value.limit,
+        }
+    }
+}
+
+impl From<RuleSearchMatch> for filter_list_manager::RuleSearchMatch {
+    fn from(value: RuleSearchMatch) -> Self {
+        Self {
+            filter_id: value.filter_id,
+            include_url: value.include_url,
+            line_number: value.line_number,
+            rule: value.rule,
+            is_disabled: value.is_disabled,
+        }
+    }
+}
+
+impl From<RuleProvenance> for filter_list_manager::RuleProvenance {
+    fn from(value: RuleProvenance) -> Self {
+        Self {
+            filter_id: value.filter_id,
+            include_url: value.include_url,
+            line_number: value.line_number,
+            is_disabled: value.is_disabled,
         }
     }
 }
@@ -460,6 +897,73 @@ impl From<filter_list_manager::FiltersCompilationPolicy> for FiltersCompilationP
     }
 }
 
+impl From<DisabledRulePattern> for filter_list_manager::DisabledRulePattern {
+    fn from(value: DisabledRulePattern) -> Self {
+        let kind = match value.kind {
+            DisabledRulePatternKind::Wildcard => {
+                filter_list_manager::DisabledRulePatternKind::Wildcard
+            }
+            DisabledRulePatternKind::Regex => filter_list_manager::DisabledRulePatternKind::Regex,
+        };
+
+        Self {
+            kind: kind.into(),
+            pattern: value.pattern,
+        }
+    }
+}
+
+impl From<filter_list_manager::DisabledRulePattern> for DisabledRulePattern {
+    fn from(value: filter_list_manager::DisabledRulePattern) -> Self {
+        Self {
+            kind: match value.kind {
+                1 => DisabledRulePatternKind::Regex,
+                _ => DisabledRulePatternKind::Wildcard,
+            },
+            pattern: value.pattern,
+        }
+    }
+}
+
+impl From<DisabledRulePatterns> for filter_list_manager::DisabledRulePatterns {
+    fn from(value: DisabledRulePatterns) -> Self {
+        Self {
+            filter_id: value.filter_id,
+            patterns: value.patterns.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ExportedFilterInfo> for filter_list_manager::ExportedFilterInfo {
+    fn from(value: ExportedFilterInfo) -> Self {
+        Self {
+            filter_id: value.filter_id,
+            is_trusted: value.is_trusted,
+            byte_offset: value.byte_offset,
+            byte_length: value.byte_length,
+            rules_count: value.rules_count,
+            duplicates_count: value.duplicates_count,
+        }
+    }
+}
+
+impl From<ActiveRulesExportManifest> for filter_list_manager::ActiveRulesExportManifest {
+    fn from(value: ActiveRulesExportManifest) -> Self {
+        Self {
+            filters: value.filters.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<DuplicatesByFilter> for filter_list_manager::DuplicatesByFilter {
+    fn from(value: DuplicatesByFilter) -> Self {
+        Self {
+            filter_id: value.filter_id,
+            duplicates_count: value.duplicates_count,
+        }
+    }
+}
+
 impl From<ActiveRulesInfoRaw> for filter_list_manager::ActiveRulesInfoRaw {
     fn from(value: ActiveRulesInfoRaw) -> Self {
         Self {
@@ -467,6 +971,98 @@ impl From<ActiveRulesInfoRaw> for filter_list_manager::ActiveRulesInfoRaw {
             group_id: value.group_id,
             is_trusted: value.is_trusted,
             rules: value.rules,
+            trust_level: value
+                .trust_level
+                .map(|level| filter_list_manager::FilterTrustLevel::from(level) as i32),
         }
     }
 }
+
+impl From<HttpRequest> for filter_list_manager::HttpRequest {
+    fn from(value: HttpRequest) -> Self {
+        Self {
+            url: value.url,
+            headers: value
+                .headers
+                .into_iter()
+                .map(|(name, value)| filter_list_manager::HttpHeader { name, value })
+                .collect(),
+        }
+    }
+}
+
+impl From<filter_list_manager::HttpResponse> for HttpResponse {
+    fn from(value: filter_list_manager::HttpResponse) -> Self {
+        Self {
+            status: value.status as u16,
+            headers: value
+                .headers
+                .into_iter()
+                .map(|header| (header.name, header.value))
+                .collect(),
+            body: value.body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protobuf_generated::filter_list_manager;
+    use crate::protobuf_generated::filter_list_manager::ag_outer_error;
+    use adguard_flm::{
+        FLMError, FilterParserError, FilterParserErrorContext, HttpClientError, UpdateFilterError,
+    };
+
+    fn make_update_filter_error(
+        error: FilterParserError,
+    ) -> filter_list_manager::UpdateFilterError {
+        UpdateFilterError {
+            filter_id: 42,
+            message: String::from("Filter update failed"),
+            filter_url: Some(String::from("https://example.org/filter.txt")),
+            http_client_error: None,
+            error: Some(FLMError::ParseFilterError(FilterParserErrorContext {
+                file: String::from("https://example.org/filter.txt"),
+                line: 3,
+                error,
+            })),
+            http_status: Some(200),
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_update_filter_error_cast() {
+        let error = make_update_filter_error(FilterParserError::InvalidDiffPatch(String::from(
+            "Wrong diff",
+        )));
+
+        assert_eq!(error.filter_id, 42);
+        assert_eq!(error.http_status, Some(200));
+
+        let outer_error = error.error.unwrap();
+        assert!(outer_error.message.contains("Wrong diff"));
+        assert_eq!(
+            outer_error.error,
+            Some(ag_outer_error::Error::FilterParserError(
+                filter_list_manager::FilterParserError {
+                    kind: filter_list_manager::FilterParserErrorKind::InvalidDiffPatch.into(),
+                    file: String::from("https://example.org/filter.txt"),
+                    line: 3,
+                }
+            ))
+        );
+
+        // Network errors are surfaced as is, so clients could decide on retrying
+        let error = make_update_filter_error(FilterParserError::Network(
+            HttpClientError::TimedOut(String::from("operation timed out")),
+        ));
+
+        assert_eq!(
+            error.error.unwrap().error,
+            Some(ag_outer_error::Error::TimedOut(
+                filter_list_manager::TimedOut {}
+            ))
+        );
+    }
+}
@@ -77,6 +77,84 @@ pub struct Configuration {
     /// Default value: 0 (no throttling).
     #[prost(int32, tag = "17")]
     pub filter_update_dispatch_delay_ms: i32,
+    /// Number of extra download attempts for a filter, if its update has failed
+    /// due to a transient error: network failure, timeout, 5xx, 408 or 429 status code.
+    /// Default value: 2. Values <= 0 disable retries.
+    #[prost(int32, tag = "18")]
+    pub filter_update_max_retries: i32,
+    /// Delay in milliseconds before the first retry. Doubles after every attempt,
+    /// but won't exceed 30 seconds.
+    /// Default value: 1000.
+    #[prost(int32, tag = "19")]
+    pub filter_update_retry_delay_ms: i32,
+    /// Delay in seconds, during which the filter, which update has failed,
+    /// will be skipped by regular updates. Doubles after every consecutive failure,
+    /// but won't exceed one day. Forced updates ignore this delay.
+    /// Default value: 3600. Values <= 0 disable skipping.
+    #[prost(int32, tag = "20")]
+    pub filter_failure_backoff_sec: i32,
+    /// Maximum number of concurrent filter downloads from the same host.
+    /// Default value: 0. Values <= 0 disable the limit.
+    #[prost(int32, tag = "21")]
+    pub filter_update_max_concurrency_per_host: i32,
+    /// Maximum number of filter download dispatches per minute for the same host.
+    /// Limit is applied with token bucket, see filter_update_host_rate_burst.
+    /// Default value: 0. Values <= 0 disable the limit.
+    #[prost(int32, tag = "22")]
+    pub filter_update_host_rate_limit_per_min: i32,
+    /// Number of dispatches to the same host, which may be done at once,
+    /// before filter_update_host_rate_limit_per_min starts to apply.
+    /// Default value: 1. Values < 1 will be treated as 1.
+    #[prost(int32, tag = "23")]
+    pub filter_update_host_rate_burst: i32,
+    /// Compression of rules and includes, which will be saved by subsequent
+    /// installations and updates. Already saved rules keep their compression.
+    /// Integrity signatures and hashes are always computed over uncompressed text.
+    /// Default value: RULES_STORAGE_COMPRESSION_NONE.
+    #[prost(enumeration = "RulesStorageCompression", tag = "24")]
+    pub rules_storage_compression: i32,
+    /// Number of previous versions of contents, which are kept for every filter after updates.
+    /// Filters may be rolled back to them.
+    /// Default value: 0. Values <= 0 disable keeping versions.
+    #[prost(int32, tag = "25")]
+    pub filter_versions_retention: i32,
+    /// Key for the database encryption at rest with SQLCipher.
+    /// Requires the library to be built with `sqlcipher` feature.
+    /// If not set, database is stored as plaintext.
+    /// Existing plaintext database will be encrypted during `lift_up_database`.
+    #[prost(string, optional, tag = "26")]
+    pub database_encryption_key: ::core::option::Option<::prost::alloc::string::String>,
+    /// Mirrors of `metadata_url`.
+    /// They are requested in order, if the previous URL fails with a network error.
+    #[prost(string, repeated, tag = "27")]
+    pub metadata_mirror_urls: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Mirrors of `metadata_locales_url`. Ignored if `metadata_locales_url` is empty.
+    #[prost(string, repeated, tag = "28")]
+    pub metadata_locales_mirror_urls: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Additional filter registries, synced by `pull_metadata` along with the main one.
+    /// Filter ids of every registry are stored in its own range of 1_000_000 ids.
+    /// Names must be non-empty, unique and must not be `main`.
+    #[prost(message, repeated, tag = "29")]
+    pub filter_registries: ::prost::alloc::vec::Vec<FilterRegistry>,
+}
+/// Named filter registry with its own index URLs
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterRegistry {
+    /// Unique name of the registry
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// URL of the filters index of the registry
+    #[prost(string, tag = "2")]
+    pub metadata_url: ::prost::alloc::string::String,
+    /// URL of the filters index localisations of the registry. May be empty
+    #[prost(string, tag = "3")]
+    pub metadata_locales_url: ::prost::alloc::string::String,
+    /// Mirrors of `metadata_url`
+    #[prost(string, repeated, tag = "4")]
+    pub metadata_mirror_urls: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Mirrors of `metadata_locales_url`
+    #[prost(string, repeated, tag = "5")]
+    pub metadata_locales_mirror_urls: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -140,6 +218,35 @@ impl RawRequestProxyMode {
         }
     }
 }
+/// Compression of stored filter rules and includes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RulesStorageCompression {
+    /// Rules are stored as plain text
+    None = 0,
+    /// Rules are compressed with zstd
+    Zstd = 1,
+}
+impl RulesStorageCompression {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::None => "RULES_STORAGE_COMPRESSION_NONE",
+            Self::Zstd => "RULES_STORAGE_COMPRESSION_ZSTD",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RULES_STORAGE_COMPRESSION_NONE" => Some(Self::None),
+            "RULES_STORAGE_COMPRESSION_ZSTD" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AgOuterError {
     #[prost(string, tag = "1")]
@@ -242,8 +349,16 @@ pub struct HttpClientBodyRecoveryFailed {}
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct FilterContentIsLikelyNotAFilter {}
 /// Do not duplicate the message
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct FilterParserError {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterParserError {
+    #[prost(enumeration = "FilterParserErrorKind", tag = "1")]
+    pub kind: i32,
+    /// Absolute url of the file, where the error has been encountered
+    #[prost(string, tag = "2")]
+    pub file: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "3")]
+    pub line: u32,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FieldIsEmpty {
     #[prost(string, tag = "1")]
@@ -266,6 +381,71 @@ pub struct FilterIntegrityCheckFailed {
     #[prost(int32, tag = "1")]
     pub filter_id: i32,
 }
+/// Kind of the filter parser error
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FilterParserErrorKind {
+    Other = 0,
+    Io = 1,
+    EmptyIf = 2,
+    UnbalancedElse = 3,
+    UnbalancedEndIf = 4,
+    UnbalancedIf = 5,
+    RecursiveInclusion = 6,
+    StackIsCorrupted = 7,
+    SchemeIsIncorrect = 8,
+    InvalidBooleanExpression = 9,
+    /// Filter checksum does not match the calculated one
+    InvalidChecksum = 10,
+    NoContent = 11,
+    NotModified = 12,
+    /// Differential update patch is malformed or cannot be applied
+    InvalidDiffPatch = 13,
+}
+impl FilterParserErrorKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Other => "FILTER_PARSER_ERROR_KIND_OTHER",
+            Self::Io => "FILTER_PARSER_ERROR_KIND_IO",
+            Self::EmptyIf => "FILTER_PARSER_ERROR_KIND_EMPTY_IF",
+            Self::UnbalancedElse => "FILTER_PARSER_ERROR_KIND_UNBALANCED_ELSE",
+            Self::UnbalancedEndIf => "FILTER_PARSER_ERROR_KIND_UNBALANCED_END_IF",
+            Self::UnbalancedIf => "FILTER_PARSER_ERROR_KIND_UNBALANCED_IF",
+            Self::RecursiveInclusion => "FILTER_PARSER_ERROR_KIND_RECURSIVE_INCLUSION",
+            Self::StackIsCorrupted => "FILTER_PARSER_ERROR_KIND_STACK_IS_CORRUPTED",
+            Self::SchemeIsIncorrect => "FILTER_PARSER_ERROR_KIND_SCHEME_IS_INCORRECT",
+            Self::InvalidBooleanExpression => "FILTER_PARSER_ERROR_KIND_INVALID_BOOLEAN_EXPRESSION",
+            Self::InvalidChecksum => "FILTER_PARSER_ERROR_KIND_INVALID_CHECKSUM",
+            Self::NoContent => "FILTER_PARSER_ERROR_KIND_NO_CONTENT",
+            Self::NotModified => "FILTER_PARSER_ERROR_KIND_NOT_MODIFIED",
+            Self::InvalidDiffPatch => "FILTER_PARSER_ERROR_KIND_INVALID_DIFF_PATCH",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FILTER_PARSER_ERROR_KIND_OTHER" => Some(Self::Other),
+            "FILTER_PARSER_ERROR_KIND_IO" => Some(Self::Io),
+            "FILTER_PARSER_ERROR_KIND_EMPTY_IF" => Some(Self::EmptyIf),
+            "FILTER_PARSER_ERROR_KIND_UNBALANCED_ELSE" => Some(Self::UnbalancedElse),
+            "FILTER_PARSER_ERROR_KIND_UNBALANCED_END_IF" => Some(Self::UnbalancedEndIf),
+            "FILTER_PARSER_ERROR_KIND_UNBALANCED_IF" => Some(Self::UnbalancedIf),
+            "FILTER_PARSER_ERROR_KIND_RECURSIVE_INCLUSION" => Some(Self::RecursiveInclusion),
+            "FILTER_PARSER_ERROR_KIND_STACK_IS_CORRUPTED" => Some(Self::StackIsCorrupted),
+            "FILTER_PARSER_ERROR_KIND_SCHEME_IS_INCORRECT" => Some(Self::SchemeIsIncorrect),
+            "FILTER_PARSER_ERROR_KIND_INVALID_BOOLEAN_EXPRESSION" => Some(Self::InvalidBooleanExpression),
+            "FILTER_PARSER_ERROR_KIND_INVALID_CHECKSUM" => Some(Self::InvalidChecksum),
+            "FILTER_PARSER_ERROR_KIND_NO_CONTENT" => Some(Self::NoContent),
+            "FILTER_PARSER_ERROR_KIND_NOT_MODIFIED" => Some(Self::NotModified),
+            "FILTER_PARSER_ERROR_KIND_INVALID_DIFF_PATCH" => Some(Self::InvalidDiffPatch),
+            _ => None,
+        }
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FilterTag {
     /// Filter tag id.
@@ -429,6 +609,14 @@ pub struct StoredFilterMetadata {
     /// List of languages the filter supports
     #[prost(string, repeated, tag = "20")]
     pub languages: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Name of the filter registry this filter list came from.
+    /// Not set for custom and service filter lists
+    #[prost(string, optional, tag = "21")]
+    pub registry: ::core::option::Option<::prost::alloc::string::String>,
+    /// Trust level of the filter list, assigned by the registry.
+    /// Not set for custom filter lists
+    #[prost(enumeration = "FilterTrustLevel", optional, tag = "22")]
+    pub trust_level: ::core::option::Option<i32>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FullFilterList {
@@ -506,6 +694,39 @@ pub struct RulesCountByFilter {
     #[prost(int32, tag = "2")]
     pub rules_count: i32,
 }
+/// Trust level of the filter list, assigned by the registry
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FilterTrustLevel {
+    /// First-party list. Only such lists are considered trusted
+    Full = 0,
+    /// Well-known third-party list
+    High = 1,
+    /// Community list
+    Low = 2,
+}
+impl FilterTrustLevel {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Full => "FILTER_TRUST_LEVEL_FULL",
+            Self::High => "FILTER_TRUST_LEVEL_HIGH",
+            Self::Low => "FILTER_TRUST_LEVEL_LOW",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FILTER_TRUST_LEVEL_FULL" => Some(Self::Full),
+            "FILTER_TRUST_LEVEL_HIGH" => Some(Self::High),
+            "FILTER_TRUST_LEVEL_LOW" => Some(Self::Low),
+            _ => None,
+        }
+    }
+}
 /// ActiveRulesInfo
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ActiveRulesInfo {
@@ -521,6 +742,9 @@ pub struct ActiveRulesInfo {
     /// List of active rules.
     #[prost(string, repeated, tag = "4")]
     pub rules: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Trust level from the registry. Not set for custom filters
+    #[prost(enumeration = "FilterTrustLevel", optional, tag = "5")]
+    pub trust_level: ::core::option::Option<i32>,
 }
 /// ActiveRulesInfo raw
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -537,6 +761,9 @@ pub struct ActiveRulesInfoRaw {
     /// List of active rules as string.
     #[prost(string, tag = "4")]
     pub rules: ::prost::alloc::string::String,
+    /// Trust level from the registry. Not set for custom filters
+    #[prost(enumeration = "FilterTrustLevel", optional, tag = "5")]
+    pub trust_level: ::core::option::Option<i32>,
 }
 /// UpdateResult
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -550,6 +777,9 @@ pub struct UpdateResult {
     /// List of entities containing the filter id and a string representation of the error
     #[prost(message, repeated, tag = "3")]
     pub filters_errors: ::prost::alloc::vec::Vec<UpdateFilterError>,
+    /// Outcome of every filter passed to the update, including skipped ones
+    #[prost(message, repeated, tag = "4")]
+    pub filters_reports: ::prost::alloc::vec::Vec<FilterUpdateReport>,
 }
 /// UpdateFilterError
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -566,6 +796,38 @@ pub struct UpdateFilterError {
     /// Http client error
     #[prost(string, optional, tag = "4")]
     pub http_client_error: ::core::option::Option<::prost::alloc::string::String>,
+    /// Structured cause of the failure
+    #[prost(message, optional, tag = "5")]
+    pub error: ::core::option::Option<AgOuterError>,
+    /// Status code of the failed filter response, if there was one
+    #[prost(uint32, optional, tag = "6")]
+    pub http_status: ::core::option::Option<u32>,
+}
+/// Per-filter update report
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterUpdateReport {
+    /// ID of the filter
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Update outcome
+    #[prost(enumeration = "FilterUpdateOutcome", tag = "2")]
+    pub outcome: i32,
+    /// Failure category. Set only for FAILED outcome
+    #[prost(enumeration = "UpdateFailureKind", optional, tag = "3")]
+    pub failure_kind: ::core::option::Option<i32>,
+    /// Number of bytes downloaded for the filter and its includes
+    #[prost(uint64, tag = "4")]
+    pub downloaded_bytes: u64,
+    /// Status code of the filter response, if it has been requested via http(s)
+    #[prost(uint32, optional, tag = "5")]
+    pub http_status: ::core::option::Option<u32>,
+    /// Time spent on downloading and compiling the filter, in milliseconds
+    #[prost(uint64, tag = "6")]
+    pub compile_duration_ms: u64,
+    /// URL, which has served the filter: its download URL or one of its mirrors from the index.
+    /// Not set if the filter hasn't been requested or all URLs failed with network errors
+    #[prost(string, optional, tag = "7")]
+    pub served_url: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Information about filter movement during index metadata update
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -589,6 +851,737 @@ pub struct PullMetadataResult {
     /// List of filters moved in the update
     #[prost(message, repeated, tag = "3")]
     pub moved_filters: ::prost::alloc::vec::Vec<MovedFilterInfo>,
+    /// Additional filter registries, which could not be synced
+    #[prost(message, repeated, tag = "4")]
+    pub failed_registries: ::prost::alloc::vec::Vec<FailedFilterRegistry>,
+}
+/// Additional filter registry, which could not be synced during index metadata update
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FailedFilterRegistry {
+    /// Name of the registry
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Description of the error
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Progress event of update_filters/pull_metadata methods
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateProgressEvent {
+    /// ID of the filter. Empty for index (registry) events
+    #[prost(int32, optional, tag = "1")]
+    pub filter_id: ::core::option::Option<i32>,
+    /// Filter download url or index url
+    #[prost(string, tag = "2")]
+    pub url: ::prost::alloc::string::String,
+    /// Current stage
+    #[prost(enumeration = "UpdateProgressStage", tag = "3")]
+    pub stage: i32,
+    /// Error message for FAILED stage
+    #[prost(string, optional, tag = "4")]
+    pub message: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Settings of the built-in update scheduler. All fields must be set explicitly
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UpdateSchedulerSettings {
+    /// Minimal period between filters updates in seconds. Must be positive
+    #[prost(int32, tag = "1")]
+    pub update_interval_sec: i32,
+    /// Period between metadata pulls in seconds. Values less than 1 disable pulling
+    #[prost(int32, tag = "2")]
+    pub pull_metadata_interval_sec: i32,
+    /// Delay before the first retry, if the task has failed due to network. Must be positive
+    #[prost(int32, tag = "3")]
+    pub offline_backoff_min_sec: i32,
+    /// Upper bound of the retry delay in seconds
+    #[prost(int32, tag = "4")]
+    pub offline_backoff_max_sec: i32,
+    /// loose_timeout argument for scheduled update_filters calls
+    #[prost(int32, tag = "5")]
+    pub loose_timeout: i32,
+}
+/// Outcome of the task, run by the update scheduler
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateSchedulerEvent {
+    /// Task, which has been run
+    #[prost(enumeration = "UpdateSchedulerTask", tag = "1")]
+    pub task: i32,
+    /// Result of UPDATE_FILTERS task
+    #[prost(message, optional, tag = "2")]
+    pub update_result: ::core::option::Option<UpdateResult>,
+    /// Result of PULL_METADATA task
+    #[prost(message, optional, tag = "3")]
+    pub pull_metadata_result: ::core::option::Option<PullMetadataResult>,
+    /// Error, if the task has failed
+    #[prost(message, optional, tag = "4")]
+    pub error: ::core::option::Option<AgOuterError>,
+    /// Task has failed due to network, so it will be retried with backoff
+    #[prost(bool, tag = "5")]
+    pub is_offline: bool,
+    /// Time of the next scheduled task. Unix timestamp in seconds
+    #[prost(int64, tag = "6")]
+    pub next_run_time: i64,
+}
+/// Entry of the user state document, which could not be imported as is
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserStateConflict {
+    /// Filter id from the document
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Conflict kind
+    #[prost(enumeration = "UserStateConflictKind", tag = "2")]
+    pub kind: i32,
+    /// Human-readable description
+    #[prost(string, tag = "3")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Result of user state import
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportUserStateResult {
+    /// Index filters, which state has been restored from the document
+    #[prost(int32, repeated, tag = "1")]
+    pub restored_filters: ::prost::alloc::vec::Vec<i32>,
+    /// Custom filters installed from the document
+    #[prost(message, repeated, tag = "2")]
+    pub installed_custom_filters: ::prost::alloc::vec::Vec<MovedFilterInfo>,
+    /// Conflicts found during the import
+    #[prost(message, repeated, tag = "3")]
+    pub conflicts: ::prost::alloc::vec::Vec<UserStateConflict>,
+}
+/// Options of rules search
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RuleSearchOptions {
+    /// Treat query as a regular expression. Otherwise, query is a substring
+    #[prost(bool, tag = "1")]
+    pub is_regex: bool,
+    /// Case-sensitive matching
+    #[prost(bool, tag = "2")]
+    pub is_case_sensitive: bool,
+    /// Also return disabled rules
+    #[prost(bool, tag = "3")]
+    pub include_disabled_rules: bool,
+    /// Maximum number of matches. Unlimited, if not set
+    #[prost(uint32, optional, tag = "4")]
+    pub limit: ::core::option::Option<u32>,
+}
+/// Single rule found by rules search
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuleSearchMatch {
+    /// Filter, which contains the rule
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Absolute URL of the include, which contains the rule. Not set if the rule is in the filter body itself
+    #[prost(string, optional, tag = "2")]
+    pub include_url: ::core::option::Option<::prost::alloc::string::String>,
+    /// Line number in the filter body or in the include body. Starts from 1
+    #[prost(uint32, tag = "3")]
+    pub line_number: u32,
+    /// Rule text
+    #[prost(string, tag = "4")]
+    pub rule: ::prost::alloc::string::String,
+    /// Rule is in the list of disabled rules of the filter
+    #[prost(bool, tag = "5")]
+    pub is_disabled: bool,
+}
+/// Location, which produces the rule in the built filter
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuleProvenance {
+    /// Filter, which produces the rule
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Absolute URL of the first-level include, which contains the rule. Not set if the rule is in the filter body itself
+    #[prost(string, optional, tag = "2")]
+    pub include_url: ::core::option::Option<::prost::alloc::string::String>,
+    /// Line number in the filter body or in the include body. Starts from 1
+    #[prost(uint32, tag = "3")]
+    pub line_number: u32,
+    /// Rule is in the list of disabled rules of the filter
+    #[prost(bool, tag = "4")]
+    pub is_disabled: bool,
+}
+/// Consecutive update failures of the filter
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterUpdateFailure {
+    /// ID of the filter
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Number of updates in a row, which have failed for this filter
+    #[prost(int32, tag = "2")]
+    pub consecutive_failures: i32,
+    /// Timestamp of the first failure in a row. Tells how long the filter has been failing
+    #[prost(int64, tag = "3")]
+    pub first_failure_time: i64,
+    /// Timestamp of the last failure
+    #[prost(int64, tag = "4")]
+    pub last_failure_time: i64,
+    /// Last error converted to a string
+    #[prost(string, tag = "5")]
+    pub last_error: ::prost::alloc::string::String,
+    /// Regular updates will skip the filter until this timestamp
+    #[prost(int64, tag = "6")]
+    pub next_retry_time: i64,
+}
+/// HTTP header
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HttpHeader {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub value: ::prost::alloc::string::String,
+}
+/// GET request, which should be performed by the native HTTP transport
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HttpRequest {
+    /// Absolute url
+    #[prost(string, tag = "1")]
+    pub url: ::prost::alloc::string::String,
+    /// Additional request headers, e.g. conditional request headers
+    #[prost(message, repeated, tag = "2")]
+    pub headers: ::prost::alloc::vec::Vec<HttpHeader>,
+}
+/// Response of the native HTTP transport
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HttpResponse {
+    /// Status code. Response must be returned for any status code, including 304, 4xx and 5xx
+    #[prost(uint32, tag = "1")]
+    pub status: u32,
+    /// Response headers
+    #[prost(message, repeated, tag = "2")]
+    pub headers: ::prost::alloc::vec::Vec<HttpHeader>,
+    /// Response body. Must be already decoded, if server used `Content-Encoding`
+    #[prost(bytes = "vec", tag = "3")]
+    pub body: ::prost::alloc::vec::Vec<u8>,
+}
+/// Failure of the native HTTP transport, if response cannot be received
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HttpTransportError {
+    /// Error message
+    #[prost(string, tag = "1")]
+    pub message: ::prost::alloc::string::String,
+    /// Request has timed out
+    #[prost(bool, tag = "2")]
+    pub is_timed_out: bool,
+}
+/// Result of the request, which is passed back from the native HTTP transport
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HttpTransportResult {
+    #[prost(oneof = "http_transport_result::Result", tags = "1, 2")]
+    pub result: ::core::option::Option<http_transport_result::Result>,
+}
+/// Nested message and enum types in `HttpTransportResult`.
+pub mod http_transport_result {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Result {
+        #[prost(message, tag = "1")]
+        Response(super::HttpResponse),
+        #[prost(message, tag = "2")]
+        Error(super::HttpTransportError),
+    }
+}
+/// Why the filter was or wasn't updated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FilterUpdateOutcome {
+    /// Filter has been downloaded in full and saved
+    Updated = 0,
+    /// Filter has been patched via differential update and saved
+    UpdatedViaDiff = 1,
+    /// Filter is not expired yet, so it wasn't requested
+    NotExpired = 2,
+    /// Version from the index is the same as the installed one, so filter wasn't requested
+    SameVersion = 3,
+    /// Filter has been requested, but its contents are not changed
+    SameContent = 4,
+    /// Filter is disabled, so it wasn't requested
+    Disabled = 5,
+    /// Filter has no download url, so it can't be updated
+    NoDownloadUrl = 6,
+    /// Filter hasn't been processed due to timeout or cancellation
+    NotProcessed = 7,
+    /// Filter couldn't be updated. See failure_kind
+    Failed = 8,
+    /// Previous updates of the filter have failed, so it won't be requested until its next retry time
+    Postponed = 9,
+    /// Filter has been rolled back and pinned, so it won't be requested until it is unpinned
+    Pinned = 10,
+    /// Next differential update patch of the filter is not published yet, so its contents haven't been checked
+    PatchNotReady = 11,
+}
+impl FilterUpdateOutcome {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Updated => "UPDATED",
+            Self::UpdatedViaDiff => "UPDATED_VIA_DIFF",
+            Self::NotExpired => "NOT_EXPIRED",
+            Self::SameVersion => "SAME_VERSION",
+            Self::SameContent => "SAME_CONTENT",
+            Self::Disabled => "DISABLED",
+            Self::NoDownloadUrl => "NO_DOWNLOAD_URL",
+            Self::NotProcessed => "NOT_PROCESSED",
+            Self::Failed => "FAILED",
+            Self::Postponed => "POSTPONED",
+            Self::Pinned => "PINNED",
+            Self::PatchNotReady => "PATCH_NOT_READY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UPDATED" => Some(Self::Updated),
+            "UPDATED_VIA_DIFF" => Some(Self::UpdatedViaDiff),
+            "NOT_EXPIRED" => Some(Self::NotExpired),
+            "SAME_VERSION" => Some(Self::SameVersion),
+            "SAME_CONTENT" => Some(Self::SameContent),
+            "DISABLED" => Some(Self::Disabled),
+            "NO_DOWNLOAD_URL" => Some(Self::NoDownloadUrl),
+            "NOT_PROCESSED" => Some(Self::NotProcessed),
+            "FAILED" => Some(Self::Failed),
+            "POSTPONED" => Some(Self::Postponed),
+            "PINNED" => Some(Self::Pinned),
+            "PATCH_NOT_READY" => Some(Self::PatchNotReady),
+            _ => None,
+        }
+    }
+}
+/// Category of filter update failure
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UpdateFailureKind {
+    /// Http request failed or server responded with unexpected status code
+    Network = 0,
+    /// Local file couldn't be read
+    Io = 1,
+    /// Filter checksum is invalid
+    InvalidChecksum = 2,
+    /// Downloaded contents are likely not a filter
+    InvalidContent = 3,
+    /// Filter contents couldn't be parsed
+    Parser = 4,
+    /// Other errors
+    Other = 5,
+}
+impl UpdateFailureKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Network => "NETWORK",
+            Self::Io => "IO",
+            Self::InvalidChecksum => "INVALID_CHECKSUM",
+            Self::InvalidContent => "INVALID_CONTENT",
+            Self::Parser => "PARSER",
+            Self::Other => "OTHER",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NETWORK" => Some(Self::Network),
+            "IO" => Some(Self::Io),
+            "INVALID_CHECKSUM" => Some(Self::InvalidChecksum),
+            "INVALID_CONTENT" => Some(Self::InvalidContent),
+            "PARSER" => Some(Self::Parser),
+            "OTHER" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+/// Previous version of filter contents, which the filter may be rolled back to
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterVersion {
+    /// ID of this version
+    #[prost(int64, tag = "1")]
+    pub version_id: i64,
+    /// Filter id
+    #[prost(int32, tag = "2")]
+    pub filter_id: i32,
+    /// Filter version from its metadata
+    #[prost(string, tag = "3")]
+    pub version: ::prost::alloc::string::String,
+    /// Filter title at the moment of the replacement
+    #[prost(string, tag = "4")]
+    pub title: ::prost::alloc::string::String,
+    /// Filter update time from its metadata
+    #[prost(int64, tag = "5")]
+    pub last_update_time: i64,
+    /// Timestamp, when these contents have been replaced
+    #[prost(int64, tag = "6")]
+    pub saved_time: i64,
+    /// Rules count of the filter and its includes
+    #[prost(int32, tag = "7")]
+    pub rules_count: i32,
+}
+/// Changes, which will be made by the filter update
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterUpdatePreview {
+    /// Filter id
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Remote version has been requested via differential update
+    #[prost(bool, tag = "2")]
+    pub is_diff_update: bool,
+    /// Rule lines of the filter and its includes, which will appear after the update
+    #[prost(string, repeated, tag = "3")]
+    pub added_rules: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Rule lines of the filter and its includes, which will disappear after the update
+    #[prost(string, repeated, tag = "4")]
+    pub removed_rules: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Current version
+    #[prost(string, tag = "5")]
+    pub old_version: ::prost::alloc::string::String,
+    /// Version after the update
+    #[prost(string, tag = "6")]
+    pub new_version: ::prost::alloc::string::String,
+    /// Current title
+    #[prost(string, tag = "7")]
+    pub old_title: ::prost::alloc::string::String,
+    /// Title after the update
+    #[prost(string, tag = "8")]
+    pub new_title: ::prost::alloc::string::String,
+    /// Current expires in seconds
+    #[prost(int32, tag = "9")]
+    pub old_expires: i32,
+    /// Expires after the update in seconds
+    #[prost(int32, tag = "10")]
+    pub new_expires: i32,
+    /// Difference between rules count after and before the update
+    #[prost(int32, tag = "11")]
+    pub rules_count_delta: i32,
+    /// Next differential update patch is not published yet, so the remote version
+    /// couldn't be checked. Other fields describe no changes then
+    #[prost(bool, tag = "12")]
+    pub is_patch_not_ready: bool,
+    /// URL, which has served the remote version: the download URL or one of the filter mirrors.
+    /// Not set if the filter hasn't been requested or all URLs failed with network errors
+    #[prost(string, optional, tag = "13")]
+    pub served_url: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A problem, found in the filter body
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterDiagnostic {
+    /// Line number in the filter body. Starts from 1
+    #[prost(uint32, tag = "1")]
+    pub line_number: u32,
+    /// How serious the problem is
+    #[prost(enumeration = "FilterDiagnosticSeverity", tag = "2")]
+    pub severity: i32,
+    /// Kind of the problem
+    #[prost(enumeration = "FilterDiagnosticKind", tag = "3")]
+    pub kind: i32,
+    /// Human-readable description
+    #[prost(string, tag = "4")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Pattern, which disables all matching rules of the filter
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DisabledRulePattern {
+    /// Syntax of the pattern
+    #[prost(enumeration = "DisabledRulePatternKind", tag = "1")]
+    pub kind: i32,
+    /// Pattern itself
+    #[prost(string, tag = "2")]
+    pub pattern: ::prost::alloc::string::String,
+}
+/// Disabled rule patterns of the filter
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DisabledRulePatterns {
+    /// Associated filter id
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Patterns in the saved order
+    #[prost(message, repeated, tag = "2")]
+    pub patterns: ::prost::alloc::vec::Vec<DisabledRulePattern>,
+}
+/// Location of the filter rules in the exported file
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ExportedFilterInfo {
+    /// Filter id
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Is this filter trusted. Untrusted filters are written to the separate file, if it's set
+    #[prost(bool, tag = "2")]
+    pub is_trusted: bool,
+    /// Offset of the filter section in the file. Section starts with the header, if any
+    #[prost(uint64, tag = "3")]
+    pub byte_offset: u64,
+    /// Length of the filter section in bytes
+    #[prost(uint64, tag = "4")]
+    pub byte_length: u64,
+    /// Count of written rules, excluding comments and empty lines
+    #[prost(int32, tag = "5")]
+    pub rules_count: i32,
+    /// Count of skipped duplicated rules, if deduplication is enabled
+    #[prost(int32, tag = "6")]
+    pub duplicates_count: i32,
+}
+/// Manifest of the exported file(s)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ActiveRulesExportManifest {
+    /// Exported filters in the order of writing
+    #[prost(message, repeated, tag = "1")]
+    pub filters: ::prost::alloc::vec::Vec<ExportedFilterInfo>,
+}
+/// Count of duplicated rules, removed from the filter
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DuplicatesByFilter {
+    /// Associated filter id
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    /// Count of removed rules, which are kept in another filter or appear earlier in the same filter
+    #[prost(int32, tag = "2")]
+    pub duplicates_count: i32,
+}
+/// Stage of filter (or index) processing during update
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UpdateProgressStage {
+    /// Filter is selected for update and waits for dispatch
+    Queued = 0,
+    /// Contents are being downloaded
+    Downloading = 1,
+    /// Filter has been updated via differential update
+    DiffPatched = 2,
+    /// Filter has been downloaded and compiled
+    Compiled = 3,
+    /// Updated data has been saved into the database
+    Saved = 4,
+    /// Contents have not been changed since the last update
+    NotChanged = 5,
+    /// Processing has failed
+    Failed = 6,
+}
+impl UpdateProgressStage {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Queued => "QUEUED",
+            Self::Downloading => "DOWNLOADING",
+            Self::DiffPatched => "DIFF_PATCHED",
+            Self::Compiled => "COMPILED",
+            Self::Saved => "SAVED",
+            Self::NotChanged => "NOT_CHANGED",
+            Self::Failed => "FAILED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "QUEUED" => Some(Self::Queued),
+            "DOWNLOADING" => Some(Self::Downloading),
+            "DIFF_PATCHED" => Some(Self::DiffPatched),
+            "COMPILED" => Some(Self::Compiled),
+            "SAVED" => Some(Self::Saved),
+            "NOT_CHANGED" => Some(Self::NotChanged),
+            "FAILED" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+/// Task, run by the update scheduler
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UpdateSchedulerTask {
+    /// update_filters call
+    UpdateFilters = 0,
+    /// pull_metadata call
+    PullMetadata = 1,
+    /// Restart of the scheduler after the configuration change. Reported only on failure
+    Restart = 2,
+}
+impl UpdateSchedulerTask {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::UpdateFilters => "UPDATE_FILTERS",
+            Self::PullMetadata => "PULL_METADATA",
+            Self::Restart => "RESTART",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UPDATE_FILTERS" => Some(Self::UpdateFilters),
+            "PULL_METADATA" => Some(Self::PullMetadata),
+            "RESTART" => Some(Self::Restart),
+            _ => None,
+        }
+    }
+}
+/// Kind of user state import conflict
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UserStateConflictKind {
+    /// Index filter from the document is not present in the database
+    FilterNotFound = 0,
+    /// Filter has no downloaded rules yet, so its disabled rules were skipped
+    RulesListNotFound = 1,
+    /// Custom filter with the same download url is already installed
+    CustomFilterAlreadyInstalled = 2,
+    /// Id of the custom filter has already been taken. Filter was installed with a new id
+    CustomFilterIdCollision = 3,
+}
+impl UserStateConflictKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::FilterNotFound => "FILTER_NOT_FOUND",
+            Self::RulesListNotFound => "RULES_LIST_NOT_FOUND",
+            Self::CustomFilterAlreadyInstalled => "CUSTOM_FILTER_ALREADY_INSTALLED",
+            Self::CustomFilterIdCollision => "CUSTOM_FILTER_ID_COLLISION",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FILTER_NOT_FOUND" => Some(Self::FilterNotFound),
+            "RULES_LIST_NOT_FOUND" => Some(Self::RulesListNotFound),
+            "CUSTOM_FILTER_ALREADY_INSTALLED" => Some(Self::CustomFilterAlreadyInstalled),
+            "CUSTOM_FILTER_ID_COLLISION" => Some(Self::CustomFilterIdCollision),
+            _ => None,
+        }
+    }
+}
+/// Severity of the filter diagnostic
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FilterDiagnosticSeverity {
+    /// Filter can't be compiled, or compiles not the way its author expects
+    Error = 0,
+    /// Filter compiles, but the construction is suspicious or will be ignored
+    Warning = 1,
+}
+impl FilterDiagnosticSeverity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Error => "FILTER_DIAGNOSTIC_SEVERITY_ERROR",
+            Self::Warning => "FILTER_DIAGNOSTIC_SEVERITY_WARNING",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FILTER_DIAGNOSTIC_SEVERITY_ERROR" => Some(Self::Error),
+            "FILTER_DIAGNOSTIC_SEVERITY_WARNING" => Some(Self::Warning),
+            _ => None,
+        }
+    }
+}
+/// Kind of problem, found by filter linting
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FilterDiagnosticKind {
+    /// `!#if` has no condition expression
+    EmptyIf = 0,
+    /// `!#else` without `!#if`, or the second `!#else` for the same `!#if`
+    UnbalancedElse = 1,
+    /// `!#endif` without `!#if`
+    UnbalancedEndIf = 2,
+    /// `!#if` is not closed with `!#endif`
+    UnbalancedIf = 3,
+    /// `!#if` condition can't be parsed
+    InvalidBooleanExpression = 4,
+    /// `!#if` condition uses unknown constant, which is always false
+    UnknownConstant = 5,
+    /// `!#include` has no path, or the path can't be resolved
+    InvalidInclude = 6,
+    /// `!#include` target has different origin than the filter
+    CrossOriginInclude = 7,
+    /// `!#include` target is the filter itself
+    RecursiveInclude = 8,
+    /// `! Expires` value can't be parsed
+    MalformedExpires = 9,
+    /// `! TimeUpdated` value is not a valid date
+    MalformedTimeUpdated = 10,
+    /// `! Checksum` doesn't match filter contents
+    ChecksumMismatch = 11,
+}
+impl FilterDiagnosticKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::EmptyIf => "FILTER_DIAGNOSTIC_KIND_EMPTY_IF",
+            Self::UnbalancedElse => "FILTER_DIAGNOSTIC_KIND_UNBALANCED_ELSE",
+            Self::UnbalancedEndIf => "FILTER_DIAGNOSTIC_KIND_UNBALANCED_END_IF",
+            Self::UnbalancedIf => "FILTER_DIAGNOSTIC_KIND_UNBALANCED_IF",
+            Self::InvalidBooleanExpression => "FILTER_DIAGNOSTIC_KIND_INVALID_BOOLEAN_EXPRESSION",
+            Self::UnknownConstant => "FILTER_DIAGNOSTIC_KIND_UNKNOWN_CONSTANT",
+            Self::InvalidInclude => "FILTER_DIAGNOSTIC_KIND_INVALID_INCLUDE",
+            Self::CrossOriginInclude => "FILTER_DIAGNOSTIC_KIND_CROSS_ORIGIN_INCLUDE",
+            Self::RecursiveInclude => "FILTER_DIAGNOSTIC_KIND_RECURSIVE_INCLUDE",
+            Self::MalformedExpires => "FILTER_DIAGNOSTIC_KIND_MALFORMED_EXPIRES",
+            Self::MalformedTimeUpdated => "FILTER_DIAGNOSTIC_KIND_MALFORMED_TIME_UPDATED",
+            Self::ChecksumMismatch => "FILTER_DIAGNOSTIC_KIND_CHECKSUM_MISMATCH",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FILTER_DIAGNOSTIC_KIND_EMPTY_IF" => Some(Self::EmptyIf),
+            "FILTER_DIAGNOSTIC_KIND_UNBALANCED_ELSE" => Some(Self::UnbalancedElse),
+            "FILTER_DIAGNOSTIC_KIND_UNBALANCED_END_IF" => Some(Self::UnbalancedEndIf),
+            "FILTER_DIAGNOSTIC_KIND_UNBALANCED_IF" => Some(Self::UnbalancedIf),
+            "FILTER_DIAGNOSTIC_KIND_INVALID_BOOLEAN_EXPRESSION" => Some(Self::InvalidBooleanExpression),
+            "FILTER_DIAGNOSTIC_KIND_UNKNOWN_CONSTANT" => Some(Self::UnknownConstant),
+            "FILTER_DIAGNOSTIC_KIND_INVALID_INCLUDE" => Some(Self::InvalidInclude),
+            "FILTER_DIAGNOSTIC_KIND_CROSS_ORIGIN_INCLUDE" => Some(Self::CrossOriginInclude),
+            "FILTER_DIAGNOSTIC_KIND_RECURSIVE_INCLUDE" => Some(Self::RecursiveInclude),
+            "FILTER_DIAGNOSTIC_KIND_MALFORMED_EXPIRES" => Some(Self::MalformedExpires),
+            "FILTER_DIAGNOSTIC_KIND_MALFORMED_TIME_UPDATED" => Some(Self::MalformedTimeUpdated),
+            "FILTER_DIAGNOSTIC_KIND_CHECKSUM_MISMATCH" => Some(Self::ChecksumMismatch),
+            _ => None,
+        }
+    }
+}
+/// Syntax of the disabled rule pattern
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DisabledRulePatternKind {
+    /// `*` matches any sequence of characters. Pattern must match the whole rule
+    Wildcard = 0,
+    /// Regular expression, which may match any part of the rule
+    Regex = 1,
+}
+impl DisabledRulePatternKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Wildcard => "DISABLED_RULE_PATTERN_KIND_WILDCARD",
+            Self::Regex => "DISABLED_RULE_PATTERN_KIND_REGEX",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DISABLED_RULE_PATTERN_KIND_WILDCARD" => Some(Self::Wildcard),
+            "DISABLED_RULE_PATTERN_KIND_REGEX" => Some(Self::Regex),
+            _ => None,
+        }
+    }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct InstallCustomFilterListRequest {
@@ -749,6 +1742,87 @@ pub struct SignAllDataWithNewKeyRequest {
     #[prost(string, tag = "1")]
     pub integrity_key: ::prost::alloc::string::String,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportUserStateRequest {
+    #[prost(string, tag = "1")]
+    pub document: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchRulesRequest {
+    #[prost(string, tag = "1")]
+    pub query: ::prost::alloc::string::String,
+    #[prost(int32, repeated, tag = "2")]
+    pub filter_ids: ::prost::alloc::vec::Vec<i32>,
+    #[prost(message, optional, tag = "3")]
+    pub options: ::core::option::Option<RuleSearchOptions>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRuleProvenanceRequest {
+    #[prost(string, tag = "1")]
+    pub rule: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListFilterVersionsRequest {
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RollbackFilterRequest {
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    #[prost(int64, tag = "2")]
+    pub version_id: i64,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UnpinFilterRequest {
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SaveDisabledRulePatternsRequest {
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+    #[prost(message, repeated, tag = "2")]
+    pub patterns: ::prost::alloc::vec::Vec<DisabledRulePattern>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDisabledRulePatternsRequest {
+    #[prost(int32, repeated, tag = "1")]
+    pub ids: ::prost::alloc::vec::Vec<i32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SaveActiveRulesToFileRequest {
+    #[prost(string, tag = "1")]
+    pub file_path: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub write_filter_headers: bool,
+    #[prost(string, optional, tag = "3")]
+    pub untrusted_file_path: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bool, tag = "4")]
+    pub deduplicate_rules: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetActiveRulesRawDeduplicatedRequest {
+    #[prost(int32, repeated, tag = "1")]
+    pub filter_by: ::prost::alloc::vec::Vec<i32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangeDatabaseEncryptionKeyRequest {
+    #[prost(string, optional, tag = "1")]
+    pub encryption_key: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct PreviewFilterUpdateRequest {
+    #[prost(int32, tag = "1")]
+    pub filter_id: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LintFilterRequest {
+    #[prost(string, tag = "1")]
+    pub body: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub base_url: ::prost::alloc::string::String,
+}
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct EmptyRequest {}
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -927,6 +2001,94 @@ pub struct PullMetadataResponse {
     pub error: ::core::option::Option<AgOuterError>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportUserStateResponse {
+    #[prost(string, tag = "1")]
+    pub document: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportUserStateResponse {
+    #[prost(message, optional, tag = "1")]
+    pub result: ::core::option::Option<ImportUserStateResult>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchRulesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub matches: ::prost::alloc::vec::Vec<RuleSearchMatch>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRuleProvenanceResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub locations: ::prost::alloc::vec::Vec<RuleProvenance>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFilterUpdateFailuresResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub failures: ::prost::alloc::vec::Vec<FilterUpdateFailure>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListFilterVersionsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub versions: ::prost::alloc::vec::Vec<FilterVersion>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDisabledRulePatternsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub patterns: ::prost::alloc::vec::Vec<DisabledRulePatterns>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SaveActiveRulesToFileResponse {
+    #[prost(message, optional, tag = "1")]
+    pub manifest: ::core::option::Option<ActiveRulesExportManifest>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetActiveRulesDeduplicatedResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub rules: ::prost::alloc::vec::Vec<ActiveRulesInfo>,
+    #[prost(message, repeated, tag = "2")]
+    pub duplicates: ::prost::alloc::vec::Vec<DuplicatesByFilter>,
+    #[prost(message, optional, tag = "3")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetActiveRulesRawDeduplicatedResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub rules: ::prost::alloc::vec::Vec<ActiveRulesInfoRaw>,
+    #[prost(message, repeated, tag = "2")]
+    pub duplicates: ::prost::alloc::vec::Vec<DuplicatesByFilter>,
+    #[prost(message, optional, tag = "3")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreviewFilterUpdateResponse {
+    #[prost(message, optional, tag = "1")]
+    pub preview: ::core::option::Option<FilterUpdatePreview>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LintFilterResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub diagnostics: ::prost::alloc::vec::Vec<FilterDiagnostic>,
+    #[prost(message, optional, tag = "2")]
+    pub error: ::core::option::Option<AgOuterError>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EmptyResponse {
     #[prost(message, optional, tag = "1")]
     pub error: ::core::option::Option<AgOuterError>,
@@ -0,0 +1,7 @@
+-- Purpose: Conditional HTTP requests. Stores response validators by requested url
+
+CREATE TABLE [http_cache_validators] (
+    [url] TEXT NOT NULL PRIMARY KEY,
+    [etag] TEXT,
+    [last_modified] TEXT
+);
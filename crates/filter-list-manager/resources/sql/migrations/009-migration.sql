@@ -0,0 +1,10 @@
+-- Purpose: Per-filter update failures tracking. Persistently failing filters are postponed until next_retry_time
+
+CREATE TABLE [filter_update_failure] (
+    [filter_id] INTEGER NOT NULL PRIMARY KEY,
+    [consecutive_failures] INTEGER NOT NULL DEFAULT 0,
+    [first_failure_time] INTEGER NOT NULL DEFAULT 0,
+    [last_failure_time] INTEGER NOT NULL DEFAULT 0,
+    [last_error] TEXT NOT NULL DEFAULT '',
+    [next_retry_time] INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,4 @@
+-- Purpose: Optional compression of rules texts and includes bodies. 0 - plain text, 1 - zstd
+
+ALTER TABLE [rules_list] ADD COLUMN [compression] INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE [filter_includes] ADD COLUMN [compression] INTEGER NOT NULL DEFAULT 0;
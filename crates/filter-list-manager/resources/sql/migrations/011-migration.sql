@@ -0,0 +1,44 @@
+-- Purpose: Previous versions of filters contents, which filters may be rolled back to.
+-- Rolled back filters are pinned and skipped by updates
+
+CREATE TABLE [filter_version] (
+    [version_id] INTEGER PRIMARY KEY AUTOINCREMENT,
+    [filter_id] INTEGER NOT NULL,
+    [saved_time] INTEGER NOT NULL, -- when these contents have been replaced
+    [version] TEXT,
+    [title] TEXT,
+    [description] TEXT,
+    [homepage] TEXT,
+    [license] TEXT,
+    [checksum] TEXT,
+    [expires] INTEGER,
+    [last_update_time] INTEGER NOT NULL,
+    [rules_text] TEXT,
+    [rules_count] INTEGER NOT NULL DEFAULT 0,
+    [has_directives] BOOLEAN NOT NULL DEFAULT 1,
+    [text_hash] TEXT,
+    [integrity_signature] TEXT,
+    [compression] INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX [filter_version_filter_id] ON [filter_version] ([filter_id]);
+
+CREATE TABLE [filter_version_includes] (
+    [row_id] INTEGER PRIMARY KEY,
+    [version_id] INTEGER NOT NULL,
+    [filter_id] INTEGER NOT NULL,
+    [absolute_url] TEXT NOT NULL,
+    [body] TEXT NOT NULL,
+    [rules_count] INTEGER NOT NULL,
+    [body_hash] TEXT,
+    [integrity_signature] TEXT,
+    [compression] INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX [filter_version_includes_version_id] ON [filter_version_includes] ([version_id]);
+
+CREATE TABLE [filter_pin] (
+    [filter_id] INTEGER NOT NULL PRIMARY KEY,
+    [version] TEXT NOT NULL DEFAULT '',
+    [pinned_time] INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,10 @@
+-- Purpose: Patterns of disabled rules. Rules matching any pattern of their filter are treated as disabled
+
+CREATE TABLE [disabled_rule_pattern] (
+    [row_id] INTEGER PRIMARY KEY,
+    [filter_id] INTEGER NOT NULL,
+    [kind] INTEGER NOT NULL, -- 0 - wildcard, 1 - regex
+    [pattern] TEXT NOT NULL
+);
+
+CREATE INDEX [disabled_rule_pattern_filter_id] ON [disabled_rule_pattern] ([filter_id]);
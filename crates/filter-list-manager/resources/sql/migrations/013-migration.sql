@@ -0,0 +1,9 @@
+-- Purpose: Alternate download URLs of index filters. They are requested in order, if the main download URL is unreachable
+
+CREATE TABLE [filter_mirror_url] (
+    [row_id] INTEGER PRIMARY KEY,
+    [filter_id] INTEGER NOT NULL,
+    [url] TEXT NOT NULL
+);
+
+CREATE INDEX [filter_mirror_url_filter_id] ON [filter_mirror_url] ([filter_id]);
@@ -0,0 +1,9 @@
+-- Purpose: Named filter registries. Filters, groups and tags of each registry occupy their own range of ids
+
+CREATE TABLE [filter_registry] (
+    [registry_id] INTEGER PRIMARY KEY,
+    [name] TEXT NOT NULL UNIQUE
+);
+
+-- Main registry keeps ids of the existing index filters
+INSERT INTO [filter_registry] ([registry_id], [name]) VALUES (0, 'main');
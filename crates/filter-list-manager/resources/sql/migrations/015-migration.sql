@@ -0,0 +1,3 @@
+-- Purpose: Trust level of index filters from the registry. NULL for custom filters
+
+ALTER TABLE [filter] ADD COLUMN [trust_level] INTEGER;
@@ -0,0 +1,4 @@
+-- Purpose: Last run times of the built-in update scheduler. NULL if the task has never been run
+
+ALTER TABLE [metadata] ADD COLUMN [scheduler_last_update_time] INTEGER;
+ALTER TABLE [metadata] ADD COLUMN [scheduler_last_pull_metadata_time] INTEGER;
@@ -0,0 +1,144 @@
+//! Matching of rules against literal disabled rules and disabled rule patterns
+use crate::manager::models::disabled_rule_pattern::DisabledRulePatternKind;
+use crate::storage::entities::rules_list::disabled_rule_pattern_entity::DisabledRulePatternEntity;
+use crate::{FLMError, FLMResult};
+use regex::bytes::RegexSet;
+use std::collections::HashSet;
+
+/// Decides whether the rule line is disabled.
+/// Literal disabled rules are matched exactly, patterns are compiled into a single [`RegexSet`]
+pub(crate) struct DisabledRulesMatcher {
+    literals: HashSet<Vec<u8>>,
+    patterns: Option<RegexSet>,
+}
+
+impl DisabledRulesMatcher {
+    /// Makes matcher from literal disabled rules lines and patterns of the filter
+    pub(crate) fn new<'a, I>(literals: I, patterns: &[DisabledRulePatternEntity]) -> FLMResult<Self>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let patterns = if patterns.is_empty() {
+            None
+        } else {
+            let sources = patterns
+                .iter()
+                .map(|entity| to_regex_source(entity.kind, &entity.pattern))
+                .collect::<Vec<String>>();
+
+            Some(RegexSet::new(sources).map_err(FLMError::from_display)?)
+        };
+
+        Ok(Self {
+            literals: literals.into_iter().map(|line| line.to_vec()).collect(),
+            patterns,
+        })
+    }
+
+    /// Makes matcher with literal disabled rules only
+    #[cfg(test)]
+    pub(crate) fn from_literals<'a, I>(literals: I) -> Self
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        Self {
+            literals: literals.into_iter().map(|line| line.to_vec()).collect(),
+            patterns: None,
+        }
+    }
+
+    /// Is this line disabled either by literal rule or by pattern
+    #[inline]
+    pub(crate) fn is_disabled(&self, line: &[u8]) -> bool {
+        self.literals.contains(line) || self.is_disabled_by_pattern(line)
+    }
+
+    /// Is this line disabled by one of the patterns
+    #[inline]
+    pub(crate) fn is_disabled_by_pattern(&self, line: &[u8]) -> bool {
+        self.patterns
+            .as_ref()
+            .is_some_and(|patterns| patterns.is_match(line))
+    }
+}
+
+/// Validates the pattern before saving.
+/// Returns [`FLMError::FieldIsEmpty`] for empty pattern or [`FLMError::Other`] for an invalid one
+pub(crate) fn validate_pattern(kind: DisabledRulePatternKind, pattern: &str) -> FLMResult<()> {
+    if pattern.is_empty() {
+        return Err(FLMError::FieldIsEmpty("pattern"));
+    }
+
+    regex::bytes::Regex::new(&to_regex_source(kind, pattern))
+        .map(|_| ())
+        .map_err(FLMError::from_display)
+}
+
+/// Converts pattern to the regular expression source
+fn to_regex_source(kind: DisabledRulePatternKind, pattern: &str) -> String {
+    match kind {
+        DisabledRulePatternKind::Regex => pattern.to_string(),
+        DisabledRulePatternKind::Wildcard => {
+            let parts = pattern.split('*').map(regex::escape).collect::<Vec<_>>();
+
+            format!("^{}$", parts.join(".*"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_pattern, DisabledRulesMatcher};
+    use crate::manager::models::disabled_rule_pattern::DisabledRulePatternKind;
+    use crate::storage::entities::rules_list::disabled_rule_pattern_entity::DisabledRulePatternEntity;
+    use crate::FLMError;
+
+    fn entity(kind: DisabledRulePatternKind, pattern: &str) -> DisabledRulePatternEntity {
+        DisabledRulePatternEntity {
+            filter_id: 1,
+            kind,
+            pattern: pattern.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matcher() {
+        let matcher = DisabledRulesMatcher::new(
+            [b"||literal.com^".as_slice(), b"*.example.*".as_slice()],
+            &[
+                entity(DisabledRulePatternKind::Wildcard, "*##+js(*"),
+                entity(DisabledRulePatternKind::Wildcard, "||ads.*.org^"),
+                entity(DisabledRulePatternKind::Regex, r"^@@\|\|.+\.net"),
+            ],
+        )
+        .unwrap();
+
+        // Literals
+        assert!(matcher.is_disabled(b"||literal.com^"));
+        assert!(!matcher.is_disabled_by_pattern(b"||literal.com^"));
+        // Literal lines are never treated as wildcards
+        assert!(matcher.is_disabled(b"*.example.*"));
+        assert!(!matcher.is_disabled(b"a.example.b"));
+
+        // Wildcards must match the whole rule
+        assert!(matcher.is_disabled(b"example.com##+js(set-constant)"));
+        assert!(matcher.is_disabled(b"||ads.tracker.org^"));
+        assert!(!matcher.is_disabled(b"||ads.tracker.org^$third-party"));
+        // Wildcard special characters other than `*` are matched literally
+        assert!(!matcher.is_disabled(b"example.com##js(set-constant)"));
+
+        // Regex matches any part of the rule
+        assert!(matcher.is_disabled(b"@@||example.net^$document"));
+        assert!(!matcher.is_disabled(b"||example.net^"));
+    }
+
+    #[test]
+    fn test_validate_pattern() {
+        assert!(validate_pattern(DisabledRulePatternKind::Wildcard, "*[*").is_ok());
+        assert!(validate_pattern(DisabledRulePatternKind::Regex, "(").is_err());
+        assert!(matches!(
+            validate_pattern(DisabledRulePatternKind::Wildcard, ""),
+            Err(FLMError::FieldIsEmpty("pattern"))
+        ));
+    }
+}
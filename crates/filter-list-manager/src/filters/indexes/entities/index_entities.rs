@@ -1,12 +1,13 @@
 #![allow(non_snake_case)]
 
+use crate::manager::models::filter_trust_level::FilterTrustLevel;
 use crate::manager::models::FilterId;
 use chrono::Utc;
 use serde::Deserialize;
 
 use crate::storage::entities::{
     filter::filter_entity::FilterEntity, filter_filter_tag_entity::FilterFilterTagEntity,
-    filter_locale_entity::FilterLocaleEntity,
+    filter_locale_entity::FilterLocaleEntity, filter_mirror_url_entity::FilterMirrorUrlEntity,
 };
 
 /// Necessary filter representation from index
@@ -27,6 +28,12 @@ pub(crate) struct FilterIndexEntity {
     pub(crate) timeUpdated: chrono::DateTime<Utc>,
     pub(crate) languages: Vec<String>,
     pub(crate) tags: Vec<i32>,
+    /// Alternate download urls, which are requested in order if `downloadUrl` is unreachable
+    #[serde(default)]
+    pub(crate) mirrorUrls: Vec<String>,
+    /// Trust level of the filter: `full`, `high` or `low`
+    #[serde(default)]
+    pub(crate) trustLevel: Option<String>,
 }
 
 impl FilterIndexEntity {
@@ -45,7 +52,16 @@ impl FilterIndexEntity {
         filter_list.download_url = self.downloadUrl;
         filter_list.subscription_url = self.subscriptionUrl;
         filter_list.display_number = self.displayNumber;
-        filter_list.is_trusted = true;
+        // Only `high` and `low` trust levels make filter untrusted.
+        // Registry filters without a known trust level stay trusted, as before trust levels
+        filter_list.trust_level = self
+            .trustLevel
+            .as_deref()
+            .and_then(FilterTrustLevel::from_index_value);
+        filter_list.is_trusted = filter_list
+            .trust_level
+            .as_ref()
+            .is_none_or(FilterTrustLevel::is_trusted);
         filter_list.expires = self.expires;
         filter_list.homepage = self.homepage;
 
@@ -67,6 +83,14 @@ impl FilterIndexEntity {
                     tag_id,
                 })
                 .collect(),
+            mirror_urls: self
+                .mirrorUrls
+                .into_iter()
+                .map(|url| FilterMirrorUrlEntity {
+                    filter_id: self.filterId,
+                    url,
+                })
+                .collect(),
         }
     }
 }
@@ -76,11 +100,13 @@ pub(crate) struct FilterIndexEntityComponents {
     pub(crate) filter: FilterEntity,
     pub(crate) locales: Vec<FilterLocaleEntity>,
     pub(crate) tags: Vec<FilterFilterTagEntity>,
+    pub(crate) mirror_urls: Vec<FilterMirrorUrlEntity>,
 }
 
 #[cfg(test)]
 mod tests {
     use crate::filters::indexes::entities::IndexEntity;
+    use crate::FilterTrustLevel;
 
     #[test]
     fn test_that_deserialization_allow_unknown_fields() {
@@ -91,4 +117,97 @@ mod tests {
         assert!(json_string.contains("\"trustLevel\": \"low\""));
         assert!(index.filters.len() > 0);
     }
+
+    #[test]
+    fn test_trust_level_is_mapped_onto_is_trusted() {
+        let json_string = include_str!("../../../../tests/fixtures/filters.json");
+        let index = serde_json::from_str::<IndexEntity>(json_string).unwrap();
+
+        for filter in index.filters {
+            let trust_level = filter.trustLevel.clone();
+            let entity = filter.into_storage_entities().filter;
+
+            match trust_level.as_deref() {
+                Some("full") => {
+                    assert_eq!(entity.trust_level, Some(FilterTrustLevel::Full));
+                    assert!(entity.is_trusted);
+                }
+                Some("high") => {
+                    assert_eq!(entity.trust_level, Some(FilterTrustLevel::High));
+                    assert!(!entity.is_trusted);
+                }
+                Some("low") => {
+                    assert_eq!(entity.trust_level, Some(FilterTrustLevel::Low));
+                    assert!(!entity.is_trusted);
+                }
+                _ => {
+                    assert_eq!(entity.trust_level, None);
+                    assert!(entity.is_trusted);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mirror_urls_are_optional() {
+        let json_string = r#"{
+            "groups": [],
+            "tags": [],
+            "filters": [
+                {
+                    "filterId": 1,
+                    "name": "First",
+                    "description": "",
+                    "homepage": "",
+                    "expires": 86400,
+                    "displayNumber": 1,
+                    "groupId": 1,
+                    "downloadUrl": "https://example.com/1.txt",
+                    "subscriptionUrl": "https://example.com/1.txt",
+                    "deprecated": false,
+                    "version": "1.0.0.0",
+                    "timeUpdated": "2024-01-01T00:00:00+0000",
+                    "languages": [],
+                    "tags": [],
+                    "mirrorUrls": ["https://mirror-1.example.com/1.txt", "https://mirror-2.example.com/1.txt"]
+                },
+                {
+                    "filterId": 2,
+                    "name": "Second",
+                    "description": "",
+                    "homepage": "",
+                    "expires": 86400,
+                    "displayNumber": 2,
+                    "groupId": 1,
+                    "downloadUrl": "https://example.com/2.txt",
+                    "subscriptionUrl": "https://example.com/2.txt",
+                    "deprecated": false,
+                    "version": "1.0.0.0",
+                    "timeUpdated": "2024-01-01T00:00:00+0000",
+                    "languages": [],
+                    "tags": []
+                }
+            ]
+        }"#;
+
+        let mut index = serde_json::from_str::<IndexEntity>(json_string).unwrap();
+        let second = index.filters.pop().unwrap().into_storage_entities();
+        let first = index.filters.pop().unwrap().into_storage_entities();
+
+        assert!(second.mirror_urls.is_empty());
+        // Filters without trust level are trusted
+        assert_eq!(second.filter.trust_level, None);
+        assert!(second.filter.is_trusted);
+        assert_eq!(
+            first
+                .mirror_urls
+                .into_iter()
+                .map(|entity| (entity.filter_id, entity.url))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, String::from("https://mirror-1.example.com/1.txt")),
+                (1, String::from("https://mirror-2.example.com/1.txt")),
+            ]
+        );
+    }
 }
@@ -4,13 +4,13 @@ pub(crate) mod index_localisation_entities;
 use crate::filters::indexes::entities::index_localisation_entities::{
     FilterLanguageMeta, GroupLanguageMeta, TagLanguageMeta,
 };
-use crate::manager::models::FilterId;
 use crate::storage::entities::localisation::filter_group_localisation_entity::FilterGroupLocalisationEntity;
 use crate::storage::entities::localisation::filter_localisation_entity::FilterLocalisationEntity;
 use crate::storage::entities::localisation::filter_tag_localisation_entity::FilterTagLocalisationEntity;
 use crate::storage::entities::{
     filter_group_entity::FilterGroupEntity, filter_tag_entity::FilterTagEntity,
 };
+use crate::storage::registry_id_namespace::RegistryIdNamespace;
 use crate::{FLMError, FLMResult};
 use index_entities::FilterIndexEntity;
 use serde::Deserialize;
@@ -35,14 +35,51 @@ pub(crate) struct IndexI18NEntity {
     pub(crate) filters: HashMap<String, HashMap<FilterLanguageCode, FilterLanguageMeta>>,
 }
 
+impl IndexEntity {
+    /// Moves ids of groups, tags and filters into the `namespace` of the registry
+    pub(in crate::filters::indexes) fn move_to_namespace(
+        &mut self,
+        namespace: RegistryIdNamespace,
+    ) {
+        for group in self.groups.iter_mut() {
+            group.group_id = namespace.to_database_id(group.group_id);
+        }
+
+        for tag in self.tags.iter_mut() {
+            tag.tag_id = namespace.to_database_id(tag.tag_id);
+        }
+
+        for filter in self.filters.iter_mut() {
+            filter.filterId = namespace.to_database_id(filter.filterId);
+            filter.groupId = namespace.to_database_id(filter.groupId);
+            for tag_id in filter.tags.iter_mut() {
+                *tag_id = namespace.to_database_id(*tag_id);
+            }
+        }
+    }
+}
+
+/// Parses id of localised entity and moves it into the `namespace`.
+/// Returns [`None`] for ids, which cannot belong to the registry
+fn parse_namespaced_id(id: &str, namespace: RegistryIdNamespace) -> FLMResult<Option<i32>> {
+    let database_id = id
+        .parse::<i32>()
+        .map_err(FLMError::from_display)?
+        .checked_add(namespace.to_database_id(0))
+        .filter(|database_id| namespace.contains(*database_id));
+
+    Ok(database_id)
+}
+
 impl IndexI18NEntity {
-    /// Exchanges `this` object onto storage entities
+    /// Exchanges `this` object onto storage entities, with ids moved into the `namespace`
     ///
     /// # Failure
     ///
     /// This function panics if problem will be encountered
     pub(in crate::filters::indexes) fn exchange(
         self,
+        namespace: RegistryIdNamespace,
     ) -> FLMResult<(
         Vec<FilterGroupLocalisationEntity>,
         Vec<FilterTagLocalisationEntity>,
@@ -50,9 +87,12 @@ impl IndexI18NEntity {
     )> {
         let mut group_vec: Vec<FilterGroupLocalisationEntity> = vec![];
         for (group_id, lang_map) in self.groups {
+            let Some(group_id) = parse_namespaced_id(&group_id, namespace)? else {
+                continue;
+            };
             for (language_code, meta) in lang_map {
                 group_vec.push(FilterGroupLocalisationEntity {
-                    group_id: group_id.parse::<i32>().map_err(FLMError::from_display)?,
+                    group_id,
                     lang: language_code,
                     name: meta.name,
                 });
@@ -61,9 +101,12 @@ impl IndexI18NEntity {
 
         let mut tags_vec: Vec<FilterTagLocalisationEntity> = vec![];
         for (tag_id, lang_map) in self.tags {
+            let Some(tag_id) = parse_namespaced_id(&tag_id, namespace)? else {
+                continue;
+            };
             for (language_code, meta) in lang_map {
                 tags_vec.push(FilterTagLocalisationEntity {
-                    tag_id: tag_id.parse::<i32>().map_err(FLMError::from_display)?,
+                    tag_id,
                     lang: language_code,
                     name: meta.name,
                     description: meta.description,
@@ -73,11 +116,12 @@ impl IndexI18NEntity {
 
         let mut filters_vec: Vec<FilterLocalisationEntity> = vec![];
         for (filter_id, lang_map) in self.filters {
+            let Some(filter_id) = parse_namespaced_id(&filter_id, namespace)? else {
+                continue;
+            };
             for (language_code, meta) in lang_map {
                 filters_vec.push(FilterLocalisationEntity {
-                    filter_id: filter_id
-                        .parse::<FilterId>()
-                        .map_err(FLMError::from_display)?,
+                    filter_id,
                     lang: language_code,
                     name: meta.name,
                     description: meta.description,
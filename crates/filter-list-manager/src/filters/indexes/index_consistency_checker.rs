@@ -1,5 +1,5 @@
 use crate::filters::indexes::entities::IndexEntity;
-use crate::{FLMError, FLMResult};
+use crate::{FLMError, FLMResult, FILTER_REGISTRY_ID_NAMESPACE_SIZE};
 use std::collections::HashSet;
 
 #[allow(clippy::bool_comparison)]
@@ -13,6 +13,29 @@ pub(super) fn check_consistency(index: &IndexEntity) -> FLMResult<()> {
     let mut existing_groups_ids: HashSet<i32> = HashSet::new();
     let mut download_urls_set: HashSet<&str> = HashSet::new();
 
+    // Ids must fit into the namespace of a filter registry
+    if let Some(group) = index
+        .groups
+        .iter()
+        .find(|group| group.group_id >= FILTER_REGISTRY_ID_NAMESPACE_SIZE)
+    {
+        return FLMError::make_err(format!(
+            "[IDX Consistency] Group id must be < {}: \"{}\"",
+            FILTER_REGISTRY_ID_NAMESPACE_SIZE, group.group_id
+        ));
+    }
+
+    if let Some(tag) = index
+        .tags
+        .iter()
+        .find(|tag| tag.tag_id >= FILTER_REGISTRY_ID_NAMESPACE_SIZE)
+    {
+        return FLMError::make_err(format!(
+            "[IDX Consistency] Tag id must be < {}: \"{}\"",
+            FILTER_REGISTRY_ID_NAMESPACE_SIZE, tag.tag_id
+        ));
+    }
+
     for filter in &index.filters {
         if filter.filterId <= 0 {
             return FLMError::make_err(format!(
@@ -21,6 +44,13 @@ pub(super) fn check_consistency(index: &IndexEntity) -> FLMResult<()> {
             ));
         }
 
+        if filter.filterId >= FILTER_REGISTRY_ID_NAMESPACE_SIZE {
+            return FLMError::make_err(format!(
+                "[IDX Consistency] Filter id must be < {}: \"{}\"",
+                FILTER_REGISTRY_ID_NAMESPACE_SIZE, filter.filterId
+            ));
+        }
+
         if download_urls_set.insert(filter.downloadUrl.as_str()) == false {
             return FLMError::make_err(format!(
                 "[IDX Consistency] Two or more filters have the same download_url: \"{}\"",
@@ -115,6 +145,22 @@ mod tests {
             });
         }
 
+        {
+            // Filter id out of registry namespace
+            let mut test_index = index.clone();
+            test_index.filters[0].filterId = 1_000_000;
+
+            let actual = check_consistency(&test_index);
+
+            assert_eq!(
+                actual,
+                FLMError::make_err(format!(
+                    "[IDX Consistency] Filter id must be < 1000000: \"{}\"",
+                    1_000_000
+                ))
+            );
+        }
+
         {
             // Duplicated download_url
             let mut test_index = index.clone();
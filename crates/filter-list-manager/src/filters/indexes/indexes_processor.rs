@@ -1,18 +1,34 @@
 use super::entities::{IndexEntity, IndexI18NEntity};
 use crate::filters::indexes::index_consistency_checker::check_consistency;
 use crate::io::http::blocking_client::BlockingClient;
+use crate::io::http::cache_validators::CacheValidators;
 use crate::io::url_schemes::UrlSchemes;
 use crate::io::{get_scheme, read_file_by_url};
+use crate::manager::models::update_progress::UpdateProgressStage;
 use crate::manager::models::{MovedFilterInfo, PullMetadataResult};
+use crate::manager::update_progress_reporter::UpdateProgressReporter;
 use crate::storage::entities::filter::filter_entity::FilterEntity;
 use crate::storage::entities::filter_filter_tag_entity::FilterFilterTagEntity;
 use crate::storage::entities::filter_locale_entity::FilterLocaleEntity;
+use crate::storage::entities::filter_mirror_url_entity::FilterMirrorUrlEntity;
+use crate::storage::entities::filter_registry_entity::FilterRegistryEntity;
+use crate::storage::entities::http_cache_validators_entity::HttpCacheValidatorsEntity;
+use crate::storage::registry_id_namespace::{RegistryIdNamespace, MAX_FILTER_REGISTRY_ID};
 use crate::storage::repositories::db_metadata_repository::DBMetadataRepository;
+use crate::storage::repositories::disabled_rule_patterns_repository::DisabledRulePatternsRepository;
 use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
+use crate::storage::repositories::filter_mirror_urls_repository::FilterMirrorUrlsRepository;
+use crate::storage::repositories::filter_pins_repository::FilterPinsRepository;
+use crate::storage::repositories::filter_registries_repository::FilterRegistriesRepository;
+use crate::storage::repositories::filter_update_failures_repository::FilterUpdateFailuresRepository;
+use crate::storage::repositories::filter_versions_repository::FilterVersionsRepository;
+use crate::storage::repositories::http_cache_validators_repository::{
+    HttpCacheValidatorsRepository, MapUrlOnCacheValidators,
+};
 use crate::storage::repositories::rules_list_repository::RulesListRepository;
-use crate::storage::repositories::BulkDeleteRepository;
-use crate::storage::spawn_transaction;
+use crate::storage::repositories::{BulkDeleteRepository, RegistryScopedRepository};
 use crate::storage::DbConnectionManager;
+use crate::storage::{spawn_transaction, with_transaction};
 use crate::utils::integrity::{derive_key_if_needed, sign_filter_count};
 use crate::{
     storage::repositories::filter_filter_tag_repository::FilterFilterTagRepository,
@@ -24,7 +40,7 @@ use crate::{
         localisation::filter_tag_localisation_repository::FilterTagLocalisationRepository,
         localisation::group_localisation_repository::GroupLocalisationRepository, Repository,
     },
-    string, Configuration, FLMError, FLMResult, FilterId, CUSTOM_FILTERS_GROUP_ID,
+    string, Configuration, FLMError, FLMResult, FilterId, FilterRegistry, CUSTOM_FILTERS_GROUP_ID,
 };
 use rusqlite::{Connection, Transaction};
 use serde::de::DeserializeOwned;
@@ -33,15 +49,25 @@ use std::mem::take;
 use std::sync::Arc;
 use std::thread::scope as thread_scope;
 
+/// Loaded index data with validators of the response, if there are any
+type LoadedData<I> = (I, Option<CacheValidators>);
+
+/// Loaded index data with the url, which has served it
+type LoadedDataWithUrl<I> = (Option<LoadedData<I>>, String);
+
 /// The class responsible for updating filters and rules from indexes
 pub struct IndexesProcessor<'a> {
     connection_source: &'a DbConnectionManager,
     loaded_index: Option<IndexEntity>,
     loaded_index_i18n: Option<IndexI18NEntity>,
+    /// Validators of loaded indices responses. Will be saved with indices
+    loaded_cache_validators: Vec<HttpCacheValidatorsEntity>,
     http_client: Arc<BlockingClient>,
     /// Derived integrity key for resigning filter metadata after index merge.
     /// `None` when integrity protection is disabled in configuration.
     derived_key: Option<[u8; 32]>,
+    /// Range of ids of the syncing registry
+    namespace: RegistryIdNamespace,
 }
 
 /// Public methods
@@ -55,26 +81,73 @@ impl<'a> IndexesProcessor<'a> {
             connection_source,
             loaded_index: None,
             loaded_index_i18n: None,
+            loaded_cache_validators: vec![],
             http_client: Arc::new(BlockingClient::new(configuration)?),
             derived_key: derive_key_if_needed(configuration),
+            namespace: RegistryIdNamespace::new(0),
         })
     }
 
-    /// Synchronizes filters metadata (with groups, locales, etc...) with remote server
+    /// Synchronizes filters metadata (with groups, locales, etc...) of the registry with remote server
     /// If database is empty or is not exist it will be created.
+    /// Indices are requested conditionally, if they were loaded before, so
+    /// nothing will be changed if server says that both indices are not modified.
+    /// Filters, groups and tags of other registries are left untouched.
     ///
-    /// * `index_url` - Remote server URL of filters index
-    /// * `index_locales_url` - Remote server URL of filters index localisation info
-    /// * `with_filters` - Filters from index will be downloaded after
-    pub fn sync_metadata(
+    /// * `registry` - Registry with URLs of filters index and its localisation info.
+    ///   Mirrors are requested in order, if the previous url fails with network error
+    /// * `progress_reporter` - Receives index progress events. If operation is
+    ///   cancelled after loading, indices won't be saved
+    pub(crate) fn sync_metadata(
         &mut self,
-        index_url: &str,
-        index_locales_url: &str,
+        registry: &FilterRegistry,
+        progress_reporter: &UpdateProgressReporter,
     ) -> FLMResult<PullMetadataResult> {
+        let index_url = registry.metadata_url.as_str();
+        let index_urls = with_mirrors(index_url, &registry.metadata_mirror_urls);
+        // Localisations are optional, so their mirrors too
+        let index_locales_urls = if registry.metadata_locales_url.is_empty() {
+            vec![]
+        } else {
+            with_mirrors(
+                &registry.metadata_locales_url,
+                &registry.metadata_locales_mirror_urls,
+            )
+        };
+
+        self.namespace = self.resolve_registry_namespace(&registry.name)?;
+
+        let stored_cache_validators = self.connection_source.execute_db(|conn: Connection| {
+            let urls = index_urls
+                .iter()
+                .chain(index_locales_urls.iter())
+                .map(String::as_str)
+                .collect::<Vec<&str>>();
+
+            HttpCacheValidatorsRepository::new()
+                .select_map(&conn, &urls)
+                .map_err(FLMError::from_database)
+        })?;
+
+        progress_reporter.notify(None, index_url, UpdateProgressStage::Downloading);
+
         // Load indices and check consistency
-        self.fetch_indices(string!(index_url), string!(index_locales_url))?;
+        let are_indices_modified = self
+            .fetch_indices(index_urls, index_locales_urls, stored_cache_validators)
+            .inspect_err(|why| progress_reporter.notify_failed(None, index_url, why.to_string()))?;
+
+        if !are_indices_modified {
+            progress_reporter.notify(None, index_url, UpdateProgressStage::NotChanged);
+
+            return Ok(PullMetadataResult::new());
+        }
 
-        self.connection_source
+        if progress_reporter.is_cancelled() {
+            return Ok(PullMetadataResult::new());
+        }
+
+        let result = self
+            .connection_source
             .execute_db(move |mut conn: Connection| {
                 let filters_optional = FilterRepository::new()
                     .select_filters_except_bootstrapped(&conn)
@@ -85,10 +158,26 @@ impl<'a> IndexesProcessor<'a> {
                 } else {
                     self.save_indices_on_empty_database(&mut conn)
                 }
-            })
+            });
+
+        match result {
+            Ok(_) => progress_reporter.notify(None, index_url, UpdateProgressStage::Saved),
+            Err(ref why) => progress_reporter.notify_failed(None, index_url, why.to_string()),
+        }
+
+        result
     }
 }
 
+/// Prepends `url` to its `mirror_urls`
+fn with_mirrors(url: &str, mirror_urls: &[String]) -> Vec<String> {
+    let mut urls = Vec::with_capacity(mirror_urls.len() + 1);
+    urls.push(string!(url));
+    urls.extend_from_slice(mirror_urls);
+
+    urls
+}
+
 /// Save strategies
 impl IndexesProcessor<'_> {
     /// Saves new indexes into existing database
@@ -101,6 +190,7 @@ impl IndexesProcessor<'_> {
         let mut new_or_updated_filters: Vec<FilterEntity> = vec![];
         let mut tags_of_filters: Vec<Vec<FilterFilterTagEntity>> = vec![];
         let mut locales_of_filters: Vec<Vec<FilterLocaleEntity>> = vec![];
+        let mut mirror_urls_of_filters: Vec<Vec<FilterMirrorUrlEntity>> = vec![];
         let mut index_filters_map: HashMap<FilterId, FilterEntity> = HashMap::new();
         let mut out = PullMetadataResult::new();
 
@@ -116,6 +206,7 @@ impl IndexesProcessor<'_> {
 
             tags_of_filters.push(storage_entities.tags);
             locales_of_filters.push(storage_entities.locales);
+            mirror_urls_of_filters.push(storage_entities.mirror_urls);
             index_filters_map.insert(filter_id, storage_entities.filter);
         }
 
@@ -140,6 +231,11 @@ impl IndexesProcessor<'_> {
                 continue;
             }
 
+            // Filter of another registry
+            if !self.namespace.contains(filter_id) {
+                continue;
+            }
+
             if let Some(filter_from_index) = index_filters_map.remove(&filter_id) {
                 filter.display_number = filter_from_index.display_number;
                 filter.title = filter_from_index.title;
@@ -149,6 +245,8 @@ impl IndexesProcessor<'_> {
                 filter.download_url = filter_from_index.download_url;
                 filter.last_update_time = filter_from_index.last_update_time;
                 filter.subscription_url = filter_from_index.subscription_url;
+                filter.is_trusted = filter_from_index.is_trusted;
+                filter.trust_level = filter_from_index.trust_level;
 
                 new_or_updated_filters.push(filter);
             } else {
@@ -156,6 +254,8 @@ impl IndexesProcessor<'_> {
                 if filter.is_enabled {
                     // Filter id will be updated right before insert
                     filter.group_id = CUSTOM_FILTERS_GROUP_ID;
+                    // Custom filters have no trust level
+                    filter.trust_level = None;
 
                     new_or_updated_filters.push(filter);
                 } else {
@@ -181,28 +281,37 @@ impl IndexesProcessor<'_> {
             let rules_repository = RulesListRepository::new();
             let includes_repository = FilterIncludesRepository::new();
             let locales_repository = FilterLocaleRepository::new();
+            let mirror_urls_repository = FilterMirrorUrlsRepository::new();
             let filter_filter_tag_repository = FilterFilterTagRepository::new();
             let filter_localisation_repository = FilterLocalisationRepository::new();
             let filter_tag_localisation_repository = FilterTagLocalisationRepository::new();
             let group_localisation_repository = GroupLocalisationRepository::new();
 
-            // Clear filter dependencies
-            locales_repository.clear(transaction)?;
-            group_repo.delete_index_groups(transaction)?;
-            tags_repo.clear(transaction)?;
+            // Clear filter dependencies of this registry
+            locales_repository.delete_for_registry(transaction, self.namespace)?;
+            mirror_urls_repository.delete_for_registry(transaction, self.namespace)?;
+            group_repo.delete_for_registry(transaction, self.namespace)?;
+            tags_repo.delete_for_registry(transaction, self.namespace)?;
             // Remove old filters mappings and non-needed filters itself
             filter_filter_tag_repository.bulk_delete(transaction, &filters_must_be_deleted)?;
             rules_repository.bulk_delete(transaction, &filters_must_be_deleted)?;
+            FilterUpdateFailuresRepository::new()
+                .bulk_delete(transaction, &filters_must_be_deleted)?;
+            FilterVersionsRepository::new()
+                .delete_for_filters(transaction, &filters_must_be_deleted)?;
+            FilterPinsRepository::new().bulk_delete(transaction, &filters_must_be_deleted)?;
+            DisabledRulePatternsRepository::new()
+                .bulk_delete(transaction, &filters_must_be_deleted)?;
             includes_repository.delete_for_filters(
                 transaction,
                 filters_must_be_deleted.iter(),
                 filters_must_be_deleted.len(),
             )?;
             filter_repository.bulk_delete(transaction, &filters_must_be_deleted)?;
-            // Clear filter dependencies localisations
-            filter_localisation_repository.clear(transaction)?;
-            filter_tag_localisation_repository.clear(transaction)?;
-            group_localisation_repository.clear(transaction)?;
+            // Clear filter dependencies localisations of this registry
+            filter_localisation_repository.delete_for_registry(transaction, self.namespace)?;
+            filter_tag_localisation_repository.delete_for_registry(transaction, self.namespace)?;
+            group_localisation_repository.delete_for_registry(transaction, self.namespace)?;
 
             // Save new or updated filters and all filter deps
             locales_repository.insert(
@@ -212,6 +321,13 @@ impl IndexesProcessor<'_> {
                     .flatten()
                     .collect::<Vec<FilterLocaleEntity>>(),
             )?;
+            mirror_urls_repository.insert(
+                transaction,
+                &mirror_urls_of_filters
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<FilterMirrorUrlEntity>>(),
+            )?;
             filter_filter_tag_repository.insert(
                 transaction,
                 &tags_of_filters
@@ -262,6 +378,7 @@ impl IndexesProcessor<'_> {
 
         // Save localisations
         self.save_index_localisations(&transaction)?;
+        self.save_cache_validators(&transaction)?;
 
         transaction.commit().map_err(FLMError::from_database)?;
 
@@ -284,6 +401,7 @@ impl IndexesProcessor<'_> {
             let filter_repository = FilterRepository::new();
             let mut locales = Vec::new();
             let mut tags = Vec::new();
+            let mut mirror_urls = Vec::new();
 
             for filter_index_entity in index.filters {
                 if filter_index_entity.deprecated {
@@ -295,6 +413,7 @@ impl IndexesProcessor<'_> {
                 filters.push(storage_entities.filter);
                 locales.push(storage_entities.locales);
                 tags.push(storage_entities.tags);
+                mirror_urls.push(storage_entities.mirror_urls);
             }
 
             let added_filters = filters
@@ -316,6 +435,12 @@ impl IndexesProcessor<'_> {
                 .collect::<Vec<FilterFilterTagEntity>>();
             FilterFilterTagRepository::new().insert(transaction, &flattened_tags)?;
 
+            let flattened_mirror_urls = mirror_urls
+                .into_iter()
+                .flatten()
+                .collect::<Vec<FilterMirrorUrlEntity>>();
+            FilterMirrorUrlsRepository::new().insert(transaction, &flattened_mirror_urls)?;
+
             self.resign_integrity_signatures_if_needed(
                 transaction,
                 &filter_repository,
@@ -327,6 +452,7 @@ impl IndexesProcessor<'_> {
         .map_err(FLMError::from_database)?;
 
         self.save_index_localisations(&transaction)?;
+        self.save_cache_validators(&transaction)?;
         transaction.commit().map_err(FLMError::from_database)?;
 
         Ok(PullMetadataResult::new_with_added_filters(added_filters))
@@ -358,28 +484,47 @@ impl IndexesProcessor<'_> {
 impl IndexesProcessor<'_> {
     /// Fetches indices from remote server, checks index consistency fills `self` object fields.
     ///
-    /// * `index_url` - Remote server URL of filters index
-    /// * `index_locales_url` - Remote server URL of filters index localisation info
+    /// * `index_urls` - Remote server URL of filters index, followed by its mirrors
+    /// * `index_locales_urls` - Remote server URL of filters index localisation info,
+    ///   followed by its mirrors. Empty list means that localisations won't be loaded
+    /// * `stored_cache_validators` - Validators of previous indices responses
+    ///
+    /// Returns `false` if server says that indices are not modified since the last load.
     ///
     /// # Failure
     ///
-    /// May return an [`Err`] if the request to the remote server is unsuccessful
+    /// May return an [`Err`] if the request to the remote server and all its mirrors is unsuccessful
     /// or if the index consistency is violated.
-    fn fetch_indices(&mut self, index_url: String, index_locales_url: String) -> FLMResult<()> {
+    fn fetch_indices(
+        &mut self,
+        index_urls: Vec<String>,
+        index_locales_urls: Vec<String>,
+        stored_cache_validators: MapUrlOnCacheValidators,
+    ) -> FLMResult<bool> {
         let http_client = Arc::clone(&self.http_client);
 
-        let index_result: FLMResult<IndexEntity>;
-        let mut index_localisations_result: Option<FLMResult<IndexI18NEntity>> = None;
+        let index_result: FLMResult<LoadedDataWithUrl<IndexEntity>>;
+        let mut index_localisations_result: Option<FLMResult<LoadedDataWithUrl<IndexI18NEntity>>> =
+            None;
 
         // Localizations are optional
-        if !index_locales_url.is_empty() {
+        if !index_locales_urls.is_empty() {
             let scope = thread_scope(|s| {
                 let client1 = Arc::clone(&http_client);
-                let h1 = s.spawn(move || Self::load_data::<IndexEntity>(&index_url, &client1));
+                let (urls1, validators1) = (index_urls.as_slice(), &stored_cache_validators);
+                let h1 = s.spawn(move || {
+                    Self::load_data_with_fallback::<IndexEntity>(urls1, &client1, Some(validators1))
+                });
 
                 let client2 = Arc::clone(&http_client);
+                let (urls2, validators2) =
+                    (index_locales_urls.as_slice(), &stored_cache_validators);
                 let h2 = s.spawn(move || {
-                    Self::load_data::<IndexI18NEntity>(&index_locales_url, &client2)
+                    Self::load_data_with_fallback::<IndexI18NEntity>(
+                        urls2,
+                        &client2,
+                        Some(validators2),
+                    )
                 });
 
                 (h1.join(), h2.join())
@@ -392,26 +537,97 @@ impl IndexesProcessor<'_> {
                 FLMError::from_display("Thread panicked while loading index localisations")
             })?);
         } else {
-            index_result = Self::load_data::<IndexEntity>(&index_url, &http_client);
+            index_result = Self::load_data_with_fallback::<IndexEntity>(
+                &index_urls,
+                &http_client,
+                Some(&stored_cache_validators),
+            );
+        }
+
+        let mut index_data = index_result?;
+        let mut index_localisations_data = index_localisations_result.transpose()?;
+
+        // Nothing has changed since the last load
+        if index_data.0.is_none()
+            && index_localisations_data
+                .as_ref()
+                .is_none_or(|(data, _)| data.is_none())
+        {
+            return Ok(false);
+        }
+
+        // Only one of the indices is not modified, but we need both of them to save
+        if index_data.0.is_none() {
+            index_data =
+                Self::load_data_with_fallback::<IndexEntity>(&index_urls, &http_client, None)?;
+        }
+        if let Some((None, _)) = index_localisations_data {
+            index_localisations_data = Some(Self::load_data_with_fallback::<IndexI18NEntity>(
+                &index_locales_urls,
+                &http_client,
+                None,
+            )?);
         }
 
         // Index operations
-        let index = index_result?;
+        let (Some((mut index, index_validators)), index_url) = index_data else {
+            return FLMError::make_err("Index could not be loaded");
+        };
         check_consistency(&index)?;
+        index.move_to_namespace(self.namespace);
         self.loaded_index = Some(index);
+        self.collect_cache_validators(index_url, index_validators);
 
         // Localizations operations
-        if let Some(result) = index_localisations_result {
-            let index_localisations = result?;
+        if let Some(data) = index_localisations_data {
+            let (Some((index_localisations, index_locales_validators)), index_locales_url) = data
+            else {
+                return FLMError::make_err("Index localisations could not be loaded");
+            };
 
             self.loaded_index_i18n = Some(index_localisations);
+            self.collect_cache_validators(index_locales_url, index_locales_validators);
         }
 
-        Ok(())
+        Ok(true)
+    }
+
+    /// Loads indices data from the first of `urls`, which doesn't fail with [`FLMError::Network`].
+    /// If all of them fail, returns the error of the first one
+    ///
+    /// * `cache_validators` - Validators of previous responses, mapped by url
+    fn load_data_with_fallback<I>(
+        urls: &[String],
+        http_client: &BlockingClient,
+        cache_validators: Option<&MapUrlOnCacheValidators>,
+    ) -> FLMResult<LoadedDataWithUrl<I>>
+    where
+        I: DeserializeOwned,
+    {
+        let mut first_network_error = None;
+
+        for url in urls {
+            let validators = cache_validators.and_then(|map| map.get(url));
+
+            match Self::load_data::<I>(url, http_client, validators) {
+                Err(why @ FLMError::Network(_)) => {
+                    first_network_error.get_or_insert(why);
+                }
+                result => return result.map(|data| (data, url.clone())),
+            }
+        }
+
+        Err(first_network_error.unwrap_or_else(|| FLMError::from_display("Index url is empty")))
     }
 
     /// Loads indices data
-    fn load_data<I>(url: &str, http_client: &BlockingClient) -> FLMResult<I>
+    ///
+    /// Returns [`None`] if `cache_validators` were passed and server says that data is not modified
+    fn load_data<I>(
+        url: &str,
+        http_client: &BlockingClient,
+        cache_validators: Option<&CacheValidators>,
+    ) -> FLMResult<Option<LoadedData<I>>>
     where
         I: DeserializeOwned,
     {
@@ -420,22 +636,74 @@ impl IndexesProcessor<'_> {
         match scheme {
             UrlSchemes::File => {
                 let contents = read_file_by_url(url).map_err::<FLMError, _>(Into::into)?;
-                serde_json::from_str::<I>(&contents).map_err(FLMError::from_display)
-            }
-            UrlSchemes::Https | UrlSchemes::Http => {
-                http_client.get_json::<I>(url).map_err(FLMError::Network)
+                serde_json::from_str::<I>(&contents)
+                    .map(|data| Some((data, None)))
+                    .map_err(FLMError::from_display)
             }
+            UrlSchemes::Https | UrlSchemes::Http => http_client
+                .get_json_conditionally::<I>(url, cache_validators)
+                .map(|loaded| {
+                    loaded.map(|(data, validators)| {
+                        (data, Some(validators).filter(|value| !value.is_empty()))
+                    })
+                })
+                .map_err(FLMError::Network),
             _ => FLMError::make_err(format!("Unknown scheme for url: {}", url)),
         }
     }
+
+    /// Remembers response validators of loaded index for saving
+    fn collect_cache_validators(&mut self, url: String, validators: Option<CacheValidators>) {
+        if let Some(validators) = validators {
+            self.loaded_cache_validators
+                .push(HttpCacheValidatorsEntity::make(url, validators));
+        }
+    }
 }
 
 /// Misc methods
 impl IndexesProcessor<'_> {
+    /// Gets id namespace of the registry by its name. Unknown registry gets the next free one
+    fn resolve_registry_namespace(&self, name: &str) -> FLMResult<RegistryIdNamespace> {
+        self.connection_source.execute_db(|mut conn: Connection| {
+            let repository = FilterRegistriesRepository::new();
+
+            if let Some(registry_id) = repository
+                .select_id_by_name(&conn, name)
+                .map_err(FLMError::from_database)?
+            {
+                return Ok(RegistryIdNamespace::new(registry_id));
+            }
+
+            let registry_id = repository
+                .select_max_id(&conn)
+                .map_err(FLMError::from_database)?
+                + 1;
+            if registry_id > MAX_FILTER_REGISTRY_ID {
+                return FLMError::make_err(format!(
+                    "Cannot register filter registry \"{}\": too many registries",
+                    name
+                ));
+            }
+
+            with_transaction(&mut conn, |transaction: &Transaction| {
+                repository.insert(
+                    transaction,
+                    &[FilterRegistryEntity {
+                        registry_id,
+                        name: string!(name),
+                    }],
+                )
+            })?;
+
+            Ok(RegistryIdNamespace::new(registry_id))
+        })
+    }
+
     /// Saves data from index localisation
     fn save_index_localisations(&mut self, transaction: &Transaction) -> FLMResult<()> {
         if let Some(localisations) = take(&mut self.loaded_index_i18n) {
-            let (group_vec, tags_vec, filters_vec) = localisations.exchange()?;
+            let (group_vec, tags_vec, filters_vec) = localisations.exchange(self.namespace)?;
 
             GroupLocalisationRepository::new()
                 .insert(transaction, &group_vec)
@@ -453,6 +721,13 @@ impl IndexesProcessor<'_> {
         Ok(())
     }
 
+    /// Saves validators of loaded indices responses
+    fn save_cache_validators(&mut self, transaction: &Transaction) -> FLMResult<()> {
+        HttpCacheValidatorsRepository::new()
+            .insert(transaction, &take(&mut self.loaded_cache_validators))
+            .map_err(FLMError::from_database)
+    }
+
     /// Tries to take index value from `self` object
     ///
     /// # Failure
@@ -485,8 +760,10 @@ impl<'a> IndexesProcessor<'a> {
             connection_source,
             loaded_index: Some(loaded_index),
             loaded_index_i18n: Some(loaded_index_i18n),
+            loaded_cache_validators: vec![],
             http_client: Arc::new(BlockingClient::new(&TEST_CONFIG).unwrap()),
             derived_key: derive_key_if_needed(&TEST_CONFIG),
+            namespace: RegistryIdNamespace::new(0),
         }
     }
 
@@ -499,10 +776,12 @@ impl<'a> IndexesProcessor<'a> {
 mod tests {
     use crate::filters::indexes::indexes_processor::IndexesProcessor;
     use crate::manager::managers::integrity_control_manager::IntegrityControlManager;
+    use crate::manager::update_progress_reporter::UpdateProgressReporter;
     use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
     use crate::storage::repositories::db_metadata_repository::DBMetadataRepository;
     use crate::storage::repositories::filter_filter_tag_repository::FilterFilterTagRepository;
     use crate::storage::repositories::filter_group_repository::FilterGroupRepository;
+    use crate::storage::repositories::filter_mirror_urls_repository::FilterMirrorUrlsRepository;
     use crate::storage::repositories::filter_repository::FilterRepository;
     use crate::storage::repositories::rules_list_repository::RulesListRepository;
     use crate::storage::repositories::Repository;
@@ -514,8 +793,9 @@ mod tests {
     use crate::utils::integrity;
     use crate::utils::memory::heap;
     use crate::{
-        string, Configuration, FLMError, FilterId, CUSTOM_FILTERS_GROUP_ID,
-        MAXIMUM_CUSTOM_FILTER_ID, MINIMUM_CUSTOM_FILTER_ID,
+        string, Configuration, FLMError, FilterId, FilterRegistry, FilterTrustLevel,
+        CUSTOM_FILTERS_GROUP_ID, MAIN_FILTER_REGISTRY_NAME, MAXIMUM_CUSTOM_FILTER_ID,
+        MINIMUM_CUSTOM_FILTER_ID,
     };
     use rand::seq::SliceRandom;
     use rand::{thread_rng, Rng};
@@ -526,6 +806,158 @@ mod tests {
 
     const DEPRECATED_FILTER_ID: FilterId = 1;
 
+    #[test]
+    fn test_save_mirror_urls_of_index_filters() {
+        let (mut index, index_localisation) = build_filters_indices_fixtures().unwrap();
+        index.filters[1].mirrorUrls = vec![
+            string!("https://mirror-1.example.com/filter.txt"),
+            string!("https://mirror-2.example.com/filter.txt"),
+        ];
+        let first_filter_id = index.filters[1].filterId;
+        let second_filter_id = index.filters[2].filterId;
+
+        let connection_source = DbConnectionManager::factory_test().unwrap();
+        unsafe {
+            connection_source.lift_up_database().unwrap();
+        }
+
+        let select_mirror_urls = || {
+            connection_source
+                .execute_db(|conn: Connection| {
+                    Ok(FilterMirrorUrlsRepository::new()
+                        .select_map(&conn, &[first_filter_id, second_filter_id])
+                        .unwrap())
+                })
+                .unwrap()
+        };
+
+        connection_source
+            .execute_db(|mut conn: Connection| {
+                IndexesProcessor::factory_test(
+                    &connection_source,
+                    index.clone(),
+                    index_localisation.clone(),
+                )
+                .save_indices_on_empty_database(&mut conn)
+            })
+            .unwrap();
+
+        let mirror_urls = select_mirror_urls();
+        assert_eq!(mirror_urls.len(), 1);
+        assert_eq!(
+            mirror_urls[&first_filter_id],
+            index.filters[1].mirrorUrls.clone()
+        );
+
+        // Mirrors are replaced with the new index ones
+        index.filters[1].mirrorUrls = vec![];
+        index.filters[2].mirrorUrls = vec![string!("https://mirror.example.com/filter.txt")];
+
+        connection_source
+            .execute_db(|mut conn: Connection| {
+                let filters = FilterRepository::new()
+                    .select_filters_except_bootstrapped(&conn)
+                    .unwrap()
+                    .unwrap();
+
+                IndexesProcessor::factory_test(
+                    &connection_source,
+                    index.clone(),
+                    index_localisation.clone(),
+                )
+                .save_index_on_existing_database(&mut conn, filters)
+            })
+            .unwrap();
+
+        let mirror_urls = select_mirror_urls();
+        assert_eq!(mirror_urls.len(), 1);
+        assert_eq!(
+            mirror_urls[&second_filter_id],
+            vec![string!("https://mirror.example.com/filter.txt")]
+        );
+    }
+
+    #[test]
+    fn test_save_trust_level_of_index_filters() {
+        let (mut index, index_localisation) = build_filters_indices_fixtures().unwrap();
+        index.filters[1].trustLevel = Some(string!("full"));
+        index.filters[2].trustLevel = Some(string!("low"));
+        index.filters[3].trustLevel = None;
+        let first_filter_id = index.filters[1].filterId;
+        let second_filter_id = index.filters[2].filterId;
+        let third_filter_id = index.filters[3].filterId;
+
+        let connection_source = DbConnectionManager::factory_test().unwrap();
+        unsafe {
+            connection_source.lift_up_database().unwrap();
+        }
+
+        let select_trust = || {
+            let filters = connection_source
+                .execute_db(|conn: Connection| {
+                    Ok(FilterRepository::new().select_mapped(&conn, None).unwrap())
+                })
+                .unwrap();
+
+            [first_filter_id, second_filter_id, third_filter_id].map(|filter_id| {
+                let filter = &filters[&filter_id];
+
+                (filter.trust_level, filter.is_trusted)
+            })
+        };
+
+        connection_source
+            .execute_db(|mut conn: Connection| {
+                IndexesProcessor::factory_test(
+                    &connection_source,
+                    index.clone(),
+                    index_localisation.clone(),
+                )
+                .save_indices_on_empty_database(&mut conn)
+            })
+            .unwrap();
+
+        assert_eq!(
+            select_trust(),
+            [
+                (Some(FilterTrustLevel::Full), true),
+                (Some(FilterTrustLevel::Low), false),
+                (None, true),
+            ]
+        );
+
+        // Trust level changes of the next sync are saved
+        index.filters[1].trustLevel = Some(string!("high"));
+        index.filters[2].trustLevel = Some(string!("full"));
+        // Unknown trust level is not saved and doesn't make filter untrusted
+        index.filters[3].trustLevel = Some(string!("unknown"));
+
+        connection_source
+            .execute_db(|mut conn: Connection| {
+                let filters = FilterRepository::new()
+                    .select_filters_except_bootstrapped(&conn)
+                    .unwrap()
+                    .unwrap();
+
+                IndexesProcessor::factory_test(
+                    &connection_source,
+                    index.clone(),
+                    index_localisation.clone(),
+                )
+                .save_index_on_existing_database(&mut conn, filters)
+            })
+            .unwrap();
+
+        assert_eq!(
+            select_trust(),
+            [
+                (Some(FilterTrustLevel::High), false),
+                (Some(FilterTrustLevel::Full), true),
+                (None, true),
+            ]
+        );
+    }
+
     #[test]
     fn test_save_indices_in_empty_db() {
         let (mut index, index_localisation) = build_filters_indices_fixtures().unwrap();
@@ -856,7 +1288,16 @@ mod tests {
         let config = Configuration::default();
         let mut processor = IndexesProcessor::factory(&connection_manager, &config).unwrap();
 
-        processor.sync_metadata(&index_url, &index_i18_url).unwrap();
+        let registry = FilterRegistry {
+            name: string!(MAIN_FILTER_REGISTRY_NAME),
+            metadata_url: index_url,
+            metadata_locales_url: index_i18_url,
+            ..FilterRegistry::default()
+        };
+
+        processor
+            .sync_metadata(&registry, &UpdateProgressReporter::default())
+            .unwrap();
     }
 
     /// Regression test for AG-* (Storage Integrity Part II).
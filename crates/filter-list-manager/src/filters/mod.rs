@@ -1,2 +1,4 @@
+pub(crate) mod disabled_rules_matcher;
 pub mod indexes;
 pub(crate) mod parser;
+pub(crate) mod rules_deduplicator;
@@ -6,6 +6,7 @@ pub(crate) mod diff_updates;
 pub(crate) mod filter_compiler;
 pub(crate) mod filter_contents_provider;
 mod filter_cursor;
+pub(crate) mod filter_linter;
 mod include_processor;
 pub(crate) mod is_rule_detector;
 pub(crate) mod metadata;
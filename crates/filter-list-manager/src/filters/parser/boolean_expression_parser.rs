@@ -88,6 +88,35 @@ impl<'out> BooleanExpressionParser<'out> {
         })
     }
 
+    /// Collects constants from `expr`, which are not defined in the compilation policy.
+    /// Such constants are evaluated as `false`
+    pub(crate) fn collect_undefined_constants<'e>(&self, expr: &'e str) -> Vec<&'e str> {
+        let mut constants = vec![];
+        let mut remainder = expr;
+
+        loop {
+            let (token, count) = BooleanExpressionParser::next_token(remainder);
+            if token.is_empty() {
+                return constants;
+            }
+
+            remainder = &remainder[count..];
+
+            if TOKEN_LIST.contains(&token) || token == "true" || token == "false" {
+                continue;
+            }
+
+            if !self
+                .policy
+                .constants
+                .iter()
+                .any(|flag| flag.as_str() == token)
+            {
+                constants.push(token);
+            }
+        }
+    }
+
     /// Parse expression
     fn expr<'a>(&'a self, expr: &'a str) -> Option<(bool, &'a str)> {
         let (mut left, mut remainder) = self.term(expr)?;
@@ -242,4 +271,17 @@ mod tests {
             assert_eq!(actual, *expected);
         });
     }
+
+    #[test]
+    fn test_collect_undefined_constants() {
+        let policy = FiltersCompilationPolicy::new(vec![String::from("windows")]);
+        let object = BooleanExpressionParser::new(&policy);
+
+        assert_eq!(
+            object.collect_undefined_constants("(windows || !mac) && (true||linux)"),
+            vec!["mac", "linux"]
+        );
+        assert!(object.collect_undefined_constants(" windows ").is_empty());
+        assert!(object.collect_undefined_constants("").is_empty());
+    }
 }
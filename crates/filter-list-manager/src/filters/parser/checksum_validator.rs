@@ -34,6 +34,53 @@ fn calculate_checksum(contents: &str) -> String {
     BASE64_STANDARD_NO_PAD.encode(digest.as_bytes())
 }
 
+/// Recalculates checksum of the contents, which already has `! Checksum` line.
+/// Returns [`None`] if checksum is not found
+#[cfg(feature = "diff-builder")]
+pub(super) fn update_checksum(contents: &str) -> Option<String> {
+    let mut normalized = String::with_capacity(contents.len());
+    let mut checksum_line_index: Option<usize> = None;
+
+    for (index, line) in contents.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+
+        if checksum_line_index.is_none()
+            && index <= HOW_MUCH_FAR_CHECKSUM_MIGHT_BE
+            && matches!(parse_checksum(trimmed), Ok((_, Some(_))))
+        {
+            checksum_line_index = Some(index);
+
+            continue;
+        }
+
+        normalized += trimmed;
+        normalized.push('\n');
+    }
+
+    // Same as in `validate_checksum`
+    if !contents.ends_with('\n') {
+        normalized.pop();
+    }
+
+    let checksum_line_index = checksum_line_index?;
+    let checksum_line = format!("! Checksum: {}", calculate_checksum(&normalized));
+
+    Some(contents.split_inclusive('\n').enumerate().fold(
+        String::with_capacity(contents.len()),
+        |mut acc, (index, line)| {
+            if index == checksum_line_index {
+                acc += &checksum_line;
+                // Keep original line terminator
+                acc += &line[line.trim_end_matches(['\r', '\n']).len()..];
+            } else {
+                acc += line;
+            }
+
+            acc
+        },
+    ))
+}
+
 /// Tries to find checksum, then tries to validate if success
 pub(super) fn validate_checksum(contents: &str) -> Result<bool, FilterParserError> {
     let mut new_str = String::new();
@@ -105,8 +152,25 @@ pub(super) fn validate_checksum(contents: &str) -> Result<bool, FilterParserErro
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "diff-builder")]
+    use super::update_checksum;
     use super::{parse_checksum, validate_checksum};
 
+    #[test]
+    #[cfg(feature = "diff-builder")]
+    fn test_update_checksum() {
+        let filter = include_str!("../../../tests/fixtures/test-checksum-nl.txt");
+        let changed = filter.replacen('\n', "\n||changed.org^\n", 1);
+
+        assert!(validate_checksum(&changed).is_err());
+
+        let updated = update_checksum(&changed).unwrap();
+        assert!(validate_checksum(&updated).unwrap());
+        assert_eq!(updated.len(), changed.len());
+
+        assert!(update_checksum("! Title: No checksum\n||example.org^\n").is_none());
+    }
+
     #[test]
     fn test_resolve_checksum() {
         [
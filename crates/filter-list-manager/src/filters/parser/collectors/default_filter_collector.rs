@@ -77,12 +77,65 @@ impl<'c> DefaultFilterCollector<'c> {
         Ok((filter_contents, count))
     }
 
+    /// Walks over the lines, which get into the filter built by [`Self::collect_from_parts`].
+    /// `block` receives the include containing the line ([`None`] for the root filter body),
+    /// zero-based line index in the body of its origin and the line without line break
+    pub(crate) fn for_each_collected_line<I, F>(
+        &self,
+        rule_entity: &RulesListEntity,
+        root_filter_url: &str,
+        filter_include_entities: Option<I>,
+        mut block: F,
+    ) -> Result<(), FilterParserErrorContext>
+    where
+        I: AsRef<Vec<FilterIncludeEntity>>,
+        F: FnMut(Option<&FilterIncludeEntity>, usize, &str),
+    {
+        let mut line_processor = LineProcessor::new(self.configuration);
+
+        let includes = match filter_include_entities {
+            Some(ref includes) => includes.as_ref(),
+            None => &Vec::new(),
+        };
+
+        for (index, line) in rule_entity.text.lines().enumerate() {
+            match line_processor.process(line).map_err(|why| {
+                FilterParserErrorContext::new(why, index, string!(root_filter_url))
+            })? {
+                ProcessedLine::Skip => {}
+                ProcessedLine::Include(include_path) => {
+                    let include = Self::find_include(include_path, root_filter_url, includes)
+                        .map_err(|why| {
+                            FilterParserErrorContext::new(why, index, string!(root_filter_url))
+                        })?;
+
+                    for (include_index, include_line) in include.body.lines().enumerate() {
+                        block(Some(include), include_index, include_line);
+                    }
+                }
+                ProcessedLine::Rule => block(None, index, line),
+            }
+        }
+
+        Ok(())
+    }
+
     /// Resolves an include path and returns the include body
     fn resolve_include<'body>(
         include_path: &str,
         parent_absolute_url: &str,
         filter_includes: &'body [FilterIncludeEntity],
     ) -> Result<&'body str, FilterParserError> {
+        Self::find_include(include_path, parent_absolute_url, filter_includes)
+            .map(|include| include.body.as_str())
+    }
+
+    /// Resolves an include path and returns the include entity
+    fn find_include<'body>(
+        include_path: &str,
+        parent_absolute_url: &str,
+        filter_includes: &'body [FilterIncludeEntity],
+    ) -> Result<&'body FilterIncludeEntity, FilterParserError> {
         let resolved_path =
             try_to_resolve_include_path_from_parent_url(parent_absolute_url, include_path)?;
 
@@ -91,7 +144,7 @@ impl<'c> DefaultFilterCollector<'c> {
             .find(|include| include.absolute_url == resolved_path);
 
         if let Some(found_include) = current_include {
-            Ok(found_include.body.as_str())
+            Ok(found_include)
         } else {
             FilterParserError::Other(format!(
                 "Couldn't find include {} for {}",
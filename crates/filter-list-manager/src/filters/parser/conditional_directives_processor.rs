@@ -52,6 +52,13 @@ impl<'c> ConditionalDirectivesProcessor<'c> {
         }
     }
 
+    /// Resets processing state, so the processor could be used for the next compilation
+    pub(crate) fn reset(&mut self) {
+        self.conditional_nesting_level = 0;
+        self.condition_disabled_at_nesting = 0;
+        self.nesting_stack.clear();
+    }
+
     /// Processes conditional directives [`DIRECTIVE_IF`], [`DIRECTIVE_ELSE`], ...
     ///
     /// # Returns
@@ -0,0 +1,38 @@
+use super::diff_directives::extract_patch;
+use super::validate_patch::validate_patch;
+use crate::filters::parser::rcs_diff::apply_patch;
+use crate::FilterParserError;
+
+/// Extracts the patch for `resource_name` from patch file contents, applies it to
+/// `base_filter_contents` and validates the result, if the patch has diff directive
+///
+/// # Returns
+///
+/// - `Ok((patched_filter, next_diff_path))`
+/// - `Err(FilterParserError)`
+pub(crate) fn apply_patch_file(
+    base_filter_contents: &str,
+    patch_file_contents: &str,
+    resource_name: Option<String>,
+) -> Result<(String, Option<String>), FilterParserError> {
+    if patch_file_contents.is_empty() {
+        return Err(FilterParserError::NoContent);
+    }
+
+    let (diff_directive_option, prepared_patch, end_of_chunk_is_eof) =
+        extract_patch(patch_file_contents, resource_name)?;
+    let mut patch_lines_count = prepared_patch.len();
+
+    // If this chunk contains eof, we are truly knows
+    if end_of_chunk_is_eof {
+        patch_lines_count -= 1;
+    }
+
+    let (patch_result, next_diff_path) = apply_patch(base_filter_contents, prepared_patch)?;
+
+    if let Some(diff_directive) = diff_directive_option {
+        validate_patch(diff_directive, patch_lines_count, patch_result.as_str())?;
+    }
+
+    Ok((patch_result, next_diff_path))
+}
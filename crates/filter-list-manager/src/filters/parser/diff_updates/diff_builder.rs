@@ -0,0 +1,353 @@
+//! Generation of differential update patches for filters hosting.
+//!
+//! See <https://github.com/ameshkov/diffupdates> for details
+use super::apply_patch_file::apply_patch_file;
+use super::diff_directives::format_diff_directive;
+use super::process_diff_path::process_diff_path;
+use crate::filters::parser::checksum_validator::update_checksum;
+use crate::filters::parser::metadata::collector::MetadataCollector;
+use crate::filters::parser::metadata::KnownMetadataProperty;
+use crate::filters::parser::rcs_diff::make_rcs_diff;
+use crate::FilterParserError;
+use sha1::{Digest, Sha1};
+use std::collections::HashSet;
+
+const DIFF_PATH_LINE_PREFIX: &str = "! Diff-Path: ";
+
+/// Versions of the filter list, which shares the batch patch with others.
+/// See [`make_batch_diff_patch`]
+#[derive(Debug, Clone)]
+pub struct BatchDiffPatchList<'a> {
+    /// Resource name of the list in the batch patch, i.e. fragment of its `! Diff-Path`.
+    /// May contain only latin letters, digits, `-` and `_`, up to 64 chars
+    pub resource_name: &'a str,
+    /// Contents of the currently published version
+    pub old_contents: &'a str,
+    /// Contents of the next version. Should already contain the next `! Diff-Path`
+    pub new_contents: &'a str,
+}
+
+/// Makes differential update patch from `old_contents` to `new_contents`:
+/// RCS diff (`diff -n`) with `diff checksum:<sha1> lines:<count>` directive.
+///
+/// `new_contents` should already contain the next `! Diff-Path`, see [`set_diff_path`].
+///
+/// # Failure
+///
+/// Returns [`FilterParserError::InvalidDiffPatch`] if the patch can't be applied
+/// by clients, e.g. when only one of versions ends with a line feed
+pub fn make_diff_patch(
+    old_contents: &str,
+    new_contents: &str,
+) -> Result<String, FilterParserError> {
+    let patch = make_patch_chunk(None, old_contents, new_contents, true)?;
+
+    check_patch(&patch, None, old_contents, new_contents)?;
+
+    Ok(patch)
+}
+
+/// Makes batch patch file, which contains differential updates for several lists.
+/// Every chunk starts with `diff name:<resource_name> checksum:<sha1> lines:<count>` directive,
+/// and lists must refer to it as `! Diff-Path: <patch_path>#<resource_name>`.
+///
+/// # Failure
+///
+/// Returns [`FilterParserError::InvalidDiffPatch`] if resource names are invalid or not unique,
+/// or the patch of some list can't be applied by clients
+pub fn make_batch_diff_patch(lists: &[BatchDiffPatchList]) -> Result<String, FilterParserError> {
+    if lists.is_empty() {
+        return FilterParserError::invalid_diff_patch("Batch patch must contain at least one list");
+    }
+
+    let mut names = HashSet::with_capacity(lists.len());
+    let mut patch = String::new();
+
+    for (index, list) in lists.iter().enumerate() {
+        if !names.insert(list.resource_name) {
+            return FilterParserError::invalid_diff_patch(format!(
+                "Resource name \"{}\" is used more than once",
+                list.resource_name
+            ));
+        }
+
+        patch += &make_patch_chunk(
+            Some(list.resource_name),
+            list.old_contents,
+            list.new_contents,
+            index == lists.len() - 1,
+        )?;
+    }
+
+    for list in lists {
+        check_patch(
+            &patch,
+            Some(list.resource_name),
+            list.old_contents,
+            list.new_contents,
+        )?;
+    }
+
+    Ok(patch)
+}
+
+/// Sets `! Diff-Path` of the filter to `diff_path`.
+/// Replaces the existing line, otherwise inserts it after `! Title`, or at the beginning.
+/// `! Checksum` is recalculated, if the filter has one.
+///
+/// # Failure
+///
+/// Returns [`FilterParserError::InvalidDiffPatch`] if `diff_path` can't be parsed by clients.
+/// Its file name must look like `<name>-[<resolution>-]<epoch>-<expiration>.patch`,
+/// where `name` doesn't contain `-`
+pub fn set_diff_path(contents: &str, diff_path: &str) -> Result<String, FilterParserError> {
+    match process_diff_path(0, diff_path.to_string()) {
+        Ok(Some(_)) => {}
+        _ => {
+            return FilterParserError::invalid_diff_patch(format!(
+                "Diff-Path \"{}\" has invalid format",
+                diff_path
+            ))
+        }
+    }
+
+    let diff_path_line = format!("{}{}", DIFF_PATH_LINE_PREFIX, diff_path);
+    let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    let terminator = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let existing = lines.iter().position(|line| {
+        MetadataCollector::parse_line_for(KnownMetadataProperty::DiffPath, line.trim_end())
+            .is_some()
+    });
+
+    let mut out = String::with_capacity(contents.len() + diff_path_line.len() + 2);
+
+    match existing {
+        Some(index) => {
+            let line_terminator =
+                &lines[index][lines[index].trim_end_matches(['\r', '\n']).len()..];
+
+            lines.iter().enumerate().for_each(|(current, line)| {
+                if current == index {
+                    out += &diff_path_line;
+                    out += line_terminator;
+                } else {
+                    out += line;
+                }
+            });
+        }
+        None => {
+            let insert_at = lines
+                .iter()
+                .position(|line| {
+                    MetadataCollector::parse_line_for(KnownMetadataProperty::Title, line.trim_end())
+                        .is_some()
+                })
+                .map(|index| index + 1)
+                .unwrap_or_default();
+
+            lines.iter().enumerate().for_each(|(index, line)| {
+                if index == insert_at {
+                    out += &diff_path_line;
+                    out += terminator;
+                }
+
+                out += line;
+            });
+
+            // Title is the last line
+            if insert_at == lines.len() {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out += terminator;
+                }
+
+                out += &diff_path_line;
+
+                if contents.ends_with('\n') {
+                    out += terminator;
+                }
+            }
+        }
+    }
+
+    Ok(update_checksum(&out).unwrap_or(out))
+}
+
+/// Makes patch with diff directive for one list.
+/// `is_last` chunk is not followed by others, so it may end without line feed
+fn make_patch_chunk(
+    resource_name: Option<&str>,
+    old_contents: &str,
+    new_contents: &str,
+    is_last: bool,
+) -> Result<String, FilterParserError> {
+    let mut diff = make_rcs_diff(old_contents, new_contents);
+
+    if !is_last && !diff.is_empty() && !diff.ends_with('\n') {
+        diff.push('\n');
+    }
+
+    let mut digest = Sha1::new();
+    digest.update(new_contents.as_bytes());
+    let checksum = format!("{:x}", digest.finalize());
+
+    // Same as `wc -l`
+    let lines = diff.matches('\n').count();
+
+    let Some(directive) = format_diff_directive(resource_name, &checksum, lines) else {
+        return FilterParserError::invalid_diff_patch(format!(
+            "Invalid resource name \"{}\". It may contain only latin letters, digits, \"-\" and \"_\", up to 64 chars",
+            resource_name.unwrap_or_default()
+        ));
+    };
+
+    Ok(format!("{}\n{}", directive, diff))
+}
+
+/// Applies the patch like clients do, and compares the result with expected contents
+fn check_patch(
+    patch: &str,
+    resource_name: Option<&str>,
+    old_contents: &str,
+    new_contents: &str,
+) -> Result<(), FilterParserError> {
+    let reason = match apply_patch_file(old_contents, patch, resource_name.map(String::from)) {
+        Ok((patched, _)) if patched == new_contents => return Ok(()),
+        Ok(_) => String::from("patched contents differ from the new version"),
+        Err(why) => why.to_string(),
+    };
+
+    FilterParserError::invalid_diff_patch(format!(
+        "Patch{} can't be applied by clients: {}. Make sure that both versions end with a line feed",
+        resource_name
+            .map(|name| format!(" for \"{}\"", name))
+            .unwrap_or_default(),
+        reason
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{make_batch_diff_patch, make_diff_patch, set_diff_path, BatchDiffPatchList};
+    use crate::filters::parser::diff_updates::apply_patch_file::apply_patch_file;
+    use crate::FilterParserError;
+
+    #[test]
+    fn test_make_diff_patch_like_fixture() {
+        let old = include_str!(
+            "../../../../tests/fixtures/diffupdates/examples/02_validation/filter_v1.0.1.txt"
+        );
+        let new = include_str!(
+            "../../../../tests/fixtures/diffupdates/examples/02_validation/filter.txt"
+        );
+        let expected = include_str!(
+            "../../../../tests/fixtures/diffupdates/examples/02_validation/patches/v1.0.1-m-28334120-60.patch"
+        );
+
+        assert_eq!(make_diff_patch(old, new).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_make_diff_patch_rejects_unappliable_patch() {
+        let result = make_diff_patch("||example.org^\n", "||example.org^\n||example.com^");
+
+        assert!(matches!(
+            result,
+            Err(FilterParserError::InvalidDiffPatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_make_batch_diff_patch() {
+        let list1_old = "! Title: List 1\n! Diff-Path: ../patches/batch-s-1700045842-3600.patch#list1\n||example.org^\n";
+        let list1_new = "! Title: List 1\n! Diff-Path: ../patches/batch-s-1700049442-3600.patch#list1\n||example.com^\n";
+        let list2_old = "! Title: List 2\n! Diff-Path: ../patches/batch-s-1700045842-3600.patch#list2\n||test.org^\n";
+        let list2_new = "! Title: List 2\n! Diff-Path: ../patches/batch-s-1700049442-3600.patch#list2\n||test.org^\n||test.com^\n";
+
+        let patch = make_batch_diff_patch(&[
+            BatchDiffPatchList {
+                resource_name: "list1",
+                old_contents: list1_old,
+                new_contents: list1_new,
+            },
+            BatchDiffPatchList {
+                resource_name: "list2",
+                old_contents: list2_old,
+                new_contents: list2_new,
+            },
+        ])
+        .unwrap();
+
+        assert_eq!(
+            patch,
+            "diff name:list1 checksum:148021883e9292359172ca50d1b2d82568d288ed lines:4
+d2 2
+a3 2
+! Diff-Path: ../patches/batch-s-1700049442-3600.patch#list1
+||example.com^
+diff name:list2 checksum:cddd48991229a728a3630072b2906db652975254 lines:5
+d2 1
+a2 1
+! Diff-Path: ../patches/batch-s-1700049442-3600.patch#list2
+a3 1
+||test.com^
+"
+        );
+
+        let (patched, next_diff_path) =
+            apply_patch_file(list2_old, &patch, Some(String::from("list2"))).unwrap();
+        assert_eq!(patched, list2_new);
+        assert_eq!(
+            next_diff_path.unwrap(),
+            "../patches/batch-s-1700049442-3600.patch#list2"
+        );
+
+        let duplicate = BatchDiffPatchList {
+            resource_name: "list1",
+            old_contents: list1_old,
+            new_contents: list1_new,
+        };
+        assert!(make_batch_diff_patch(&[duplicate.clone(), duplicate]).is_err());
+        assert!(make_batch_diff_patch(&[BatchDiffPatchList {
+            resource_name: "list.txt",
+            old_contents: list1_old,
+            new_contents: list1_new,
+        }])
+        .is_err());
+    }
+
+    #[test]
+    fn test_set_diff_path() {
+        [
+            (
+                "! Title: List\n! Diff-Path: patches/v1-m-28334060-60.patch\n||example.org^\n",
+                "! Title: List\n! Diff-Path: patches/v2-m-28334120-60.patch\n||example.org^\n",
+            ),
+            (
+                "! Title: List\r\n||example.org^\r\n",
+                "! Title: List\r\n! Diff-Path: patches/v2-m-28334120-60.patch\r\n||example.org^\r\n",
+            ),
+            (
+                "! Title: List",
+                "! Title: List\n! Diff-Path: patches/v2-m-28334120-60.patch",
+            ),
+            (
+                "||example.org^\n",
+                "! Diff-Path: patches/v2-m-28334120-60.patch\n||example.org^\n",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(contents, expected)| {
+            assert_eq!(
+                set_diff_path(contents, "patches/v2-m-28334120-60.patch").unwrap(),
+                expected
+            );
+        });
+
+        assert!(set_diff_path("||example.org^\n", "patches/v2.patch").is_err());
+    }
+}
@@ -52,7 +52,7 @@ pub(crate) fn extract_patch(
     // Early return for patches without diff directive
     match diff_lines_raw.peek() {
         None => {
-            return FilterParserError::other_err_from_to_string(
+            return FilterParserError::invalid_diff_patch(
                 "Cannot preprocess patch, because it looks like empty",
             )
         }
@@ -68,7 +68,7 @@ pub(crate) fn extract_patch(
 
                     recognized_directive = recognized;
                 }
-                Err(e) => return FilterParserError::other_err_from_to_string(e),
+                Err(e) => return FilterParserError::invalid_diff_patch(e),
             }
         }
     }
@@ -86,7 +86,7 @@ pub(crate) fn extract_patch(
     // Resource name must be defined here
     // because we need to match it against directives in batch file
     let Some(resource_name) = resource_name_option else {
-        return FilterParserError::other_err_from_to_string(
+        return FilterParserError::invalid_diff_patch(
             "Patch have found, but resource name is not given",
         );
     };
@@ -115,7 +115,7 @@ pub(crate) fn extract_patch(
                     None => {
                         // We've found diff directive, but it doesn't contain name
                         // So patch is broken
-                        return FilterParserError::other_err_from_to_string(
+                        return FilterParserError::invalid_diff_patch(
                             r"We've found diff directive, but it doesn't contain name.
 So patch is broken",
                         );
@@ -138,6 +138,38 @@ So patch is broken",
     Ok((Some(recognized_directive), out, !break_encountered))
 }
 
+/// Makes diff directive line without line terminator.
+/// Returns [`None`] if `name` is not a valid resource name
+#[cfg(feature = "diff-builder")]
+pub(crate) fn format_diff_directive(
+    name: Option<&str>,
+    checksum: &str,
+    lines: usize,
+) -> Option<String> {
+    let directive = match name {
+        Some(name) => format!(
+            "{} {}:{} {}:{} {}:{}",
+            DIFF_DIRECTIVE,
+            DIFF_DIRECTIVE_NAME,
+            name,
+            DIFF_DIRECTIVE_CHECKSUM,
+            checksum,
+            DIFF_DIRECTIVE_LINE,
+            lines
+        ),
+        None => format!(
+            "{} {}:{} {}:{}",
+            DIFF_DIRECTIVE, DIFF_DIRECTIVE_CHECKSUM, checksum, DIFF_DIRECTIVE_LINE, lines
+        ),
+    };
+
+    // The name must be read back as is
+    match recognize_diff_directive(&directive) {
+        Ok(("", Some(recognized))) if recognized.name.as_deref() == name => Some(directive),
+        _ => None,
+    }
+}
+
 /// Recognizes diff directive
 /// See more: <https://github.com/ameshkov/diffupdates?tab=readme-ov-file#diff-files-format>
 pub(crate) fn recognize_diff_directive(
@@ -200,8 +232,34 @@ fn map_diff_directive<'a>(
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "diff-builder")]
+    use super::format_diff_directive;
     use super::{extract_patch, recognize_diff_directive, RecognizedDiffDirective};
 
+    #[test]
+    #[cfg(feature = "diff-builder")]
+    fn test_format_diff_directive() {
+        const CHECKSUM: &str = "1ce52b527d56a245f32138e014b1571c19cfb659";
+
+        assert_eq!(
+            format_diff_directive(None, CHECKSUM, 4).unwrap(),
+            "diff checksum:1ce52b527d56a245f32138e014b1571c19cfb659 lines:4"
+        );
+        assert_eq!(
+            format_diff_directive(Some("list-1_"), CHECKSUM, 7).unwrap(),
+            "diff name:list-1_ checksum:1ce52b527d56a245f32138e014b1571c19cfb659 lines:7"
+        );
+
+        [
+            "",
+            "list 1",
+            "list.txt",
+            "12345678901234567890123456789012345678901234567890123456789012345",
+        ]
+        .into_iter()
+        .for_each(|name| assert!(format_diff_directive(Some(name), CHECKSUM, 1).is_none()));
+    }
+
     #[test]
     fn test_recognize_diff_directive() {
         [
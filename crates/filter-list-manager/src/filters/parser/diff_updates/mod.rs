@@ -1,4 +1,7 @@
+pub(crate) mod apply_patch_file;
 pub(crate) mod batch_patches_container;
+#[cfg(feature = "diff-builder")]
+pub(crate) mod diff_builder;
 pub(super) mod diff_directives;
 pub(crate) mod process_diff_path;
 pub(super) mod validate_patch;
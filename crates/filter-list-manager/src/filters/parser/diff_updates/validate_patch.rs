@@ -15,7 +15,7 @@ pub(crate) fn validate_patch(
     patch_result: &str,
 ) -> Result<(), FilterParserError> {
     if patch_result_lines_count != recognize_diff_directive.lines {
-        return FilterParserError::other_err_from_to_string(
+        return FilterParserError::invalid_diff_patch(
             format!(
                 "The number of lines in the patch ({}) differs from the number in the patch header ({})",
                 patch_result_lines_count,
@@ -34,7 +34,7 @@ pub(crate) fn validate_patch(
         recognize_diff_directive.checksum.as_bytes(),
         &mut checksum_hex,
     )
-    .or_else(|why| FilterParserError::other_err_from_to_string(why.to_string()))?;
+    .or_else(|why| FilterParserError::invalid_diff_patch(why.to_string()))?;
 
     if result.as_bytes() != checksum_hex {
         return FilterParserError::invalid_checksum(
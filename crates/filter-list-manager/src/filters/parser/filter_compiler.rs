@@ -16,9 +16,11 @@ use crate::filters::parser::rule_lines_collector::RuleLinesCollector;
 use crate::filters::parser::DIRECTIVE_INCLUDE;
 use crate::io::get_scheme;
 use crate::io::http::blocking_client::BlockingClient;
+use crate::io::http::cache_validators::CacheValidators;
 use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
 use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
-use crate::{string, Configuration, FilterId, FilterParserError};
+use crate::{string, Configuration, FilterId, FilterParserError, RulesStorageCompression};
+use std::cell::Cell;
 use std::fmt::Display;
 
 /// Information about includes collected during main filter text compilation
@@ -83,6 +85,10 @@ pub(crate) struct FilterCompiler<'a> {
     filter_parser_result: FilterParserResult,
     /// True if at least one directive was encountered in the filter
     directives_encountered: bool,
+    /// Number of bytes downloaded for the filter and its includes
+    downloaded_bytes: Cell<usize>,
+    /// How compiled entities should be stored
+    rules_storage_compression: RulesStorageCompression,
 }
 
 impl<'a> FilterCompiler<'a> {
@@ -102,6 +108,8 @@ impl<'a> FilterCompiler<'a> {
                 includes: vec![],
             },
             directives_encountered: false,
+            downloaded_bytes: Cell::new(0),
+            rules_storage_compression: configuration.rules_storage_compression,
         }
     }
 
@@ -113,6 +121,22 @@ impl<'a> FilterCompiler<'a> {
         Self::new(configuration, Box::new(IOProvider::new(shared_http_client)))
     }
 
+    /// Factory for compiler, which requests root filter conditionally,
+    /// using `cache_validators` from the previous response
+    pub(crate) fn with_cache_validators(
+        configuration: &'a Configuration,
+        shared_http_client: &'a BlockingClient,
+        cache_validators: Option<CacheValidators>,
+    ) -> Self {
+        Self::new(
+            configuration,
+            Box::new(IOProvider::with_cache_validators(
+                shared_http_client,
+                cache_validators,
+            )),
+        )
+    }
+
     /// Constructor for custom [`FilterContentsProvider`]
     pub(crate) fn with_custom_provider(
         filter_downloader: Box<dyn FilterContentsProvider + 'a>,
@@ -209,6 +233,16 @@ impl<'a> FilterCompiler<'a> {
         }
     }
 
+    /// Resets compilation state after failed compilation, so the filter could be compiled once again.
+    /// Number of downloaded bytes is kept
+    pub(crate) fn reset(&mut self) {
+        self.conditional_directives_processor.reset();
+        self.metadata_collector = MetadataCollector::new();
+        self.filters_cursor.clear();
+        self.filter_parser_result = FilterParserResult::default();
+        self.directives_encountered = false;
+    }
+
     /// Gets raw value by metadata property
     pub(crate) fn get_metadata(&self, property: KnownMetadataProperty) -> String {
         self.metadata_collector.get(property)
@@ -221,12 +255,15 @@ impl<'a> FilterCompiler<'a> {
             .includes
             .into_iter()
             .map(|include| {
-                FilterIncludeEntity::make(
+                let mut entity = FilterIncludeEntity::make(
                     filter_id,
                     include.absolute_url,
                     include.lines.get_rules_count(),
                     include.lines.into_body(),
-                )
+                );
+                entity.compression = self.rules_storage_compression;
+
+                entity
             })
             .collect();
 
@@ -237,6 +274,7 @@ impl<'a> FilterCompiler<'a> {
         );
 
         rules_list_entity.set_has_directives(self.directives_encountered);
+        rules_list_entity.compression = self.rules_storage_compression;
 
         CompiledFilterEntities {
             rules_list_entity,
@@ -244,6 +282,21 @@ impl<'a> FilterCompiler<'a> {
         }
     }
 
+    /// Takes validators of the root filter response, if there are any
+    pub(crate) fn take_response_cache_validators(&self) -> Option<CacheValidators> {
+        self.filter_downloader.take_response_cache_validators()
+    }
+
+    /// Gets number of bytes downloaded for the filter and its includes
+    pub(crate) fn get_downloaded_bytes(&self) -> usize {
+        self.downloaded_bytes.get()
+    }
+
+    /// Gets status code of the root filter response, if it has been requested via http(s)
+    pub(crate) fn get_response_status(&self) -> Option<u16> {
+        self.filter_downloader.get_response_status()
+    }
+
     /// Gets metadata collector clone
     pub(crate) fn clone_metadata(&self) -> MetadataCollector {
         self.metadata_collector.clone()
@@ -410,6 +463,11 @@ impl FilterCompiler<'_> {
             } else {
                 let absolute_url = absolute_url.to_string();
                 let contents = self.filter_downloader.get_filter_contents(&absolute_url)?;
+                self.add_downloaded_bytes(
+                    self.filter_downloader
+                        .get_downloaded_bytes()
+                        .unwrap_or(contents.len()),
+                );
 
                 if !self.should_skip_checksum_validation {
                     validate_checksum(contents.as_str())?;
@@ -426,6 +484,7 @@ impl FilterCompiler<'_> {
             let contents = self
                 .filter_downloader
                 .get_included_filter_contents(absolute_url, current_scheme.into())?;
+            self.add_downloaded_bytes(contents.len());
 
             if !self.should_skip_checksum_validation {
                 validate_checksum(contents.as_str())?;
@@ -440,6 +499,13 @@ impl FilterCompiler<'_> {
             self.raise_stack_is_corrupted()
         }
     }
+
+    /// Accumulates number of downloaded bytes
+    #[inline]
+    fn add_downloaded_bytes(&self, bytes: usize) {
+        self.downloaded_bytes
+            .set(self.downloaded_bytes.get() + bytes);
+    }
 }
 
 /// Errors
@@ -1,10 +1,11 @@
-use crate::filters::parser::diff_updates::validate_patch::validate_patch;
 use crate::filters::parser::{
+    diff_updates::apply_patch_file::apply_patch_file,
     diff_updates::batch_patches_container::BatchPatchesContainer,
-    diff_updates::diff_directives::extract_patch, filter_contents_provider::FilterContentsProvider,
-    paths::resolve_absolute_uri, rcs_diff::apply_patch,
+    filter_contents_provider::FilterContentsProvider, paths::resolve_absolute_uri,
+};
+use crate::io::fetch_by_schemes::{
+    fetch_filter_by_scheme_conditionally, FetchedFilterContents, FilterFetchPolicy,
 };
-use crate::io::fetch_by_schemes::{fetch_filter_by_scheme_with_content_check, FilterFetchPolicy};
 use crate::io::http::blocking_client::BlockingClient;
 use crate::io::url_schemes::UrlSchemes;
 use crate::io::{get_hash_from_url, get_scheme};
@@ -35,6 +36,10 @@ pub(crate) struct DiffPathProvider<'a> {
     diff_applied_at_least_once: Cell<bool>,
     /// Number of consecutive patch updates remaining
     patch_steps_remaining: Cell<i32>,
+    /// Status code of the last patch file response
+    response_status: Cell<Option<u16>>,
+    /// Total size of downloaded patch files
+    downloaded_bytes: Cell<usize>,
 }
 
 impl<'a> DiffPathProvider<'a> {
@@ -51,37 +56,35 @@ impl<'a> DiffPathProvider<'a> {
             shared_http_client,
             diff_applied_at_least_once: Cell::new(false),
             patch_steps_remaining: Cell::new(MAX_DIFF_UPDATES_IN_A_ROW),
+            response_status: Cell::new(None),
+            downloaded_bytes: Cell::new(0),
         }
     }
 
-    /// Will work here with patch contents, apply and validate them
-    #[cfg_attr(not(test), inline)]
-    fn do_patch(
+    /// Downloads patch file and records response details
+    fn fetch_patch_file(
         &self,
-        base_filter_contents: &str,
-        patch_file_contents: &str,
-        resource_name: Option<String>,
-    ) -> Result<(String, Option<String>), FilterParserError> {
-        if patch_file_contents.is_empty() {
-            return Err(FilterParserError::NoContent);
-        }
-
-        let (diff_directive_option, prepared_patch, end_of_chunk_is_eof) =
-            extract_patch(patch_file_contents, resource_name)?;
-        let mut patch_lines_count = prepared_patch.len();
-
-        // If this chunk contains eof, we are truly knows
-        if end_of_chunk_is_eof {
-            patch_lines_count -= 1;
-        }
-
-        let (patch_result, next_diff_path) = apply_patch(base_filter_contents, prepared_patch)?;
+        absolute_url: &str,
+        scheme: UrlSchemes,
+        fetch_policy: FilterFetchPolicy,
+    ) -> Result<String, FilterParserError> {
+        let FetchedFilterContents {
+            contents,
+            http_status,
+            ..
+        } = fetch_filter_by_scheme_conditionally(
+            absolute_url,
+            scheme,
+            self.get_http_client(),
+            fetch_policy,
+            None,
+        )?;
 
-        if let Some(diff_directive) = diff_directive_option {
-            validate_patch(diff_directive, patch_lines_count, patch_result.as_str())?;
-        }
+        self.response_status.set(http_status);
+        self.downloaded_bytes
+            .set(self.downloaded_bytes.get() + contents.len());
 
-        Ok((patch_result, next_diff_path))
+        Ok(contents)
     }
 
     /// Apply patch to current filter contents
@@ -106,10 +109,9 @@ impl<'a> DiffPathProvider<'a> {
 
                     let diff_contents = match locked_container.get_a_copy(&patch_path) {
                         None => {
-                            let body = fetch_filter_by_scheme_with_content_check(
+                            let body = self.fetch_patch_file(
                                 &patch_path,
                                 scheme,
-                                self.get_http_client(),
                                 FilterFetchPolicy::RegularFilter,
                             )?;
 
@@ -123,10 +125,9 @@ impl<'a> DiffPathProvider<'a> {
                     (Some(resource_name), diff_contents)
                 }
                 None => {
-                    let diff_contents = fetch_filter_by_scheme_with_content_check(
+                    let diff_contents = self.fetch_patch_file(
                         &patch_file_absolute_uri,
                         scheme,
-                        self.get_http_client(),
                         FilterFetchPolicy::DiffUpdates,
                     )?;
 
@@ -138,7 +139,7 @@ impl<'a> DiffPathProvider<'a> {
             .set(self.patch_steps_remaining.get() - 1);
 
         // Extracts current patch from diff file and applies it
-        let (patch_result, next_diff_path) = self.do_patch(
+        let (patch_result, next_diff_path) = apply_patch_file(
             current_filter_contents.as_ref(),
             &diff_file_contents,
             resource_name,
@@ -198,6 +199,14 @@ impl FilterContentsProvider for DiffPathProvider<'_> {
     fn get_http_client(&self) -> &BlockingClient {
         self.shared_http_client
     }
+
+    fn get_response_status(&self) -> Option<u16> {
+        self.response_status.get()
+    }
+
+    fn get_downloaded_bytes(&self) -> Option<usize> {
+        Some(self.downloaded_bytes.get())
+    }
 }
 
 #[cfg(test)]
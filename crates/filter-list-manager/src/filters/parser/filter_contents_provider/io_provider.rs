@@ -1,19 +1,41 @@
 use super::FilterContentsProvider;
-use crate::io::fetch_by_schemes::{fetch_filter_by_scheme_with_content_check, FilterFetchPolicy};
+use crate::io::fetch_by_schemes::{fetch_filter_by_scheme_conditionally, FilterFetchPolicy};
 use crate::io::get_scheme;
 use crate::io::http::blocking_client::BlockingClient;
+use crate::io::http::cache_validators::CacheValidators;
 use crate::FilterParserError;
+use std::cell::Cell;
 
 /// Used for downloading filters for filters parser purposes
 /// Can download from remote servers (`https?:`) or from local machine, using `file:` scheme
 pub(crate) struct IOProvider<'a> {
     /// Shared sync http client
     shared_http_client: &'a BlockingClient,
+    /// Validators of the previous root filter response.
+    /// If set, root filter will be requested conditionally
+    cache_validators: Option<CacheValidators>,
+    /// Validators of the last root filter response
+    response_cache_validators: Cell<Option<CacheValidators>>,
+    /// Status code of the last root filter response
+    response_status: Cell<Option<u16>>,
 }
 
 impl<'a> IOProvider<'a> {
     pub(crate) const fn new(shared_http_client: &'a BlockingClient) -> Self {
-        Self { shared_http_client }
+        Self::with_cache_validators(shared_http_client, None)
+    }
+
+    /// Constructor for provider, which requests root filter conditionally
+    pub(crate) const fn with_cache_validators(
+        shared_http_client: &'a BlockingClient,
+        cache_validators: Option<CacheValidators>,
+    ) -> Self {
+        Self {
+            shared_http_client,
+            cache_validators,
+            response_cache_validators: Cell::new(None),
+            response_status: Cell::new(None),
+        }
     }
 }
 
@@ -21,15 +43,29 @@ impl FilterContentsProvider for IOProvider<'_> {
     fn get_filter_contents(&self, root_filter_url: &str) -> Result<String, FilterParserError> {
         let scheme = get_scheme(root_filter_url).unwrap_or_default();
 
-        fetch_filter_by_scheme_with_content_check(
+        let fetched = fetch_filter_by_scheme_conditionally(
             root_filter_url,
             scheme.into(),
             self.get_http_client(),
             FilterFetchPolicy::RegularFilter,
-        )
+            self.cache_validators.as_ref(),
+        )?;
+
+        self.response_cache_validators.set(fetched.cache_validators);
+        self.response_status.set(fetched.http_status);
+
+        Ok(fetched.contents)
     }
 
     fn get_http_client(&self) -> &BlockingClient {
         self.shared_http_client
     }
+
+    fn take_response_cache_validators(&self) -> Option<CacheValidators> {
+        self.response_cache_validators.take()
+    }
+
+    fn get_response_status(&self) -> Option<u16> {
+        self.response_status.get()
+    }
 }
@@ -7,6 +7,7 @@ pub(in crate::filters) mod io_provider;
 pub(crate) mod string_provider;
 
 use crate::io::http::blocking_client::BlockingClient;
+use crate::io::http::cache_validators::CacheValidators;
 
 /// Provides filters contents.
 /// It can provide filter by `root_filter_url` and resolves its includes
@@ -31,4 +32,22 @@ pub(crate) trait FilterContentsProvider: Send {
 
     /// Gets blocking client. Every provider needs it
     fn get_http_client(&self) -> &BlockingClient;
+
+    /// Takes validators of the root filter response, if provider has them.
+    /// They should be used for the next conditional request
+    fn take_response_cache_validators(&self) -> Option<CacheValidators> {
+        None
+    }
+
+    /// Gets status code of the last root filter response,
+    /// if it has been requested via http(s)
+    fn get_response_status(&self) -> Option<u16> {
+        None
+    }
+
+    /// Gets number of bytes downloaded to get root filter contents.
+    /// [`None`] means that contents have been downloaded as is
+    fn get_downloaded_bytes(&self) -> Option<usize> {
+        None
+    }
 }
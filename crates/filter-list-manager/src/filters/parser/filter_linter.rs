@@ -0,0 +1,393 @@
+//! Static checks of the filter body, which report all problems at once, unlike [`super::filter_compiler::FilterCompiler`].
+//! Included files are not downloaded, only their urls are checked
+
+use super::boolean_expression_parser::BooleanExpressionParser;
+use super::checksum_validator::validate_checksum;
+use super::include_processor::get_include_path;
+use super::metadata::collector::MetadataCollector;
+use super::metadata::parsers::expires::process_expires;
+use super::metadata::KnownMetadataProperty;
+use super::paths::{compare_same_origin, try_to_resolve_include_path_from_parent_url};
+use super::{DIRECTIVE_ELSE, DIRECTIVE_ENDIF, DIRECTIVE_IF, DIRECTIVE_INCLUDE};
+use crate::io::get_scheme;
+use crate::io::url_schemes::UrlSchemes;
+use crate::manager::models::filter_diagnostic::{
+    FilterDiagnostic, FilterDiagnosticKind, FilterDiagnosticSeverity,
+};
+use crate::{Configuration, FilterParserError};
+use chrono::{DateTime, Utc};
+use nom::Slice;
+use std::str::FromStr;
+
+/// Constants, which are defined by AdGuard products, so they are not reported
+/// even if they are not in the compilation policy
+const KNOWN_COMPILER_CONSTANTS: [&str; 16] = [
+    "adguard",
+    "adguard_app_windows",
+    "adguard_app_mac",
+    "adguard_app_android",
+    "adguard_app_ios",
+    "adguard_app_cli",
+    "adguard_ext_safari",
+    "adguard_ext_android_cb",
+    "adguard_ext_chromium",
+    "adguard_ext_chromium_mv3",
+    "adguard_ext_firefox",
+    "adguard_ext_edge",
+    "adguard_ext_opera",
+    "ext_ublock",
+    "cap_html_filtering",
+    "cap_replace_modifier",
+];
+
+/// Open `!#if` directive
+struct OpenCondition {
+    /// Line number of the directive
+    line_number: u32,
+    /// `!#else` has been encountered for this directive
+    has_else: bool,
+}
+
+/// Collects [`FilterDiagnostic`] for the filter body
+pub(crate) struct FilterLinter<'c> {
+    boolean_expression_parser: BooleanExpressionParser<'c>,
+    diagnostics: Vec<FilterDiagnostic>,
+}
+
+impl<'c> FilterLinter<'c> {
+    pub(crate) fn new(configuration: &'c Configuration) -> Self {
+        Self {
+            boolean_expression_parser: BooleanExpressionParser::new(
+                &configuration.filters_compilation_policy,
+            ),
+            diagnostics: vec![],
+        }
+    }
+
+    /// Checks `body` of the filter, which is downloaded from `base_url`.
+    ///
+    /// # Returns
+    ///
+    /// Diagnostics ordered by line number
+    pub(crate) fn lint(mut self, body: &str, base_url: &str) -> Vec<FilterDiagnostic> {
+        let mut metadata_collector = MetadataCollector::new();
+        let mut open_conditions: Vec<OpenCondition> = vec![];
+
+        for (index, line) in body.split('\n').enumerate() {
+            let line_number = index as u32 + 1;
+            let trimmed = line.trim();
+
+            if trimmed.starts_with(DIRECTIVE_INCLUDE) {
+                metadata_collector.mark_reached_eod();
+                self.lint_include(trimmed, base_url, line_number);
+            } else if trimmed.starts_with(DIRECTIVE_IF) {
+                self.lint_condition(trimmed, line_number);
+                open_conditions.push(OpenCondition {
+                    line_number,
+                    has_else: false,
+                });
+            } else if trimmed.starts_with(DIRECTIVE_ELSE) {
+                match open_conditions.last_mut() {
+                    Some(condition) if !condition.has_else => condition.has_else = true,
+                    _ => self.error(
+                        line_number,
+                        FilterDiagnosticKind::UnbalancedElse,
+                        "!#else has no matching !#if",
+                    ),
+                }
+            } else if trimmed.starts_with(DIRECTIVE_ENDIF) {
+                if open_conditions.pop().is_none() {
+                    self.error(
+                        line_number,
+                        FilterDiagnosticKind::UnbalancedEndIf,
+                        "!#endif has no matching !#if",
+                    );
+                }
+            } else if !metadata_collector.is_reached_eod {
+                metadata_collector.collect_line(trimmed, index);
+
+                if !metadata_collector.is_reached_eod {
+                    self.lint_metadata_line(trimmed, line_number);
+                }
+            }
+        }
+
+        for condition in open_conditions {
+            self.error(
+                condition.line_number,
+                FilterDiagnosticKind::UnbalancedIf,
+                "!#if is not closed with !#endif",
+            );
+        }
+
+        self.lint_checksum(body);
+
+        self.diagnostics
+            .sort_by_key(|diagnostic| diagnostic.line_number);
+
+        self.diagnostics
+    }
+}
+
+impl FilterLinter<'_> {
+    fn lint_condition(&mut self, line: &str, line_number: u32) {
+        let expression = line.slice(DIRECTIVE_IF.len()..);
+        if expression.trim().is_empty() {
+            return self.error(
+                line_number,
+                FilterDiagnosticKind::EmptyIf,
+                "!#if has no condition",
+            );
+        }
+
+        if self.boolean_expression_parser.eval(expression).is_none() {
+            return self.error(
+                line_number,
+                FilterDiagnosticKind::InvalidBooleanExpression,
+                format!("Invalid condition: {}", expression.trim()),
+            );
+        }
+
+        let unknown_constants = self
+            .boolean_expression_parser
+            .collect_undefined_constants(expression)
+            .into_iter()
+            .filter(|constant| !KNOWN_COMPILER_CONSTANTS.contains(constant))
+            .map(str::to_string)
+            .collect::<Vec<String>>();
+
+        for constant in unknown_constants {
+            self.warning(
+                line_number,
+                FilterDiagnosticKind::UnknownConstant,
+                format!("Unknown constant \"{}\" is always false", constant),
+            );
+        }
+    }
+
+    fn lint_include(&mut self, line: &str, base_url: &str, line_number: u32) {
+        let include_path = match get_include_path(line) {
+            Ok(Some(include_path)) => include_path,
+            Ok(None) => {
+                return self.error(
+                    line_number,
+                    FilterDiagnosticKind::InvalidInclude,
+                    "!#include has no path",
+                )
+            }
+            Err(why) => {
+                return self.error(
+                    line_number,
+                    FilterDiagnosticKind::InvalidInclude,
+                    why.to_string(),
+                )
+            }
+        };
+
+        let base_scheme = UrlSchemes::from(get_scheme(base_url));
+        if let Some(include_scheme) = get_scheme(include_path) {
+            if base_scheme.is_web_scheme()
+                && compare_same_origin(base_url, include_path, base_scheme, include_scheme.into())
+                    .is_err()
+            {
+                return self.error(
+                    line_number,
+                    FilterDiagnosticKind::CrossOriginInclude,
+                    format!(
+                        "Included filter {} must have the same origin with the filter",
+                        include_path
+                    ),
+                );
+            }
+        }
+
+        match try_to_resolve_include_path_from_parent_url(base_url, include_path) {
+            Ok(absolute_url) if absolute_url == base_url => self.error(
+                line_number,
+                FilterDiagnosticKind::RecursiveInclude,
+                "Filter includes itself",
+            ),
+            Ok(_) => {}
+            Err(why) => self.error(
+                line_number,
+                FilterDiagnosticKind::InvalidInclude,
+                format!("Cannot resolve {}: {}", include_path, why),
+            ),
+        }
+    }
+
+    fn lint_metadata_line(&mut self, line: &str, line_number: u32) {
+        if let Some(value) = MetadataCollector::parse_line_for(KnownMetadataProperty::Expires, line)
+        {
+            if !value.is_empty() && process_expires(value.as_str()) <= 0 {
+                self.warning(
+                    line_number,
+                    FilterDiagnosticKind::MalformedExpires,
+                    format!("Cannot parse expiration period: {}", value),
+                );
+            }
+        }
+
+        if let Some(value) =
+            MetadataCollector::parse_line_for(KnownMetadataProperty::TimeUpdated, line)
+        {
+            if !value.is_empty() && DateTime::<Utc>::from_str(value.as_str()).is_err() {
+                self.warning(
+                    line_number,
+                    FilterDiagnosticKind::MalformedTimeUpdated,
+                    format!("Cannot parse update time: {}", value),
+                );
+            }
+        }
+    }
+
+    fn lint_checksum(&mut self, body: &str) {
+        let Err(FilterParserError::InvalidChecksum(actual, expected)) = validate_checksum(body)
+        else {
+            return;
+        };
+
+        let line_number = body
+            .split('\n')
+            .position(|line| {
+                MetadataCollector::parse_line_for(KnownMetadataProperty::Checksum, line.trim())
+                    .is_some()
+            })
+            .map(|index| index as u32 + 1)
+            .unwrap_or(1);
+
+        self.error(
+            line_number,
+            FilterDiagnosticKind::ChecksumMismatch,
+            format!("Checksum {} doesn't match contents: {}", expected, actual),
+        );
+    }
+
+    fn error(&mut self, line_number: u32, kind: FilterDiagnosticKind, message: impl Into<String>) {
+        self.push(line_number, FilterDiagnosticSeverity::Error, kind, message)
+    }
+
+    fn warning(
+        &mut self,
+        line_number: u32,
+        kind: FilterDiagnosticKind,
+        message: impl Into<String>,
+    ) {
+        self.push(
+            line_number,
+            FilterDiagnosticSeverity::Warning,
+            kind,
+            message,
+        )
+    }
+
+    fn push(
+        &mut self,
+        line_number: u32,
+        severity: FilterDiagnosticSeverity,
+        kind: FilterDiagnosticKind,
+        message: impl Into<String>,
+    ) {
+        self.diagnostics.push(FilterDiagnostic {
+            line_number,
+            severity,
+            kind,
+            message: message.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilterLinter;
+    use crate::manager::models::configuration::FiltersCompilationPolicy;
+    use crate::manager::models::filter_diagnostic::{
+        FilterDiagnosticKind, FilterDiagnosticSeverity,
+    };
+    use crate::Configuration;
+
+    fn lint(body: &str) -> Vec<(u32, FilterDiagnosticKind)> {
+        let mut conf = Configuration::default();
+        conf.filters_compilation_policy = FiltersCompilationPolicy::new(vec![String::from("my")]);
+
+        FilterLinter::new(&conf)
+            .lint(body, "https://example.org/filters/filter.txt")
+            .into_iter()
+            .map(|diagnostic| (diagnostic.line_number, diagnostic.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_directives() {
+        let body = "! Title: Test
+! Expires: soon
+! TimeUpdated: yesterday
+!#if (adguard && my)
+||a.com^
+!#else
+!#else
+!#endif
+!#endif
+!#if
+!#endif
+!#if (unknown || adguard_app_ios
+!#endif
+!#if unknown_platform
+||b.com^";
+
+        assert_eq!(
+            lint(body),
+            vec![
+                (2, FilterDiagnosticKind::MalformedExpires),
+                (3, FilterDiagnosticKind::MalformedTimeUpdated),
+                (7, FilterDiagnosticKind::UnbalancedElse),
+                (9, FilterDiagnosticKind::UnbalancedEndIf),
+                (10, FilterDiagnosticKind::EmptyIf),
+                (12, FilterDiagnosticKind::InvalidBooleanExpression),
+                (14, FilterDiagnosticKind::UnknownConstant),
+                (14, FilterDiagnosticKind::UnbalancedIf),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_includes() {
+        let body = "! Title: Test
+!#include common.txt
+!#include https://example.org/filters/other.txt
+!#include https://other.org/filter.txt
+!#include file:///etc/hosts
+!#include
+!#include filter.txt
+! Expires: garbage below metadata is not checked";
+
+        assert_eq!(
+            lint(body),
+            vec![
+                (4, FilterDiagnosticKind::CrossOriginInclude),
+                (5, FilterDiagnosticKind::CrossOriginInclude),
+                (6, FilterDiagnosticKind::InvalidInclude),
+                (7, FilterDiagnosticKind::RecursiveInclude),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_checksum() {
+        let valid = include_str!("../../../tests/fixtures/test_checksum.txt");
+        assert!(lint(valid).is_empty());
+
+        let invalid = valid.replacen("||", "||changed", 1);
+        let conf = Configuration::default();
+        let diagnostics = FilterLinter::new(&conf).lint(&invalid, "https://example.org/filter.txt");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, FilterDiagnosticKind::ChecksumMismatch);
+        assert_eq!(diagnostics[0].severity, FilterDiagnosticSeverity::Error);
+        assert!(invalid
+            .lines()
+            .nth(diagnostics[0].line_number as usize - 1)
+            .unwrap()
+            .contains("Checksum"));
+    }
+}
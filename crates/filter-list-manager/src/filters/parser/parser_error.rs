@@ -57,10 +57,19 @@ pub enum FilterParserError {
     #[error("NoContent")]
     NoContent,
 
+    /// Remote filter is not modified since the last download.
+    /// Server responded with `304 Not Modified` to the conditional request
+    #[error("NotModified")]
+    NotModified,
+
     /// When filter's body is not valid filters body by parser's heuristics
     #[error("Filter content is likely not a filter")]
     FilterContentIsLikelyNotAFilter,
 
+    /// Differential update patch is malformed or cannot be applied to the filter
+    #[error("InvalidDiffPatch: {0}")]
+    InvalidDiffPatch(String),
+
     /// Other errors
     #[error("{0}")]
     Other(String),
@@ -91,6 +100,29 @@ impl FilterParserError {
         Err(FilterParserError::InvalidChecksum(actual, expected))
     }
 
+    #[inline]
+    pub(crate) fn invalid_diff_patch<R, S>(error_source: S) -> Result<R, FilterParserError>
+    where
+        S: ToString,
+    {
+        Err(FilterParserError::InvalidDiffPatch(
+            error_source.to_string(),
+        ))
+    }
+
+    /// This error may disappear on the next attempt: network failure, timeout,
+    /// or server responded with 5xx, 408 or 429 status code
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            FilterParserError::Network(HttpClientError::Strict200Response(status, _)) => {
+                matches!(status, 408 | 429 | 500..=599)
+            }
+            FilterParserError::Network(_) => true,
+            FilterParserError::Io(IOError::TimedOut(_)) => true,
+            _ => false,
+        }
+    }
+
     #[inline]
     pub(crate) fn err<R>(self) -> Result<R, FilterParserError> {
         Err(self)
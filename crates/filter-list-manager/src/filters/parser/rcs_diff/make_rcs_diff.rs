@@ -0,0 +1,143 @@
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+use std::fmt::Write;
+
+/// Makes RCS diff from `old_contents` to `new_contents`, same as `diff -n` does.
+///
+/// Lines are compared with their terminators, so the last line without `\n`
+/// differs from the same line with it. Added lines are written as is, thus
+/// the diff doesn't end with `\n` if `new_contents` doesn't.
+pub(crate) fn make_rcs_diff(old_contents: &str, new_contents: &str) -> String {
+    let old_lines: Vec<&str> = old_contents.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new_contents.split_inclusive('\n').collect();
+
+    let mut out = String::new();
+
+    for operation in capture_diff_slices(Algorithm::Myers, &old_lines, &new_lines) {
+        match operation {
+            DiffOp::Equal { .. } => {}
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => {
+                write_delete(&mut out, old_index, old_len);
+            }
+            DiffOp::Insert {
+                old_index,
+                new_index,
+                new_len,
+            } => {
+                write_add(
+                    &mut out,
+                    old_index,
+                    &new_lines[new_index..new_index + new_len],
+                );
+            }
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => {
+                write_delete(&mut out, old_index, old_len);
+                // Lines are added after the last deleted one
+                write_add(
+                    &mut out,
+                    old_index + old_len,
+                    &new_lines[new_index..new_index + new_len],
+                );
+            }
+        }
+    }
+
+    out
+}
+
+/// Writes `d<line> <count>` command. `old_index` is zero-based
+fn write_delete(out: &mut String, old_index: usize, count: usize) {
+    let _ = writeln!(out, "d{} {}", old_index + 1, count);
+}
+
+/// Writes `a<line> <count>` command and added lines.
+/// `after_line` is one-based number of the old line, after which lines are added
+fn write_add(out: &mut String, after_line: usize, lines: &[&str]) {
+    let _ = writeln!(out, "a{} {}", after_line, lines.len());
+
+    lines.iter().for_each(|line| out.push_str(line));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::make_rcs_diff;
+    use crate::filters::parser::rcs_diff::apply_patch;
+    use crate::utils::iterators::lines_with_terminator::lines_with_terminator;
+
+    #[test]
+    fn test_make_rcs_diff_like_diff_utility() {
+        // See `test_apply_patch`
+        const LAO: &str = "The Way that can be told of is not the eternal Way;
+The name that can be named is not the eternal name.
+The Nameless is the origin of Heaven and Earth;
+The Named is the mother of all things.
+Therefore let there always be non-being,
+  so we may see their subtlety,
+And let there always be being,
+  so we may see their outcome.
+The two are the same,
+But after they are produced,
+  they have different names.
+";
+
+        const TZU: &str = "The Nameless is the origin of Heaven and Earth;
+The named is the mother of all things.
+
+Therefore let there always be non-being,
+  so we may see their subtlety,
+And let there always be being,
+  so we may see their outcome.
+The two are the same,
+But after they are produced,
+  they have different names.
+They both may be called deep and profound.
+Deeper and more profound,
+The door of all subtleties!
+";
+
+        const PATCH: &str = "d1 2
+d4 1
+a4 2
+The named is the mother of all things.
+
+a11 3
+They both may be called deep and profound.
+Deeper and more profound,
+The door of all subtleties!
+";
+
+        assert_eq!(make_rcs_diff(LAO, TZU), PATCH);
+    }
+
+    #[test]
+    fn test_make_rcs_diff_round_trip() {
+        // `apply_patch` may lose the final line feed, if only one of versions has it.
+        // So, patches are checked before publishing, see `make_diff_patch`
+        let versions = [
+            "\n",
+            "a\n",
+            "a\nb\n",
+            "b\na\n",
+            "a\n\n\nb\n\n",
+            "! Title: Filter\n! Diff-Path: patches/v1-s-1700000000-60.patch\n||example.org^\n",
+            "! Title: Filter\n! Diff-Path: patches/v2-s-1700000060-60.patch\n||example.org^\n||example.com^\n",
+            "||example.com^\n! Title: Filter\n\n||example.org^\n",
+        ];
+
+        for old in versions.iter() {
+            for new in versions.iter() {
+                let diff = make_rcs_diff(old, new);
+                let (patched, _) = apply_patch(old, lines_with_terminator(&diff).collect())
+                    .unwrap_or_else(|why| panic!("{:?} -> {:?}: {}", old, new, why));
+
+                assert_eq!(&patched, new, "{:?} -> {:?} with diff {:?}", old, new, diff);
+            }
+        }
+    }
+}
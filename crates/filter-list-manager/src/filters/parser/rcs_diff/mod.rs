@@ -1,5 +1,10 @@
+#[cfg(feature = "diff-builder")]
+mod make_rcs_diff;
 mod recognize_rcs;
 
+#[cfg(feature = "diff-builder")]
+pub(crate) use self::make_rcs_diff::make_rcs_diff;
+
 use self::recognize_rcs::{recognize_rcs, RCSOperations};
 use crate::filters::parser::metadata::collector::MetadataCollector;
 use crate::filters::parser::metadata::KnownMetadataProperty;
@@ -105,7 +110,7 @@ pub(crate) fn apply_patch(
                     }
                 }
             }
-            Err(e) => return FilterParserError::other_err_from_to_string(e),
+            Err(e) => return FilterParserError::invalid_diff_patch(e),
         }
     }
 
@@ -154,7 +159,7 @@ fn make_line_out_of_bounds_error<R>(
 ) -> Result<R, FilterParserError> {
     let default_str = "";
 
-    FilterParserError::other_err_from_to_string(
+    FilterParserError::invalid_diff_patch(
         format!(
             "Wrong diff. Request base file line {}, but it only has {} lines. \nFirst line of base filter: \"{}\".\n First diff line: \"{}\".",
             requested_line,
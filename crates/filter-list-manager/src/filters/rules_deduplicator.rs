@@ -0,0 +1,75 @@
+//! Deduplication of rules across filters
+use crate::filters::parser::is_rule_detector::is_line_is_rule;
+use std::collections::HashSet;
+
+/// Remembers rules, which have been already emitted.
+/// Comments and empty lines are never treated as duplicates
+#[derive(Default)]
+pub(crate) struct RulesDeduplicator {
+    emitted_rules: HashSet<String>,
+}
+
+impl RulesDeduplicator {
+    /// Returns `true` if this rule has been already emitted,
+    /// otherwise remembers it and returns `false`
+    pub(crate) fn is_duplicate(&mut self, line: &str) -> bool {
+        if !is_line_is_rule(line) {
+            return false;
+        }
+
+        if self.emitted_rules.contains(line) {
+            return true;
+        }
+
+        self.emitted_rules.insert(line.to_string());
+
+        false
+    }
+}
+
+/// Returns indices of filters in the order of rules ownership:
+/// trusted filters go first, the original order is kept otherwise.
+/// So each rule is kept in the first trusted filter containing it
+pub(crate) fn ownership_order<I>(is_trusted: I) -> Vec<usize>
+where
+    I: Iterator<Item = bool>,
+{
+    let (trusted, untrusted): (Vec<_>, Vec<_>) = is_trusted
+        .enumerate()
+        .partition(|(_, is_trusted)| *is_trusted);
+
+    trusted
+        .into_iter()
+        .chain(untrusted)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ownership_order, RulesDeduplicator};
+
+    #[test]
+    fn test_is_duplicate() {
+        let mut deduplicator = RulesDeduplicator::default();
+
+        assert!(!deduplicator.is_duplicate("||example.com^"));
+        assert!(deduplicator.is_duplicate("||example.com^"));
+        assert!(!deduplicator.is_duplicate("||example.org^"));
+
+        // Comments and empty lines are kept
+        assert!(!deduplicator.is_duplicate("! comment"));
+        assert!(!deduplicator.is_duplicate("! comment"));
+        assert!(!deduplicator.is_duplicate(""));
+        assert!(!deduplicator.is_duplicate(""));
+    }
+
+    #[test]
+    fn test_ownership_order() {
+        assert_eq!(
+            ownership_order([false, true, false, true].into_iter()),
+            vec![1, 3, 0, 2]
+        );
+        assert!(ownership_order([].into_iter()).is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::filters::parser::parser_error::FilterParserError;
 use crate::io::content_checkers::{check_contents_is_filter_contents, is_likely_media};
 use crate::io::http::blocking_client::BlockingClient;
+use crate::io::http::cache_validators::CacheValidators;
 use crate::io::{
     read_binary_by_url, read_file_by_url, url_schemes::UrlSchemes, ReadFilterFileError,
 };
@@ -20,6 +21,16 @@ pub(crate) enum FilterFetchPolicy {
     DiffUpdates,
 }
 
+/// Filter contents with details of the response
+pub(crate) struct FetchedFilterContents {
+    /// Filter contents
+    pub(crate) contents: String,
+    /// Validators of the response, if any
+    pub(crate) cache_validators: Option<CacheValidators>,
+    /// Status code of the response. [`None`] for non-http(s) urls
+    pub(crate) http_status: Option<u16>,
+}
+
 /// Synchronously fetch filter contents from absolute url, and makes content check
 pub(crate) fn fetch_filter_by_scheme_with_content_check(
     absolute_url: &str,
@@ -27,6 +38,35 @@ pub(crate) fn fetch_filter_by_scheme_with_content_check(
     shared_http_client: &BlockingClient,
     fetch_policy: FilterFetchPolicy,
 ) -> Result<String, FilterParserError> {
+    fetch_filter_by_scheme_conditionally(
+        absolute_url,
+        scheme,
+        shared_http_client,
+        fetch_policy,
+        None,
+    )
+    .map(|fetched| fetched.contents)
+}
+
+/// Works like [`fetch_filter_by_scheme_with_content_check`], but sends
+/// conditional request headers built from `cache_validators` for http(s) urls.
+///
+/// Returns filter contents, validators and status code of the response, if any.
+///
+/// # Failure
+///
+/// Returns [`FilterParserError::NotModified`] if `cache_validators` were sent
+/// and server responded with `304 Not Modified`
+pub(crate) fn fetch_filter_by_scheme_conditionally(
+    absolute_url: &str,
+    scheme: UrlSchemes,
+    shared_http_client: &BlockingClient,
+    fetch_policy: FilterFetchPolicy,
+    cache_validators: Option<&CacheValidators>,
+) -> Result<FetchedFilterContents, FilterParserError> {
+    let mut response_validators = None;
+    let mut http_status = None;
+
     let contents = match scheme {
         UrlSchemes::File => {
             let result = read_binary_by_url(absolute_url);
@@ -43,10 +83,31 @@ pub(crate) fn fetch_filter_by_scheme_with_content_check(
         }
 
         UrlSchemes::Https | UrlSchemes::Http => {
-            let (bytes, status) = shared_http_client
-                .get_filter_bytes(absolute_url)
+            let (bytes, status, validators, retry_after) = shared_http_client
+                .get_filter_bytes(absolute_url, cache_validators)
                 .map_err(FilterParserError::Network)?;
 
+            http_status = Some(status.as_u16());
+
+            if cache_validators.is_some() && status == StatusCode::NOT_MODIFIED {
+                return Err(FilterParserError::NotModified);
+            }
+
+            // Server is overloaded or rate limits us and tells when to come back
+            if let Some(retry_after_sec) = retry_after {
+                return Err(FilterParserError::Network(
+                    HttpClientError::make_retry_after(
+                        status,
+                        retry_after_sec,
+                        absolute_url.to_owned(),
+                    ),
+                ));
+            }
+
+            if !validators.is_empty() {
+                response_validators = Some(validators);
+            }
+
             if fetch_policy == FilterFetchPolicy::DiffUpdates {
                 if matches!(status, StatusCode::NOT_FOUND | StatusCode::NO_CONTENT)
                     || status == StatusCode::OK && bytes.is_empty()
@@ -55,12 +116,9 @@ pub(crate) fn fetch_filter_by_scheme_with_content_check(
                 }
 
                 if status.is_client_error() || status.is_server_error() {
-                    return Err(FilterParserError::Network(HttpClientError::NetworkError(
-                        format!(
-                            "Got HTTP status code \"{}\" while requesting filter",
-                            status
-                        ),
-                    )));
+                    return Err(FilterParserError::Network(
+                        HttpClientError::make_only_200_strict(status, absolute_url.to_owned()),
+                    ));
                 }
             } else {
                 // Regular filter policy
@@ -88,7 +146,11 @@ pub(crate) fn fetch_filter_by_scheme_with_content_check(
 
     check_contents_is_filter_contents(&string)?;
 
-    Ok(string)
+    Ok(FetchedFilterContents {
+        contents: string,
+        cache_validators: response_validators,
+        http_status,
+    })
 }
 
 /// Fetches json by scheme from url
@@ -0,0 +1,94 @@
+use crate::io::http::reqwest_transport::make_user_agent;
+use crate::io::http::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::manager::models::configuration::request_proxy_mode::RequestProxyMode;
+use crate::{Configuration, FLMError, FLMResult, HttpClientError};
+use reqwest::{Client, ClientBuilder, Proxy};
+use std::time::Duration;
+use tokio::runtime::Handle;
+
+/// [`HttpTransport`], which sends requests with async `reqwest` client on the tokio runtime.
+///
+/// Requests are awaited with [`Handle::block_on`], so the transport
+/// must be used only outside of the runtime threads, e.g. in `spawn_blocking`.
+///
+/// [`Handle::block_on`] can't drive IO and timers of a `current_thread` runtime.
+/// There, requests make progress only while another thread is inside `Runtime::block_on`
+/// of the same runtime, otherwise they hang until the request timeout
+pub(crate) struct AsyncReqwestTransport {
+    inner: Client,
+    runtime: Handle,
+}
+
+impl AsyncReqwestTransport {
+    /// Makes transport with timeout, user agent and proxy mode from `configuration`
+    pub(crate) fn new(configuration: &Configuration, runtime: Handle) -> FLMResult<Self> {
+        let mut builder = ClientBuilder::new()
+            .timeout(Duration::from_millis(
+                configuration.request_timeout_ms as u64,
+            ))
+            .user_agent(make_user_agent(configuration));
+
+        match configuration.request_proxy_mode {
+            RequestProxyMode::UseSystemProxy => {}
+            RequestProxyMode::NoProxy => {
+                builder = builder.no_proxy();
+            }
+            RequestProxyMode::UseCustomProxy { ref addr } => {
+                builder = builder.proxy(Proxy::all(addr).map_err(FLMError::from_display)?)
+            }
+        }
+
+        let client = builder.build().map_err(FLMError::from_display)?;
+
+        Ok(Self {
+            inner: client,
+            runtime,
+        })
+    }
+}
+
+impl HttpTransport for AsyncReqwestTransport {
+    /// Blocks the current thread until the response is received.
+    /// See the note on `current_thread` runtime in [`AsyncReqwestTransport`]
+    fn get(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+        let mut builder = self.inner.get(request.url);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+
+        // Request is spawned, so it is driven by runtime workers, not by this thread
+        let response_future = self.runtime.spawn(async move {
+            let response = builder
+                .send()
+                .await
+                .map_err(HttpClientError::make_network)?;
+
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    value
+                        .to_str()
+                        .ok()
+                        .map(|value| (name.to_string(), value.to_string()))
+                })
+                .collect();
+
+            let body = response
+                .bytes()
+                .await
+                .map_err(HttpClientError::make_body_recovery)?;
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body: body.to_vec(),
+            })
+        });
+
+        self.runtime
+            .block_on(response_future)
+            .map_err(|why| HttpClientError::NetworkError(why.to_string()))?
+    }
+}
@@ -1,14 +1,18 @@
-use crate::manager::models::configuration::request_proxy_mode::RequestProxyMode;
-use crate::{Configuration, FLMError, FLMResult, HttpClientError};
+use crate::io::http::cache_validators::CacheValidators;
+use crate::io::http::reqwest_transport::ReqwestTransport;
+use crate::io::http::retry_after::parse_retry_after;
+use crate::io::http::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::{Configuration, FLMResult, HttpClientError};
 use bytes::Bytes;
-use reqwest::blocking::{Client, ClientBuilder};
-use reqwest::{Proxy, StatusCode};
+use chrono::Utc;
+use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
-use std::time::Duration;
+use std::sync::Arc;
 
-/// Standard blocking client wrapper
+/// Standard blocking client wrapper.
+/// Sends requests via [`HttpTransport`] from configuration or via [`ReqwestTransport`]
 pub(crate) struct BlockingClient {
-    inner: Client,
+    transport: Arc<dyn HttpTransport>,
 }
 
 impl BlockingClient {
@@ -16,68 +20,221 @@ impl BlockingClient {
     ///
     /// * `configuration` - FLM [`Configuration`]
     pub(crate) fn new(configuration: &Configuration) -> FLMResult<Self> {
-        let mut builder = ClientBuilder::new()
-            .timeout(Duration::from_millis(
-                configuration.request_timeout_ms as u64,
-            ))
-            .user_agent(format!(
-                "{}/{} {}/{}",
-                configuration.app_name,
-                configuration.version,
-                env!("CARGO_PKG_NAME"),
-                env!("CARGO_PKG_VERSION")
-            ));
-
-        match configuration.request_proxy_mode {
-            RequestProxyMode::UseSystemProxy => {}
-            RequestProxyMode::NoProxy => {
-                builder = builder.no_proxy();
-            }
-            RequestProxyMode::UseCustomProxy { ref addr } => {
-                builder = builder.proxy(Proxy::all(addr).map_err(FLMError::from_display)?)
+        let transport: Arc<dyn HttpTransport> = match configuration.http_transport {
+            Some(ref transport) => Arc::clone(transport),
+            None => Arc::new(ReqwestTransport::new(configuration)?),
+        };
+
+        Ok(Self { transport })
+    }
+
+    /// Gets filter bytes, status code, response validators and `Retry-After` delay in seconds.
+    /// `Retry-After` is read only for `429 Too Many Requests` and `503 Service Unavailable` responses.
+    /// If `cache_validators` are passed, request will be conditional,
+    /// so server may answer with `304 Not Modified` and empty body
+    pub(crate) fn get_filter_bytes(
+        &self,
+        url: &str,
+        cache_validators: Option<&CacheValidators>,
+    ) -> Result<(Bytes, StatusCode, CacheValidators, Option<u64>), HttpClientError> {
+        let (response, status) = self.get(url, cache_validators)?;
+
+        let headers = response.header_map();
+        let response_validators = CacheValidators::from_headers(&headers);
+        let retry_after = match status {
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+                parse_retry_after(&headers, Utc::now())
             }
+            _ => None,
+        };
+
+        Ok((
+            Bytes::from(response.body),
+            status,
+            response_validators,
+            retry_after,
+        ))
+    }
+
+    /// Gets a json from `url` and constructs type `T`
+    pub(crate) fn get_json<T>(&self, url: &str) -> Result<T, HttpClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let (response, status) = self.get(url, None)?;
+
+        Self::parse_json(url, status, &response.body)
+    }
+
+    /// Gets a json from `url` with conditional request headers and constructs type `T`
+    ///
+    /// Returns [`None`] if server responded with `304 Not Modified`
+    pub(crate) fn get_json_conditionally<T>(
+        &self,
+        url: &str,
+        cache_validators: Option<&CacheValidators>,
+    ) -> Result<Option<(T, CacheValidators)>, HttpClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let (response, status) = self.get(url, cache_validators)?;
+
+        if cache_validators.is_some() && status == StatusCode::NOT_MODIFIED {
+            return Ok(None);
         }
 
-        let client = builder.build().map_err(FLMError::from_display)?;
+        let value = Self::parse_json(url, status, &response.body)?;
 
-        Ok(Self { inner: client })
+        Ok(Some((
+            value,
+            CacheValidators::from_headers(&response.header_map()),
+        )))
     }
 
-    /// Gets filter bytes and status code
-    pub(crate) fn get_filter_bytes(
+    /// Sends GET request with conditional headers built from `cache_validators`
+    fn get(
         &self,
         url: &str,
-    ) -> Result<(Bytes, StatusCode), HttpClientError> {
-        let response = self
-            .inner
-            .get(url)
-            .send()
-            .map_err(HttpClientError::make_network)?;
+        cache_validators: Option<&CacheValidators>,
+    ) -> Result<(HttpResponse, StatusCode), HttpClientError> {
+        let mut headers = vec![];
+        if let Some(validators) = cache_validators {
+            validators.apply(&mut headers);
+        }
 
-        let status = response.status();
+        let response = self.transport.get(HttpRequest {
+            url: url.to_string(),
+            headers,
+        })?;
 
-        let bytes = response
-            .bytes()
-            .map_err(HttpClientError::make_body_recovery)?;
+        let status = StatusCode::from_u16(response.status)
+            .map_err(|why| HttpClientError::NetworkError(why.to_string()))?;
 
-        Ok((bytes, status))
+        Ok((response, status))
     }
 
-    /// Gets a json from `url` and constructs type `T`
-    pub(crate) fn get_json<T>(&self, url: &str) -> Result<T, HttpClientError>
+    /// Constructs type `T` from the body of successful response
+    fn parse_json<T>(url: &str, status: StatusCode, body: &[u8]) -> Result<T, HttpClientError>
     where
         T: DeserializeOwned,
     {
-        let response = self
-            .inner
-            .get(url)
-            .send()
-            .map_err(HttpClientError::make_network)?
-            .error_for_status()
-            .map_err(HttpClientError::make_network)?;
-
-        response
-            .json::<T>()
-            .map_err(HttpClientError::make_body_recovery)
+        if !status.is_success() {
+            return Err(HttpClientError::make_status(status, url));
+        }
+
+        serde_json::from_slice::<T>(body)
+            .map_err(|why| HttpClientError::BodyRecoveryFailed(why.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockingClient;
+    use crate::io::http::cache_validators::CacheValidators;
+    use crate::io::http::transport::{HttpRequest, HttpResponse, HttpTransport};
+    use crate::{Configuration, HttpClientError};
+    use reqwest::StatusCode;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    /// Responds with predefined response and remembers requests
+    struct FakeTransport {
+        response: HttpResponse,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl HttpTransport for FakeTransport {
+        fn get(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+            self.requests.lock().unwrap().push(request);
+
+            Ok(self.response.clone())
+        }
+    }
+
+    fn make_client(
+        status: u16,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> (BlockingClient, Arc<FakeTransport>) {
+        let transport = Arc::new(FakeTransport {
+            response: HttpResponse {
+                status,
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                body: body.as_bytes().to_vec(),
+            },
+            requests: Mutex::new(vec![]),
+        });
+
+        let mut configuration = Configuration::default();
+        configuration.http_transport = Some(transport.clone());
+
+        (BlockingClient::new(&configuration).unwrap(), transport)
+    }
+
+    #[test]
+    fn test_get_filter_bytes_via_transport() {
+        let (client, transport) = make_client(
+            429,
+            &[("etag", "\"v2\""), ("Retry-After", "30")],
+            "! Title: Test",
+        );
+
+        let validators = CacheValidators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+
+        let (bytes, status, response_validators, retry_after) = client
+            .get_filter_bytes("https://example.org/filter.txt", Some(&validators))
+            .unwrap();
+
+        assert_eq!(bytes.as_ref(), b"! Title: Test");
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response_validators.etag.as_deref(), Some("\"v2\""));
+        assert_eq!(retry_after, Some(30));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[0].url, "https://example.org/filter.txt");
+        assert_eq!(
+            requests[0].headers,
+            vec![("if-none-match".to_string(), "\"v1\"".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_get_json_via_transport() {
+        let (client, _) = make_client(200, &[], r#"{"a": 1}"#);
+        let json = client
+            .get_json::<HashMap<String, i32>>("https://example.org/index.json")
+            .unwrap();
+        assert_eq!(json.get("a"), Some(&1));
+
+        let (client, _) = make_client(304, &[], "");
+        let validators = CacheValidators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+        assert!(client
+            .get_json_conditionally::<HashMap<String, i32>>(
+                "https://example.org/index.json",
+                Some(&validators)
+            )
+            .unwrap()
+            .is_none());
+
+        let (client, _) = make_client(404, &[], "");
+        assert!(matches!(
+            client.get_json::<HashMap<String, i32>>("https://example.org/index.json"),
+            Err(HttpClientError::NetworkError(_))
+        ));
+
+        let (client, _) = make_client(200, &[], "not a json");
+        assert!(matches!(
+            client.get_json::<HashMap<String, i32>>("https://example.org/index.json"),
+            Err(HttpClientError::BodyRecoveryFailed(_))
+        ));
     }
 }
@@ -0,0 +1,82 @@
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+
+/// Response validators for conditional HTTP requests.
+/// Values are taken from `ETag` and `Last-Modified` response headers and sent
+/// back as `If-None-Match` and `If-Modified-Since` request headers
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(crate) struct CacheValidators {
+    /// `ETag` header value
+    pub(crate) etag: Option<String>,
+    /// `Last-Modified` header value
+    pub(crate) last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    /// Collects validators from response headers
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let get_header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+                .filter(|value| !value.is_empty())
+        };
+
+        Self {
+            etag: get_header(ETAG),
+            last_modified: get_header(LAST_MODIFIED),
+        }
+    }
+
+    /// Returns `true` if there is nothing to send with the next request
+    pub(crate) fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Adds conditional headers to the request `headers`
+    pub(crate) fn apply(&self, headers: &mut Vec<(String, String)>) {
+        if let Some(ref etag) = self.etag {
+            headers.push((IF_NONE_MATCH.to_string(), etag.clone()));
+        }
+
+        if let Some(ref last_modified) = self.last_modified {
+            headers.push((IF_MODIFIED_SINCE.to_string(), last_modified.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CacheValidators;
+    use reqwest::header::{HeaderMap, HeaderValue, ETAG, LAST_MODIFIED};
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"5f3e-1a2b\""));
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+
+        let validators = CacheValidators::from_headers(&headers);
+
+        assert_eq!(validators.etag.as_deref(), Some("\"5f3e-1a2b\""));
+        assert_eq!(
+            validators.last_modified.as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        assert!(!validators.is_empty());
+    }
+
+    #[test]
+    fn test_from_headers_without_validators() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static(""));
+
+        let validators = CacheValidators::from_headers(&headers);
+
+        assert!(validators.is_empty());
+    }
+}
@@ -8,6 +8,10 @@ pub enum HttpClientError {
     #[error("Network error: {0}")]
     NetworkError(String),
 
+    /// Request or response reading has timed out
+    #[error("Request timed out: {0}")]
+    TimedOut(String),
+
     /// Deserialization/Body reading failed
     #[error("Body recovery failed: {0}")]
     BodyRecoveryFailed(String),
@@ -15,14 +19,30 @@ pub enum HttpClientError {
     /// Should have only 200 OK successful status code. e.g. 204 would be an error.
     #[error("Expected strictly 200 status code, but {0} given for url: {1}")]
     Strict200Response(u16, String),
+
+    /// Server responded with 429 or 503 status code and asked to retry after the given number of seconds
+    #[error(
+        "Server responded with {0} status code and asked to retry after {1} seconds for url: {2}"
+    )]
+    RetryAfter(u16, u64, String),
 }
 
 impl HttpClientError {
     #[inline]
     pub(crate) fn make_network(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return Self::TimedOut(error.to_string());
+        }
+
         Self::NetworkError(error.to_string())
     }
 
+    /// Makes error for unsuccessful status code of the response
+    #[inline]
+    pub(crate) fn make_status(status: StatusCode, url: &str) -> Self {
+        Self::NetworkError(format!("HTTP status {} for url ({})", status, url))
+    }
+
     #[inline]
     pub(crate) fn make_body_recovery(error: reqwest::Error) -> Self {
         Self::BodyRecoveryFailed(error.to_string())
@@ -32,4 +52,13 @@ impl HttpClientError {
     pub(crate) fn make_only_200_strict(actual_code: StatusCode, url: String) -> Self {
         Self::Strict200Response(actual_code.as_u16(), url)
     }
+
+    #[inline]
+    pub(crate) fn make_retry_after(
+        actual_code: StatusCode,
+        retry_after_sec: u64,
+        url: String,
+    ) -> Self {
+        Self::RetryAfter(actual_code.as_u16(), retry_after_sec, url)
+    }
 }
@@ -1,2 +1,8 @@
+#[cfg(feature = "async")]
+pub(crate) mod async_reqwest_transport;
 pub(crate) mod blocking_client;
+pub(crate) mod cache_validators;
 pub mod error;
+pub(crate) mod reqwest_transport;
+pub(crate) mod retry_after;
+pub mod transport;
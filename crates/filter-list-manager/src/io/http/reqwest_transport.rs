@@ -0,0 +1,80 @@
+use crate::io::http::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::manager::models::configuration::request_proxy_mode::RequestProxyMode;
+use crate::{Configuration, FLMError, FLMResult, HttpClientError};
+use reqwest::blocking::{Client, ClientBuilder};
+use reqwest::Proxy;
+use std::time::Duration;
+
+/// Default [`HttpTransport`] implementation
+pub(crate) struct ReqwestTransport {
+    inner: Client,
+}
+
+impl ReqwestTransport {
+    /// Makes transport with timeout, user agent and proxy mode from `configuration`
+    pub(crate) fn new(configuration: &Configuration) -> FLMResult<Self> {
+        let mut builder = ClientBuilder::new()
+            .timeout(Duration::from_millis(
+                configuration.request_timeout_ms as u64,
+            ))
+            .user_agent(make_user_agent(configuration));
+
+        match configuration.request_proxy_mode {
+            RequestProxyMode::UseSystemProxy => {}
+            RequestProxyMode::NoProxy => {
+                builder = builder.no_proxy();
+            }
+            RequestProxyMode::UseCustomProxy { ref addr } => {
+                builder = builder.proxy(Proxy::all(addr).map_err(FLMError::from_display)?)
+            }
+        }
+
+        let client = builder.build().map_err(FLMError::from_display)?;
+
+        Ok(Self { inner: client })
+    }
+}
+
+/// Makes `User-Agent` header value from the app name and version
+pub(crate) fn make_user_agent(configuration: &Configuration) -> String {
+    format!(
+        "{}/{} {}/{}",
+        configuration.app_name,
+        configuration.version,
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn get(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+        let mut builder = self.inner.get(request.url);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send().map_err(HttpClientError::make_network)?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect();
+
+        let body = response
+            .bytes()
+            .map_err(HttpClientError::make_body_recovery)?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body: body.to_vec(),
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+/// Parses `Retry-After` header into the number of seconds to wait.
+/// Header may contain either delay in seconds or HTTP-date.
+/// Dates in the past are treated as zero delay
+///
+/// * `headers` - Response headers
+/// * `now` - Current time, for HTTP-date values
+pub(crate) fn parse_retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<u64> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(seconds);
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+
+    Some((date.timestamp() - now.timestamp()).max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_retry_after;
+    use chrono::{DateTime, Utc};
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+    fn parse(value: &'static str) -> Option<u64> {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(value));
+
+        parse_retry_after(&headers, now)
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse("120"), Some(120));
+        assert_eq!(parse(" 0 "), Some(0));
+        assert_eq!(parse("Wed, 21 Oct 2015 07:29:30 GMT"), Some(90));
+        assert_eq!(parse("Wed, 21 Oct 2015 07:00:00 GMT"), Some(0));
+        assert_eq!(parse("-5"), None);
+        assert_eq!(parse("soon"), None);
+
+        assert_eq!(parse_retry_after(&HeaderMap::new(), Utc::now()), None);
+    }
+}
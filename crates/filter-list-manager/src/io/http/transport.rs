@@ -0,0 +1,82 @@
+//! Pluggable transport for HTTP(S) requests of the library
+
+use crate::HttpClientError;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// HTTP GET request
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpRequest {
+    /// Absolute url
+    pub url: String,
+    /// Additional request headers, e.g. conditional request headers.
+    /// Header names are case-insensitive
+    pub headers: Vec<(String, String)>,
+}
+
+/// HTTP response
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HttpResponse {
+    /// Status code
+    pub status: u16,
+    /// Response headers. Header names are case-insensitive
+    pub headers: Vec<(String, String)>,
+    /// Response body. Must be already decoded, if server used `Content-Encoding`
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Collects headers into [`HeaderMap`]. Invalid headers are skipped
+    pub(crate) fn header_map(&self) -> HeaderMap {
+        self.headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Transport for all HTTP(S) requests of the library.
+///
+/// By default, requests are made by the built-in `reqwest` client, which respects
+/// [`crate::Configuration::request_timeout_ms`], [`crate::Configuration::request_proxy_mode`]
+/// and sends `User-Agent` built from the app name and version.
+/// Custom transport, set via [`crate::Configuration::http_transport`], is responsible for all of that itself.
+pub trait HttpTransport: Send + Sync {
+    /// Performs GET request.
+    ///
+    /// Response must be returned for any status code, including 304, 4xx and 5xx.
+    ///
+    /// # Failure
+    ///
+    /// Returns [`HttpClientError::NetworkError`] if response cannot be received
+    /// and [`HttpClientError::TimedOut`] if request has timed out.
+    /// Will be called from worker threads
+    fn get(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpResponse;
+    use reqwest::header::ETAG;
+
+    #[test]
+    fn test_header_map() {
+        let response = HttpResponse {
+            status: 200,
+            headers: vec![
+                ("ETag".to_string(), "\"abc\"".to_string()),
+                ("Bad Header".to_string(), "value".to_string()),
+            ],
+            body: vec![],
+        };
+
+        let headers = response.header_map();
+
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers.get(ETAG).unwrap(), "\"abc\"");
+    }
+}
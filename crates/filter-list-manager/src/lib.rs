@@ -245,32 +245,70 @@
 //! [Facade Interface](./src/manager/mod.rs)
 //!
 
-pub use crate::filters::parser::parser_error::FilterParserError;
+#[cfg(feature = "diff-builder")]
+pub use crate::filters::parser::diff_updates::diff_builder::{
+    make_batch_diff_patch, make_diff_patch, set_diff_path, BatchDiffPatchList,
+};
+pub use crate::filters::parser::parser_error::{FilterParserError, FilterParserErrorContext};
 pub use crate::io::error::IOError;
 pub use crate::io::http::error::HttpClientError;
+pub use crate::io::http::transport::{HttpRequest, HttpResponse, HttpTransport};
+#[cfg(feature = "async")]
+pub use crate::manager::async_filter_list_manager::AsyncFilterListManager;
 /// # Re-exports
 pub use crate::manager::filter_list_manager_impl::FilterListManagerImpl;
+pub use crate::manager::models::active_rules_export::{
+    ActiveRulesExportManifest, ActiveRulesExportOptions, ExportedFilterInfo,
+};
 pub use crate::manager::models::active_rules_info::ActiveRulesInfo;
 pub use crate::manager::models::active_rules_info_raw::ActiveRulesInfoRaw;
 pub use crate::manager::models::configuration::Configuration;
 pub use crate::manager::models::configuration::FilterListType;
+pub use crate::manager::models::configuration::FilterRegistry;
 pub use crate::manager::models::configuration::Locale;
 pub use crate::manager::models::configuration::RequestProxyMode;
+pub use crate::manager::models::configuration::RulesStorageCompression;
+pub use crate::manager::models::deduplicated_active_rules::{
+    DeduplicatedActiveRules, DeduplicatedActiveRulesRaw, DuplicatesByFilter,
+};
+pub use crate::manager::models::disabled_rule_pattern::{
+    DisabledRulePattern, DisabledRulePatternKind, DisabledRulePatterns,
+};
 pub use crate::manager::models::disabled_rules_raw::DisabledRulesRaw;
+pub use crate::manager::models::filter_diagnostic::{
+    FilterDiagnostic, FilterDiagnosticKind, FilterDiagnosticSeverity,
+};
 pub use crate::manager::models::filter_group::FilterGroup;
 pub use crate::manager::models::filter_list_rules::FilterListRules;
 pub use crate::manager::models::filter_list_rules_raw::FilterListRulesRaw;
 pub use crate::manager::models::filter_tag::FilterTag;
+pub use crate::manager::models::filter_trust_level::FilterTrustLevel;
+pub use crate::manager::models::filter_update_failure::FilterUpdateFailure;
+pub use crate::manager::models::filter_update_preview::FilterUpdatePreview;
+pub use crate::manager::models::filter_version::FilterVersion;
 pub use crate::manager::models::flm_error::FLMError;
+pub use crate::manager::models::import_user_state_result::{
+    ImportUserStateResult, UserStateConflict, UserStateConflictKind,
+};
+pub use crate::manager::models::rule_provenance::RuleProvenance;
+pub use crate::manager::models::rule_search::{RuleSearchMatch, RuleSearchOptions};
 pub use crate::manager::models::rules_count_by_filter::RulesCountByFilter;
 pub use crate::manager::models::stored_filter_metadata::StoredFilterMetadata;
-pub use crate::manager::models::update_result::UpdateFilterError;
+pub use crate::manager::models::update_progress::{
+    UpdateCancellationToken, UpdateObserver, UpdateProgressEvent, UpdateProgressStage,
+};
+pub use crate::manager::models::update_result::{
+    FilterUpdateOutcome, FilterUpdateReport, UpdateFailureKind, UpdateFilterError,
+};
+pub use crate::manager::models::update_scheduler::{
+    UpdateSchedulerEvent, UpdateSchedulerObserver, UpdateSchedulerSettings, UpdateSchedulerTask,
+};
 pub use crate::manager::models::FilterId;
 pub use crate::manager::models::FilterListMetadata;
 pub use crate::manager::models::FilterListMetadataWithBody;
 pub use crate::manager::models::FullFilterList;
 pub use crate::manager::models::UpdateResult;
-pub use crate::manager::models::{MovedFilterInfo, PullMetadataResult};
+pub use crate::manager::models::{FailedFilterRegistry, MovedFilterInfo, PullMetadataResult};
 pub use crate::manager::FilterListManager;
 pub use crate::storage::constants::*;
 pub use crate::utils::integrity::generate_random_key;
@@ -0,0 +1,361 @@
+//! Async facade over [`FilterListManagerImpl`] for tokio-based applications
+
+use super::filter_list_manager_impl::FilterListManagerImpl;
+use super::models::configuration::Configuration;
+use super::models::filter_update_preview::FilterUpdatePreview;
+use super::models::{FilterId, FilterListMetadata, FilterListMetadataWithBody};
+use super::models::{PullMetadataResult, UpdateResult};
+use super::FilterListManager;
+use crate::io::http::async_reqwest_transport::AsyncReqwestTransport;
+use crate::io::http::transport::HttpTransport;
+use crate::manager::models::configuration::request_proxy_mode::RequestProxyMode;
+use crate::{FLMError, FLMResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::runtime::Handle;
+use tokio::task::spawn_blocking;
+
+/// Async version of [`FilterListManager`].
+///
+/// All operations are executed with [`spawn_blocking`] on the blocking threads of the runtime,
+/// so they never block its workers, and long updates don't hold back other calls.
+/// Network requests are sent with async `reqwest` client on the runtime, where the manager
+/// has been created. Methods have the same semantics and results as the corresponding
+/// methods of [`FilterListManagerImpl`].
+///
+/// On `current_thread` runtime requests are driven only by the thread inside `Runtime::block_on`,
+/// so the manager must be awaited there, see [`AsyncReqwestTransport`].
+///
+/// Methods, which don't have async version, are available via [`Self::run`] and [`Self::run_mut`]
+pub struct AsyncFilterListManager {
+    inner: Arc<RwLock<FilterListManagerImpl>>,
+    runtime: Handle,
+    /// Transport has been set by the user, so it must not be replaced on proxy mode change
+    has_custom_transport: AtomicBool,
+}
+
+impl AsyncFilterListManager {
+    /// Makes manager with `configuration`. Must be called within tokio runtime.
+    ///
+    /// If [`Configuration::http_transport`] is not set, async `reqwest` client will be used
+    ///
+    /// # Failure
+    ///
+    /// Returns [`FLMError::Other`] if there is no current runtime,
+    /// otherwise fails as [`FilterListManager::new`]
+    pub async fn new(mut configuration: Configuration) -> FLMResult<Self> {
+        let runtime = Handle::try_current().map_err(FLMError::from_display)?;
+
+        let has_custom_transport = configuration.http_transport.is_some();
+        if !has_custom_transport {
+            configuration.http_transport = Some(Arc::new(AsyncReqwestTransport::new(
+                &configuration,
+                runtime.clone(),
+            )?));
+        }
+
+        let inner = run_blocking(move || FilterListManagerImpl::new(configuration)).await?;
+
+        Ok(Self {
+            inner: Arc::new(RwLock::new(*inner)),
+            runtime,
+            has_custom_transport: AtomicBool::new(has_custom_transport),
+        })
+    }
+
+    /// Runs `job` with shared access to the manager on a blocking thread
+    pub async fn run<F, R>(&self, job: F) -> FLMResult<R>
+    where
+        F: FnOnce(&FilterListManagerImpl) -> FLMResult<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+
+        run_blocking(move || job(&*inner.read().map_err(FLMError::from_display)?)).await
+    }
+
+    /// Runs `job` with exclusive access to the manager on a blocking thread
+    pub async fn run_mut<F, R>(&self, job: F) -> FLMResult<R>
+    where
+        F: FnOnce(&mut FilterListManagerImpl) -> FLMResult<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+
+        run_blocking(move || job(&mut *inner.write().map_err(FLMError::from_display)?)).await
+    }
+
+    /// See [`FilterListManager::fetch_filter_list_metadata`]
+    pub async fn fetch_filter_list_metadata(&self, url: String) -> FLMResult<FilterListMetadata> {
+        self.run(move |flm| flm.fetch_filter_list_metadata(url))
+            .await
+    }
+
+    /// See [`FilterListManager::fetch_filter_list_metadata_with_body`]
+    pub async fn fetch_filter_list_metadata_with_body(
+        &self,
+        url: String,
+    ) -> FLMResult<FilterListMetadataWithBody> {
+        self.run(move |flm| flm.fetch_filter_list_metadata_with_body(url))
+            .await
+    }
+
+    /// See [`FilterListManager::update_filters`]
+    pub async fn update_filters(
+        &self,
+        ignore_filters_expiration: bool,
+        loose_timeout: i32,
+        ignore_filters_status: bool,
+    ) -> FLMResult<Option<UpdateResult>> {
+        self.run(move |flm| {
+            flm.update_filters(
+                ignore_filters_expiration,
+                loose_timeout,
+                ignore_filters_status,
+            )
+        })
+        .await
+    }
+
+    /// See [`FilterListManager::update_filters_by_ids`]
+    pub async fn update_filters_by_ids(
+        &self,
+        ids: Vec<FilterId>,
+        ignore_filters_expiration: bool,
+        loose_timeout: i32,
+        ignore_filters_status: bool,
+    ) -> FLMResult<Option<UpdateResult>> {
+        self.run(move |flm| {
+            flm.update_filters_by_ids(
+                ids,
+                ignore_filters_expiration,
+                loose_timeout,
+                ignore_filters_status,
+            )
+        })
+        .await
+    }
+
+    /// See [`FilterListManager::force_update_filters_by_ids`]
+    pub async fn force_update_filters_by_ids(
+        &self,
+        ids: Vec<FilterId>,
+        loose_timeout: i32,
+    ) -> FLMResult<Option<UpdateResult>> {
+        self.run(move |flm| flm.force_update_filters_by_ids(ids, loose_timeout))
+            .await
+    }
+
+    /// See [`FilterListManager::preview_filter_update`]
+    pub async fn preview_filter_update(
+        &self,
+        filter_id: FilterId,
+    ) -> FLMResult<FilterUpdatePreview> {
+        self.run(move |flm| flm.preview_filter_update(filter_id))
+            .await
+    }
+
+    /// See [`FilterListManager::pull_metadata`]
+    pub async fn pull_metadata(&self) -> FLMResult<PullMetadataResult> {
+        self.run(|flm| flm.pull_metadata()).await
+    }
+
+    /// Cancels current filters update or metadata pull.
+    /// See [`FilterListManager::get_update_cancellation_token`]
+    pub fn cancel_update(&self) -> FLMResult<()> {
+        self.inner
+            .read()
+            .map_err(FLMError::from_display)?
+            .get_update_cancellation_token()
+            .cancel();
+
+        Ok(())
+    }
+
+    /// See [`FilterListManager::set_proxy_mode`].
+    /// Default transport will be rebuilt with the new mode
+    pub async fn set_proxy_mode(&self, mode: RequestProxyMode) -> FLMResult<()> {
+        let has_custom_transport = self.has_custom_transport.load(Ordering::SeqCst);
+        let runtime = self.runtime.clone();
+
+        self.run_mut(move |flm| {
+            flm.set_proxy_mode(mode);
+
+            if !has_custom_transport {
+                let transport = AsyncReqwestTransport::new(flm.get_configuration(), runtime)?;
+                flm.set_http_transport(Some(Arc::new(transport)));
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// See [`FilterListManager::set_http_transport`].
+    /// [`None`] restores default async `reqwest` transport
+    pub async fn set_http_transport(
+        &self,
+        transport: Option<Arc<dyn HttpTransport>>,
+    ) -> FLMResult<()> {
+        let runtime = self.runtime.clone();
+        let has_custom_transport = transport.is_some();
+
+        self.run_mut(move |flm| {
+            let transport = match transport {
+                Some(transport) => transport,
+                None => Arc::new(AsyncReqwestTransport::new(
+                    flm.get_configuration(),
+                    runtime,
+                )?),
+            };
+
+            flm.set_http_transport(Some(transport));
+
+            Ok(())
+        })
+        .await?;
+
+        self.has_custom_transport
+            .store(has_custom_transport, Ordering::SeqCst);
+
+        Ok(())
+    }
+}
+
+/// Runs blocking `job` with [`spawn_blocking`] and awaits its result
+///
+/// # Failure
+///
+/// Returns [`FLMError::Other`] if the job has panicked or the runtime is shutting down
+async fn run_blocking<F, R>(job: F) -> FLMResult<R>
+where
+    F: FnOnce() -> FLMResult<R> + Send + 'static,
+    R: Send + 'static,
+{
+    spawn_blocking(job).await.map_err(FLMError::from_display)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsyncFilterListManager;
+    use crate::test_utils::tests_http_server::{TestsHttpResponse, TestsHttpServer};
+    use crate::{Configuration, FilterListManager};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tokio::runtime::Builder;
+    use tokio::task::yield_now;
+
+    #[test]
+    fn test_async_fetch_and_update_filters() {
+        let server = TestsHttpServer::start(|_| {
+            TestsHttpResponse::new(
+                200,
+                "! Title: Async filter\n! Version: 1.0.0\n||example.org^\n",
+            )
+        });
+        let url = server.url("/filter.txt");
+
+        // Requests are driven by the thread, which awaits the manager
+        let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+
+        runtime.block_on(async {
+            let mut conf = Configuration::default();
+            conf.app_name = "FlmApp".to_string();
+            conf.version = "1.2.3".to_string();
+            let flm = AsyncFilterListManager::new(conf).await.unwrap();
+
+            let metadata = flm.fetch_filter_list_metadata(url.clone()).await.unwrap();
+            assert_eq!(metadata.title, "Async filter");
+            assert_eq!(metadata.version, "1.0.0");
+
+            let install_url = url.clone();
+            let filter = flm
+                .run(move |flm| flm.install_custom_filter_list(install_url, true, None, None))
+                .await
+                .unwrap();
+
+            let result = flm
+                .force_update_filters_by_ids(vec![filter.id], 0)
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(result.filters_errors.is_empty());
+
+            assert!(flm
+                .fetch_filter_list_metadata(server.url("/missing").replace("http", "ftp"))
+                .await
+                .is_err());
+        });
+
+        assert!(server.requests().iter().all(|request| request
+            .header("user-agent")
+            .unwrap()
+            .starts_with("FlmApp/1.2.3")));
+    }
+
+    #[test]
+    fn test_long_calls_dont_block_others_on_current_thread_runtime() {
+        const SLOW_CALLS_COUNT: usize = 8;
+
+        let is_released = Arc::new(AtomicBool::new(false));
+        let server_is_released = Arc::clone(&is_released);
+        let server = TestsHttpServer::start(move |_| {
+            let started_at = Instant::now();
+            while !server_is_released.load(Ordering::SeqCst)
+                && started_at.elapsed() < Duration::from_secs(10)
+            {
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            TestsHttpResponse::new(200, "! Title: Slow filter\n||example.org^\n")
+        });
+        let url = server.url("/filter.txt");
+
+        let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+
+        runtime.block_on(async {
+            let mut conf = Configuration::default();
+            conf.app_name = "FlmApp".to_string();
+            conf.version = "1.2.3".to_string();
+            let flm = Arc::new(AsyncFilterListManager::new(conf).await.unwrap());
+
+            let started_count = Arc::new(AtomicUsize::new(0));
+            let slow_calls = (0..SLOW_CALLS_COUNT)
+                .map(|_| {
+                    let flm = Arc::clone(&flm);
+                    let url = url.clone();
+                    let started_count = Arc::clone(&started_count);
+
+                    tokio::spawn(async move {
+                        flm.run(move |flm| {
+                            started_count.fetch_add(1, Ordering::SeqCst);
+                            flm.fetch_filter_list_metadata(url)
+                        })
+                        .await
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            // All slow calls are running at once, none of them waits for a free thread
+            let started_at = Instant::now();
+            while started_count.load(Ordering::SeqCst) < SLOW_CALLS_COUNT
+                && started_at.elapsed() < Duration::from_secs(5)
+            {
+                yield_now().await;
+            }
+            assert_eq!(started_count.load(Ordering::SeqCst), SLOW_CALLS_COUNT);
+
+            flm.run(|flm| flm.get_all_groups()).await.unwrap();
+            assert!(!is_released.load(Ordering::SeqCst));
+
+            // Requests are driven by this thread, while it awaits them
+            is_released.store(true, Ordering::SeqCst);
+            for slow_call in slow_calls {
+                let metadata = slow_call.await.unwrap().unwrap();
+                assert_eq!(metadata.title, "Slow filter");
+            }
+        });
+    }
+}
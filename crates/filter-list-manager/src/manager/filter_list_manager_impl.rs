@@ -7,21 +7,46 @@ use super::managers::filter_manager::FilterManager;
 use super::managers::filter_metadata_grabber::FilterMetadataGrabber;
 use super::managers::filter_tag_manager::FilterTagManager;
 use super::managers::filter_update_manager::FilterUpdateManager;
+use super::managers::filter_versions_manager::FilterVersionsManager;
 use super::managers::integrity_control_manager::IntegrityControlManager;
 use super::managers::rules_list_manager::RulesListManager;
+use super::managers::rules_search_manager::RulesSearchManager;
 use super::managers::streaming_rules_manager::StreamingRulesManager;
+use super::managers::user_state_manager::UserStateManager;
 use super::models::{
     configuration::Configuration, FilterId, FilterListMetadata, FilterListMetadataWithBody,
     FullFilterList, PullMetadataResult, UpdateResult,
 };
+use crate::filters::parser::filter_linter::FilterLinter;
+use crate::io::http::transport::HttpTransport;
+use crate::manager::models::active_rules_export::{
+    ActiveRulesExportManifest, ActiveRulesExportOptions,
+};
 use crate::manager::models::configuration::request_proxy_mode::RequestProxyMode;
 use crate::manager::models::configuration::Locale;
+use crate::manager::models::deduplicated_active_rules::{
+    DeduplicatedActiveRules, DeduplicatedActiveRulesRaw,
+};
+use crate::manager::models::disabled_rule_pattern::{DisabledRulePattern, DisabledRulePatterns};
 use crate::manager::models::disabled_rules_raw::DisabledRulesRaw;
+use crate::manager::models::filter_diagnostic::FilterDiagnostic;
 use crate::manager::models::filter_group::FilterGroup;
 use crate::manager::models::filter_list_rules::FilterListRules;
 use crate::manager::models::filter_list_rules_raw::FilterListRulesRaw;
 use crate::manager::models::filter_tag::FilterTag;
+use crate::manager::models::filter_update_failure::FilterUpdateFailure;
+use crate::manager::models::filter_update_preview::FilterUpdatePreview;
+use crate::manager::models::filter_version::FilterVersion;
+use crate::manager::models::import_user_state_result::ImportUserStateResult;
+use crate::manager::models::rule_provenance::RuleProvenance;
+use crate::manager::models::rule_search::{RuleSearchMatch, RuleSearchOptions};
 use crate::manager::models::rules_count_by_filter::RulesCountByFilter;
+use crate::manager::models::update_progress::{UpdateCancellationToken, UpdateObserver};
+use crate::manager::models::update_scheduler::{
+    UpdateSchedulerEvent, UpdateSchedulerObserver, UpdateSchedulerSettings, UpdateSchedulerTask,
+};
+use crate::manager::update_progress_reporter::UpdateProgressReporter;
+use crate::manager::update_scheduler::UpdateScheduler;
 use crate::storage::repositories::db_metadata_repository::DBMetadataRepository;
 use crate::storage::repositories::filter_repository::FilterRepository;
 use crate::storage::sql_generators::operator::SQLOperator;
@@ -34,11 +59,17 @@ use crate::{
 use rusqlite::types::Value;
 use rusqlite::Connection;
 use std::path::Path;
+use std::sync::Arc;
+
+/// Settings and observer of the stopped update scheduler, which must be started again
+type PausedUpdateScheduler = (UpdateSchedulerSettings, Arc<dyn UpdateSchedulerObserver>);
 
 /// Default implementation for [`FilterListManager`]
 pub struct FilterListManagerImpl {
     configuration: Configuration,
     pub(crate) connection_manager: DbConnectionManager,
+    update_progress_reporter: UpdateProgressReporter,
+    update_scheduler: Option<UpdateScheduler>,
 }
 
 impl FilterListManager for FilterListManagerImpl {
@@ -56,6 +87,14 @@ impl FilterListManager for FilterListManagerImpl {
                 ));
             }
         }
+        if let Some(ref key) = configuration.database_encryption_key {
+            if key.is_empty() {
+                return Err(FLMError::InvalidConfiguration(
+                    "database_encryption_key is set, but empty",
+                ));
+            }
+        }
+        configuration.validate_filter_registries()?;
 
         configuration.normalized();
 
@@ -67,6 +106,8 @@ impl FilterListManager for FilterListManagerImpl {
         Ok(Box::new(Self {
             configuration,
             connection_manager,
+            update_progress_reporter: UpdateProgressReporter::default(),
+            update_scheduler: None,
         }))
     }
 
@@ -200,6 +241,20 @@ impl FilterListManager for FilterListManagerImpl {
             })
     }
 
+    fn save_disabled_rule_patterns(
+        &self,
+        filter_id: FilterId,
+        patterns: Vec<DisabledRulePattern>,
+    ) -> FLMResult<()> {
+        let derived_key = integrity::derive_key_if_needed(&self.configuration);
+
+        self.connection_manager
+            .execute_db(move |mut conn: Connection| {
+                Self::verify_filter_count_in_conn(&derived_key, &conn)?;
+                RulesListManager::new().save_disabled_rule_patterns(&mut conn, filter_id, patterns)
+            })
+    }
+
     fn update_filters(
         &self,
         ignore_filters_expiration: bool,
@@ -228,6 +283,7 @@ impl FilterListManager for FilterListManagerImpl {
             ignore_filters_expiration,
             loose_timeout,
             ignore_filters_status,
+            &self.update_progress_reporter,
         )?;
 
         Ok(Some(update_result))
@@ -267,6 +323,7 @@ impl FilterListManager for FilterListManagerImpl {
             ignore_filters_expiration,
             loose_timeout,
             ignore_filters_status,
+            &self.update_progress_reporter,
         )?;
 
         Ok(Some(update_result))
@@ -298,23 +355,82 @@ impl FilterListManager for FilterListManagerImpl {
             &self.connection_manager,
             &self.configuration,
             loose_timeout,
+            &self.update_progress_reporter,
         )?;
 
         Ok(Some(update_result))
     }
 
+    fn preview_filter_update(&self, filter_id: FilterId) -> FLMResult<FilterUpdatePreview> {
+        let derived_key = integrity::derive_key_if_needed(&self.configuration);
+
+        let filter = self
+            .connection_manager
+            .execute_db(move |conn: Connection| {
+                Self::verify_filter_count_in_conn(&derived_key, &conn)?;
+                FilterRepository::new()
+                    .select(
+                        &conn,
+                        Some(SQLOperator::FieldEqualValue("filter_id", filter_id.into())),
+                    )
+                    .map_err(FLMError::from_database)
+            })?
+            .and_then(|filters| filters.into_iter().next())
+            .ok_or(FLMError::EntityNotFound(filter_id as i64))?;
+
+        FilterUpdateManager::new().preview_filter_update(
+            filter,
+            &self.connection_manager,
+            &self.configuration,
+        )
+    }
+
+    fn list_filter_versions(&self, filter_id: FilterId) -> FLMResult<Vec<FilterVersion>> {
+        self.connection_manager.execute_db(|conn: Connection| {
+            FilterVersionsManager::new().list_filter_versions(&conn, filter_id)
+        })
+    }
+
+    fn rollback_filter(&self, filter_id: FilterId, version_id: i64) -> FLMResult<()> {
+        let derived_key = integrity::derive_key_if_needed(&self.configuration);
+
+        self.connection_manager
+            .execute_db(move |mut conn: Connection| {
+                Self::verify_filter_count_in_conn(&derived_key, &conn)?;
+                FilterVersionsManager::new().rollback_filter(
+                    &mut conn,
+                    &self.configuration,
+                    filter_id,
+                    version_id,
+                )
+            })
+    }
+
+    fn unpin_filter(&self, filter_id: FilterId) -> FLMResult<()> {
+        self.connection_manager.execute_db(|mut conn: Connection| {
+            FilterVersionsManager::new().unpin_filter(&mut conn, filter_id)
+        })
+    }
+
     fn change_locale(&mut self, suggested_locale: Locale) -> FLMResult<bool> {
-        ConfigurationUpdateManager::new().change_locale(
+        let result = ConfigurationUpdateManager::new().change_locale(
             &self.connection_manager,
             &mut self.configuration,
             suggested_locale,
-        )
+        );
+        let restarted = self.restart_update_scheduler();
+
+        result.and_then(|is_changed| restarted.map(|_| is_changed))
     }
 
     fn pull_metadata(&self) -> FLMResult<PullMetadataResult> {
         self.verify_filter_count_if_needed()?;
 
-        FilterUpdateManager::new().pull_metadata(&self.connection_manager, &self.configuration)
+        FilterUpdateManager::new().pull_metadata(
+            &self.connection_manager,
+            &self.configuration,
+            &self.update_progress_reporter,
+        )
     }
 
     fn update_custom_filter_metadata(
@@ -395,6 +511,26 @@ impl FilterListManager for FilterListManagerImpl {
         )
     }
 
+    fn get_active_rules_deduplicated(&self) -> FLMResult<DeduplicatedActiveRules> {
+        self.verify_filter_count_if_needed()?;
+
+        RulesListManager::new()
+            .get_active_rules_deduplicated(&self.connection_manager, &self.configuration)
+    }
+
+    fn get_active_rules_raw_deduplicated(
+        &self,
+        filter_by: Vec<FilterId>,
+    ) -> FLMResult<DeduplicatedActiveRulesRaw> {
+        self.verify_filter_count_if_needed()?;
+
+        RulesListManager::new().get_active_rules_raw_deduplicated(
+            &self.connection_manager,
+            &self.configuration,
+            filter_by,
+        )
+    }
+
     fn get_filter_rules_as_strings(
         &self,
         ids: Vec<FilterId>,
@@ -423,6 +559,21 @@ impl FilterListManager for FilterListManagerImpl {
         )
     }
 
+    fn save_active_rules_to_file<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        options: ActiveRulesExportOptions,
+    ) -> FLMResult<ActiveRulesExportManifest> {
+        self.verify_filter_count_if_needed()?;
+
+        StreamingRulesManager::new().save_active_rules_to_file(
+            &self.connection_manager,
+            &self.configuration,
+            file_path,
+            options,
+        )
+    }
+
     fn get_disabled_rules(&self, ids: Vec<FilterId>) -> FLMResult<Vec<DisabledRulesRaw>> {
         let derived_key = integrity::derive_key_if_needed(&self.configuration);
 
@@ -436,8 +587,65 @@ impl FilterListManager for FilterListManagerImpl {
         Ok(result.into_iter().map(Into::into).collect())
     }
 
+    fn get_disabled_rule_patterns(
+        &self,
+        ids: Vec<FilterId>,
+    ) -> FLMResult<Vec<DisabledRulePatterns>> {
+        let derived_key = integrity::derive_key_if_needed(&self.configuration);
+
+        self.connection_manager.execute_db(move |conn: Connection| {
+            Self::verify_filter_count_in_conn(&derived_key, &conn)?;
+            RulesListManager::new().get_disabled_rule_patterns(&conn, &ids)
+        })
+    }
+
     fn set_proxy_mode(&mut self, mode: RequestProxyMode) {
-        ConfigurationUpdateManager::new().set_proxy_mode(&mut self.configuration, mode)
+        ConfigurationUpdateManager::new().set_proxy_mode(&mut self.configuration, mode);
+        self.restart_update_scheduler_or_notify();
+    }
+
+    fn set_update_observer(&mut self, observer: Option<Arc<dyn UpdateObserver>>) {
+        self.update_progress_reporter.set_observer(observer);
+        self.restart_update_scheduler_or_notify();
+    }
+
+    fn set_http_transport(&mut self, transport: Option<Arc<dyn HttpTransport>>) {
+        self.configuration.http_transport = transport;
+        self.restart_update_scheduler_or_notify();
+    }
+
+    fn get_update_cancellation_token(&self) -> UpdateCancellationToken {
+        self.update_progress_reporter.cancellation_token()
+    }
+
+    fn start_update_scheduler(
+        &mut self,
+        settings: UpdateSchedulerSettings,
+        observer: Arc<dyn UpdateSchedulerObserver>,
+    ) -> FLMResult<()> {
+        settings.validate()?;
+
+        self.stop_update_scheduler();
+
+        let (manager, cancellation_token) = self.make_scheduler_copy();
+        self.update_scheduler = Some(UpdateScheduler::start(
+            manager,
+            cancellation_token,
+            settings,
+            observer,
+        )?);
+
+        Ok(())
+    }
+
+    fn stop_update_scheduler(&mut self) {
+        if let Some(scheduler) = self.update_scheduler.take() {
+            scheduler.stop();
+        }
+    }
+
+    fn is_update_scheduler_running(&self) -> bool {
+        self.update_scheduler.is_some()
     }
 
     fn get_rules_count(&self, ids: Vec<FilterId>) -> FLMResult<Vec<RulesCountByFilter>> {
@@ -453,6 +661,49 @@ impl FilterListManager for FilterListManagerImpl {
         Ok(result.into_iter().map(Into::into).collect())
     }
 
+    fn search_rules(
+        &self,
+        query: String,
+        filter_ids: Vec<FilterId>,
+        options: RuleSearchOptions,
+    ) -> FLMResult<Vec<RuleSearchMatch>> {
+        let derived_key = integrity::derive_key_if_needed(&self.configuration);
+
+        self.connection_manager.execute_db(move |conn: Connection| {
+            Self::verify_filter_count_in_conn(&derived_key, &conn)?;
+            RulesSearchManager::new().search_rules(
+                &conn,
+                &self.configuration,
+                query,
+                filter_ids,
+                options,
+            )
+        })
+    }
+
+    fn get_rule_provenance(&self, rule: String) -> FLMResult<Vec<RuleProvenance>> {
+        let derived_key = integrity::derive_key_if_needed(&self.configuration);
+
+        self.connection_manager.execute_db(move |conn: Connection| {
+            Self::verify_filter_count_in_conn(&derived_key, &conn)?;
+            RulesSearchManager::new().get_rule_provenance(&conn, &self.configuration, rule)
+        })
+    }
+
+    fn get_filter_update_failures(&self) -> FLMResult<Vec<FilterUpdateFailure>> {
+        self.connection_manager.execute_db(|conn: Connection| {
+            FilterUpdateManager::new().get_filter_update_failures(&conn)
+        })
+    }
+
+    fn lint_filter(&self, body: String, base_url: String) -> FLMResult<Vec<FilterDiagnostic>> {
+        if base_url.is_empty() {
+            return Err(FLMError::FieldIsEmpty("base_url"));
+        }
+
+        Ok(FilterLinter::new(&self.configuration).lint(&body, &base_url))
+    }
+
     fn sign_all_data(&self) -> FLMResult<()> {
         IntegrityControlManager::new().sign_all_data(&self.connection_manager, &self.configuration)
     }
@@ -464,14 +715,63 @@ impl FilterListManager for FilterListManagerImpl {
             ));
         }
 
+        // Scheduled tasks must not write the data, signed with the previous key
+        let paused = self.pause_update_scheduler();
+
         self.configuration.integrity_key = Some(integrity_key);
-        IntegrityControlManager::new().sign_all_data(&self.connection_manager, &self.configuration)
+        let result = IntegrityControlManager::new()
+            .sign_all_data(&self.connection_manager, &self.configuration);
+        let resumed = self.resume_update_scheduler(paused);
+
+        result.and(resumed)
+    }
+
+    fn change_database_encryption_key(&mut self, encryption_key: Option<String>) -> FLMResult<()> {
+        if encryption_key.as_ref().is_some_and(String::is_empty) {
+            return Err(FLMError::InvalidConfiguration(
+                "database_encryption_key is set, but empty",
+            ));
+        }
+
+        self.verify_filter_count_if_needed()?;
+
+        // Scheduled tasks must not use the database during rekey
+        let paused = self.pause_update_scheduler();
+
+        let result = self
+            .connection_manager
+            .change_encryption_key(encryption_key.clone());
+        if result.is_ok() {
+            self.configuration.database_encryption_key = encryption_key;
+        }
+        let resumed = self.resume_update_scheduler(paused);
+
+        result.and(resumed)
     }
 
     fn verify_integrity(&self) -> FLMResult<()> {
         IntegrityControlManager::new()
             .verify_integrity(&self.connection_manager, &self.configuration)
     }
+
+    fn export_user_state(&self) -> FLMResult<String> {
+        let derived_key = integrity::derive_key_if_needed(&self.configuration);
+
+        self.connection_manager.execute_db(move |conn: Connection| {
+            Self::verify_filter_count_in_conn(&derived_key, &conn)?;
+            UserStateManager::new().export_user_state(&conn, &self.configuration)
+        })
+    }
+
+    fn import_user_state(&self, document: String) -> FLMResult<ImportUserStateResult> {
+        let derived_key = integrity::derive_key_if_needed(&self.configuration);
+
+        self.connection_manager
+            .execute_db(move |mut conn: Connection| {
+                Self::verify_filter_count_in_conn(&derived_key, &conn)?;
+                UserStateManager::new().import_user_state(&mut conn, &self.configuration, document)
+            })
+    }
 }
 
 impl FilterListManagerImpl {
@@ -526,9 +826,74 @@ impl FilterListManagerImpl {
             Self::verify_filter_count_in_conn(&derived_key, &conn)
         })
     }
+
+    /// Makes a copy of the manager for the update scheduler thread.
+    /// Copy has its own cancellation token, so scheduled tasks don't
+    /// interfere with the calls of this manager
+    fn make_scheduler_copy(&self) -> (Self, UpdateCancellationToken) {
+        let cancellation_token = UpdateCancellationToken::new();
+        let manager = Self {
+            configuration: self.configuration.clone(),
+            connection_manager: self.connection_manager.clone(),
+            update_progress_reporter: self
+                .update_progress_reporter
+                .with_cancellation_token(cancellation_token.clone()),
+            update_scheduler: None,
+        };
+
+        (manager, cancellation_token)
+    }
+
+    /// Stops running update scheduler. Returns what is needed to start it again
+    /// with [`Self::resume_update_scheduler`]
+    fn pause_update_scheduler(&mut self) -> Option<PausedUpdateScheduler> {
+        self.update_scheduler.take().map(UpdateScheduler::stop)
+    }
+
+    /// Starts paused update scheduler again with the current configuration.
+    /// No-op if the scheduler hasn't been running
+    fn resume_update_scheduler(&mut self, paused: Option<PausedUpdateScheduler>) -> FLMResult<()> {
+        if let Some((settings, observer)) = paused {
+            let (manager, cancellation_token) = self.make_scheduler_copy();
+
+            self.update_scheduler = Some(UpdateScheduler::start(
+                manager,
+                cancellation_token,
+                settings,
+                observer,
+            )?);
+        }
+
+        Ok(())
+    }
+
+    /// Restarts running update scheduler, so it picks up the changed configuration
+    fn restart_update_scheduler(&mut self) -> FLMResult<()> {
+        let paused = self.pause_update_scheduler();
+
+        self.resume_update_scheduler(paused)
+    }
+
+    /// Same as [`Self::restart_update_scheduler`], but for methods, which can't
+    /// return an error. Failure is passed to the scheduler observer instead
+    fn restart_update_scheduler_or_notify(&mut self) {
+        let Some((settings, observer)) = self.pause_update_scheduler() else {
+            return;
+        };
+
+        if let Err(error) = self.resume_update_scheduler(Some((settings, Arc::clone(&observer)))) {
+            observer.on_scheduled_task_finished(UpdateSchedulerEvent {
+                task: UpdateSchedulerTask::Restart,
+                update_result: None,
+                pull_metadata_result: None,
+                error: Some(error),
+                is_offline: false,
+                next_run_time: 0,
+            });
+        }
+    }
 }
 
-#[cfg(test)]
 impl FilterListManagerImpl {
     pub(crate) fn get_configuration(&self) -> &Configuration {
         &self.configuration
@@ -538,6 +903,7 @@ impl FilterListManagerImpl {
 #[cfg(test)]
 mod tests {
     use crate::manager::managers::filter_manager::FilterManager;
+    use crate::manager::update_scheduler::UpdateScheduler;
     use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
     use crate::storage::repositories::db_metadata_repository::DBMetadataRepository;
     use crate::storage::repositories::filter_repository::FilterRepository;
@@ -547,17 +913,24 @@ mod tests {
     use crate::storage::with_transaction;
     use crate::storage::DbConnectionManager;
     use crate::test_utils::spawn_test_db_with_metadata;
+    use crate::test_utils::tests_http_server::{TestsHttpResponse, TestsHttpServer};
+    use crate::test_utils::tests_path;
     use crate::{
         generate_random_key, string, Configuration, FLMError, FilterId, FilterListManager,
-        FilterListManagerImpl, FilterListRules, USER_RULES_FILTER_LIST_ID,
+        FilterListManagerImpl, FilterListRules, FilterRegistry, HttpClientError, HttpRequest,
+        HttpResponse, HttpTransport, RequestProxyMode, UpdateSchedulerEvent,
+        UpdateSchedulerObserver, UpdateSchedulerSettings, UpdateSchedulerTask,
+        FILTER_REGISTRY_ID_NAMESPACE_SIZE, USER_RULES_FILTER_LIST_ID,
     };
     use chrono::{Duration, Utc};
     use rand::prelude::SliceRandom;
     use rand::thread_rng;
     use rusqlite::Connection;
     use std::fs;
-    use std::ops::Sub;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::ops::{Range, Sub};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::time::{Duration as StdDuration, SystemTime, UNIX_EPOCH};
     use url::Url;
 
     #[test]
@@ -638,6 +1011,55 @@ mod tests {
         assert_eq!(custom_was_deleted, 1)
     }
 
+    #[test]
+    fn test_install_custom_filter_via_http_transport() {
+        struct FilterTransport;
+
+        impl HttpTransport for FilterTransport {
+            fn get(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+                if request.url != "https://filters.example.org/custom.txt" {
+                    return Err(HttpClientError::NetworkError(request.url));
+                }
+
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: vec![],
+                    body: b"! Title: Transported filter\n||example.org^\n".to_vec(),
+                })
+            }
+        }
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        let mut flm = FilterListManagerImpl::new(conf).unwrap();
+        flm.set_http_transport(Some(Arc::new(FilterTransport)));
+
+        let filter = flm
+            .install_custom_filter_list(
+                String::from("https://filters.example.org/custom.txt"),
+                true,
+                None,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(filter.title, "Transported filter");
+        assert_eq!(
+            filter.rules.unwrap().rules,
+            vec!["! Title: Transported filter", "||example.org^"]
+        );
+
+        assert!(flm
+            .install_custom_filter_list(
+                String::from("https://filters.example.org/missing.txt"),
+                true,
+                None,
+                None,
+            )
+            .is_err());
+    }
+
     #[test]
     fn test_install_local_custom_filter() {
         let source = DbConnectionManager::factory_test().unwrap();
@@ -1561,4 +1983,431 @@ mod tests {
             "Expected FilterIntegrityCheckFailed(0), got: {result:?}",
         );
     }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    #[test]
+    fn test_database_encryption_requires_sqlcipher_feature() {
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.database_encryption_key = Some("secret".to_string());
+
+        assert!(matches!(
+            FilterListManagerImpl::new(conf),
+            Err(FLMError::InvalidConfiguration(_))
+        ));
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        let mut flm = FilterListManagerImpl::new(conf).unwrap();
+
+        assert!(matches!(
+            flm.change_database_encryption_key(Some("secret".to_string())),
+            Err(FLMError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn test_change_database_encryption_key() {
+        use crate::storage::encryption::is_plaintext_database;
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.database_encryption_key = Some("first".to_string());
+        let mut flm = FilterListManagerImpl::new(conf).unwrap();
+
+        let path = flm.connection_manager.get_calculated_path().clone();
+        assert!(!is_plaintext_database(&path).unwrap());
+
+        flm.save_custom_filter_rules(FilterListRules {
+            filter_id: USER_RULES_FILTER_LIST_ID,
+            rules: vec![string!("||example.com^")],
+            disabled_rules: vec![],
+            rules_count: 0,
+        })
+        .unwrap();
+
+        assert!(matches!(
+            flm.change_database_encryption_key(Some(String::new())),
+            Err(FLMError::InvalidConfiguration(_))
+        ));
+
+        flm.change_database_encryption_key(Some("second".to_string()))
+            .unwrap();
+        assert_eq!(
+            flm.get_configuration().database_encryption_key.as_deref(),
+            Some("second")
+        );
+
+        let rules = flm
+            .get_filter_rules_as_strings(vec![USER_RULES_FILTER_LIST_ID])
+            .unwrap();
+        assert_eq!(rules[0].rules, "||example.com^");
+
+        // Decryption
+        flm.change_database_encryption_key(None).unwrap();
+        assert!(is_plaintext_database(&path).unwrap());
+
+        let rules = flm
+            .get_filter_rules_as_strings(vec![USER_RULES_FILTER_LIST_ID])
+            .unwrap();
+        assert_eq!(rules[0].rules, "||example.com^");
+    }
+
+    #[test]
+    fn test_filter_registries_validation() {
+        let cases = [
+            (["", "extra"], "filter registry name is empty"),
+            (
+                ["extra", "main"],
+                "filter registry name is reserved for the main registry",
+            ),
+            (["extra", "extra"], "filter registry names must be unique"),
+        ];
+
+        for (names, message) in cases {
+            let mut conf = Configuration::default();
+            conf.app_name = "FlmApp".to_string();
+            conf.version = "1.2.3".to_string();
+            conf.filter_registries = names
+                .into_iter()
+                .map(|name| FilterRegistry {
+                    name: string!(name),
+                    ..FilterRegistry::default()
+                })
+                .collect();
+
+            let result = FilterListManagerImpl::new(conf);
+            assert!(
+                matches!(result, Err(FLMError::InvalidConfiguration(actual)) if actual == message)
+            );
+        }
+    }
+
+    #[test]
+    fn test_pull_metadata_of_multiple_registries() {
+        let index = fs::read_to_string(tests_path(
+            "fixtures/pull_metadata_existent_db_test/filters1.json",
+        ))
+        .unwrap();
+        let index_i18n = fs::read_to_string(tests_path(
+            "fixtures/pull_metadata_existent_db_test/filters_i18n.json",
+        ))
+        .unwrap();
+
+        let is_extra_available = Arc::new(AtomicBool::new(true));
+        let is_extra_available_in_server = is_extra_available.clone();
+        let server = TestsHttpServer::start(move |request| {
+            let is_extra_available = is_extra_available_in_server.load(Ordering::SeqCst);
+
+            match request.path.as_str() {
+                "/main/filters.json" => TestsHttpResponse::new(200, index.clone()),
+                "/main/filters_i18n.json" => TestsHttpResponse::new(200, index_i18n.clone()),
+                "/extra/filters.json" if is_extra_available => {
+                    TestsHttpResponse::new(200, index.clone())
+                }
+                "/extra/filters_i18n.json" if is_extra_available => {
+                    TestsHttpResponse::new(200, index_i18n.clone())
+                }
+                _ => TestsHttpResponse::new(503, ""),
+            }
+        });
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.metadata_url = server.url("/main/filters.json");
+        conf.metadata_locales_url = server.url("/main/filters_i18n.json");
+        conf.filter_registries = vec![
+            FilterRegistry {
+                name: string!("extra"),
+                metadata_url: server.url("/extra/filters.json"),
+                metadata_locales_url: server.url("/extra/filters_i18n.json"),
+                ..FilterRegistry::default()
+            },
+            FilterRegistry {
+                name: string!("broken"),
+                metadata_url: server.url("/broken/filters.json"),
+                ..FilterRegistry::default()
+            },
+        ];
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+
+        let result = flm.pull_metadata().unwrap();
+        assert_eq!(result.added_filters.len(), 26);
+        assert_eq!(result.failed_registries.len(), 1);
+        assert_eq!(result.failed_registries[0].name, "broken");
+
+        let filters = flm.get_stored_filters_metadata().unwrap();
+        let filters_of_registry = |name: &str| {
+            filters
+                .iter()
+                .filter(|filter| filter.registry.as_deref() == Some(name))
+                .collect::<Vec<_>>()
+        };
+        let main_filters = filters_of_registry("main");
+        let extra_filters = filters_of_registry("extra");
+        assert_eq!(main_filters.len(), 13);
+        assert_eq!(extra_filters.len(), 13);
+
+        // Ids of the extra registry are moved into its namespace
+        for main_filter in main_filters.iter() {
+            assert!(extra_filters.iter().any(|extra_filter| {
+                extra_filter.id == main_filter.id + FILTER_REGISTRY_ID_NAMESPACE_SIZE
+                    && extra_filter.group_id
+                        == main_filter.group_id + FILTER_REGISTRY_ID_NAMESPACE_SIZE
+                    && extra_filter.title == main_filter.title
+            }));
+        }
+
+        let user_rules = filters
+            .iter()
+            .find(|filter| filter.id == USER_RULES_FILTER_LIST_ID)
+            .unwrap();
+        assert_eq!(user_rules.registry, None);
+
+        let groups = flm.get_all_groups().unwrap();
+        let count_groups = |range: Range<i32>| {
+            groups
+                .iter()
+                .filter(|group| range.contains(&group.id))
+                .count()
+        };
+        let main_groups_count = count_groups(1..FILTER_REGISTRY_ID_NAMESPACE_SIZE);
+        assert!(main_groups_count > 0);
+        assert_eq!(
+            count_groups(FILTER_REGISTRY_ID_NAMESPACE_SIZE..FILTER_REGISTRY_ID_NAMESPACE_SIZE * 2),
+            main_groups_count
+        );
+
+        // Failed registry doesn't affect filters of others
+        is_extra_available.store(false, Ordering::SeqCst);
+        let result = flm.pull_metadata().unwrap();
+        assert!(result.added_filters.is_empty());
+        assert!(result.removed_filters.is_empty());
+        let failed_names = result
+            .failed_registries
+            .iter()
+            .map(|registry| registry.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(failed_names, ["extra", "broken"]);
+        assert_eq!(
+            flm.get_stored_filters_metadata().unwrap().len(),
+            filters.len()
+        );
+        assert_eq!(flm.get_all_groups().unwrap().len(), groups.len());
+    }
+
+    fn make_scheduler_observer() -> (
+        Arc<dyn UpdateSchedulerObserver>,
+        mpsc::Receiver<UpdateSchedulerEvent>,
+    ) {
+        struct ChannelObserver(Mutex<mpsc::Sender<UpdateSchedulerEvent>>);
+
+        impl UpdateSchedulerObserver for ChannelObserver {
+            fn on_scheduled_task_finished(&self, event: UpdateSchedulerEvent) {
+                let _ = self.0.lock().unwrap().send(event);
+            }
+        }
+
+        let (sender, receiver) = mpsc::channel();
+
+        (Arc::new(ChannelObserver(Mutex::new(sender))), receiver)
+    }
+
+    #[test]
+    fn test_update_scheduler_settings_validation() {
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+
+        let mut flm = FilterListManagerImpl::new(conf).unwrap();
+        let (observer, _) = make_scheduler_observer();
+
+        let error = flm
+            .start_update_scheduler(
+                UpdateSchedulerSettings {
+                    offline_backoff_max_sec: 1,
+                    ..UpdateSchedulerSettings::default()
+                },
+                observer,
+            )
+            .unwrap_err();
+
+        assert!(matches!(error, FLMError::InvalidConfiguration(_)));
+        assert!(!flm.is_update_scheduler_running());
+    }
+
+    #[test]
+    fn test_update_scheduler_runs_tasks_and_saves_run_times() {
+        let index = fs::read_to_string(tests_path(
+            "fixtures/pull_metadata_existent_db_test/filters1.json",
+        ))
+        .unwrap();
+        let index_i18n = fs::read_to_string(tests_path(
+            "fixtures/pull_metadata_existent_db_test/filters_i18n.json",
+        ))
+        .unwrap();
+
+        let server = TestsHttpServer::start(move |request| match request.path.as_str() {
+            "/filters.json" => TestsHttpResponse::new(200, index.clone()),
+            "/filters_i18n.json" => TestsHttpResponse::new(200, index_i18n.clone()),
+            _ => TestsHttpResponse::new(404, ""),
+        });
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.metadata_url = server.url("/filters.json");
+        conf.metadata_locales_url = server.url("/filters_i18n.json");
+
+        let mut flm = FilterListManagerImpl::new(conf).unwrap();
+        let (observer, events) = make_scheduler_observer();
+        let started_at = Utc::now().timestamp();
+
+        flm.start_update_scheduler(UpdateSchedulerSettings::default(), observer)
+            .unwrap();
+        assert!(flm.is_update_scheduler_running());
+
+        // Never run before, so metadata is pulled first, then filters are updated
+        let pull_event = events.recv_timeout(StdDuration::from_secs(30)).unwrap();
+        assert_eq!(pull_event.task, UpdateSchedulerTask::PullMetadata);
+        assert!(pull_event.error.is_none());
+        assert!(!pull_event.is_offline);
+        assert!(pull_event.pull_metadata_result.is_some());
+
+        let update_event = events.recv_timeout(StdDuration::from_secs(30)).unwrap();
+        assert_eq!(update_event.task, UpdateSchedulerTask::UpdateFilters);
+        assert!(update_event.error.is_none());
+        assert!(update_event.update_result.is_some());
+        assert!(update_event.next_run_time >= started_at + 3600);
+
+        flm.stop_update_scheduler();
+        assert!(!flm.is_update_scheduler_running());
+
+        let metadata = flm
+            .connection_manager
+            .execute_db(|conn: Connection| {
+                DBMetadataRepository::read(&conn).map_err(FLMError::from_database)
+            })
+            .unwrap()
+            .unwrap();
+
+        assert!(metadata.scheduler_last_update_time.unwrap() >= started_at);
+        assert!(metadata.scheduler_last_pull_metadata_time.unwrap() >= started_at);
+
+        // Run times are restored, so nothing is due after restart
+        let (observer, events) = make_scheduler_observer();
+        flm.start_update_scheduler(UpdateSchedulerSettings::default(), observer)
+            .unwrap();
+
+        assert!(events.recv_timeout(StdDuration::from_millis(500)).is_err());
+    }
+
+    #[test]
+    fn test_update_scheduler_backs_off_when_offline() {
+        let server = TestsHttpServer::start(|_| TestsHttpResponse::new(500, ""));
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.metadata_url = server.url("/filters.json");
+        conf.metadata_locales_url = server.url("/filters_i18n.json");
+
+        let mut flm = FilterListManagerImpl::new(conf).unwrap();
+        let (observer, events) = make_scheduler_observer();
+        let started_at = Utc::now().timestamp();
+
+        flm.start_update_scheduler(
+            UpdateSchedulerSettings {
+                offline_backoff_min_sec: 120,
+                ..UpdateSchedulerSettings::default()
+            },
+            observer,
+        )
+        .unwrap();
+
+        let event = events.recv_timeout(StdDuration::from_secs(30)).unwrap();
+        assert_eq!(event.task, UpdateSchedulerTask::PullMetadata);
+        assert!(event.is_offline);
+        assert!(matches!(event.error, Some(FLMError::Network(_))));
+        assert!(event.next_run_time >= started_at + 120);
+
+        flm.stop_update_scheduler();
+
+        let metadata = flm
+            .connection_manager
+            .execute_db(|conn: Connection| {
+                DBMetadataRepository::read(&conn).map_err(FLMError::from_database)
+            })
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(metadata.scheduler_last_pull_metadata_time, None);
+    }
+
+    #[test]
+    fn test_failed_update_scheduler_restart_is_reported() {
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+
+        let mut flm = FilterListManagerImpl::new(conf).unwrap();
+        let (observer, events) = make_scheduler_observer();
+
+        // Invalid settings make the restart fail
+        flm.update_scheduler = Some(UpdateScheduler::factory_test(
+            UpdateSchedulerSettings {
+                update_interval_sec: 0,
+                ..UpdateSchedulerSettings::default()
+            },
+            observer,
+        ));
+
+        flm.set_proxy_mode(RequestProxyMode::NoProxy);
+
+        let event = events.recv_timeout(StdDuration::from_secs(1)).unwrap();
+        assert_eq!(event.task, UpdateSchedulerTask::Restart);
+        assert!(matches!(
+            event.error,
+            Some(FLMError::InvalidConfiguration(_))
+        ));
+        assert_eq!(event.next_run_time, 0);
+        assert!(!flm.is_update_scheduler_running());
+    }
+
+    #[test]
+    fn test_sign_all_data_with_new_key_resumes_update_scheduler() {
+        let server = TestsHttpServer::start(|_| TestsHttpResponse::new(500, ""));
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.metadata_url = server.url("/filters.json");
+        conf.metadata_locales_url = server.url("/filters_i18n.json");
+
+        let mut flm = FilterListManagerImpl::new(conf).unwrap();
+        let (observer, events) = make_scheduler_observer();
+
+        flm.start_update_scheduler(UpdateSchedulerSettings::default(), observer)
+            .unwrap();
+        events.recv_timeout(StdDuration::from_secs(30)).unwrap();
+
+        flm.sign_all_data_with_new_key("scheduler-test-key".to_string())
+            .unwrap();
+
+        assert!(flm.is_update_scheduler_running());
+        assert_eq!(
+            flm.get_configuration().integrity_key.as_deref(),
+            Some("scheduler-test-key")
+        );
+
+        // Restarted scheduler retries the failed task with the new configuration
+        let event = events.recv_timeout(StdDuration::from_secs(30)).unwrap();
+        assert_eq!(event.task, UpdateSchedulerTask::PullMetadata);
+        assert!(event.is_offline);
+    }
 }
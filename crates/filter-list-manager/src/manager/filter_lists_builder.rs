@@ -2,10 +2,12 @@ use crate::filters::parser::collectors::default_filter_collector::DefaultFilterC
 use crate::manager::models::filter_list_rules::FilterListRules;
 use crate::manager::models::full_filter_list::FullFilterList;
 use crate::storage::entities::filter::filter_entity::FilterEntity;
+use crate::storage::registry_id_namespace::RegistryIdNamespace;
 use crate::storage::repositories::filter_includes_repository::{
     FilterIncludesRepository, MapFilterIdOnFilterIncludes,
 };
 use crate::storage::repositories::filter_locale_repository::FilterLocaleRepository;
+use crate::storage::repositories::filter_registries_repository::FilterRegistriesRepository;
 use crate::storage::repositories::filter_tag_repository::FilterTagRepository;
 use crate::storage::repositories::localisation::filter_localisations_repository::FilterLocalisationRepository;
 use crate::storage::repositories::rules_list_repository::{
@@ -125,6 +127,10 @@ impl<'a> FullFilterListBuilder<'a> {
             .select_with_filter_tag(conn)
             .map_err(FLMError::from_database)?;
 
+        let registry_names = FilterRegistriesRepository::new()
+            .select_mapped(conn)
+            .map_err(FLMError::from_database)?;
+
         FilterLocalisationRepository::new()
             .enrich_filter_lists_with_localisation(conn, &mut entities, self.locale)
             .map_err(FLMError::from_database)?;
@@ -146,8 +152,15 @@ impl<'a> FullFilterListBuilder<'a> {
                 Some(languages) => languages.iter().map(|locale| locale.lang.clone()).collect(),
             };
 
+            let registry = if filter.is_custom() {
+                None
+            } else {
+                RegistryIdNamespace::of(id)
+                    .and_then(|namespace| registry_names.get(&namespace.registry_id()).cloned())
+            };
+
             let stored_entity =
-                match StoredFilterMetadata::from_filter_entity(filter, tags, languages) {
+                match StoredFilterMetadata::from_filter_entity(filter, tags, languages, registry) {
                     None => return FLMError::make_err(format!("Cannot build filter_id: {}", id)),
                     Some(stored_filter_entity) => stored_filter_entity,
                 };
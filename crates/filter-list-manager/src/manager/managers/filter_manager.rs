@@ -18,8 +18,12 @@ use crate::manager::filter_lists_builder::FullFilterListBuilder;
 use crate::storage::entities::filter::filter_entity::FilterEntity;
 use crate::storage::repositories::db_metadata_repository::DBMetadataRepository;
 use crate::storage::repositories::diff_updates_repository::DiffUpdateRepository;
+use crate::storage::repositories::disabled_rule_patterns_repository::DisabledRulePatternsRepository;
 use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
+use crate::storage::repositories::filter_pins_repository::FilterPinsRepository;
 use crate::storage::repositories::filter_repository::FilterRepository;
+use crate::storage::repositories::filter_update_failures_repository::FilterUpdateFailuresRepository;
+use crate::storage::repositories::filter_versions_repository::FilterVersionsRepository;
 use crate::storage::repositories::rules_list_repository::RulesListRepository;
 use crate::storage::repositories::BulkDeleteRepository;
 use crate::storage::repositories::Repository;
@@ -168,6 +172,10 @@ impl FilterManager {
         with_transaction(conn, move |tx: &Transaction| {
             let rows_deleted = filter_repository.bulk_delete(tx, &custom_filters)?;
             rules_repository.bulk_delete(tx, &custom_filters)?;
+            FilterUpdateFailuresRepository::new().bulk_delete(tx, &custom_filters)?;
+            FilterVersionsRepository::new().delete_for_filters(tx, &custom_filters)?;
+            FilterPinsRepository::new().bulk_delete(tx, &custom_filters)?;
+            DisabledRulePatternsRepository::new().bulk_delete(tx, &custom_filters)?;
 
             // Update count signature after deletion
             if let Some(ref key) = derived_key {
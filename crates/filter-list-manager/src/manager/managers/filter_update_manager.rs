@@ -1,11 +1,15 @@
 use crate::filters::indexes::indexes_processor::IndexesProcessor;
-use crate::manager::models::PullMetadataResult;
-use crate::manager::update_filters_action::update_filters_action;
+use crate::manager::models::filter_update_preview::FilterUpdatePreview;
+use crate::manager::models::{FailedFilterRegistry, PullMetadataResult};
+use crate::manager::update_filters_action::{preview_filter_update_action, update_filters_action};
+use crate::manager::update_progress_reporter::UpdateProgressReporter;
 use crate::storage::entities::filter::filter_entity::FilterEntity;
+use crate::storage::repositories::filter_update_failures_repository::FilterUpdateFailuresRepository;
 use crate::storage::DbConnectionManager;
-use crate::Configuration;
-use crate::FLMResult;
 use crate::UpdateResult;
+use crate::{Configuration, MAIN_FILTER_REGISTRY_NAME};
+use crate::{FLMError, FLMResult, FilterUpdateFailure};
+use rusqlite::Connection;
 
 pub(crate) struct FilterUpdateManager;
 
@@ -21,6 +25,7 @@ impl FilterUpdateManager {
         connection_manager: &DbConnectionManager,
         configuration: &Configuration,
         loose_timeout: i32,
+        progress_reporter: &UpdateProgressReporter,
     ) -> FLMResult<UpdateResult> {
         update_filters_action(
             records,
@@ -29,21 +34,53 @@ impl FilterUpdateManager {
             true,
             loose_timeout,
             configuration,
+            progress_reporter,
         )
     }
 
-    /// Pulls metadata
+    /// Downloads and compiles remote version of the filter without saving it
+    pub(crate) fn preview_filter_update(
+        &self,
+        filter: FilterEntity,
+        connection_manager: &DbConnectionManager,
+        configuration: &Configuration,
+    ) -> FLMResult<FilterUpdatePreview> {
+        preview_filter_update_action(filter, connection_manager, configuration)
+    }
+
+    /// Pulls metadata of every configured filter registry.
+    /// Failure of the main registry fails the whole operation,
+    /// failures of additional registries are collected into the result
     pub(crate) fn pull_metadata(
         &self,
         connection_manager: &DbConnectionManager,
         configuration: &Configuration,
+        progress_reporter: &UpdateProgressReporter,
     ) -> FLMResult<PullMetadataResult> {
-        let mut processor = IndexesProcessor::factory(connection_manager, configuration)?;
+        let _operation = progress_reporter.begin();
 
-        processor.sync_metadata(
-            configuration.metadata_url.as_str(),
-            configuration.metadata_locales_url.as_str(),
-        )
+        let mut out = PullMetadataResult::new();
+        for registry in configuration.collect_filter_registries() {
+            if progress_reporter.is_cancelled() {
+                break;
+            }
+
+            let result = IndexesProcessor::factory(connection_manager, configuration)
+                .and_then(|mut processor| processor.sync_metadata(&registry, progress_reporter));
+
+            match result {
+                Ok(result) => out.merge(result),
+                Err(why) if registry.name != MAIN_FILTER_REGISTRY_NAME => {
+                    out.failed_registries.push(FailedFilterRegistry {
+                        name: registry.name,
+                        message: why.to_string(),
+                    })
+                }
+                Err(why) => return Err(why),
+            }
+        }
+
+        Ok(out)
     }
 
     /// Updates filters
@@ -55,6 +92,7 @@ impl FilterUpdateManager {
         ignore_filters_expiration: bool,
         loose_timeout: i32,
         ignore_filters_status: bool,
+        progress_reporter: &UpdateProgressReporter,
     ) -> FLMResult<UpdateResult> {
         update_filters_action(
             records,
@@ -63,20 +101,53 @@ impl FilterUpdateManager {
             ignore_filters_status,
             loose_timeout,
             configuration,
+            progress_reporter,
         )
     }
+
+    /// Gets persisted update failures of filters
+    pub(crate) fn get_filter_update_failures(
+        &self,
+        conn: &Connection,
+    ) -> FLMResult<Vec<FilterUpdateFailure>> {
+        let entities = FilterUpdateFailuresRepository::new()
+            .select_all(conn)
+            .map_err(FLMError::from_database)?;
+
+        Ok(entities.into_iter().map(Into::into).collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::FilterUpdateManager;
+    use crate::manager::models::update_progress::{
+        UpdateObserver, UpdateProgressEvent, UpdateProgressStage,
+    };
+    use crate::manager::update_progress_reporter::UpdateProgressReporter;
     use crate::storage::repositories::filter_repository::FilterRepository;
     use crate::storage::{with_transaction, DbConnectionManager};
+    use crate::test_utils::tests_http_server::{TestsHttpResponse, TestsHttpServer};
     use crate::test_utils::tests_path;
-    use crate::{Configuration, FilterId};
+    use crate::{Configuration, FLMError, FilterId};
     use rusqlite::Connection;
+    use std::fs;
+    use std::sync::{Arc, Mutex};
     use url::Url;
 
+    #[derive(Default)]
+    struct StagesObserver {
+        stages: Mutex<Vec<UpdateProgressStage>>,
+    }
+
+    impl UpdateObserver for StagesObserver {
+        fn on_update_progress(&self, event: UpdateProgressEvent) {
+            assert_eq!(event.filter_id, None);
+
+            self.stages.lock().unwrap().push(event.stage);
+        }
+    }
+
     #[test]
     fn test_pull_metadata() {
         let conn = DbConnectionManager::factory_test().unwrap();
@@ -97,7 +168,9 @@ mod tests {
         configuration1.metadata_url = Url::from_file_path(file1).unwrap().to_string();
         configuration1.metadata_locales_url = Url::from_file_path(i18n_file1).unwrap().to_string();
 
-        let result1 = manager.pull_metadata(&conn, &configuration1).unwrap();
+        let result1 = manager
+            .pull_metadata(&conn, &configuration1, &UpdateProgressReporter::default())
+            .unwrap();
 
         assert_eq!(result1.moved_filters.len(), 0);
         assert_eq!(result1.removed_filters.len(), 0);
@@ -128,7 +201,9 @@ mod tests {
         configuration2.metadata_url = Url::from_file_path(file2).unwrap().to_string();
         configuration2.metadata_locales_url = Url::from_file_path(i18n_file2).unwrap().to_string();
 
-        let result2 = manager.pull_metadata(&conn, &configuration2).unwrap();
+        let result2 = manager
+            .pull_metadata(&conn, &configuration2, &UpdateProgressReporter::default())
+            .unwrap();
 
         // 255, 257 were added
         assert_eq!(result2.added_filters.len(), 2);
@@ -177,4 +252,136 @@ mod tests {
         })
         .unwrap();
     }
+
+    #[test]
+    fn test_pull_metadata_with_not_modified_indices() {
+        let index = fs::read_to_string(tests_path(
+            "fixtures/pull_metadata_existent_db_test/filters1.json",
+        ))
+        .unwrap();
+        let index_i18n = fs::read_to_string(tests_path(
+            "fixtures/pull_metadata_existent_db_test/filters_i18n.json",
+        ))
+        .unwrap();
+
+        let server = TestsHttpServer::start(move |request| {
+            let etag = format!("\"{}\"", request.path);
+            if request.header("if-none-match") == Some(etag.as_str()) {
+                return TestsHttpResponse::new(304, "");
+            }
+
+            let body = match request.path.as_str() {
+                "/filters.json" => index.clone(),
+                _ => index_i18n.clone(),
+            };
+
+            TestsHttpResponse::new(200, body).with_header("ETag", etag)
+        });
+
+        let conn = DbConnectionManager::factory_test().unwrap();
+        unsafe {
+            conn.lift_up_database().unwrap();
+        }
+
+        let mut configuration = Configuration::default();
+        configuration.metadata_url = server.url("/filters.json");
+        configuration.metadata_locales_url = server.url("/filters_i18n.json");
+
+        let manager = FilterUpdateManager::new();
+        let observer = Arc::new(StagesObserver::default());
+        let mut reporter = UpdateProgressReporter::default();
+        reporter.set_observer(Some(observer.clone()));
+
+        let result1 = manager
+            .pull_metadata(&conn, &configuration, &reporter)
+            .unwrap();
+        assert_eq!(result1.added_filters.len(), 13);
+
+        // Both indices are not modified, so nothing must be changed
+        let result2 = manager
+            .pull_metadata(&conn, &configuration, &reporter)
+            .unwrap();
+        assert!(result2.added_filters.is_empty());
+        assert!(result2.removed_filters.is_empty());
+        assert!(result2.moved_filters.is_empty());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[2..]
+            .iter()
+            .all(|request| request.header("if-none-match").is_some()));
+
+        assert_eq!(
+            *observer.stages.lock().unwrap(),
+            vec![
+                UpdateProgressStage::Downloading,
+                UpdateProgressStage::Saved,
+                UpdateProgressStage::Downloading,
+                UpdateProgressStage::NotChanged,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pull_metadata_falls_back_to_mirrors() {
+        let index = fs::read_to_string(tests_path(
+            "fixtures/pull_metadata_existent_db_test/filters1.json",
+        ))
+        .unwrap();
+        let index_i18n = fs::read_to_string(tests_path(
+            "fixtures/pull_metadata_existent_db_test/filters_i18n.json",
+        ))
+        .unwrap();
+
+        let server = TestsHttpServer::start(move |request| {
+            match request.path.as_str() {
+                "/mirror/filters.json" => TestsHttpResponse::new(200, index.clone()),
+                "/mirror/filters_i18n.json" => TestsHttpResponse::new(200, index_i18n.clone()),
+                _ => TestsHttpResponse::new(503, ""),
+            }
+            .with_header("ETag", format!("\"{}\"", request.path))
+        });
+
+        let conn = DbConnectionManager::factory_test().unwrap();
+        unsafe {
+            conn.lift_up_database().unwrap();
+        }
+
+        let mut configuration = Configuration::default();
+        configuration.metadata_url = server.url("/filters.json");
+        configuration.metadata_locales_url = server.url("/filters_i18n.json");
+        configuration.metadata_mirror_urls = vec![
+            server.url("/broken/filters.json"),
+            server.url("/mirror/filters.json"),
+        ];
+        configuration.metadata_locales_mirror_urls = vec![server.url("/mirror/filters_i18n.json")];
+
+        let manager = FilterUpdateManager::new();
+        let result = manager
+            .pull_metadata(&conn, &configuration, &UpdateProgressReporter::default())
+            .unwrap();
+        assert_eq!(result.added_filters.len(), 13);
+
+        // Mirrors validators are sent back to mirrors
+        let result = manager
+            .pull_metadata(&conn, &configuration, &UpdateProgressReporter::default())
+            .unwrap();
+        assert_eq!(result.added_filters.len(), 0);
+
+        let mirror_requests = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.path.starts_with("/mirror/"))
+            .collect::<Vec<_>>();
+        assert_eq!(mirror_requests.len(), 4);
+        assert!(mirror_requests[2..]
+            .iter()
+            .all(|request| request.header("if-none-match").is_some()));
+
+        // All urls are unreachable
+        configuration.metadata_mirror_urls = vec![server.url("/broken/filters.json")];
+        let result =
+            manager.pull_metadata(&conn, &configuration, &UpdateProgressReporter::default());
+        assert!(matches!(result, Err(FLMError::Network(_))));
+    }
 }
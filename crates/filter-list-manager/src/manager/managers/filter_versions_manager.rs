@@ -0,0 +1,432 @@
+use crate::storage::entities::filter_pin_entity::FilterPinEntity;
+use crate::storage::repositories::diff_updates_repository::DiffUpdateRepository;
+use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
+use crate::storage::repositories::filter_pins_repository::FilterPinsRepository;
+use crate::storage::repositories::filter_repository::FilterRepository;
+use crate::storage::repositories::filter_versions_repository::FilterVersionsRepository;
+use crate::storage::repositories::http_cache_validators_repository::HttpCacheValidatorsRepository;
+use crate::storage::repositories::rules_list_repository::RulesListRepository;
+use crate::storage::repositories::{BulkDeleteRepository, Repository};
+use crate::storage::sql_generators::operator::SQLOperator;
+use crate::storage::with_transaction;
+use crate::utils::integrity;
+use crate::{Configuration, FLMError, FLMResult, FilterId, FilterVersion};
+use chrono::Utc;
+use rusqlite::{Connection, Transaction};
+use std::slice;
+
+/// Manager for previous versions of filters contents
+pub(crate) struct FilterVersionsManager;
+
+impl FilterVersionsManager {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Lists kept versions of the filter, from the latest to the oldest
+    pub(crate) fn list_filter_versions(
+        &self,
+        conn: &Connection,
+        filter_id: FilterId,
+    ) -> FLMResult<Vec<FilterVersion>> {
+        let entities = FilterVersionsRepository::new()
+            .select_for_filter(conn, filter_id)
+            .map_err(FLMError::from_database)?;
+
+        Ok(entities.into_iter().map(Into::into).collect())
+    }
+
+    /// Replaces filter rules, includes and metadata with the kept version and pins the filter.
+    /// Current contents are kept as a new version, so the rollback may be reverted
+    pub(crate) fn rollback_filter(
+        &self,
+        conn: &mut Connection,
+        configuration: &Configuration,
+        filter_id: FilterId,
+        version_id: i64,
+    ) -> FLMResult<()> {
+        let filter_repository = FilterRepository::new();
+        let rules_list_repository = RulesListRepository::new();
+        let filter_includes_repository = FilterIncludesRepository::new();
+        let filter_versions_repository = FilterVersionsRepository::new();
+
+        let mut filter = filter_repository
+            .select(
+                conn,
+                Some(SQLOperator::FieldEqualValue("filter_id", filter_id.into())),
+            )
+            .map_err(FLMError::from_database)?
+            .and_then(|mut filters| filters.pop())
+            .ok_or(FLMError::EntityNotFound(filter_id as i64))?;
+
+        if let Some(key) = integrity::derive_key_if_needed(configuration) {
+            integrity::verify_filter_entities(&key, slice::from_ref(&filter))?;
+        }
+
+        let version = filter_versions_repository
+            .select_by_id(conn, version_id)
+            .map_err(FLMError::from_database)?
+            .filter(|version| version.filter_id == filter_id)
+            .ok_or(FLMError::EntityNotFound(version_id))?;
+
+        let mut rules_entity = filter_versions_repository
+            .select_rules_list_entity(conn, version_id)
+            .map_err(FLMError::from_database)?;
+
+        let mut includes_entities = filter_versions_repository
+            .select_include_entities(conn, version_id)
+            .map_err(FLMError::from_database)?;
+
+        // Disabled rules belong to the user, not to the version
+        rules_entity.disabled_text = rules_list_repository
+            .get_disabled_rules_by_ids(conn, &[filter_id])
+            .map_err(FLMError::from_database)?
+            .pop()
+            .map(|entity| entity.disabled_text)
+            .unwrap_or_default();
+
+        // Metadata is restored the same way the update saves it
+        filter.version = version.version.clone();
+        filter.license = version.license;
+        filter.checksum = version.checksum;
+        filter.expires = version.expires;
+        filter.last_update_time = version.last_update_time;
+        if filter.is_custom() {
+            filter.homepage = version.homepage;
+            if !filter.is_user_title() {
+                filter.title = version.title;
+            }
+            if !filter.is_user_description() {
+                filter.description = version.description;
+            }
+        }
+
+        // Versions keep signatures of the replaced contents, so tampered rows must not be re-signed
+        if let Some(key) = integrity::derive_key_if_needed(configuration) {
+            integrity::verify_rules_list_entity(&key, &rules_entity)?;
+            integrity::verify_filter_include_entities(&key, &includes_entities)?;
+        }
+
+        integrity::sign_entities_if_needed(
+            configuration,
+            &mut rules_entity,
+            &mut includes_entities,
+        );
+        integrity::sign_filter_entity_if_needed(configuration, &mut filter);
+
+        let current_time = Utc::now().timestamp();
+        let pin = FilterPinEntity {
+            filter_id,
+            version: version.version,
+            pinned_time: current_time,
+        };
+
+        with_transaction(conn, |tx: &Transaction| {
+            filter_versions_repository.save_current_versions(tx, &[filter_id], current_time)?;
+            filter_versions_repository.delete_versions(tx, &[version_id])?;
+            filter_versions_repository.delete_outdated(
+                tx,
+                &[filter_id],
+                configuration.filter_versions_retention,
+            )?;
+
+            filter_includes_repository.delete_for_filters(tx, [filter_id].iter(), 1)?;
+            filter_includes_repository.insert(tx, &includes_entities)?;
+            rules_list_repository.insert(tx, slice::from_ref(&rules_entity))?;
+            filter_repository.insert(tx, slice::from_ref(&filter))?;
+
+            // Patches and cache validators are related to the replaced contents
+            DiffUpdateRepository::new().bulk_delete(tx, &vec![filter_id])?;
+            HttpCacheValidatorsRepository::new().bulk_delete(tx, &vec![filter.download_url])?;
+
+            FilterPinsRepository::new().insert(tx, &[pin])
+        })
+    }
+
+    /// Allows updates of the pinned filter
+    pub(crate) fn unpin_filter(&self, conn: &mut Connection, filter_id: FilterId) -> FLMResult<()> {
+        with_transaction(conn, |tx: &Transaction| {
+            FilterPinsRepository::new()
+                .bulk_delete(tx, &vec![filter_id])
+                .map(|_| ())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilterVersionsManager;
+    use crate::manager::managers::filter_update_manager::FilterUpdateManager;
+    use crate::manager::managers::integrity_control_manager::IntegrityControlManager;
+    use crate::manager::update_progress_reporter::UpdateProgressReporter;
+    use crate::storage::entities::filter::filter_entity::FilterEntity;
+    use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
+    use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
+    use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
+    use crate::storage::repositories::filter_repository::FilterRepository;
+    use crate::storage::repositories::rules_list_repository::RulesListRepository;
+    use crate::storage::repositories::Repository;
+    use crate::storage::sql_generators::operator::SQLOperator;
+    use crate::storage::{with_transaction, DbConnectionManager};
+    use crate::test_utils::tests_http_server::{TestsHttpResponse, TestsHttpServer};
+    use crate::{string, Configuration, FLMError, FilterUpdateOutcome, CUSTOM_FILTERS_GROUP_ID};
+    use rusqlite::Connection;
+
+    const FILTER_ID: i32 = -45;
+    const OLD_TEXT: &str = "! Title: Versions\n! Version: 1.0\n||a.com^\n||b.com^";
+    const NEW_TEXT: &str = "! Title: Versions v2\n! Version: 2.0\n||c.com^";
+
+    fn select_filter(source: &DbConnectionManager) -> FilterEntity {
+        source
+            .execute_db(|conn: Connection| {
+                Ok(FilterRepository::new()
+                    .select(
+                        &conn,
+                        Some(SQLOperator::FieldEqualValue("filter_id", FILTER_ID.into())),
+                    )
+                    .unwrap()
+                    .unwrap()
+                    .remove(0))
+            })
+            .unwrap()
+    }
+
+    fn select_rules(source: &DbConnectionManager) -> RulesListEntity {
+        source
+            .execute_db(|conn: Connection| {
+                Ok(RulesListRepository::new()
+                    .select_mapped(
+                        &conn,
+                        Some(SQLOperator::FieldEqualValue("filter_id", FILTER_ID.into())),
+                    )
+                    .unwrap()
+                    .remove(&FILTER_ID)
+                    .unwrap())
+            })
+            .unwrap()
+    }
+
+    fn insert_filter(source: &DbConnectionManager, download_url: String) {
+        let mut filter = FilterEntity::default();
+        filter.filter_id = Some(FILTER_ID);
+        filter.group_id = CUSTOM_FILTERS_GROUP_ID;
+        filter.is_enabled = true;
+        filter.download_url = download_url;
+        filter.title = string!("Versions");
+        filter.version = string!("1.0");
+
+        source
+            .execute_db(|mut conn: Connection| {
+                with_transaction(&mut conn, |tx| {
+                    FilterRepository::new().insert(tx, &[filter])?;
+                    RulesListRepository::new().insert(
+                        tx,
+                        &[RulesListEntity::with_disabled_text(
+                            FILTER_ID,
+                            string!(OLD_TEXT),
+                            string!("||a.com^"),
+                            2,
+                        )],
+                    )?;
+                    FilterIncludesRepository::new().insert(
+                        tx,
+                        &[FilterIncludeEntity::make(
+                            FILTER_ID,
+                            string!("https://example.org/include.txt"),
+                            1,
+                            string!("||include.com^"),
+                        )],
+                    )
+                })
+            })
+            .unwrap();
+    }
+
+    fn update(source: &DbConnectionManager, configuration: &Configuration) -> FilterUpdateOutcome {
+        FilterUpdateManager::new()
+            .force_update_filters_by_ids(
+                vec![select_filter(source)],
+                source,
+                configuration,
+                0,
+                &UpdateProgressReporter::default(),
+            )
+            .unwrap()
+            .filters_reports
+            .remove(0)
+            .outcome
+    }
+
+    #[test]
+    fn test_rollback_filter_restores_previous_version_and_pins_filter() {
+        let server = TestsHttpServer::start(|_| TestsHttpResponse::new(200, NEW_TEXT));
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut configuration = Configuration::default();
+        configuration.filter_versions_retention = 2;
+
+        insert_filter(&source, server.url("/filter.txt"));
+
+        assert_eq!(
+            update(&source, &configuration),
+            FilterUpdateOutcome::Updated
+        );
+        assert_eq!(select_rules(&source).text, NEW_TEXT);
+
+        let manager = FilterVersionsManager::new();
+        let versions = source
+            .execute_db(|conn: Connection| manager.list_filter_versions(&conn, FILTER_ID))
+            .unwrap();
+
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, "1.0");
+        assert_eq!(versions[0].title, "Versions");
+        assert_eq!(versions[0].rules_count, 3);
+
+        source
+            .execute_db(|mut conn: Connection| {
+                manager.rollback_filter(
+                    &mut conn,
+                    &configuration,
+                    FILTER_ID,
+                    versions[0].version_id,
+                )
+            })
+            .unwrap();
+
+        let rules = select_rules(&source);
+        assert_eq!(rules.text, OLD_TEXT);
+        assert_eq!(rules.disabled_text, "||a.com^");
+
+        let filter = select_filter(&source);
+        assert_eq!(filter.version, "1.0");
+        assert_eq!(filter.title, "Versions");
+
+        let (includes, versions) = source
+            .execute_db(|conn: Connection| {
+                let includes = FilterIncludesRepository::new()
+                    .select_mapped(&conn, None)
+                    .unwrap()
+                    .remove(&FILTER_ID)
+                    .unwrap();
+
+                Ok((includes, manager.list_filter_versions(&conn, FILTER_ID)?))
+            })
+            .unwrap();
+
+        assert_eq!(includes.len(), 1);
+        assert_eq!(includes[0].body, "||include.com^");
+        // Replaced version is kept, restored one is removed
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, "2.0");
+
+        // Pinned filter is not updated
+        assert_eq!(update(&source, &configuration), FilterUpdateOutcome::Pinned);
+        assert_eq!(select_rules(&source).text, OLD_TEXT);
+
+        source
+            .execute_db(|mut conn: Connection| manager.unpin_filter(&mut conn, FILTER_ID))
+            .unwrap();
+
+        assert_eq!(
+            update(&source, &configuration),
+            FilterUpdateOutcome::Updated
+        );
+        assert_eq!(select_rules(&source).text, NEW_TEXT);
+
+        let versions = source
+            .execute_db(|conn: Connection| manager.list_filter_versions(&conn, FILTER_ID))
+            .unwrap();
+
+        assert_eq!(
+            versions
+                .iter()
+                .map(|version| version.version.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["1.0", "2.0"]
+        );
+    }
+
+    #[test]
+    fn test_rollback_filter_fails_for_tampered_version() {
+        let server = TestsHttpServer::start(|_| TestsHttpResponse::new(200, NEW_TEXT));
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut configuration = Configuration::default();
+        configuration.filter_versions_retention = 2;
+        configuration.integrity_key = Some(string!("versions-test-key"));
+
+        insert_filter(&source, server.url("/filter.txt"));
+        IntegrityControlManager::new()
+            .sign_all_data(&source, &configuration)
+            .unwrap();
+
+        assert_eq!(
+            update(&source, &configuration),
+            FilterUpdateOutcome::Updated
+        );
+
+        // Kept versions are signed again with the new key
+        configuration.integrity_key = Some(string!("versions-test-key-2"));
+        IntegrityControlManager::new()
+            .sign_all_data(&source, &configuration)
+            .unwrap();
+
+        let manager = FilterVersionsManager::new();
+        let version_id = source
+            .execute_db(|conn: Connection| manager.list_filter_versions(&conn, FILTER_ID))
+            .unwrap()[0]
+            .version_id;
+
+        let original_text = source
+            .execute_db(|conn: Connection| {
+                let text = conn
+                    .query_row(
+                        "SELECT rules_text FROM [filter_version] WHERE version_id = ?",
+                        [version_id],
+                        |row| row.get::<usize, String>(0),
+                    )
+                    .unwrap();
+
+                conn.execute(
+                    "UPDATE [filter_version] SET rules_text = '||tampered.com^' WHERE version_id = ?",
+                    [version_id],
+                )
+                .unwrap();
+
+                Ok(text)
+            })
+            .unwrap();
+
+        let result = source.execute_db(|mut conn: Connection| {
+            manager.rollback_filter(&mut conn, &configuration, FILTER_ID, version_id)
+        });
+
+        assert_eq!(result, Err(FLMError::FilterIntegrityCheckFailed(FILTER_ID)));
+        assert_eq!(select_rules(&source).text, NEW_TEXT);
+
+        source
+            .execute_db(|conn: Connection| {
+                conn.execute(
+                    "UPDATE [filter_version] SET rules_text = ? WHERE version_id = ?",
+                    rusqlite::params![original_text, version_id],
+                )
+                .unwrap();
+
+                Ok(())
+            })
+            .unwrap();
+
+        source
+            .execute_db(|mut conn: Connection| {
+                manager.rollback_filter(&mut conn, &configuration, FILTER_ID, version_id)
+            })
+            .unwrap();
+
+        assert_eq!(select_rules(&source).text, OLD_TEXT);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::storage::repositories::db_metadata_repository::DBMetadataRepository;
 use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
 use crate::storage::repositories::filter_repository::FilterRepository;
+use crate::storage::repositories::filter_versions_repository::FilterVersionsRepository;
 use crate::storage::repositories::rules_list_repository::RulesListRepository;
 use crate::storage::with_transaction;
 use crate::storage::DbConnectionManager;
@@ -16,8 +17,8 @@ impl IntegrityControlManager {
         Self {}
     }
 
-    /// Signs all filter rules, includes, metadata, and the filter count using
-    /// the integrity key from configuration.
+    /// Signs all filter rules, includes, metadata, kept filter versions, and the
+    /// filter count using the integrity key from configuration.
     ///
     /// Uses streaming iteration to avoid loading all rule bodies into memory
     /// at once — only `(id, signature)` pairs are accumulated.
@@ -45,6 +46,7 @@ impl IntegrityControlManager {
             let rules_list_repository = RulesListRepository::new();
             let filter_includes_repository = FilterIncludesRepository::new();
             let filter_repository = FilterRepository::new();
+            let filter_versions_repository = FilterVersionsRepository::new();
 
             // 1. Collect signatures for rules and includes (streaming)
             let rules_signatures = rules_list_repository
@@ -55,6 +57,15 @@ impl IntegrityControlManager {
                 .sign_and_collect_signatures_streaming(&conn, &derived_key)
                 .map_err(FLMError::from_database)?;
 
+            // Kept versions are verified on rollback, so they are signed with the same key
+            let version_signatures = filter_versions_repository
+                .sign_and_collect_signatures_streaming(&conn, &derived_key)
+                .map_err(FLMError::from_database)?;
+
+            let version_includes_signatures = filter_versions_repository
+                .sign_and_collect_include_signatures_streaming(&conn, &derived_key)
+                .map_err(FLMError::from_database)?;
+
             // 2. Collect signatures for filter metadata (streaming)
             let metadata_signatures = filter_repository
                 .sign_and_collect_metadata_signatures_streaming(&conn, &derived_key)
@@ -71,6 +82,11 @@ impl IntegrityControlManager {
             with_transaction(&mut conn, |tx: &Transaction| {
                 rules_list_repository.batch_update_signatures(tx, &rules_signatures)?;
                 filter_includes_repository.batch_update_signatures(tx, &includes_signatures)?;
+                filter_versions_repository.batch_update_signatures(
+                    tx,
+                    &version_signatures,
+                    &version_includes_signatures,
+                )?;
                 filter_repository.batch_update_metadata_signatures(tx, &metadata_signatures)?;
 
                 // Update count signature in metadata table
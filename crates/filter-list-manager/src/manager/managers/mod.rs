@@ -5,6 +5,9 @@ pub(crate) mod filter_manager;
 pub(crate) mod filter_metadata_grabber;
 pub(crate) mod filter_tag_manager;
 pub(crate) mod filter_update_manager;
+pub(crate) mod filter_versions_manager;
 pub(crate) mod integrity_control_manager;
 pub(crate) mod rules_list_manager;
+pub(crate) mod rules_search_manager;
 pub(crate) mod streaming_rules_manager;
+pub(crate) mod user_state_manager;
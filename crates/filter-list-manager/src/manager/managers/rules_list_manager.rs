@@ -1,13 +1,21 @@
+use crate::filters::disabled_rules_matcher::{validate_pattern, DisabledRulesMatcher};
 use crate::filters::parser::collectors::default_filter_collector::DefaultFilterCollector;
 use crate::filters::parser::filter_compiler::FilterCompiler;
 use crate::filters::parser::filter_contents_provider::string_provider::StringProvider;
 use crate::filters::parser::is_rule_detector::is_line_is_rule;
+use crate::filters::rules_deduplicator::{ownership_order, RulesDeduplicator};
 use crate::io::http::blocking_client::BlockingClient;
 use crate::manager::models::active_rules_info_raw::ActiveRulesInfoRaw;
+use crate::manager::models::deduplicated_active_rules::{
+    DeduplicatedActiveRules, DeduplicatedActiveRulesRaw, DuplicatesByFilter,
+};
+use crate::manager::models::disabled_rule_pattern::{DisabledRulePattern, DisabledRulePatterns};
 use crate::storage::entities::filter::filter_entity::FilterEntity;
+use crate::storage::entities::rules_list::disabled_rule_pattern_entity::DisabledRulePatternEntity;
 use crate::storage::entities::rules_list::disabled_rules_entity::DisabledRulesEntity;
 use crate::storage::entities::rules_list::rules_count_entity::RulesCountEntity;
 use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
+use crate::storage::repositories::disabled_rule_patterns_repository::DisabledRulePatternsRepository;
 use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
 use crate::storage::repositories::filter_repository::FilterRepository;
 use crate::storage::repositories::rules_list_repository::RulesListRepository;
@@ -46,12 +54,12 @@ impl RulesListManager {
             connection_manager,
             configuration,
             vec![],
-            |disabled_lines, rule_entity, filter_entity, filter_id| {
-                // Make a difference of rule_entity.text from rule_entity.disabled_text
+            |disabled_rules_matcher, rule_entity, filter_entity, filter_id| {
+                // Make a difference of rule_entity.text from disabled rules and patterns
                 let filtered_rules = rule_entity
                     .text
                     .lines()
-                    .filter(|line| !disabled_lines.contains(*line))
+                    .filter(|line| !disabled_rules_matcher.is_disabled(line.as_bytes()))
                     .map(ToString::to_string)
                     .collect::<Vec<String>>();
 
@@ -59,6 +67,7 @@ impl RulesListManager {
                     filter_id,
                     group_id: filter_entity.group_id,
                     is_trusted: filter_entity.is_trusted,
+                    trust_level: filter_entity.trust_level,
                     rules: filtered_rules,
                 }
             },
@@ -76,24 +85,95 @@ impl RulesListManager {
             connection_manager,
             configuration,
             filter_by,
-            |disabled_lines, rule_entity, filter_entity, filter_id| {
-                // Make a difference of rule_entity.text from rule_entity.disabled_text
+            |disabled_rules_matcher, rule_entity, filter_entity, filter_id| {
+                // Make a difference of rule_entity.text from disabled rules and patterns
                 let filtered_rules: Vec<&str> = rule_entity
                     .text
                     .lines()
-                    .filter(|line| !disabled_lines.contains(*line))
+                    .filter(|line| !disabled_rules_matcher.is_disabled(line.as_bytes()))
                     .collect();
 
                 ActiveRulesInfoRaw {
                     filter_id,
                     group_id: filter_entity.group_id,
                     is_trusted: filter_entity.is_trusted,
+                    trust_level: filter_entity.trust_level,
                     rules: filtered_rules.join("\n"),
                 }
             },
         )
     }
 
+    /// Build a list of [`ActiveRulesInfo`], where each unique rule is kept
+    /// only in the first trusted filter containing it
+    pub(crate) fn get_active_rules_deduplicated(
+        &self,
+        connection_manager: &DbConnectionManager,
+        configuration: &Configuration,
+    ) -> FLMResult<DeduplicatedActiveRules> {
+        let mut rules = self.get_active_rules(connection_manager, configuration)?;
+
+        let mut deduplicator = RulesDeduplicator::default();
+        let mut duplicates_counts = vec![0; rules.len()];
+        for index in ownership_order(rules.iter().map(|info| info.is_trusted)) {
+            let info = &mut rules[index];
+            let count_before = info.rules.len();
+
+            info.rules.retain(|rule| !deduplicator.is_duplicate(rule));
+
+            duplicates_counts[index] = (count_before - info.rules.len()) as i32;
+        }
+
+        let duplicates = Self::make_duplicates_by_filter(
+            rules.iter().map(|info| info.filter_id),
+            duplicates_counts,
+        );
+
+        Ok(DeduplicatedActiveRules { rules, duplicates })
+    }
+
+    /// Build a list of [`ActiveRulesInfoRaw`], where each unique rule is kept
+    /// only in the first trusted filter containing it
+    pub(crate) fn get_active_rules_raw_deduplicated(
+        &self,
+        connection_manager: &DbConnectionManager,
+        configuration: &Configuration,
+        filter_by: Vec<FilterId>,
+    ) -> FLMResult<DeduplicatedActiveRulesRaw> {
+        let mut rules = self.get_active_rules_raw(connection_manager, configuration, filter_by)?;
+
+        let mut deduplicator = RulesDeduplicator::default();
+        let mut duplicates_counts = vec![0; rules.len()];
+        for index in ownership_order(rules.iter().map(|info| info.is_trusted)) {
+            let info = &mut rules[index];
+            let mut duplicates_count = 0;
+
+            let filtered_rules = info
+                .rules
+                .lines()
+                .filter(|line| {
+                    let is_duplicate = deduplicator.is_duplicate(line);
+                    if is_duplicate {
+                        duplicates_count += 1;
+                    }
+
+                    !is_duplicate
+                })
+                .collect::<Vec<&str>>()
+                .join("\n");
+
+            info.rules = filtered_rules;
+            duplicates_counts[index] = duplicates_count;
+        }
+
+        let duplicates = Self::make_duplicates_by_filter(
+            rules.iter().map(|info| info.filter_id),
+            duplicates_counts,
+        );
+
+        Ok(DeduplicatedActiveRulesRaw { rules, duplicates })
+    }
+
     /// Gets disabled rules
     pub(crate) fn get_disabled_rules(
         &self,
@@ -112,8 +192,12 @@ impl RulesListManager {
         configuration: &Configuration,
         ids: Vec<FilterId>,
     ) -> FLMResult<Vec<FilterListRulesRaw>> {
-        let (filters, mut result, these_includes, download_urls) =
+        let (filters, mut result, these_includes, download_urls, mut patterns_map) =
             connection_manager.execute_db(|conn: Connection| {
+                let patterns_map = DisabledRulePatternsRepository::new()
+                    .select_map(&conn, &ids)
+                    .map_err(FLMError::from_database)?;
+
                 let values: Vec<Value> = ids.into_iter().map(Into::into).collect();
 
                 let filters = FilterRepository::new()
@@ -143,7 +227,7 @@ impl RulesListManager {
                     .select(&conn, Some(SQLOperator::FieldIn("filter_id", values)))
                     .map_err(FLMError::from_database)?;
 
-                Ok((filters, rules, includes, download_urls_map))
+                Ok((filters, rules, includes, download_urls_map, patterns_map))
             })?;
 
         let derived_key = integrity::derive_key_if_needed(configuration);
@@ -181,6 +265,10 @@ impl RulesListManager {
                         )));
                     }
                 }
+
+                if let Some(patterns) = patterns_map.remove(&rule.filter_id) {
+                    Self::append_rules_disabled_by_patterns(rule, &patterns)?;
+                }
             }
         }
 
@@ -305,6 +393,103 @@ impl RulesListManager {
 
         Ok(())
     }
+
+    /// Gets disabled rule patterns of the filters
+    pub(crate) fn get_disabled_rule_patterns(
+        &self,
+        conn: &Connection,
+        ids: &[FilterId],
+    ) -> FLMResult<Vec<DisabledRulePatterns>> {
+        let mut map = DisabledRulePatternsRepository::new()
+            .select_map(conn, ids)
+            .map_err(FLMError::from_database)?;
+
+        Ok(ids
+            .iter()
+            .filter_map(|filter_id| {
+                map.remove(filter_id).map(|entities| DisabledRulePatterns {
+                    filter_id: *filter_id,
+                    patterns: entities.into_iter().map(Into::into).collect(),
+                })
+            })
+            .collect())
+    }
+
+    /// Replaces disabled rule patterns of the filter
+    pub(crate) fn save_disabled_rule_patterns(
+        &self,
+        conn: &mut Connection,
+        filter_id: FilterId,
+        patterns: Vec<DisabledRulePattern>,
+    ) -> FLMResult<()> {
+        let rules_lists_count = RulesListRepository::new()
+            .count(
+                conn,
+                Some(SQLOperator::FieldEqualValue("filter_id", filter_id.into())),
+            )
+            .map_err(FLMError::from_database)?;
+
+        if rules_lists_count == 0 {
+            return Err(FLMError::EntityNotFound(filter_id as i64));
+        }
+
+        for pattern in patterns.iter() {
+            validate_pattern(pattern.kind, &pattern.pattern)?;
+        }
+
+        let entities = patterns
+            .into_iter()
+            .map(|pattern| DisabledRulePatternEntity::make(filter_id, pattern))
+            .collect::<Vec<DisabledRulePatternEntity>>();
+
+        with_transaction(conn, |transaction: &Transaction| {
+            DisabledRulePatternsRepository::new().replace_for_filter(
+                transaction,
+                filter_id,
+                &entities,
+            )
+        })
+    }
+
+    fn make_duplicates_by_filter<I>(
+        filter_ids: I,
+        duplicates_counts: Vec<i32>,
+    ) -> Vec<DuplicatesByFilter>
+    where
+        I: Iterator<Item = FilterId>,
+    {
+        filter_ids
+            .zip(duplicates_counts)
+            .map(|(filter_id, duplicates_count)| DuplicatesByFilter {
+                filter_id,
+                duplicates_count,
+            })
+            .collect()
+    }
+
+    /// Appends rules, which are disabled only by `patterns`, to `rule.disabled_text`,
+    /// so raw disabled rules stay sufficient for filtering the raw rules
+    fn append_rules_disabled_by_patterns(
+        rule: &mut RulesListEntity,
+        patterns: &[DisabledRulePatternEntity],
+    ) -> FLMResult<()> {
+        let matcher = DisabledRulesMatcher::new([], patterns)?;
+
+        let mut disabled_lines = rule.disabled_text.lines().collect::<HashSet<&str>>();
+        let mut appended = String::new();
+        for line in rule.text.lines() {
+            if matcher.is_disabled_by_pattern(line.as_bytes()) && disabled_lines.insert(line) {
+                if !rule.disabled_text.is_empty() || !appended.is_empty() {
+                    appended.push('\n');
+                }
+                appended.push_str(line);
+            }
+        }
+
+        rule.disabled_text.push_str(&appended);
+
+        Ok(())
+    }
 }
 
 impl RulesListManager {
@@ -317,10 +502,10 @@ impl RulesListManager {
         block: Block,
     ) -> FLMResult<Vec<ActiveRules>>
     where
-        Block: Fn(HashSet<&str>, &RulesListEntity, &FilterEntity, FilterId) -> ActiveRules,
+        Block: Fn(&DisabledRulesMatcher, &RulesListEntity, &FilterEntity, FilterId) -> ActiveRules,
     {
         // Get all active filters and stuff
-        let (list, mut rules, includes_list) =
+        let (list, mut rules, includes_list, mut patterns_map) =
             connection_manager.execute_db(|conn: Connection| {
                 let active_filters_operator = {
                     let base_operator = SQLOperator::FieldEqualValue("is_enabled", true.into());
@@ -344,11 +529,16 @@ impl RulesListManager {
                     .map_err(FLMError::from_database)?
                     .unwrap_or_default();
 
-                let filter_ids = enabled_filters
+                let ids = enabled_filters
                     .iter()
                     .filter_map(|entity| entity.filter_id)
-                    .map(Into::into)
-                    .collect::<Vec<Value>>();
+                    .collect::<Vec<FilterId>>();
+
+                let patterns_map = DisabledRulePatternsRepository::new()
+                    .select_map(&conn, &ids)
+                    .map_err(FLMError::from_database)?;
+
+                let filter_ids = ids.into_iter().map(Into::into).collect::<Vec<Value>>();
 
                 let map = RulesListRepository::new()
                     .select_mapped(
@@ -361,7 +551,7 @@ impl RulesListManager {
                     .select_mapped(&conn, Some(SQLOperator::FieldIn("filter_id", filter_ids)))
                     .map_err(FLMError::from_database)?;
 
-                Ok((enabled_filters, map, includes_list))
+                Ok((enabled_filters, map, includes_list, patterns_map))
             })?;
 
         // Collect new active rules from filters, rules, includes and stuff
@@ -395,11 +585,13 @@ impl RulesListManager {
                         rule_entity.rules_count = new_count;
                     }
 
-                    let disabled_lines =
-                        rule_entity.disabled_text.lines().collect::<HashSet<&str>>();
+                    let disabled_rules_matcher = DisabledRulesMatcher::new(
+                        rule_entity.disabled_text.lines().map(str::as_bytes),
+                        &patterns_map.remove(&filter_id).unwrap_or_default(),
+                    )?;
 
                     active_rules.push(block(
-                        disabled_lines,
+                        &disabled_rules_matcher,
                         &rule_entity,
                         &filter_entity,
                         filter_id,
@@ -437,7 +629,10 @@ mod tests {
     use crate::storage::with_transaction;
     use crate::string;
     use crate::test_utils::spawn_test_db_with_metadata;
-    use crate::{Configuration, FilterId, FilterListManagerImpl, FilterListRules};
+    use crate::{
+        Configuration, DisabledRulePattern, DisabledRulePatternKind, FLMError, FilterId,
+        FilterListManagerImpl, FilterListRules,
+    };
 
     #[test]
     fn test_get_active_rules_with_disabled_rules() {
@@ -594,4 +789,191 @@ mod tests {
         assert_eq!(new_disabled_rules, disabled_rules);
         assert_eq!(new_rules_count, user_rules_count_result);
     }
+
+    #[test]
+    fn test_disabled_rule_patterns() {
+        let mut conf = Configuration::default();
+        conf.app_name = string!("FlmApp");
+        conf.version = string!("1.2.3");
+
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+        let _ = spawn_test_db_with_metadata(&flm.connection_manager);
+
+        let new_filter = flm
+            .install_custom_filter_from_string(
+                string!("https://i-dont-ca.re"),
+                1970,
+                true,
+                true,
+                string!("||example.com^\n||ads.example.com^\nexample.org##+js(abort)\nexample.net##.banner\n||literal.org^"),
+                None,
+                None,
+            )
+            .unwrap();
+
+        let patterns = vec![
+            DisabledRulePattern {
+                kind: DisabledRulePatternKind::Wildcard,
+                pattern: string!("*example.com^"),
+            },
+            DisabledRulePattern {
+                kind: DisabledRulePatternKind::Regex,
+                pattern: string!(r"##\+js\("),
+            },
+        ];
+
+        assert!(matches!(
+            flm.save_disabled_rule_patterns(
+                new_filter.id,
+                vec![DisabledRulePattern {
+                    kind: DisabledRulePatternKind::Regex,
+                    pattern: string!("(")
+                }]
+            ),
+            Err(FLMError::Other(_))
+        ));
+        assert!(matches!(
+            flm.save_disabled_rule_patterns(1029423522, patterns.clone()),
+            Err(FLMError::EntityNotFound(1029423522))
+        ));
+
+        flm.save_disabled_rules(new_filter.id, vec![string!("||literal.org^")])
+            .unwrap();
+        flm.save_disabled_rule_patterns(new_filter.id, patterns.clone())
+            .unwrap();
+
+        let saved_patterns = flm.get_disabled_rule_patterns(vec![new_filter.id]).unwrap();
+        assert_eq!(saved_patterns.len(), 1);
+        assert_eq!(saved_patterns[0].patterns, patterns);
+
+        let active_rules = flm.get_active_rules().unwrap();
+        let actual_filter = active_rules
+            .iter()
+            .find(|info| info.filter_id == new_filter.id)
+            .unwrap();
+        assert_eq!(actual_filter.rules, vec![string!("example.net##.banner")]);
+
+        let active_rules_raw = flm.get_active_rules_raw(vec![new_filter.id]).unwrap();
+        assert_eq!(active_rules_raw[0].rules, "example.net##.banner");
+
+        let rules_raw = flm
+            .get_filter_rules_as_strings(vec![new_filter.id])
+            .unwrap();
+        assert_eq!(
+            rules_raw[0].disabled_rules,
+            "||literal.org^\n||example.com^\n||ads.example.com^\nexample.org##+js(abort)"
+        );
+
+        // Patterns must survive the filter update
+        flm.save_custom_filter_rules(FilterListRules {
+            filter_id: new_filter.id,
+            rules: vec![
+                string!("||new.example.com^"),
+                string!("new.org##+js(abort)"),
+                string!("||example.com^$third-party"),
+            ],
+            disabled_rules: vec![],
+            rules_count: 0,
+        })
+        .unwrap();
+
+        let active_rules_raw = flm.get_active_rules_raw(vec![new_filter.id]).unwrap();
+        assert_eq!(active_rules_raw[0].rules, "||example.com^$third-party");
+
+        // Empty list removes all patterns
+        flm.save_disabled_rule_patterns(new_filter.id, vec![])
+            .unwrap();
+        assert!(flm
+            .get_disabled_rule_patterns(vec![new_filter.id])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_get_active_rules_deduplicated() {
+        let mut conf = Configuration::default();
+        conf.app_name = string!("FlmApp");
+        conf.version = string!("1.2.3");
+
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+        let _ = spawn_test_db_with_metadata(&flm.connection_manager);
+
+        let untrusted_filter = flm
+            .install_custom_filter_from_string(
+                string!("https://untrusted.example"),
+                1970,
+                true,
+                false,
+                string!("||shared.com^\n! comment\n||a.com^\n||a.com^"),
+                None,
+                None,
+            )
+            .unwrap();
+
+        let trusted_filter = flm
+            .install_custom_filter_from_string(
+                string!("https://trusted.example"),
+                1970,
+                true,
+                true,
+                string!("! comment\n||shared.com^\n||b.com^"),
+                None,
+                None,
+            )
+            .unwrap();
+
+        let deduplicated = flm.get_active_rules_deduplicated().unwrap();
+
+        let untrusted_rules = deduplicated
+            .rules
+            .iter()
+            .find(|info| info.filter_id == untrusted_filter.id)
+            .unwrap();
+        assert_eq!(
+            untrusted_rules.rules,
+            vec![string!("! comment"), string!("||a.com^")]
+        );
+
+        let trusted_rules = deduplicated
+            .rules
+            .iter()
+            .find(|info| info.filter_id == trusted_filter.id)
+            .unwrap();
+        assert_eq!(
+            trusted_rules.rules,
+            vec![
+                string!("! comment"),
+                string!("||shared.com^"),
+                string!("||b.com^")
+            ]
+        );
+
+        let duplicates_count = |filter_id: FilterId| {
+            deduplicated
+                .duplicates
+                .iter()
+                .find(|duplicates| duplicates.filter_id == filter_id)
+                .unwrap()
+                .duplicates_count
+        };
+        assert_eq!(duplicates_count(untrusted_filter.id), 2);
+        assert_eq!(duplicates_count(trusted_filter.id), 0);
+
+        let deduplicated_raw = flm
+            .get_active_rules_raw_deduplicated(vec![untrusted_filter.id, trusted_filter.id])
+            .unwrap();
+
+        let untrusted_rules_raw = deduplicated_raw
+            .rules
+            .iter()
+            .find(|info| info.filter_id == untrusted_filter.id)
+            .unwrap();
+        assert_eq!(untrusted_rules_raw.rules, "! comment\n||a.com^");
+        assert_eq!(deduplicated_raw.duplicates.len(), 2);
+        assert!(deduplicated_raw
+            .duplicates
+            .iter()
+            .any(|duplicates| duplicates.filter_id == untrusted_filter.id
+                && duplicates.duplicates_count == 2));
+    }
 }
@@ -0,0 +1,623 @@
+use crate::filters::parser::collectors::default_filter_collector::DefaultFilterCollector;
+use crate::filters::parser::is_rule_detector::is_line_is_rule;
+use crate::manager::models::rule_provenance::RuleProvenance;
+use crate::manager::models::rule_search::{RuleSearchMatch, RuleSearchOptions};
+use crate::storage::compression::COMPRESSION_FIELD;
+use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
+use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
+use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
+use crate::storage::repositories::filter_repository::FilterRepository;
+use crate::storage::repositories::rules_list_repository::RulesListRepository;
+use crate::storage::sql_generators::operator::SQLOperator;
+use crate::utils::integrity;
+use crate::utils::memory::heap;
+use crate::{Configuration, FLMError, FLMResult, FilterId};
+use regex::{Regex, RegexBuilder};
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+/// Manager for rules search
+pub(crate) struct RulesSearchManager;
+
+impl RulesSearchManager {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Searches rules in filters bodies and their includes.
+    /// Rows are prefiltered by the database, if possible, and read one by one
+    pub(crate) fn search_rules(
+        &self,
+        conn: &Connection,
+        configuration: &Configuration,
+        query: String,
+        filter_ids: Vec<FilterId>,
+        options: RuleSearchOptions,
+    ) -> FLMResult<Vec<RuleSearchMatch>> {
+        if query.is_empty() {
+            return Err(FLMError::FieldIsEmpty("query"));
+        }
+
+        let mut collector = MatchesCollector {
+            matcher: RuleMatcher::new(&query, &options)?,
+            include_disabled_rules: options.include_disabled_rules,
+            limit: options
+                .limit
+                .map(|limit| limit as usize)
+                .unwrap_or(usize::MAX),
+            matches: vec![],
+        };
+
+        if collector.is_full() {
+            return Ok(collector.matches);
+        }
+
+        let derived_key = integrity::derive_key_if_needed(configuration);
+        let scope: Option<Vec<Value>> =
+            (!filter_ids.is_empty()).then(|| filter_ids.into_iter().map(Into::into).collect());
+
+        RulesListRepository::new().for_each(
+            conn,
+            Self::make_where_clause("rules_text", &scope, &query, &options),
+            |entity: RulesListEntity| -> FLMResult<bool> {
+                if let Some(ref key) = derived_key {
+                    integrity::verify_rules_list_entity(key, &entity)?;
+                }
+
+                Ok(collector.collect(entity.filter_id, None, &entity.text, &entity.disabled_text))
+            },
+        )?;
+
+        if collector.is_full() {
+            return Ok(collector.matches);
+        }
+
+        // Disabled rules of the filter are applied to its includes too
+        let rules_list_repository = RulesListRepository::new();
+        let mut disabled_rules_by_filter: HashMap<FilterId, String> = HashMap::new();
+
+        FilterIncludesRepository::new().for_each(
+            conn,
+            Self::make_where_clause("body", &scope, &query, &options),
+            |entity: FilterIncludeEntity| -> FLMResult<bool> {
+                if let Some(ref key) = derived_key {
+                    integrity::verify_filter_include_entity(key, &entity)?;
+                }
+
+                let disabled_text = match disabled_rules_by_filter.entry(entity.filter_id) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(
+                        rules_list_repository
+                            .get_disabled_rules_by_ids(conn, &[entity.filter_id])?
+                            .pop()
+                            .map(|disabled_rules| disabled_rules.disabled_text)
+                            .unwrap_or_default(),
+                    ),
+                };
+
+                Ok(collector.collect(
+                    entity.filter_id,
+                    Some(&entity.absolute_url),
+                    &entity.body,
+                    disabled_text,
+                ))
+            },
+        )?;
+
+        Ok(collector.matches)
+    }
+
+    /// Finds all locations, which produce `rule` in the built filters.
+    /// Conditional directives are evaluated, so lines from inactive `!#if` branches are omitted
+    pub(crate) fn get_rule_provenance(
+        &self,
+        conn: &Connection,
+        configuration: &Configuration,
+        rule: String,
+    ) -> FLMResult<Vec<RuleProvenance>> {
+        let rule = rule.trim();
+        if rule.is_empty() {
+            return Err(FLMError::FieldIsEmpty("rule"));
+        }
+
+        let derived_key = integrity::derive_key_if_needed(configuration);
+
+        // Filters, which includes may contain the rule
+        let mut filter_ids_by_includes: HashSet<FilterId> = HashSet::new();
+        FilterIncludesRepository::new().for_each(
+            conn,
+            Some(Self::pass_compressed_rows(SQLOperator::FieldContains(
+                "body",
+                rule.to_string().into(),
+            ))),
+            |entity: FilterIncludeEntity| -> FLMResult<bool> {
+                filter_ids_by_includes.insert(entity.filter_id);
+
+                Ok(true)
+            },
+        )?;
+
+        let body_contains_rule = Self::pass_compressed_rows(SQLOperator::FieldContains(
+            "rules_text",
+            rule.to_string().into(),
+        ));
+        let where_clause = if filter_ids_by_includes.is_empty() {
+            body_contains_rule
+        } else {
+            SQLOperator::Or(
+                heap(body_contains_rule),
+                heap(SQLOperator::FieldIn(
+                    "filter_id",
+                    filter_ids_by_includes
+                        .iter()
+                        .map(|id| (*id).into())
+                        .collect(),
+                )),
+            )
+        };
+
+        let filter_repository = FilterRepository::new();
+        let includes_repository = FilterIncludesRepository::new();
+        let collector = DefaultFilterCollector::new(configuration);
+        let mut locations = vec![];
+
+        RulesListRepository::new().for_each(
+            conn,
+            Some(where_clause),
+            |entity: RulesListEntity| -> FLMResult<bool> {
+                if let Some(ref key) = derived_key {
+                    integrity::verify_rules_list_entity(key, &entity)?;
+                }
+
+                // Includes and root url are needed only to resolve include directives
+                let (includes, download_url) = if entity.has_directives() {
+                    let includes = includes_repository
+                        .select_mapped(
+                            conn,
+                            Some(SQLOperator::FieldEqualValue(
+                                "filter_id",
+                                entity.filter_id.into(),
+                            )),
+                        )?
+                        .remove(&entity.filter_id);
+
+                    if let (Some(ref key), Some(ref includes)) = (&derived_key, &includes) {
+                        integrity::verify_filter_include_entities(key, includes)?;
+                    }
+
+                    let download_url = filter_repository
+                        .select_download_urls(conn, [entity.filter_id].iter(), 1)?
+                        .remove(&entity.filter_id)
+                        .unwrap_or_default();
+
+                    (includes, download_url)
+                } else {
+                    (None, String::new())
+                };
+
+                let is_disabled = entity.disabled_text.lines().any(|line| line == rule);
+
+                collector
+                    .for_each_collected_line(
+                        &entity,
+                        &download_url,
+                        includes,
+                        |include, index, line| {
+                            if line.trim() == rule {
+                                locations.push(RuleProvenance {
+                                    filter_id: entity.filter_id,
+                                    include_url: include
+                                        .map(|include| include.absolute_url.clone()),
+                                    line_number: index as u32 + 1,
+                                    is_disabled,
+                                });
+                            }
+                        },
+                    )
+                    .map_err(FLMError::from_parser_error)?;
+
+                Ok(true)
+            },
+        )?;
+
+        Ok(locations)
+    }
+
+    /// Builds condition for filters scope and text prefilter of `field`
+    fn make_where_clause<'a>(
+        field: &'a str,
+        scope: &Option<Vec<Value>>,
+        query: &str,
+        options: &RuleSearchOptions,
+    ) -> Option<SQLOperator<'a>> {
+        let scope = scope
+            .as_ref()
+            .map(|ids| SQLOperator::FieldIn("filter_id", ids.clone()));
+
+        let prefilter =
+            Self::make_text_prefilter(field, query, options).map(Self::pass_compressed_rows);
+
+        match (scope, prefilter) {
+            (Some(scope), Some(prefilter)) => Some(SQLOperator::And(heap(scope), heap(prefilter))),
+            (scope, prefilter) => scope.or(prefilter),
+        }
+    }
+
+    /// Text of compressed rows can't be prefiltered by the database,
+    /// so they always pass `prefilter` and are matched after decompression
+    fn pass_compressed_rows(prefilter: SQLOperator) -> SQLOperator {
+        SQLOperator::Or(
+            heap(SQLOperator::Not(heap(SQLOperator::FieldEqualValue(
+                COMPRESSION_FIELD,
+                0.into(),
+            )))),
+            heap(prefilter),
+        )
+    }
+
+    /// Database-side prefilter for substring queries.
+    /// `LIKE` folds only ASCII letters, so it can't be used for other case-insensitive queries
+    fn make_text_prefilter<'a>(
+        field: &'a str,
+        query: &str,
+        options: &RuleSearchOptions,
+    ) -> Option<SQLOperator<'a>> {
+        if options.is_regex {
+            return None;
+        }
+
+        if options.is_case_sensitive {
+            return Some(SQLOperator::FieldContains(field, query.to_string().into()));
+        }
+
+        if !query.is_ascii() {
+            return None;
+        }
+
+        let mut pattern = String::with_capacity(query.len() + 2);
+        pattern.push('%');
+        for char in query.chars() {
+            if matches!(char, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(char);
+        }
+        pattern.push('%');
+
+        Some(SQLOperator::FieldLike(field, pattern.into()))
+    }
+}
+
+/// Matches single line against the search query
+enum RuleMatcher {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl RuleMatcher {
+    fn new(query: &str, options: &RuleSearchOptions) -> FLMResult<Self> {
+        if !options.is_regex && options.is_case_sensitive {
+            return Ok(Self::Substring(query.to_string()));
+        }
+
+        let pattern = if options.is_regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!options.is_case_sensitive)
+            .build()
+            .map(Self::Regex)
+            .map_err(FLMError::from_display)
+    }
+
+    #[inline]
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            Self::Substring(needle) => line.contains(needle.as_str()),
+            Self::Regex(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// Collects matches until the limit is reached
+struct MatchesCollector {
+    matcher: RuleMatcher,
+    include_disabled_rules: bool,
+    limit: usize,
+    matches: Vec<RuleSearchMatch>,
+}
+
+impl MatchesCollector {
+    #[inline]
+    fn is_full(&self) -> bool {
+        self.matches.len() >= self.limit
+    }
+
+    /// Collects matched rules from `text`.
+    /// Returns `false` if the limit has been reached
+    fn collect(
+        &mut self,
+        filter_id: FilterId,
+        include_url: Option<&str>,
+        text: &str,
+        disabled_text: &str,
+    ) -> bool {
+        let disabled_lines: HashSet<&str> = disabled_text.lines().collect();
+
+        for (index, line) in text.lines().enumerate() {
+            if self.is_full() {
+                return false;
+            }
+
+            if !is_line_is_rule(line) || !self.matcher.is_match(line) {
+                continue;
+            }
+
+            let is_disabled = disabled_lines.contains(line);
+            if is_disabled && !self.include_disabled_rules {
+                continue;
+            }
+
+            self.matches.push(RuleSearchMatch {
+                filter_id,
+                include_url: include_url.map(ToString::to_string),
+                line_number: index as u32 + 1,
+                rule: line.to_string(),
+                is_disabled,
+            });
+        }
+
+        !self.is_full()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manager::models::rule_search::RuleSearchOptions;
+    use crate::test_utils::tests_path;
+    use crate::{
+        Configuration, FLMError, FilterId, FilterListManager, FilterListManagerImpl,
+        RulesStorageCompression,
+    };
+
+    fn spawn_filters() -> (Box<FilterListManagerImpl>, FilterId, FilterId) {
+        spawn_filters_with_compression(RulesStorageCompression::None)
+    }
+
+    fn spawn_filters_with_compression(
+        compression: RulesStorageCompression,
+    ) -> (Box<FilterListManagerImpl>, FilterId, FilterId) {
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.rules_storage_compression = compression;
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+
+        let includes_filter_path = tests_path("fixtures/includes/main.txt");
+        let includes_filter_id = flm
+            .install_custom_filter_from_string(
+                "file://".to_string() + includes_filter_path.to_str().unwrap(),
+                1234567890,
+                true,
+                true,
+                std::fs::read_to_string(&includes_filter_path).unwrap(),
+                None,
+                None,
+            )
+            .unwrap()
+            .id;
+
+        let plain_filter_id = flm
+            .install_custom_filter_from_string(
+                String::new(),
+                1234567890,
+                true,
+                true,
+                String::from("! Title: Plain\n||example.com^\n||other.org^"),
+                None,
+                None,
+            )
+            .unwrap()
+            .id;
+
+        flm.save_disabled_rules(plain_filter_id, vec![String::from("||example.com^")])
+            .unwrap();
+
+        (flm, includes_filter_id, plain_filter_id)
+    }
+
+    #[test]
+    fn test_search_rules() {
+        let (flm, includes_filter_id, plain_filter_id) = spawn_filters();
+
+        let options = RuleSearchOptions {
+            is_case_sensitive: true,
+            ..RuleSearchOptions::default()
+        };
+        let matches = flm
+            .search_rules(String::from("||example.com^"), vec![], options.clone())
+            .unwrap();
+
+        let main_match = matches
+            .iter()
+            .find(|found| found.include_url.is_none())
+            .unwrap();
+        assert_eq!(main_match.filter_id, includes_filter_id);
+        assert_eq!(main_match.line_number, 9);
+        assert_eq!(main_match.rule, "||example.com^$third-party");
+
+        let include_match = matches
+            .iter()
+            .find(|found| found.include_url.is_some())
+            .unwrap();
+        assert_eq!(include_match.filter_id, includes_filter_id);
+        assert_eq!(include_match.rule, "||example.com^$cookie=NAME");
+
+        // Disabled rule is skipped by default
+        assert!(matches
+            .iter()
+            .all(|found| found.filter_id != plain_filter_id));
+
+        let matches = flm
+            .search_rules(
+                String::from("||example.com^"),
+                vec![plain_filter_id],
+                RuleSearchOptions {
+                    include_disabled_rules: true,
+                    ..options
+                },
+            )
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_number, 2);
+        assert!(matches[0].is_disabled);
+    }
+
+    #[test]
+    fn test_search_rules_with_options() {
+        let (flm, includes_filter_id, _) = spawn_filters();
+
+        let matches = flm
+            .search_rules(
+                String::from("EXAMPLE.NET"),
+                vec![],
+                RuleSearchOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].rule, "||example.net^$script");
+
+        let matches = flm
+            .search_rules(
+                String::from(r"^\|\|[a-z]+\.example\.(com|org)\^"),
+                vec![includes_filter_id],
+                RuleSearchOptions {
+                    is_regex: true,
+                    ..RuleSearchOptions::default()
+                },
+            )
+            .unwrap();
+        let mut rules: Vec<String> = matches.into_iter().map(|found| found.rule).collect();
+        rules.sort();
+        assert_eq!(
+            rules,
+            vec![
+                "||analytics.example.org^",
+                "||tracking.example.com^$important"
+            ]
+        );
+
+        let matches = flm
+            .search_rules(
+                String::from("example"),
+                vec![],
+                RuleSearchOptions {
+                    limit: Some(2),
+                    ..RuleSearchOptions::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(matches.len(), 2);
+
+        assert!(matches!(
+            flm.search_rules(String::new(), vec![], RuleSearchOptions::default()),
+            Err(FLMError::FieldIsEmpty(_))
+        ));
+        assert!(flm
+            .search_rules(
+                String::from("(unclosed"),
+                vec![],
+                RuleSearchOptions {
+                    is_regex: true,
+                    ..RuleSearchOptions::default()
+                },
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_get_rule_provenance() {
+        let (flm, includes_filter_id, plain_filter_id) = spawn_filters();
+
+        let locations = flm
+            .get_rule_provenance(String::from("||example.net^$script"))
+            .unwrap();
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].filter_id, includes_filter_id);
+        assert_eq!(locations[0].include_url, None);
+        assert_eq!(locations[0].line_number, 29);
+
+        // Nested include is inlined into its first-level include, line is counted in the inlined body
+        let locations = flm
+            .get_rule_provenance(String::from("||example.com^$cookie=NAME"))
+            .unwrap();
+        assert_eq!(locations.len(), 1);
+        assert!(locations[0]
+            .include_url
+            .as_ref()
+            .unwrap()
+            .ends_with("included_part2.txt"));
+        assert_eq!(locations[0].line_number, 10);
+
+        // Line from the inactive conditional branch
+        assert!(flm
+            .get_rule_provenance(String::from("Should not be included"))
+            .unwrap()
+            .is_empty());
+
+        let locations = flm
+            .get_rule_provenance(String::from(" ||example.com^ "))
+            .unwrap();
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].filter_id, plain_filter_id);
+        assert_eq!(locations[0].line_number, 2);
+        assert!(locations[0].is_disabled);
+
+        assert!(matches!(
+            flm.get_rule_provenance(String::new()),
+            Err(FLMError::FieldIsEmpty(_))
+        ));
+    }
+
+    #[test]
+    fn test_search_compressed_rules() {
+        let (flm, includes_filter_id, plain_filter_id) =
+            spawn_filters_with_compression(RulesStorageCompression::Zstd);
+
+        let matches = flm
+            .search_rules(
+                String::from("EXAMPLE.NET"),
+                vec![],
+                RuleSearchOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].filter_id, includes_filter_id);
+        assert_eq!(matches[0].rule, "||example.net^$script");
+
+        let matches = flm
+            .search_rules(
+                String::from("||example.com^$cookie"),
+                vec![],
+                RuleSearchOptions {
+                    is_case_sensitive: true,
+                    ..RuleSearchOptions::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].include_url.is_some());
+
+        let locations = flm
+            .get_rule_provenance(String::from("||other.org^"))
+            .unwrap();
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].filter_id, plain_filter_id);
+        assert_eq!(locations[0].line_number, 3);
+    }
+}
@@ -1,16 +1,23 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use rusqlite::Connection;
 use rusqlite::Error;
 
+use crate::filters::disabled_rules_matcher::DisabledRulesMatcher;
 use crate::filters::parser::collectors::streaming_filter_collector::StreamingFilterCollector;
+use crate::filters::parser::is_rule_detector::is_line_is_rule;
+use crate::filters::rules_deduplicator::RulesDeduplicator;
+use crate::manager::models::active_rules_export::{
+    ActiveRulesExportManifest, ActiveRulesExportOptions, ExportedFilterInfo,
+};
 use crate::manager::models::configuration::Configuration;
 use crate::storage::blob::filter_stream::FilterStream;
 use crate::storage::blob::{write_to_stream, BLOB_CHUNK_SIZE};
+use crate::storage::repositories::disabled_rule_patterns_repository::DisabledRulePatternsRepository;
 use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
 use crate::storage::repositories::filter_repository::FilterRepository;
 use crate::storage::repositories::rules_list_repository::RulesListRepository;
@@ -53,113 +60,420 @@ impl StreamingRulesManager {
 
         connection_manager
             .execute_db(|conn: Connection| {
-                let rules_repository = RulesListRepository::new();
-                let includes_repository = FilterIncludesRepository::new();
-                let filter_repository = FilterRepository::new();
-
-                // 1. Verify filter metadata integrity before reading any rules
-                if let Some(ref dk) = derived_key {
-                    let filters = filter_repository
-                        .select(
-                            &conn,
-                            Some(SQLOperator::FieldEqualValue("filter_id", filter_id.into())),
-                        )
-                        .map_err(FLMError::from_database)?
-                        .unwrap_or_default();
-
-                    integrity::verify_filter_entities(dk, &filters)?;
+                self.write_filter_rules(
+                    &conn,
+                    configuration,
+                    derived_key.as_ref(),
+                    filter_id,
+                    &mut handler,
+                )
+            })
+            .and_then(|_| handler.flush().map_err(FLMError::from_io))
+            .inspect_err(|_| {
+                drop(handler);
+                if !file_already_exists {
+                    fs::remove_file(&file_path).unwrap_or(());
                 }
+            })
+    }
 
-                // 2. Get lightweight metadata without loading rules_text/disabled_rules_text
-                let metadata = rules_repository
-                    .get_metadata(&conn, filter_id)
-                    .map_err(|why| match why {
-                        Error::QueryReturnedNoRows => FLMError::EntityNotFound(filter_id as i64),
-                        err => FLMError::from_database(err),
-                    })?;
-
-                // 3. Streaming integrity verification of rules_text via blob
-                if let Some(ref dk) = derived_key {
-                    if !rules_repository
-                        .verify_blob_integrity_streaming(&conn, dk, &metadata)
-                        .map_err(FLMError::from_database)?
-                    {
-                        return Err(FLMError::FilterIntegrityCheckFailed(filter_id));
-                    }
+    /// Saves rules of all enabled filters into a single file.
+    /// Untrusted filters are written into the separate file, if it's set in `options`
+    pub(crate) fn save_active_rules_to_file<P: AsRef<Path>>(
+        &self,
+        connection_manager: &DbConnectionManager,
+        configuration: &Configuration,
+        file_path: P,
+        options: ActiveRulesExportOptions,
+    ) -> FLMResult<ActiveRulesExportManifest> {
+        let mut trusted_output = ExportOutput::create(file_path.as_ref())?;
+        let mut untrusted_output = match options.untrusted_file_path {
+            Some(ref path) => match ExportOutput::create(path) {
+                Ok(output) => Some(output),
+                Err(why) => {
+                    trusted_output.discard();
+                    return Err(why);
                 }
+            },
+            None => None,
+        };
 
-                // 4. Get blob handle of rules and disabled rules
-                let (disabled_rules, blob) = rules_repository
-                    .get_blob_handle_and_disabled_rules(&conn, filter_id)
-                    .map_err(|why| match why {
-                        Error::QueryReturnedNoRows => FLMError::EntityNotFound(filter_id as i64),
-                        err => FLMError::from_database(err),
-                    })?;
-
-                let disabled_rules_set = disabled_rules
-                    .split(|i| i == &LF_BYTES_SLICE)
-                    .map(|value| value.to_vec())
-                    .collect::<HashSet<Vec<u8>>>();
-
-                if metadata.has_directives {
-                    // 5a. Path WITH directives: load include metadata, verify, stream with directives
-
-                    // Load include metadata (without body)
-                    let include_metas = includes_repository
-                        .get_include_metadata_for_filter(&conn, filter_id)
-                        .map_err(FLMError::from_database)?;
-
-                    // Verify integrity of each include via blob streaming
-                    if let Some(ref dk) = derived_key {
-                        for inc_meta in &include_metas {
-                            if !includes_repository
-                                .verify_include_blob_integrity_streaming(&conn, dk, inc_meta)
-                                .map_err(FLMError::from_database)?
-                            {
-                                return Err(FLMError::FilterIntegrityCheckFailed(
-                                    inc_meta.filter_id,
-                                ));
-                            }
-                        }
-                    }
-
-                    // Build url -> row_id map
-                    let includes_url_to_row_id: HashMap<String, i64> = include_metas
-                        .into_iter()
-                        .map(|m| (m.absolute_url, m.row_id))
-                        .collect();
+        let derived_key = integrity::derive_key_if_needed(configuration);
 
-                    // Get download_url for resolving relative include paths
-                    let download_url = FilterRepository::new()
-                        .select_download_urls(&conn, [filter_id].iter(), 1)
-                        .map_err(FLMError::from_database)?
-                        .remove(&filter_id)
-                        .unwrap_or_default();
-
-                    let mut filter_stream = FilterStream::new(
-                        blob,
-                        &mut handler,
-                        &disabled_rules_set,
-                        &includes_url_to_row_id,
+        let result = connection_manager
+            .execute_db(|conn: Connection| {
+                let mut filters = FilterRepository::new()
+                    .select(
                         &conn,
-                    );
+                        Some(SQLOperator::FieldEqualValue("is_enabled", true.into())),
+                    )
+                    .map_err(FLMError::from_database)?
+                    .unwrap_or_default();
+
+                let mut deduplicator = if options.deduplicate_rules {
+                    // Trusted filters are written first, so they own the duplicated rules
+                    filters.sort_by_key(|entity| !entity.is_trusted);
 
-                    StreamingFilterCollector::new(configuration)
-                        .collect(&mut filter_stream, &download_url)?;
+                    Some(RulesDeduplicator::default())
                 } else {
-                    // 5b. Path WITHOUT directives: simple blob streaming
-                    write_to_stream(&mut handler, blob, &disabled_rules_set)?;
+                    None
+                };
+
+                let ids = filters
+                    .iter()
+                    .filter_map(|entity| entity.filter_id)
+                    .collect::<Vec<FilterId>>();
+
+                // Filters without rules lists are skipped, like in active rules
+                let ids_with_rules = RulesListRepository::new()
+                    .get_rules_count(&conn, &ids)
+                    .map_err(FLMError::from_database)?
+                    .into_iter()
+                    .map(|entity| entity.filter_id)
+                    .collect::<HashSet<FilterId>>();
+
+                let mut manifest = ActiveRulesExportManifest::default();
+                for filter in filters {
+                    let Some(filter_id) = filter.filter_id else {
+                        continue;
+                    };
+
+                    if !ids_with_rules.contains(&filter_id) {
+                        continue;
+                    }
+
+                    let writer = match untrusted_output {
+                        Some(ref mut output) if !filter.is_trusted => &mut output.writer,
+                        _ => &mut trusted_output.writer,
+                    };
+
+                    writer.start_section()?;
+                    let byte_offset = writer.position;
+
+                    let mut section = SectionWriter::new(&mut *writer, deduplicator.as_mut());
+
+                    if options.write_filter_headers {
+                        writeln!(section, "! Filter: {} (id: {})", filter.title, filter_id)
+                            .map_err(FLMError::from_io)?;
+                    }
+
+                    self.write_filter_rules(
+                        &conn,
+                        configuration,
+                        derived_key.as_ref(),
+                        filter_id,
+                        &mut section,
+                    )?;
+
+                    let (rules_count, duplicates_count) = section.finish()?;
+
+                    manifest.filters.push(ExportedFilterInfo {
+                        filter_id,
+                        is_trusted: filter.is_trusted,
+                        byte_offset,
+                        byte_length: writer.position - byte_offset,
+                        rules_count,
+                        duplicates_count,
+                    });
                 }
 
-                Ok(())
+                Ok(manifest)
             })
-            .and_then(|_| handler.flush().map_err(FLMError::from_io))
-            .inspect_err(|_| {
-                drop(handler);
-                if !file_already_exists {
-                    fs::remove_file(&file_path).unwrap_or(());
+            .and_then(|manifest| {
+                trusted_output.flush()?;
+                if let Some(ref mut output) = untrusted_output {
+                    output.flush()?;
                 }
-            })
+
+                Ok(manifest)
+            });
+
+        if result.is_err() {
+            trusted_output.discard();
+            if let Some(output) = untrusted_output {
+                output.discard();
+            }
+        }
+
+        result
+    }
+
+    /// Writes rules of the single filter into `handler`,
+    /// resolving includes and skipping disabled rules
+    fn write_filter_rules<W: Write>(
+        &self,
+        conn: &Connection,
+        configuration: &Configuration,
+        derived_key: Option<&[u8; 32]>,
+        filter_id: FilterId,
+        handler: &mut W,
+    ) -> FLMResult<()> {
+        let rules_repository = RulesListRepository::new();
+        let includes_repository = FilterIncludesRepository::new();
+        let filter_repository = FilterRepository::new();
+
+        // 1. Verify filter metadata integrity before reading any rules
+        if let Some(dk) = derived_key {
+            let filters = filter_repository
+                .select(
+                    conn,
+                    Some(SQLOperator::FieldEqualValue("filter_id", filter_id.into())),
+                )
+                .map_err(FLMError::from_database)?
+                .unwrap_or_default();
+
+            integrity::verify_filter_entities(dk, &filters)?;
+        }
+
+        // 2. Get lightweight metadata without loading rules_text/disabled_rules_text
+        let metadata = rules_repository
+            .get_metadata(conn, filter_id)
+            .map_err(|why| match why {
+                Error::QueryReturnedNoRows => FLMError::EntityNotFound(filter_id as i64),
+                err => FLMError::from_database(err),
+            })?;
+
+        // 3. Streaming integrity verification of rules_text via blob
+        if let Some(dk) = derived_key {
+            if !rules_repository
+                .verify_blob_integrity_streaming(conn, dk, &metadata)
+                .map_err(FLMError::from_database)?
+            {
+                return Err(FLMError::FilterIntegrityCheckFailed(filter_id));
+            }
+        }
+
+        // 4. Get blob handle of rules and disabled rules
+        let (disabled_rules, blob) = rules_repository
+            .get_blob_handle_and_disabled_rules(conn, filter_id)
+            .map_err(|why| match why {
+                Error::QueryReturnedNoRows => FLMError::EntityNotFound(filter_id as i64),
+                err => FLMError::from_database(err),
+            })?;
+
+        let disabled_rule_patterns = DisabledRulePatternsRepository::new()
+            .select_for_filter(conn, filter_id)
+            .map_err(FLMError::from_database)?;
+
+        let disabled_rules_matcher = DisabledRulesMatcher::new(
+            disabled_rules.split(|i| i == &LF_BYTES_SLICE),
+            &disabled_rule_patterns,
+        )?;
+
+        if metadata.has_directives {
+            // 5a. Path WITH directives: load include metadata, verify, stream with directives
+
+            // Load include metadata (without body)
+            let include_metas = includes_repository
+                .get_include_metadata_for_filter(conn, filter_id)
+                .map_err(FLMError::from_database)?;
+
+            // Verify integrity of each include via blob streaming
+            if let Some(dk) = derived_key {
+                for inc_meta in &include_metas {
+                    if !includes_repository
+                        .verify_include_blob_integrity_streaming(conn, dk, inc_meta)
+                        .map_err(FLMError::from_database)?
+                    {
+                        return Err(FLMError::FilterIntegrityCheckFailed(inc_meta.filter_id));
+                    }
+                }
+            }
+
+            // Build url -> row_id map
+            let includes_url_to_row_id: HashMap<String, i64> = include_metas
+                .into_iter()
+                .map(|m| (m.absolute_url, m.row_id))
+                .collect();
+
+            // Get download_url for resolving relative include paths
+            let download_url = FilterRepository::new()
+                .select_download_urls(conn, [filter_id].iter(), 1)
+                .map_err(FLMError::from_database)?
+                .remove(&filter_id)
+                .unwrap_or_default();
+
+            let mut filter_stream = FilterStream::new(
+                blob,
+                handler,
+                &disabled_rules_matcher,
+                &includes_url_to_row_id,
+                conn,
+            )?;
+
+            StreamingFilterCollector::new(configuration)
+                .collect(&mut filter_stream, &download_url)?;
+        } else {
+            // 5b. Path WITHOUT directives: simple blob streaming
+            write_to_stream(handler, blob, &disabled_rules_matcher)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Output file of the active rules export
+struct ExportOutput {
+    writer: OutputWriter<BufWriter<File>>,
+    path: PathBuf,
+    already_exists: bool,
+}
+
+impl ExportOutput {
+    fn create(path: &Path) -> FLMResult<Self> {
+        let already_exists = fs::metadata(path).is_ok();
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .map_err(FLMError::from_io)?;
+
+        Ok(Self {
+            writer: OutputWriter::new(BufWriter::with_capacity(BLOB_CHUNK_SIZE, file)),
+            path: path.to_path_buf(),
+            already_exists,
+        })
+    }
+
+    fn flush(&mut self) -> FLMResult<()> {
+        self.writer.flush().map_err(FLMError::from_io)
+    }
+
+    /// Removes the file, if it has been created by the export
+    fn discard(self) {
+        drop(self.writer);
+        if !self.already_exists {
+            fs::remove_file(&self.path).unwrap_or(());
+        }
+    }
+}
+
+/// [`Write`] adapter, which tracks the position in the output
+struct OutputWriter<W: Write> {
+    inner: W,
+    /// Count of bytes written so far
+    position: u64,
+    /// Last written byte
+    last_byte: Option<u8>,
+}
+
+impl<W: Write> OutputWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            position: 0,
+            last_byte: None,
+        }
+    }
+
+    /// Terminates the previous section, if it doesn't end with `\n`
+    fn start_section(&mut self) -> FLMResult<()> {
+        if self.last_byte.is_some_and(|byte| byte != b'\n') {
+            self.write_all(b"\n").map_err(FLMError::from_io)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for OutputWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+
+        if let Some(byte) = buf[..written].last() {
+            self.last_byte = Some(*byte);
+        }
+        self.position += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// [`Write`] adapter for the single filter section of the export.
+/// Passes written data line by line, counts rules and skips duplicates,
+/// if `deduplicator` is set
+struct SectionWriter<'a, W: Write> {
+    output: &'a mut OutputWriter<W>,
+    deduplicator: Option<&'a mut RulesDeduplicator>,
+    /// Current unterminated line
+    line: Vec<u8>,
+    rules_count: i32,
+    duplicates_count: i32,
+}
+
+impl<'a, W: Write> SectionWriter<'a, W> {
+    fn new(
+        output: &'a mut OutputWriter<W>,
+        deduplicator: Option<&'a mut RulesDeduplicator>,
+    ) -> Self {
+        Self {
+            output,
+            deduplicator,
+            line: Vec::new(),
+            rules_count: 0,
+            duplicates_count: 0,
+        }
+    }
+
+    /// Writes the last unterminated line.
+    /// Returns count of written rules and count of skipped duplicates
+    fn finish(mut self) -> FLMResult<(i32, i32)> {
+        if !self.line.is_empty() {
+            self.write_line(false).map_err(FLMError::from_io)?;
+        }
+
+        Ok((self.rules_count, self.duplicates_count))
+    }
+
+    fn write_line(&mut self, has_newline: bool) -> std::io::Result<()> {
+        let line = String::from_utf8_lossy(&self.line);
+
+        let is_duplicate = self
+            .deduplicator
+            .as_deref_mut()
+            .is_some_and(|deduplicator| deduplicator.is_duplicate(&line));
+
+        if is_duplicate {
+            self.duplicates_count += 1;
+        } else {
+            if is_line_is_rule(&line) {
+                self.rules_count += 1;
+            }
+
+            self.output.write_all(&self.line)?;
+            if has_newline {
+                self.output.write_all(b"\n")?;
+            }
+        }
+
+        self.line.clear();
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for SectionWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut parts = buf.split(|byte| *byte == b'\n');
+        if let Some(first) = parts.next() {
+            self.line.extend_from_slice(first);
+        }
+        for part in parts {
+            self.write_line(true)?;
+            self.line.extend_from_slice(part);
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
     }
 }
 
@@ -170,15 +484,19 @@ mod tests {
     use crate::manager::FilterListManager;
     use crate::storage::entities::filter::filter_entity::FilterEntity;
     use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
+    use crate::storage::entities::rules_list::disabled_rule_pattern_entity::DisabledRulePatternEntity;
     use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
+    use crate::storage::repositories::disabled_rule_patterns_repository::DisabledRulePatternsRepository;
     use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
     use crate::storage::repositories::filter_repository::FilterRepository;
     use crate::storage::repositories::rules_list_repository::RulesListRepository;
     use crate::storage::repositories::Repository;
     use crate::storage::with_transaction;
     use crate::test_utils::tests_fixtures::get_tests_fixtures_path;
+    use crate::utils::integrity;
     use crate::{
-        Configuration, FilterId, FilterListManagerImpl, FilterListRules, USER_RULES_FILTER_LIST_ID,
+        ActiveRulesExportOptions, Configuration, DisabledRulePatternKind, FilterId,
+        FilterListManagerImpl, FilterListRules, RulesStorageCompression, USER_RULES_FILTER_LIST_ID,
     };
     use chrono::Utc;
     use rusqlite::Connection;
@@ -313,6 +631,99 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_save_compressed_rules_to_file_blob() {
+        let mut path = get_tests_fixtures_path();
+        path.push(format!(
+            "test_filter_rules_compressed_{}.txt",
+            Utc::now().timestamp_micros()
+        ));
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.integrity_key = Some("compressed-rules-test-key".to_string());
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+
+        let custom_filter_id: FilterId = -10002;
+        let download_url = "https://example.com/filters/main2.txt";
+        let include_url = "https://example.com/filters/included2.txt";
+
+        let include_body = "included_rule_1\nincluded_rule_2";
+        let rules_text = format!(
+            "rule_before\n!#include {}\nrule_after\nдлинное_правило_после\ndisabled_rule",
+            "included2.txt"
+        );
+
+        flm.connection_manager
+            .execute_db(|mut conn: Connection| {
+                let mut filter = FilterEntity::default();
+                filter.filter_id = Some(custom_filter_id);
+                filter.download_url = download_url.to_string();
+                filter.is_enabled = true;
+                filter.is_installed = true;
+                integrity::sign_filter_entity_if_needed(flm.get_configuration(), &mut filter);
+
+                // Compressed filter with plain include
+                let mut rules_entity = RulesListEntity::with_disabled_text(
+                    custom_filter_id,
+                    rules_text.clone(),
+                    "disabled_rule".to_string(),
+                    5,
+                );
+                rules_entity.set_has_directives(true);
+                rules_entity.compression = RulesStorageCompression::Zstd;
+
+                let mut include_entities = [FilterIncludeEntity::make(
+                    custom_filter_id,
+                    include_url.to_string(),
+                    2,
+                    include_body.to_string(),
+                )];
+
+                integrity::sign_entities_if_needed(
+                    flm.get_configuration(),
+                    &mut rules_entity,
+                    &mut include_entities,
+                );
+
+                let _ = with_transaction(&mut conn, |tx| {
+                    FilterRepository::new().insert(tx, &[filter])?;
+                    RulesListRepository::new().insert(tx, &[rules_entity])?;
+                    FilterIncludesRepository::new()
+                        .replace_entities_for_filters(tx, &include_entities)
+                });
+
+                let stored_text = RulesListRepository::new()
+                    .select_mapped(&conn, None)
+                    .unwrap()
+                    .remove(&custom_filter_id)
+                    .unwrap()
+                    .text;
+                assert_eq!(stored_text, rules_text);
+
+                Ok(())
+            })
+            .unwrap();
+
+        StreamingRulesManager::new()
+            .save_rules_to_file_blob(
+                &flm.connection_manager,
+                flm.get_configuration(),
+                custom_filter_id,
+                &path,
+            )
+            .unwrap();
+
+        let test_string = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            test_string.as_str(),
+            "rule_before\nincluded_rule_1\nincluded_rule_2\nrule_after\nдлинное_правило_после\n"
+        );
+    }
+
     #[test]
     fn test_save_rules_to_file_blob_with_includes_trailing_newline() {
         let mut path = get_tests_fixtures_path();
@@ -465,4 +876,298 @@ mod tests {
             "inc_rule_1\ninc_rule_3\nrule_b\nrule_c"
         );
     }
+
+    #[test]
+    fn test_save_rules_to_file_blob_with_disabled_rule_patterns() {
+        let mut path = get_tests_fixtures_path();
+        path.push(format!(
+            "test_filter_rules_disabled_patterns_{}.txt",
+            Utc::now().timestamp_micros()
+        ));
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+
+        let custom_filter_id: FilterId = -10003;
+        let download_url = "https://example.com/filters/main3.txt";
+        let include_url = "https://example.com/filters/included3.txt";
+
+        let include_body = "inc_rule_1\ninc_rule_2\ninc_rule_3";
+
+        let rules_text = format!("rule_a\n!#include {}\nrule_b\nrule_c", "included3.txt");
+
+        flm.connection_manager
+            .execute_db(|mut conn: Connection| {
+                let mut filter = FilterEntity::default();
+                filter.filter_id = Some(custom_filter_id);
+                filter.download_url = download_url.to_string();
+                filter.is_enabled = true;
+                filter.is_installed = true;
+
+                let _ = with_transaction(&mut conn, |tx| {
+                    FilterRepository::new().insert(tx, &[filter])?;
+
+                    let mut rules_entity =
+                        RulesListEntity::make(custom_filter_id, rules_text.clone(), 4);
+                    rules_entity.set_has_directives(true);
+                    rules_entity.disabled_text = "rule_a".to_string();
+                    RulesListRepository::new().insert(tx, &[rules_entity])?;
+
+                    DisabledRulePatternsRepository::new().insert(
+                        tx,
+                        &[
+                            DisabledRulePatternEntity {
+                                filter_id: custom_filter_id,
+                                kind: DisabledRulePatternKind::Wildcard,
+                                pattern: "inc_*_2".to_string(),
+                            },
+                            DisabledRulePatternEntity {
+                                filter_id: custom_filter_id,
+                                kind: DisabledRulePatternKind::Regex,
+                                pattern: "^rule_[b]$".to_string(),
+                            },
+                        ],
+                    )?;
+
+                    let include_entity = FilterIncludeEntity::make(
+                        custom_filter_id,
+                        include_url.to_string(),
+                        3,
+                        include_body.to_string(),
+                    );
+                    FilterIncludesRepository::new()
+                        .replace_entities_for_filters(tx, &[include_entity])
+                });
+
+                Ok(())
+            })
+            .unwrap();
+
+        StreamingRulesManager::new()
+            .save_rules_to_file_blob(
+                &flm.connection_manager,
+                flm.get_configuration(),
+                custom_filter_id,
+                &path,
+            )
+            .unwrap();
+
+        let test_string = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(test_string.as_str(), "inc_rule_1\ninc_rule_3\nrule_c");
+    }
+
+    #[test]
+    fn test_save_active_rules_to_file() {
+        let mut path = get_tests_fixtures_path();
+        path.push(format!(
+            "test_active_rules_{}.txt",
+            Utc::now().timestamp_micros()
+        ));
+        let mut untrusted_path = get_tests_fixtures_path();
+        untrusted_path.push(format!(
+            "test_active_rules_untrusted_{}.txt",
+            Utc::now().timestamp_micros()
+        ));
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+
+        flm.save_custom_filter_rules(FilterListRules {
+            filter_id: USER_RULES_FILTER_LIST_ID,
+            rules: vec![
+                String::from("first"),
+                String::from("! comment"),
+                String::from("second"),
+                String::from("third"),
+            ],
+            disabled_rules: vec![String::from("second")],
+            rules_count: 0,
+        })
+        .unwrap();
+
+        flm.connection_manager
+            .execute_db(|mut conn: Connection| {
+                let mut untrusted_filter = FilterEntity::default();
+                untrusted_filter.filter_id = Some(-10004);
+                untrusted_filter.title = "Untrusted".to_string();
+                untrusted_filter.is_enabled = true;
+                untrusted_filter.is_installed = true;
+
+                let mut disabled_filter = untrusted_filter.clone();
+                disabled_filter.filter_id = Some(-10005);
+                disabled_filter.is_enabled = false;
+
+                with_transaction(&mut conn, |tx| {
+                    FilterRepository::new().insert(tx, &[untrusted_filter, disabled_filter])?;
+
+                    RulesListRepository::new().insert(
+                        tx,
+                        &[
+                            RulesListEntity::make(-10004, "u1\nu2\n".to_string(), 2),
+                            RulesListEntity::make(-10005, "d1".to_string(), 1),
+                        ],
+                    )
+                })
+            })
+            .unwrap();
+
+        let manifest = flm
+            .save_active_rules_to_file(
+                &path,
+                ActiveRulesExportOptions {
+                    write_filter_headers: false,
+                    untrusted_file_path: None,
+                    deduplicate_rules: false,
+                },
+            )
+            .unwrap();
+
+        let test_string = fs::read_to_string(&path).unwrap();
+        assert_eq!(test_string.as_str(), "first\n! comment\nthird\nu1\nu2\n");
+
+        let user_rules_info = manifest
+            .filters
+            .iter()
+            .find(|info| info.filter_id == USER_RULES_FILTER_LIST_ID)
+            .unwrap();
+        assert_eq!(user_rules_info.byte_offset, 0);
+        assert_eq!(user_rules_info.byte_length, 21);
+        assert_eq!(user_rules_info.rules_count, 2);
+
+        let untrusted_info = manifest
+            .filters
+            .iter()
+            .find(|info| info.filter_id == -10004)
+            .unwrap();
+        assert!(!untrusted_info.is_trusted);
+        assert_eq!(untrusted_info.byte_offset, 22);
+        assert_eq!(untrusted_info.byte_length, 6);
+        assert_eq!(untrusted_info.rules_count, 2);
+
+        assert!(manifest.filters.iter().all(|info| info.filter_id != -10005));
+
+        // Separated untrusted filters with headers
+        let manifest = flm
+            .save_active_rules_to_file(
+                &path,
+                ActiveRulesExportOptions {
+                    write_filter_headers: true,
+                    untrusted_file_path: Some(untrusted_path.clone()),
+                    deduplicate_rules: false,
+                },
+            )
+            .unwrap();
+
+        let trusted_string = fs::read_to_string(&path).unwrap();
+        let untrusted_string = fs::read_to_string(&untrusted_path).unwrap();
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&untrusted_path).unwrap();
+
+        assert!(trusted_string.ends_with("first\n! comment\nthird"));
+        assert!(!trusted_string.contains("u1"));
+        assert_eq!(
+            untrusted_string.as_str(),
+            "! Filter: Untrusted (id: -10004)\nu1\nu2\n"
+        );
+
+        let untrusted_info = manifest
+            .filters
+            .iter()
+            .find(|info| info.filter_id == -10004)
+            .unwrap();
+        assert_eq!(untrusted_info.byte_offset, 0);
+        assert_eq!(untrusted_info.byte_length, untrusted_string.len() as u64);
+        assert_eq!(untrusted_info.rules_count, 2);
+    }
+
+    #[test]
+    fn test_save_active_rules_to_file_deduplicated() {
+        let mut path = get_tests_fixtures_path();
+        path.push(format!(
+            "test_active_rules_dedup_{}.txt",
+            Utc::now().timestamp_micros()
+        ));
+
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+
+        flm.save_custom_filter_rules(FilterListRules {
+            filter_id: USER_RULES_FILTER_LIST_ID,
+            rules: vec![
+                String::from("shared"),
+                String::from("! comment"),
+                String::from("own"),
+            ],
+            disabled_rules: vec![],
+            rules_count: 0,
+        })
+        .unwrap();
+
+        flm.connection_manager
+            .execute_db(|mut conn: Connection| {
+                let mut untrusted_filter = FilterEntity::default();
+                untrusted_filter.filter_id = Some(-10004);
+                untrusted_filter.title = "Untrusted".to_string();
+                untrusted_filter.is_enabled = true;
+                untrusted_filter.is_installed = true;
+
+                with_transaction(&mut conn, |tx| {
+                    FilterRepository::new().insert(tx, &[untrusted_filter])?;
+
+                    RulesListRepository::new().insert(
+                        tx,
+                        &[RulesListEntity::make(
+                            -10004,
+                            "! comment\nshared\nu1\nu1\n".to_string(),
+                            4,
+                        )],
+                    )
+                })
+            })
+            .unwrap();
+
+        let manifest = flm
+            .save_active_rules_to_file(
+                &path,
+                ActiveRulesExportOptions {
+                    deduplicate_rules: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let test_string = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            test_string.as_str(),
+            "shared\n! comment\nown\n! comment\nu1\n"
+        );
+
+        let user_rules_info = manifest
+            .filters
+            .iter()
+            .find(|info| info.filter_id == USER_RULES_FILTER_LIST_ID)
+            .unwrap();
+        assert_eq!(user_rules_info.rules_count, 2);
+        assert_eq!(user_rules_info.duplicates_count, 0);
+
+        let untrusted_info = manifest
+            .filters
+            .iter()
+            .find(|info| info.filter_id == -10004)
+            .unwrap();
+        assert_eq!(untrusted_info.byte_offset, 21);
+        assert_eq!(untrusted_info.byte_length, 13);
+        assert_eq!(untrusted_info.rules_count, 1);
+        assert_eq!(untrusted_info.duplicates_count, 2);
+    }
 }
@@ -0,0 +1,879 @@
+use crate::filters::disabled_rules_matcher::validate_pattern;
+use crate::filters::parser::is_rule_detector::is_line_is_rule;
+use crate::manager::models::disabled_rule_pattern::DisabledRulePattern;
+use crate::manager::models::import_user_state_result::{
+    ImportUserStateResult, UserStateConflict, UserStateConflictKind,
+};
+use crate::manager::models::MovedFilterInfo;
+use crate::manager::user_state_document::{
+    CustomFilterIncludeState, CustomFilterState, DisabledRulePatternState, IndexFilterState,
+    UserRulesState, UserStateDocument, USER_STATE_DOCUMENT_VERSION,
+};
+use crate::storage::entities::filter::filter_entity::FilterEntity;
+use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
+use crate::storage::entities::rules_list::disabled_rule_pattern_entity::DisabledRulePatternEntity;
+use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
+use crate::storage::repositories::db_metadata_repository::DBMetadataRepository;
+use crate::storage::repositories::disabled_rule_patterns_repository::DisabledRulePatternsRepository;
+use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
+use crate::storage::repositories::filter_repository::FilterRepository;
+use crate::storage::repositories::rules_list_repository::RulesListRepository;
+use crate::storage::repositories::Repository;
+use crate::storage::sql_generators::operator::SQLOperator;
+use crate::storage::with_transaction;
+use crate::utils::integrity;
+use crate::{
+    Configuration, FLMError, FLMResult, FilterId, CUSTOM_FILTERS_GROUP_ID,
+    MAXIMUM_CUSTOM_FILTER_ID, MINIMUM_CUSTOM_FILTER_ID, USER_RULES_FILTER_LIST_ID,
+};
+use chrono::Utc;
+use rusqlite::types::Value;
+use rusqlite::{Connection, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::slice;
+
+/// Manager for export and import of the user state
+pub(crate) struct UserStateManager;
+
+impl UserStateManager {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Collects user state into the JSON document
+    pub(crate) fn export_user_state(
+        &self,
+        conn: &Connection,
+        configuration: &Configuration,
+    ) -> FLMResult<String> {
+        let derived_key = integrity::derive_key_if_needed(configuration);
+
+        let filters = FilterRepository::new()
+            .select(conn, None)
+            .map_err(FLMError::from_database)?
+            .unwrap_or_default();
+
+        if let Some(ref key) = derived_key {
+            integrity::verify_filter_entities(key, &filters)?;
+        }
+
+        let mut user_rules_filter = None;
+        let mut custom_filters = vec![];
+        let mut index_filters = vec![];
+        for filter in filters {
+            match filter.filter_id {
+                Some(USER_RULES_FILTER_LIST_ID) => user_rules_filter = Some(filter),
+                Some(_) if filter.is_custom() => custom_filters.push(filter),
+                Some(_) => index_filters.push(filter),
+                None => {}
+            }
+        }
+
+        let Some(user_rules_filter) = user_rules_filter else {
+            return Err(FLMError::EntityNotFound(USER_RULES_FILTER_LIST_ID as i64));
+        };
+
+        // Full contents are needed only for custom filters and user rules
+        let own_filter_ids: Vec<Value> = custom_filters
+            .iter()
+            .filter_map(|filter| filter.filter_id)
+            .chain([USER_RULES_FILTER_LIST_ID])
+            .map(Into::into)
+            .collect();
+
+        let mut rules_lists = RulesListRepository::new()
+            .select_mapped(
+                conn,
+                Some(SQLOperator::FieldIn("filter_id", own_filter_ids.clone())),
+            )
+            .map_err(FLMError::from_database)?;
+
+        let mut includes = FilterIncludesRepository::new()
+            .select_mapped(
+                conn,
+                Some(SQLOperator::FieldIn("filter_id", own_filter_ids)),
+            )
+            .map_err(FLMError::from_database)?;
+
+        if let Some(ref key) = derived_key {
+            for rules_list in rules_lists.values() {
+                integrity::verify_rules_list_entity(key, rules_list)?;
+            }
+            for filter_includes in includes.values() {
+                integrity::verify_filter_include_entities(key, filter_includes)?;
+            }
+        }
+
+        let index_filter_ids: Vec<FilterId> = index_filters
+            .iter()
+            .filter_map(|filter| filter.filter_id)
+            .collect();
+
+        let all_filter_ids: Vec<FilterId> = index_filter_ids
+            .iter()
+            .copied()
+            .chain(custom_filters.iter().filter_map(|filter| filter.filter_id))
+            .chain([USER_RULES_FILTER_LIST_ID])
+            .collect();
+
+        let mut patterns = DisabledRulePatternsRepository::new()
+            .select_map(conn, &all_filter_ids)
+            .map_err(FLMError::from_database)?;
+        let mut take_patterns = |filter_id: FilterId| -> Vec<DisabledRulePatternState> {
+            patterns
+                .remove(&filter_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entity| DisabledRulePatternState::from(DisabledRulePattern::from(entity)))
+                .collect()
+        };
+
+        let mut index_disabled_rules: HashMap<FilterId, String> = if index_filter_ids.is_empty() {
+            HashMap::new()
+        } else {
+            RulesListRepository::new()
+                .get_disabled_rules_by_ids(conn, &index_filter_ids)
+                .map_err(FLMError::from_database)?
+                .into_iter()
+                .map(|entity| (entity.filter_id, entity.disabled_text))
+                .collect()
+        };
+
+        let index_filters = index_filters
+            .into_iter()
+            .filter_map(|filter| {
+                let filter_id = filter.filter_id?;
+                let disabled_rules = index_disabled_rules.remove(&filter_id).unwrap_or_default();
+                let disabled_rule_patterns = take_patterns(filter_id);
+
+                // Untouched filters are not a part of the user state
+                if !filter.is_enabled
+                    && !filter.is_installed
+                    && disabled_rules.is_empty()
+                    && disabled_rule_patterns.is_empty()
+                {
+                    return None;
+                }
+
+                Some(IndexFilterState {
+                    filter_id,
+                    is_enabled: filter.is_enabled,
+                    is_installed: filter.is_installed,
+                    disabled_rules,
+                    disabled_rule_patterns: Some(disabled_rule_patterns),
+                })
+            })
+            .collect();
+
+        let custom_filters = custom_filters
+            .into_iter()
+            .filter_map(|filter| {
+                let filter_id = filter.filter_id?;
+                let rules_list = rules_lists.remove(&filter_id);
+
+                Some(CustomFilterState {
+                    filter_id,
+                    is_user_title: filter.is_user_title(),
+                    is_user_description: filter.is_user_description(),
+                    title: filter.title,
+                    description: filter.description,
+                    download_url: filter.download_url,
+                    subscription_url: filter.subscription_url,
+                    version: filter.version,
+                    homepage: filter.homepage,
+                    license: filter.license,
+                    checksum: filter.checksum,
+                    expires: filter.expires,
+                    last_update_time: filter.last_update_time,
+                    last_download_time: filter.last_download_time,
+                    is_enabled: filter.is_enabled,
+                    is_installed: filter.is_installed,
+                    is_trusted: filter.is_trusted,
+                    rules_count: rules_list
+                        .as_ref()
+                        .map(|entity| entity.rules_count)
+                        .unwrap_or_default(),
+                    disabled_rules: rules_list
+                        .as_ref()
+                        .map(|entity| entity.disabled_text.clone())
+                        .unwrap_or_default(),
+                    disabled_rule_patterns: Some(take_patterns(filter_id)),
+                    rules: rules_list.map(|entity| entity.text).unwrap_or_default(),
+                    includes: includes
+                        .remove(&filter_id)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|include| CustomFilterIncludeState {
+                            absolute_url: include.absolute_url,
+                            body: include.body,
+                            rules_count: include.rules_count,
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+
+        let user_rules_list = rules_lists.remove(&USER_RULES_FILTER_LIST_ID);
+
+        let document = UserStateDocument {
+            version: USER_STATE_DOCUMENT_VERSION,
+            exported_at: Utc::now().timestamp(),
+            index_filters,
+            custom_filters,
+            user_rules: UserRulesState {
+                is_enabled: user_rules_filter.is_enabled,
+                disabled_rules: user_rules_list
+                    .as_ref()
+                    .map(|entity| entity.disabled_text.clone())
+                    .unwrap_or_default(),
+                disabled_rule_patterns: Some(take_patterns(USER_RULES_FILTER_LIST_ID)),
+                rules: user_rules_list
+                    .map(|entity| entity.text)
+                    .unwrap_or_default(),
+            },
+        };
+
+        serde_json::to_string(&document).map_err(FLMError::from_display)
+    }
+
+    /// Applies user state from the JSON document
+    pub(crate) fn import_user_state(
+        &self,
+        conn: &mut Connection,
+        configuration: &Configuration,
+        document: String,
+    ) -> FLMResult<ImportUserStateResult> {
+        let document =
+            serde_json::from_str::<UserStateDocument>(&document).map_err(FLMError::from_display)?;
+
+        if document.version == 0 || document.version > USER_STATE_DOCUMENT_VERSION {
+            return Err(FLMError::Other(format!(
+                "Unsupported user state document version: {}",
+                document.version
+            )));
+        }
+
+        let all_patterns = document
+            .index_filters
+            .iter()
+            .filter_map(|state| state.disabled_rule_patterns.as_ref())
+            .chain(
+                document
+                    .custom_filters
+                    .iter()
+                    .filter_map(|state| state.disabled_rule_patterns.as_ref()),
+            )
+            .chain(document.user_rules.disabled_rule_patterns.as_ref())
+            .flatten();
+        // Invalid patterns fail the whole import, like they fail `save_disabled_rule_patterns`
+        for pattern in all_patterns {
+            validate_pattern(pattern.kind.into(), &pattern.pattern)?;
+        }
+
+        let existing_filters = FilterRepository::new()
+            .select_mapped(conn, None)
+            .map_err(FLMError::from_database)?;
+
+        with_transaction(conn, move |tx: &Transaction| {
+            let mut result = ImportUserStateResult::default();
+
+            self.import_index_filters(
+                tx,
+                configuration,
+                &existing_filters,
+                document.index_filters,
+                &mut result,
+            )?;
+
+            self.import_custom_filters(
+                tx,
+                configuration,
+                &existing_filters,
+                document.custom_filters,
+                &mut result,
+            )?;
+
+            if let Some(user_rules_filter) = existing_filters.get(&USER_RULES_FILTER_LIST_ID) {
+                self.import_user_rules(
+                    tx,
+                    configuration,
+                    user_rules_filter.clone(),
+                    document.user_rules,
+                )?;
+            }
+
+            if let Some(ref key) = integrity::derive_key_if_needed(configuration) {
+                let count = FilterRepository::new().count_all(tx)?;
+
+                let mut meta = DBMetadataRepository::read(tx)?.unwrap_or_default();
+                meta.filter_count_signature = Some(integrity::sign_filter_count(key, count));
+
+                DBMetadataRepository::save(tx, &meta)?;
+            }
+
+            Ok(result)
+        })
+    }
+}
+
+impl UserStateManager {
+    /// Restores flags and disabled rules of index filters.
+    /// Index filters, which are missing in the document, become disabled and uninstalled
+    fn import_index_filters(
+        &self,
+        tx: &Transaction,
+        configuration: &Configuration,
+        existing_filters: &HashMap<FilterId, FilterEntity>,
+        states: Vec<IndexFilterState>,
+        result: &mut ImportUserStateResult,
+    ) -> rusqlite::Result<()> {
+        let rules_list_repository = RulesListRepository::new();
+        let mut enabled_ids = HashSet::new();
+        let mut installed_ids = HashSet::new();
+
+        for state in states {
+            match existing_filters.get(&state.filter_id) {
+                Some(filter) if !filter.is_custom() => {}
+                _ => {
+                    result.conflicts.push(UserStateConflict::new(
+                        state.filter_id,
+                        UserStateConflictKind::FilterNotFound,
+                        format!("Filter with id {} is not found", state.filter_id),
+                    ));
+
+                    continue;
+                }
+            }
+
+            if state.is_enabled {
+                enabled_ids.insert(state.filter_id);
+            }
+            if state.is_installed {
+                installed_ids.insert(state.filter_id);
+            }
+
+            let has_disabled_rules = !state.disabled_rules.is_empty();
+            let rows_updated = rules_list_repository.set_disabled_rules(
+                tx,
+                state.filter_id,
+                state.disabled_rules,
+            )?;
+
+            if rows_updated == 0 && has_disabled_rules {
+                result.conflicts.push(UserStateConflict::new(
+                    state.filter_id,
+                    UserStateConflictKind::RulesListNotFound,
+                    format!(
+                        "Filter with id {} has not been downloaded yet, its disabled rules were skipped",
+                        state.filter_id
+                    ),
+                ));
+            }
+
+            if let Some(patterns) = state.disabled_rule_patterns {
+                Self::import_disabled_rule_patterns(tx, state.filter_id, patterns)?;
+            }
+
+            result.restored_filters.push(state.filter_id);
+        }
+
+        let index_filter_ids: Vec<FilterId> = existing_filters
+            .values()
+            .filter(|filter| !filter.is_custom())
+            .filter_map(|filter| filter.filter_id)
+            .collect();
+
+        let (enabled, disabled): (Vec<FilterId>, Vec<FilterId>) = index_filter_ids
+            .iter()
+            .partition(|filter_id| enabled_ids.contains(*filter_id));
+        let (installed, uninstalled): (Vec<FilterId>, Vec<FilterId>) = index_filter_ids
+            .iter()
+            .partition(|filter_id| installed_ids.contains(*filter_id));
+
+        let filter_repository = FilterRepository::new();
+        filter_repository.toggle_filter_lists(tx, &enabled, true)?;
+        filter_repository.toggle_filter_lists(tx, &disabled, false)?;
+        filter_repository.toggle_is_installed(tx, &installed, true)?;
+        filter_repository.toggle_is_installed(tx, &uninstalled, false)?;
+
+        if let Some(ref key) = integrity::derive_key_if_needed(configuration) {
+            filter_repository.resign_filters_in_tx(tx, &index_filter_ids, key)?;
+        }
+
+        Ok(())
+    }
+
+    /// Installs custom filters from the document.
+    /// Exported id is kept if it is free, otherwise a new one is taken from `custom_filter_increment`
+    #[allow(clippy::field_reassign_with_default)]
+    fn import_custom_filters(
+        &self,
+        tx: &Transaction,
+        configuration: &Configuration,
+        existing_filters: &HashMap<FilterId, FilterEntity>,
+        states: Vec<CustomFilterState>,
+        result: &mut ImportUserStateResult,
+    ) -> rusqlite::Result<()> {
+        let mut taken_ids: HashSet<FilterId> = existing_filters.keys().copied().collect();
+        let mut installed_urls: HashSet<String> = existing_filters
+            .values()
+            .filter(|filter| filter.is_custom() && !filter.download_url.is_empty())
+            .map(|filter| filter.download_url.clone())
+            .collect();
+
+        let mut lowest_kept_id: Option<FilterId> = None;
+        let mut chosen_states = Vec::with_capacity(states.len());
+        for state in states {
+            if !state.download_url.is_empty() && !installed_urls.insert(state.download_url.clone())
+            {
+                result.conflicts.push(UserStateConflict::new(
+                    state.filter_id,
+                    UserStateConflictKind::CustomFilterAlreadyInstalled,
+                    format!(
+                        "Custom filter with url \"{}\" is already installed",
+                        state.download_url
+                    ),
+                ));
+
+                continue;
+            }
+
+            let is_id_free = (MINIMUM_CUSTOM_FILTER_ID..=MAXIMUM_CUSTOM_FILTER_ID)
+                .contains(&state.filter_id)
+                && taken_ids.insert(state.filter_id);
+
+            if is_id_free {
+                lowest_kept_id = Some(
+                    lowest_kept_id.map_or(state.filter_id, |lowest| lowest.min(state.filter_id)),
+                );
+            }
+
+            chosen_states.push((is_id_free.then_some(state.filter_id), state));
+        }
+
+        // Ids allocated later must not collide with kept ones
+        if let Some(lowest_kept_id) = lowest_kept_id {
+            let mut meta = DBMetadataRepository::read(tx)?.unwrap_or_default();
+
+            if meta.custom_filters_autoincrement_value > lowest_kept_id {
+                meta.custom_filters_autoincrement_value = lowest_kept_id;
+                DBMetadataRepository::save(tx, &meta)?;
+            }
+        }
+
+        let derived_key = integrity::derive_key_if_needed(configuration);
+        let filter_repository = FilterRepository::new();
+        for (filter_id, state) in chosen_states {
+            let previous_id = state.filter_id;
+            let mut entity = FilterEntity::default();
+            entity.filter_id = filter_id;
+            entity.group_id = CUSTOM_FILTERS_GROUP_ID;
+            entity.title = state.title;
+            entity.description = state.description;
+            entity.download_url = state.download_url;
+            entity.subscription_url = state.subscription_url;
+            entity.version = state.version;
+            entity.homepage = state.homepage;
+            entity.license = state.license;
+            entity.checksum = state.checksum;
+            entity.expires = state.expires;
+            entity.last_update_time = state.last_update_time;
+            entity.last_download_time = state.last_download_time;
+            entity.is_enabled = state.is_enabled;
+            entity.is_installed = state.is_installed;
+            entity.is_trusted = state.is_trusted;
+            entity.set_is_user_title(state.is_user_title);
+            entity.set_is_user_description(state.is_user_description);
+
+            let mut chosen_id = None;
+            filter_repository.insert_with_chosen_filters_callback(
+                tx,
+                slice::from_ref(&entity),
+                |_, filter_id| chosen_id = filter_id,
+            )?;
+
+            let Some(new_id) = chosen_id else {
+                return Err(rusqlite::Error::QueryReturnedNoRows);
+            };
+
+            if let Some(ref key) = derived_key {
+                entity.filter_id = Some(new_id);
+                integrity::sign_filter_entity(key, &mut entity);
+                if let Some(signature) = entity.integrity_signature() {
+                    filter_repository.update_integrity_signature(tx, new_id, signature)?;
+                }
+            }
+
+            let mut rules_list_entity =
+                RulesListEntity::make(new_id, state.rules, state.rules_count);
+            rules_list_entity.disabled_text = state.disabled_rules;
+            rules_list_entity.compression = configuration.rules_storage_compression;
+
+            let mut include_entities: Vec<FilterIncludeEntity> = state
+                .includes
+                .into_iter()
+                .map(|include| {
+                    let mut entity = FilterIncludeEntity::make(
+                        new_id,
+                        include.absolute_url,
+                        include.rules_count,
+                        include.body,
+                    );
+                    entity.compression = configuration.rules_storage_compression;
+
+                    entity
+                })
+                .collect();
+
+            integrity::sign_entities_if_needed(
+                configuration,
+                &mut rules_list_entity,
+                &mut include_entities,
+            );
+
+            RulesListRepository::new().insert(tx, &[rules_list_entity])?;
+            FilterIncludesRepository::new().insert(tx, &include_entities)?;
+
+            if let Some(patterns) = state.disabled_rule_patterns {
+                Self::import_disabled_rule_patterns(tx, new_id, patterns)?;
+            }
+
+            if new_id != previous_id {
+                result.conflicts.push(UserStateConflict::new(
+                    previous_id,
+                    UserStateConflictKind::CustomFilterIdCollision,
+                    format!(
+                        "Custom filter id {} is already taken, filter was installed with id {}",
+                        previous_id, new_id
+                    ),
+                ));
+            }
+
+            result
+                .installed_custom_filters
+                .push(MovedFilterInfo::new(previous_id, new_id));
+        }
+
+        Ok(())
+    }
+
+    /// Replaces user rules with rules from the document
+    fn import_user_rules(
+        &self,
+        tx: &Transaction,
+        configuration: &Configuration,
+        mut user_rules_filter: FilterEntity,
+        state: UserRulesState,
+    ) -> rusqlite::Result<()> {
+        user_rules_filter.is_enabled = state.is_enabled;
+        integrity::sign_filter_entity_if_needed(configuration, &mut user_rules_filter);
+
+        FilterRepository::new().insert(tx, &[user_rules_filter])?;
+
+        let rules_count = state
+            .rules
+            .lines()
+            .filter(|line| is_line_is_rule(line))
+            .count() as i32;
+
+        let mut rules_list_entity =
+            RulesListEntity::make(USER_RULES_FILTER_LIST_ID, state.rules, rules_count);
+        rules_list_entity.disabled_text = state.disabled_rules;
+        rules_list_entity.compression = configuration.rules_storage_compression;
+
+        integrity::sign_entities_if_needed(configuration, &mut rules_list_entity, &mut []);
+
+        RulesListRepository::new().insert(tx, &[rules_list_entity])?;
+
+        if let Some(patterns) = state.disabled_rule_patterns {
+            Self::import_disabled_rule_patterns(tx, USER_RULES_FILTER_LIST_ID, patterns)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces disabled rule patterns of the filter. Patterns must be validated beforehand
+    fn import_disabled_rule_patterns(
+        tx: &Transaction,
+        filter_id: FilterId,
+        patterns: Vec<DisabledRulePatternState>,
+    ) -> rusqlite::Result<()> {
+        let entities = patterns
+            .into_iter()
+            .map(|pattern| DisabledRulePatternEntity::make(filter_id, pattern.into()))
+            .collect::<Vec<DisabledRulePatternEntity>>();
+
+        DisabledRulePatternsRepository::new().replace_for_filter(tx, filter_id, &entities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manager::models::import_user_state_result::UserStateConflictKind;
+    use crate::test_utils::spawn_test_db_with_metadata;
+    use crate::{
+        generate_random_key, Configuration, DisabledRulePattern, DisabledRulePatternKind, FilterId,
+        FilterListManager, FilterListManagerImpl, FilterListRules, USER_RULES_FILTER_LIST_ID,
+    };
+    use serde_json::Value;
+
+    fn make_manager_with_metadata() -> (Box<FilterListManagerImpl>, Vec<FilterId>) {
+        let mut conf = Configuration::default();
+        conf.app_name = "FlmApp".to_string();
+        conf.version = "1.2.3".to_string();
+        conf.integrity_key = Some(generate_random_key().unwrap());
+
+        let flm = FilterListManagerImpl::new(conf).unwrap();
+        let (_, inserted_filters) = spawn_test_db_with_metadata(&flm.connection_manager);
+        flm.sign_all_data().unwrap();
+
+        let index_filter_ids = inserted_filters
+            .into_iter()
+            .filter(|filter| !filter.is_custom())
+            .filter_map(|filter| filter.filter_id)
+            .collect();
+
+        (flm, index_filter_ids)
+    }
+
+    fn install_custom_filter(flm: &FilterListManagerImpl, download_url: &str) -> FilterId {
+        flm.install_custom_filter_from_string(
+            download_url.to_string(),
+            1234567890,
+            true,
+            false,
+            String::from("! Title: Custom\n||example.org^\n||example.com^"),
+            None,
+            None,
+        )
+        .unwrap()
+        .id
+    }
+
+    #[test]
+    fn test_export_and_import_user_state() {
+        let (source, index_filter_ids) = make_manager_with_metadata();
+        let enabled_ids = vec![index_filter_ids[0], index_filter_ids[1]];
+        source
+            .enable_filter_lists(enabled_ids.clone(), true)
+            .unwrap();
+        source
+            .install_filter_lists(vec![index_filter_ids[0]], true)
+            .unwrap();
+
+        let source_custom_filter_id = install_custom_filter(&source, "");
+        source
+            .save_disabled_rules(
+                source_custom_filter_id,
+                vec![String::from("||example.com^")],
+            )
+            .unwrap();
+
+        source
+            .save_custom_filter_rules(FilterListRules {
+                filter_id: USER_RULES_FILTER_LIST_ID,
+                rules: vec![String::from("||first.org^"), String::from("||second.org^")],
+                disabled_rules: vec![String::from("||second.org^")],
+                rules_count: 0,
+            })
+            .unwrap();
+
+        let custom_filter_patterns = vec![DisabledRulePattern {
+            kind: DisabledRulePatternKind::Wildcard,
+            pattern: String::from("*example.org*"),
+        }];
+        source
+            .save_disabled_rule_patterns(source_custom_filter_id, custom_filter_patterns.clone())
+            .unwrap();
+
+        let user_rules_patterns = vec![
+            DisabledRulePattern {
+                kind: DisabledRulePatternKind::Regex,
+                pattern: String::from(r"^\|\|first\."),
+            },
+            DisabledRulePattern {
+                kind: DisabledRulePatternKind::Wildcard,
+                pattern: String::from("*##+js(*"),
+            },
+        ];
+        source
+            .save_disabled_rule_patterns(USER_RULES_FILTER_LIST_ID, user_rules_patterns.clone())
+            .unwrap();
+
+        let document = source.export_user_state().unwrap();
+
+        let (target, _) = make_manager_with_metadata();
+        // Takes the same id as the exported custom filter
+        let target_custom_filter_id = install_custom_filter(&target, "");
+        assert_eq!(target_custom_filter_id, source_custom_filter_id);
+
+        let result = target.import_user_state(document).unwrap();
+
+        let mut restored_filters = result.restored_filters.clone();
+        restored_filters.sort();
+        let mut expected_restored_filters = enabled_ids.clone();
+        expected_restored_filters.sort();
+        assert_eq!(restored_filters, expected_restored_filters);
+
+        assert_eq!(result.installed_custom_filters.len(), 1);
+        let moved = &result.installed_custom_filters[0];
+        assert_eq!(moved.previous_id, source_custom_filter_id);
+        assert_ne!(moved.new_id, source_custom_filter_id);
+
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(
+            result.conflicts[0].kind,
+            UserStateConflictKind::CustomFilterIdCollision
+        );
+
+        let imported_filter = target
+            .get_full_filter_list_by_id(moved.new_id)
+            .unwrap()
+            .unwrap();
+        let imported_rules = imported_filter.rules.unwrap();
+        assert!(imported_rules
+            .rules
+            .contains(&String::from("||example.org^")));
+        assert_eq!(
+            imported_rules.disabled_rules,
+            vec![String::from("||example.com^")]
+        );
+
+        let user_rules = target
+            .get_full_filter_list_by_id(USER_RULES_FILTER_LIST_ID)
+            .unwrap()
+            .unwrap()
+            .rules
+            .unwrap();
+        assert_eq!(
+            user_rules.rules,
+            vec![String::from("||first.org^"), String::from("||second.org^")]
+        );
+        assert_eq!(
+            user_rules.disabled_rules,
+            vec![String::from("||second.org^")]
+        );
+
+        let patterns = target
+            .get_disabled_rule_patterns(vec![moved.new_id, USER_RULES_FILTER_LIST_ID])
+            .unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].filter_id, moved.new_id);
+        assert_eq!(patterns[0].patterns, custom_filter_patterns);
+        assert_eq!(patterns[1].filter_id, USER_RULES_FILTER_LIST_ID);
+        assert_eq!(patterns[1].patterns, user_rules_patterns);
+
+        // Patterns disable rules of the imported filter
+        let active_rules = target
+            .get_active_rules()
+            .unwrap()
+            .into_iter()
+            .find(|active_rules| active_rules.filter_id == moved.new_id)
+            .unwrap();
+        assert!(!active_rules.rules.contains(&String::from("||example.org^")));
+
+        let first_filter = target
+            .get_full_filter_list_by_id(index_filter_ids[0])
+            .unwrap()
+            .unwrap();
+        assert!(first_filter.is_enabled);
+        assert!(first_filter.is_installed);
+
+        let second_filter = target
+            .get_full_filter_list_by_id(index_filter_ids[1])
+            .unwrap()
+            .unwrap();
+        assert!(second_filter.is_enabled);
+        assert!(!second_filter.is_installed);
+
+        target.verify_integrity().unwrap();
+
+        // Next custom filter must not collide with imported ones
+        let next_custom_filter_id = install_custom_filter(&target, "");
+        assert!(next_custom_filter_id < moved.new_id);
+    }
+
+    #[test]
+    fn test_import_user_state_reports_conflicts() {
+        let download_url = "https://example.org/custom_filter.txt";
+
+        let (source, _) = make_manager_with_metadata();
+        install_custom_filter(&source, download_url);
+
+        let mut document: Value =
+            serde_json::from_str(&source.export_user_state().unwrap()).unwrap();
+        document["index_filters"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({
+                "filter_id": 999_999,
+                "is_enabled": true,
+                "is_installed": true,
+            }));
+
+        let (target, _) = make_manager_with_metadata();
+        install_custom_filter(&target, download_url);
+
+        let result = target.import_user_state(document.to_string()).unwrap();
+
+        assert!(result.installed_custom_filters.is_empty());
+
+        let mut kinds: Vec<UserStateConflictKind> = result
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.kind)
+            .collect();
+        kinds.sort_by_key(|kind| *kind as i32);
+        assert_eq!(
+            kinds,
+            vec![
+                UserStateConflictKind::FilterNotFound,
+                UserStateConflictKind::CustomFilterAlreadyInstalled,
+            ]
+        );
+
+        document["version"] = Value::from(100);
+        assert!(target.import_user_state(document.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_import_user_state_of_first_version_keeps_patterns() {
+        let (source, _) = make_manager_with_metadata();
+        let mut document: Value =
+            serde_json::from_str(&source.export_user_state().unwrap()).unwrap();
+
+        assert_eq!(document["version"], Value::from(2));
+        assert_eq!(
+            document["user_rules"]["disabled_rule_patterns"],
+            serde_json::json!([])
+        );
+
+        let (target, _) = make_manager_with_metadata();
+        let patterns = vec![DisabledRulePattern {
+            kind: DisabledRulePatternKind::Wildcard,
+            pattern: String::from("*example.org*"),
+        }];
+        target
+            .save_disabled_rule_patterns(USER_RULES_FILTER_LIST_ID, patterns.clone())
+            .unwrap();
+
+        // Version 1 has no patterns
+        document["version"] = Value::from(1);
+        document["user_rules"]
+            .as_object_mut()
+            .unwrap()
+            .remove("disabled_rule_patterns");
+        target.import_user_state(document.to_string()).unwrap();
+
+        let saved_patterns = target
+            .get_disabled_rule_patterns(vec![USER_RULES_FILTER_LIST_ID])
+            .unwrap();
+        assert_eq!(saved_patterns[0].patterns, patterns);
+
+        // Invalid pattern fails the import
+        document["version"] = Value::from(2);
+        document["user_rules"]["disabled_rule_patterns"] =
+            serde_json::json!([{ "kind": "regex", "pattern": "(" }]);
+        assert!(target.import_user_state(document.to_string()).is_err());
+    }
+}
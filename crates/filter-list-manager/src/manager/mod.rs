@@ -1,19 +1,42 @@
 //! Filter list manager library main facade interface.
+#[cfg(feature = "async")]
+pub mod async_filter_list_manager;
 pub mod filter_list_manager_impl;
 pub(crate) mod filter_lists_builder;
 pub mod managers;
 pub mod models;
+pub(crate) mod update_dispatch_queue;
 mod update_filters_action;
-
+pub(crate) mod update_progress_reporter;
+pub(crate) mod update_scheduler;
+pub(crate) mod user_state_document;
+
+use crate::io::http::transport::HttpTransport;
+use crate::manager::models::active_rules_export::{
+    ActiveRulesExportManifest, ActiveRulesExportOptions,
+};
 use crate::manager::models::active_rules_info::ActiveRulesInfo;
 use crate::manager::models::configuration::request_proxy_mode::RequestProxyMode;
 use crate::manager::models::configuration::Locale;
+use crate::manager::models::deduplicated_active_rules::{
+    DeduplicatedActiveRules, DeduplicatedActiveRulesRaw,
+};
+use crate::manager::models::disabled_rule_pattern::{DisabledRulePattern, DisabledRulePatterns};
 use crate::manager::models::disabled_rules_raw::DisabledRulesRaw;
+use crate::manager::models::filter_diagnostic::FilterDiagnostic;
 use crate::manager::models::filter_group::FilterGroup;
 use crate::manager::models::filter_list_rules::FilterListRules;
 use crate::manager::models::filter_list_rules_raw::FilterListRulesRaw;
 use crate::manager::models::filter_tag::FilterTag;
+use crate::manager::models::filter_update_failure::FilterUpdateFailure;
+use crate::manager::models::filter_update_preview::FilterUpdatePreview;
+use crate::manager::models::filter_version::FilterVersion;
+use crate::manager::models::import_user_state_result::ImportUserStateResult;
+use crate::manager::models::rule_provenance::RuleProvenance;
+use crate::manager::models::rule_search::{RuleSearchMatch, RuleSearchOptions};
 use crate::manager::models::rules_count_by_filter::RulesCountByFilter;
+use crate::manager::models::update_progress::{UpdateCancellationToken, UpdateObserver};
+use crate::manager::models::update_scheduler::{UpdateSchedulerObserver, UpdateSchedulerSettings};
 use crate::manager::models::{PullMetadataResult, UpdateResult};
 use crate::{ActiveRulesInfoRaw, FLMResult, StoredFilterMetadata};
 use models::configuration::Configuration;
@@ -22,6 +45,7 @@ use models::filter_list_metadata_with_body::FilterListMetadataWithBody;
 use models::full_filter_list::FullFilterList;
 use models::FilterId;
 use std::path::Path;
+use std::sync::Arc;
 
 /// FilterListManager is the interface of a filter list manager.
 pub trait FilterListManager {
@@ -133,6 +157,25 @@ pub trait FilterListManager {
         disabled_rules: Vec<String>,
     ) -> FLMResult<()>;
 
+    /// Replaces all disabled rule patterns of the filter with `patterns`.
+    /// Rules matching any of them are excluded from active rules, raw rules and
+    /// [`Self::save_rules_to_file_blob`] output, in addition to literal disabled rules.
+    /// Patterns are kept across filter updates.
+    ///
+    /// * `filter_id` - ID of the filter
+    /// * `patterns` - New patterns. Pass an empty list to remove all patterns
+    ///
+    /// # Failure
+    ///
+    /// Returns [`crate::FLMError::EntityNotFound`] if rules_list entity does not exist
+    /// for passed `filter_id`, [`crate::FLMError::FieldIsEmpty`] if one of the patterns
+    /// is empty, or [`crate::FLMError::Other`] if one of the patterns is invalid.
+    fn save_disabled_rule_patterns(
+        &self,
+        filter_id: FilterId,
+        patterns: Vec<DisabledRulePattern>,
+    ) -> FLMResult<()>;
+
     /// Filters updates is conducted in the multiple steps:
     /// - Search for filters ready for update.
     /// - Fetch them.
@@ -148,6 +191,9 @@ pub trait FilterListManager {
     /// - Local urls without `download_url` won't be updated
     /// - For index filters, versions are checked through `Version` metadata field checking up against the latest version from the index file
     /// - Also `loose_timeout` can limit count of updated filters if non-zero
+    /// - Processing can be cancelled via [`Self::get_update_cancellation_token`].
+    ///   Already processed filters will be saved, the rest will be counted in
+    ///   `remaining_filters_count`
     ///
     /// # Parameters
     ///
@@ -205,6 +251,52 @@ pub trait FilterListManager {
         loose_timeout: i32,
     ) -> FLMResult<Option<UpdateResult>>;
 
+    /// Shows what will be changed by the update of the filter, without applying it.
+    ///
+    /// The remote version is downloaded and compiled the same way as [`Self::update_filters`] does,
+    /// including differential updates, the registry version check, retries, `Retry-After` waits
+    /// and mirror fallback, but nothing is saved. Unlike updates, this ignores `expires` and the filter status,
+    /// and doesn't send conditional requests.
+    ///
+    /// If the next differential update patch is not published yet,
+    /// [`FilterUpdatePreview::is_patch_not_ready`] is set.
+    ///
+    /// * `filter_id` - ID of the filter
+    ///
+    /// # Failure
+    ///
+    /// Returns [`crate::FLMError::EntityNotFound`] if there is no such filter,
+    /// [`crate::FLMError::FieldIsEmpty`] if the filter has no download url,
+    /// or [`crate::FLMError::ParseFilterError`] if the remote version can't be downloaded or compiled.
+    fn preview_filter_update(&self, filter_id: FilterId) -> FLMResult<FilterUpdatePreview>;
+
+    /// Lists previous versions of the filter contents, from the latest to the oldest.
+    /// Versions are kept by updates, see [`Configuration::filter_versions_retention`].
+    ///
+    /// * `filter_id` - ID of the filter
+    fn list_filter_versions(&self, filter_id: FilterId) -> FLMResult<Vec<FilterVersion>>;
+
+    /// Atomically replaces rules, includes, metadata and integrity signatures of the filter
+    /// with the previous version. Disabled rules are kept.
+    ///
+    /// Current contents are kept as a new version, so the rollback may be reverted.
+    /// The filter is pinned and will be skipped by all updates until [`Self::unpin_filter`] is called.
+    ///
+    /// * `filter_id` - ID of the filter
+    /// * `version_id` - [`FilterVersion::version_id`] from [`Self::list_filter_versions`]
+    ///
+    /// # Failure
+    ///
+    /// Returns [`crate::FLMError::EntityNotFound`] if there is no such filter
+    /// or the filter has no such version.
+    fn rollback_filter(&self, filter_id: FilterId, version_id: i64) -> FLMResult<()>;
+
+    /// Allows updates of the filter, which has been pinned by [`Self::rollback_filter`].
+    /// Does nothing if the filter is not pinned.
+    ///
+    /// * `filter_id` - ID of the filter
+    fn unpin_filter(&self, filter_id: FilterId) -> FLMResult<()>;
+
     /// Tries to change [`Locale`] in configuration.
     /// Will search `suggested_locale` in database. If it cannot find exact
     /// locale, like `en_GB`, it will try to find language code - `en`. Locales
@@ -260,6 +352,11 @@ pub trait FilterListManager {
     /// 7. Fill in our updated filters along with the raw filters from the
     ///    index.
     ///
+    /// Progress events are reported to the observer set with
+    /// [`Self::set_update_observer`]. If the operation is cancelled before
+    /// the indices are saved, nothing will be changed and an empty
+    /// [`PullMetadataResult`] will be returned.
+    ///
     /// Note: should be used no more than once a week.
     ///
     /// # Returns
@@ -348,8 +445,26 @@ pub trait FilterListManager {
     /// * `filter_by` - If empty, returns all active rules, otherwise returns intersection between `filter_by` and all active rules
     fn get_active_rules_raw(&self, filter_by: Vec<FilterId>) -> FLMResult<Vec<ActiveRulesInfoRaw>>;
 
+    /// Same as [`Self::get_active_rules`], but each unique rule is emitted once.
+    /// The rule is kept in the first trusted filter containing it, or in the first
+    /// untrusted one, if no trusted filter contains it. Comments and empty lines
+    /// are not deduplicated.
+    ///
+    /// Returns count of removed duplicates for each filter.
+    fn get_active_rules_deduplicated(&self) -> FLMResult<DeduplicatedActiveRules>;
+
+    /// Same as [`Self::get_active_rules_raw`], but each unique rule is emitted once.
+    /// See [`Self::get_active_rules_deduplicated`].
+    /// * `filter_by` - If empty, returns all active rules, otherwise returns intersection between `filter_by` and all active rules
+    fn get_active_rules_raw_deduplicated(
+        &self,
+        filter_by: Vec<FilterId>,
+    ) -> FLMResult<DeduplicatedActiveRulesRaw>;
+
     /// Gets a list of [`FilterListRulesRaw`] structures containing.
     /// `rules` and `disabled_rules` as strings, directly from database fields.
+    /// Rules disabled by patterns (see [`Self::save_disabled_rule_patterns`])
+    /// are appended to `disabled_rules`.
     ///
     /// This method acts in the same way as the `IN` database operator. Only found entities will be returned
     fn get_filter_rules_as_strings(&self, ids: Vec<FilterId>)
@@ -368,15 +483,139 @@ pub trait FilterListManager {
         file_path: P,
     ) -> FLMResult<()>;
 
+    /// Streams rules of all enabled filters into a single file, like
+    /// [`Self::save_rules_to_file_blob`] does for one filter. Includes are resolved,
+    /// disabled rules and patterns are skipped. Filters are separated with a line break.
+    ///
+    /// * `file_path` - Output file. It is truncated, if exists
+    /// * `options` - Filter headers and separate file for untrusted filters
+    ///
+    /// Returns [`ActiveRulesExportManifest`] with byte ranges and rules counts of
+    /// the written filters. Created files are removed, if the export fails.
+    fn save_active_rules_to_file<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        options: ActiveRulesExportOptions,
+    ) -> FLMResult<ActiveRulesExportManifest>;
+
     /// Returns lists of disabled rules by list of filter IDs
     fn get_disabled_rules(&self, ids: Vec<FilterId>) -> FLMResult<Vec<DisabledRulesRaw>>;
 
+    /// Returns lists of disabled rule patterns by list of filter IDs.
+    /// Filters without patterns are omitted
+    fn get_disabled_rule_patterns(
+        &self,
+        ids: Vec<FilterId>,
+    ) -> FLMResult<Vec<DisabledRulePatterns>>;
+
     /// Sets a new proxy mode. Value will be applied on next method call
     fn set_proxy_mode(&mut self, mode: RequestProxyMode);
 
+    /// Sets an observer for progress events of filters update methods and
+    /// [`Self::pull_metadata`]. Pass [`None`] to remove the observer.
+    fn set_update_observer(&mut self, observer: Option<Arc<dyn UpdateObserver>>);
+
+    /// Sets a transport for all HTTP(S) requests. Pass [`None`] to use the built-in client.
+    /// Value will be applied on next method call
+    fn set_http_transport(&mut self, transport: Option<Arc<dyn HttpTransport>>);
+
+    /// Returns cancellation token for filters update methods and
+    /// [`Self::pull_metadata`]. Calling [`UpdateCancellationToken::cancel`]
+    /// stops the currently running operation, or the next one if none is running.
+    /// Cancellation, which has stopped the operation, never affects subsequent calls.
+    fn get_update_cancellation_token(&self) -> UpdateCancellationToken;
+
+    /// Starts a background thread, which calls [`Self::pull_metadata`] and
+    /// [`Self::update_filters`] on schedule, and passes their results to `observer`.
+    ///
+    /// Filters are updated when at least one enabled filter is expired or its
+    /// differential update is due, but not more often than
+    /// [`UpdateSchedulerSettings::update_interval_sec`]. Tasks failed due to
+    /// network are retried with exponential backoff. Last run times are kept
+    /// in the database, so the schedule survives restarts.
+    ///
+    /// Scheduler uses the configuration at the moment of the call. Methods,
+    /// which change the configuration, restart it. Methods, which rotate the keys,
+    /// stop it until they finish. Running scheduler is restarted with the new settings.
+    /// It is stopped on the manager drop.
+    ///
+    /// # Failure
+    ///
+    /// Returns [`crate::FLMError::InvalidConfiguration`] if settings are invalid
+    fn start_update_scheduler(
+        &mut self,
+        settings: UpdateSchedulerSettings,
+        observer: Arc<dyn UpdateSchedulerObserver>,
+    ) -> FLMResult<()>;
+
+    /// Stops the scheduler, started by [`Self::start_update_scheduler`].
+    /// Running task is cancelled, and this method waits for it.
+    /// Does nothing if the scheduler is not running.
+    fn stop_update_scheduler(&mut self);
+
+    /// Is the scheduler, started by [`Self::start_update_scheduler`], running
+    fn is_update_scheduler_running(&self) -> bool;
+
     /// Returns lists of rules count by list of filter IDs
     fn get_rules_count(&self, ids: Vec<FilterId>) -> FLMResult<Vec<RulesCountByFilter>>;
 
+    /// Searches rules in stored filters bodies and their includes.
+    /// Only rule lines are matched, comments and directives are skipped.
+    /// Conditional directives are not evaluated, so rules from all `!#if`
+    /// branches are matched.
+    ///
+    /// * `query` - Substring or regular expression, see [`RuleSearchOptions::is_regex`].
+    /// * `filter_ids` - If empty, searches in all filters, otherwise only in passed ones.
+    /// * `options` - Search options.
+    ///
+    /// Disabled rules are skipped, unless [`RuleSearchOptions::include_disabled_rules`] is set.
+    ///
+    /// # Failure
+    ///
+    /// Returns [`crate::FLMError::FieldIsEmpty`] if `query` is empty.
+    fn search_rules(
+        &self,
+        query: String,
+        filter_ids: Vec<FilterId>,
+        options: RuleSearchOptions,
+    ) -> FLMResult<Vec<RuleSearchMatch>>;
+
+    /// Finds where `rule` comes from: all filters and their `!#include` files,
+    /// which produce the line equal to `rule` in the built filter.
+    /// Conditional directives are evaluated the same way as for [`Self::get_active_rules`],
+    /// so lines from inactive `!#if` branches are not returned.
+    ///
+    /// Returned locations of disabled rules are marked with [`RuleProvenance::is_disabled`].
+    ///
+    /// # Failure
+    ///
+    /// Returns [`crate::FLMError::FieldIsEmpty`] if `rule` is empty.
+    fn get_rule_provenance(&self, rule: String) -> FLMResult<Vec<RuleProvenance>>;
+
+    /// Checks filter `body` and returns all found problems with line numbers and severities,
+    /// e.g. for custom filter editors. Unlike filter installation, this doesn't stop at the first problem.
+    ///
+    /// Checks conditional directives balance and conditions (using constants from
+    /// [`Configuration::filters_compilation_policy`]), `!#include` targets, `! Expires` and
+    /// `! TimeUpdated` values, and `! Checksum`. Included files are not downloaded.
+    ///
+    /// * `body` - Filter contents
+    /// * `base_url` - Url, which the filter is downloaded from. Relative `!#include`
+    ///   paths are resolved against it, and included files must have the same origin
+    ///
+    /// # Failure
+    ///
+    /// Returns [`crate::FLMError::FieldIsEmpty`] if `base_url` is empty.
+    fn lint_filter(&self, body: String, base_url: String) -> FLMResult<Vec<FilterDiagnostic>>;
+
+    /// Returns update failures of filters, which have failed during the last updates
+    /// and haven't been requested successfully since then.
+    /// [`FilterUpdateFailure::first_failure_time`] tells how long the filter has been failing.
+    ///
+    /// Such filters are skipped by [`Self::update_filters`] until
+    /// [`FilterUpdateFailure::next_retry_time`], unless `ignore_filters_expiration` is set.
+    fn get_filter_update_failures(&self) -> FLMResult<Vec<FilterUpdateFailure>>;
+
     /// Signs all filter rules, includes, metadata, and the filter count using
     /// the integrity key from configuration.
     ///
@@ -393,6 +632,7 @@ pub trait FilterListManager {
     /// 1. Updates `configuration.integrity_key` with the provided key.
     /// 2. Re-signs all stored filter data with the new key.
     ///
+    /// Running update scheduler is stopped until the data is signed.
     /// Use this method when rotating the integrity key.
     ///
     /// # Arguments
@@ -400,6 +640,23 @@ pub trait FilterListManager {
     /// * `integrity_key` - New integrity key to use for signing.
     fn sign_all_data_with_new_key(&mut self, integrity_key: String) -> FLMResult<()>;
 
+    /// Re-encrypts the database with the new key and updates
+    /// `configuration.database_encryption_key`.
+    ///
+    /// Running update scheduler is stopped until the database is re-encrypted.
+    /// Use this method when rotating the database encryption key.
+    /// Pass the new key to [`Configuration`] on the next start.
+    ///
+    /// # Arguments
+    ///
+    /// * `encryption_key` - New encryption key. [`None`] decrypts the database into plaintext.
+    ///
+    /// # Failure
+    ///
+    /// Returns [`crate::FLMError::InvalidConfiguration`] if the library is built without
+    /// `sqlcipher` feature or the key is empty.
+    fn change_database_encryption_key(&mut self, encryption_key: Option<String>) -> FLMResult<()>;
+
     /// Verifies integrity signatures of all filter rules and includes
     /// entities in the database.
     ///
@@ -410,4 +667,29 @@ pub trait FilterListManager {
     /// - Returns [`crate::FLMError::FilterIntegrityCheckFailed`] if any entity
     ///   has a missing or invalid signature.
     fn verify_integrity(&self) -> FLMResult<()>;
+
+    /// Exports the user state as a portable versioned JSON document.
+    ///
+    /// The document contains flags, disabled rules and disabled rule patterns
+    /// of enabled or installed index filters, custom filters with their
+    /// contents, and user rules.
+    fn export_user_state(&self) -> FLMResult<String>;
+
+    /// Imports the user state from a document made by [`Self::export_user_state`].
+    ///
+    /// * Index filters from the document get their flags, disabled rules and
+    ///   disabled rule patterns restored. Other index filters become disabled
+    ///   and uninstalled. Documents of the first version don't contain
+    ///   patterns, so existing ones are kept.
+    /// * Custom filters are installed in addition to existing ones. The
+    ///   exported id is kept if it is free, otherwise a new id is taken.
+    ///   Filters with an already installed download url are skipped.
+    /// * User rules are replaced.
+    ///
+    /// Invalid disabled rule pattern fails the whole import.
+    /// Everything that could not be imported as is will be listed in
+    /// [`ImportUserStateResult::conflicts`].
+    ///
+    /// * `document` - JSON document
+    fn import_user_state(&self, document: String) -> FLMResult<ImportUserStateResult>;
 }
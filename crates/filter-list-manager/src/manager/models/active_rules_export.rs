@@ -0,0 +1,42 @@
+//! Export of all active rules into a single file
+use crate::FilterId;
+use std::path::PathBuf;
+
+/// Options of [`crate::FilterListManager::save_active_rules_to_file`]
+#[derive(Debug, Clone, Default)]
+pub struct ActiveRulesExportOptions {
+    /// Write `! Filter: <title> (id: <filter_id>)` comment before rules of each filter
+    pub write_filter_headers: bool,
+    /// If set, rules of untrusted filters are written to this file
+    /// instead of the main one
+    pub untrusted_file_path: Option<PathBuf>,
+    /// Write each unique rule once. Trusted filters are written first then,
+    /// so the rule is kept in the first trusted filter containing it
+    pub deduplicate_rules: bool,
+}
+
+/// Location of the filter rules in the exported file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedFilterInfo {
+    /// Filter id
+    pub filter_id: FilterId,
+    /// Is this filter trusted. Untrusted filters are written to
+    /// [`ActiveRulesExportOptions::untrusted_file_path`], if it's set
+    pub is_trusted: bool,
+    /// Offset of the filter section in the file. Section starts with the header, if any
+    pub byte_offset: u64,
+    /// Length of the filter section in bytes
+    pub byte_length: u64,
+    /// Count of written rules, excluding comments and empty lines
+    pub rules_count: i32,
+    /// Count of skipped duplicated rules.
+    /// See [`ActiveRulesExportOptions::deduplicate_rules`]
+    pub duplicates_count: i32,
+}
+
+/// Manifest of the exported file(s)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActiveRulesExportManifest {
+    /// Exported filters in the order of writing
+    pub filters: Vec<ExportedFilterInfo>,
+}
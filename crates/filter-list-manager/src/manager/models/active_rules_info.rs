@@ -1,5 +1,5 @@
 //! Represents list of active (not disabled) rules with extra filter data
-use crate::FilterId;
+use crate::{FilterId, FilterTrustLevel};
 
 /// Represents list of active (not disabled) rules with extra filter data
 pub struct ActiveRulesInfo {
@@ -9,6 +9,8 @@ pub struct ActiveRulesInfo {
     pub group_id: i32,
     /// Is this filter trusted?
     pub is_trusted: bool,
+    /// Trust level from the registry. [`None`] for custom filters
+    pub trust_level: Option<FilterTrustLevel>,
     /// List of active rules.
     /// There rules is the difference between all rules of filter and disabled rules of filter
     pub rules: Vec<String>,
@@ -0,0 +1,16 @@
+/// Additional source of index filters, groups and tags
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FilterRegistry {
+    /// Unique name of the registry. The name binds the registry to its range of ids
+    /// in the database, so it must not be changed.
+    /// [`crate::MAIN_FILTER_REGISTRY_NAME`] is reserved
+    pub name: String,
+    /// URL of the index (filters.json) file
+    pub metadata_url: String,
+    /// URL of the locales (filters_i18n.json) file. May be empty
+    pub metadata_locales_url: String,
+    /// Mirrors of [`Self::metadata_url`]
+    pub metadata_mirror_urls: Vec<String>,
+    /// Mirrors of [`Self::metadata_locales_url`]
+    pub metadata_locales_mirror_urls: Vec<String>,
+}
@@ -1,4 +1,4 @@
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct FiltersCompilationPolicy {
     /// List of constants for filters conditional compilation
     pub constants: Vec<String>,
@@ -1,16 +1,23 @@
 //! Configuration-related objects for [`crate::FilterListManager`]
 pub mod filter_list_type;
+pub mod filter_registry;
 pub mod filters_compilation_policy;
 pub mod locale;
 pub mod request_proxy_mode;
+pub mod rules_storage_compression;
 
 pub use self::filter_list_type::FilterListType;
+pub use self::filter_registry::FilterRegistry;
 pub use self::filters_compilation_policy::FiltersCompilationPolicy;
 pub use self::locale::Locale;
 pub use self::request_proxy_mode::RequestProxyMode;
+pub use self::rules_storage_compression::RulesStorageCompression;
 
-use crate::string;
+use crate::io::http::transport::HttpTransport;
+use crate::{string, FLMError, FLMResult, MAIN_FILTER_REGISTRY_NAME};
 use std::cmp::max;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 /// Expires value shouldn't be less than this constant. In seconds
 const MINIMAL_EXPIRES_VALUE: i32 = 3600;
@@ -29,7 +36,27 @@ pub(crate) const DEFAULT_FILTER_UPDATE_CONCURRENCY: usize = 16;
 /// dispatches to avoid HTTP 429 (Too Many Requests) errors.
 pub(crate) const DEFAULT_FILTER_UPDATE_DISPATCH_DELAY_MS: i32 = 60;
 
+/// Default number of extra download attempts after transient filter update failure.
+const DEFAULT_FILTER_UPDATE_MAX_RETRIES: i32 = 2;
+
+/// Default delay in milliseconds before the first retry of filter download.
+const DEFAULT_FILTER_UPDATE_RETRY_DELAY_MS: i32 = 1000;
+
+/// Retry delay is doubled after every attempt, but won't exceed this value. In ms
+pub(crate) const MAX_FILTER_UPDATE_RETRY_DELAY_MS: u64 = 30000;
+
+/// Default delay in seconds before the filter, which update has failed, will be requested again.
+const DEFAULT_FILTER_FAILURE_BACKOFF_SEC: i32 = 3600;
+
+/// Failure backoff is doubled after every consecutive failure, but won't exceed this value. In seconds
+pub(crate) const MAX_FILTER_FAILURE_BACKOFF_SEC: u64 = 86400;
+
+/// Longest `Retry-After` delay, which will be waited for within the update. In seconds.
+/// Filters of the host, which asks to wait longer, will fail.
+pub(crate) const MAX_HOST_RETRY_AFTER_SEC: u64 = 300;
+
 /// Configuration object
+#[derive(Clone)]
 pub struct Configuration {
     /// Type of filter lists to manage
     pub filter_list_type: FilterListType,
@@ -63,6 +90,19 @@ pub struct Configuration {
     pub metadata_url: String,
     /// URL of the locales (filters_i18n.json) file
     pub metadata_locales_url: String,
+    /// Mirrors of [`Self::metadata_url`].
+    /// They are requested in order, if the previous URL fails with a network error.
+    /// Default value: empty
+    pub metadata_mirror_urls: Vec<String>,
+    /// Mirrors of [`Self::metadata_locales_url`]. Ignored if `metadata_locales_url` is empty.
+    /// Default value: empty
+    pub metadata_locales_mirror_urls: Vec<String>,
+    /// Additional filter registries, which are synced by `pull_metadata` after the main one,
+    /// described by [`Self::metadata_url`]. Each registry has its own range of filters ids,
+    /// see [`crate::FILTER_REGISTRY_ID_NAMESPACE_SIZE`].
+    /// Main registry is skipped, if `metadata_url` is empty and there are additional ones.
+    /// Default value: empty
+    pub filter_registries: Vec<FilterRegistry>,
     /// Requests timeouts in milliseconds. Default value 60000
     pub request_timeout_ms: i32,
     /// Requests proxy mode
@@ -99,6 +139,58 @@ pub struct Configuration {
     /// simultaneously.
     /// Default value: 60.
     pub filter_update_dispatch_delay_ms: i32,
+    /// Number of extra download attempts for a filter, if its update has failed
+    /// due to a transient error: network failure, timeout, 5xx, 408 or 429 status code.
+    /// If server responded with `Retry-After`, the filter is put back to the update queue
+    /// instead of immediate retry, and this also counts as an attempt.
+    /// Default value: 2.
+    /// Values <= 0 disable retries.
+    pub filter_update_max_retries: i32,
+    /// Delay in milliseconds before the first retry. Doubles after every attempt,
+    /// but won't exceed 30 seconds.
+    /// Default value: 1000.
+    pub filter_update_retry_delay_ms: i32,
+    /// Delay in seconds, during which the filter, which update has failed,
+    /// will be skipped by regular updates. Doubles after every consecutive failure,
+    /// but won't exceed one day. Forced updates ignore this delay.
+    /// Default value: 3600.
+    /// Values <= 0 disable skipping.
+    pub filter_failure_backoff_sec: i32,
+    /// Maximum number of concurrent filter downloads from the same host.
+    /// Default value: 0.
+    /// Values <= 0 disable the limit, so only `filter_update_concurrency` is applied.
+    pub filter_update_max_concurrency_per_host: i32,
+    /// Maximum number of filter download dispatches per minute for the same host.
+    /// Limit is applied with token bucket, see `filter_update_host_rate_burst`.
+    /// Default value: 0.
+    /// Values <= 0 disable the limit.
+    pub filter_update_host_rate_limit_per_min: i32,
+    /// Number of dispatches to the same host, which may be done at once,
+    /// before `filter_update_host_rate_limit_per_min` starts to apply.
+    /// Default value: 1.
+    /// Values < 1 will be treated as 1.
+    pub filter_update_host_rate_burst: i32,
+    /// Compression of rules texts and includes bodies saved into the database.
+    /// Hashes and integrity signatures are computed on uncompressed contents.
+    /// Default value: [`RulesStorageCompression::None`]
+    pub rules_storage_compression: RulesStorageCompression,
+    /// Number of previous versions of contents, which are kept for every filter after updates.
+    /// Filter may be rolled back to any of them with [`crate::FilterListManager::rollback_filter`].
+    /// Default value: 0.
+    /// Values <= 0 disable keeping versions, so already kept versions will be deleted
+    /// by the next updates of their filters.
+    pub filter_versions_retention: i32,
+    /// Transport for all HTTP(S) requests.
+    /// If value is [`None`], built-in client will be used.
+    /// Custom transport ignores `request_timeout_ms`, `request_proxy_mode`, `app_name` and `version`
+    pub http_transport: Option<Arc<dyn HttpTransport>>,
+    /// Key for the database encryption at rest with SQLCipher.
+    /// Requires the library to be built with `sqlcipher` feature.
+    /// If value is [`None`], database is stored as plaintext.
+    /// Existing plaintext database will be encrypted during `lift_up_database`.
+    /// Use [`crate::FilterListManager::change_database_encryption_key`] for the key rotation.
+    /// Default value: [`None`]
+    pub database_encryption_key: Option<String>,
 }
 
 /// Normalized locales delimiter
@@ -110,6 +202,51 @@ impl Configuration {
         self.locale = Configuration::normalize_locale_string(&self.locale);
     }
 
+    /// Checks that additional filter registries have unique non-reserved names
+    pub(crate) fn validate_filter_registries(&self) -> FLMResult<()> {
+        let mut names = HashSet::with_capacity(self.filter_registries.len());
+
+        for registry in self.filter_registries.iter() {
+            if registry.name.is_empty() {
+                return Err(FLMError::InvalidConfiguration(
+                    "filter registry name is empty",
+                ));
+            }
+            if registry.name == MAIN_FILTER_REGISTRY_NAME {
+                return Err(FLMError::InvalidConfiguration(
+                    "filter registry name is reserved for the main registry",
+                ));
+            }
+            if !names.insert(registry.name.as_str()) {
+                return Err(FLMError::InvalidConfiguration(
+                    "filter registry names must be unique",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets registries, which must be synced, starting from the main one.
+    /// Main registry is skipped, if its url is empty and there are additional registries
+    pub(crate) fn collect_filter_registries(&self) -> Vec<FilterRegistry> {
+        let mut registries = Vec::with_capacity(self.filter_registries.len() + 1);
+
+        if !self.metadata_url.is_empty() || self.filter_registries.is_empty() {
+            registries.push(FilterRegistry {
+                name: string!(MAIN_FILTER_REGISTRY_NAME),
+                metadata_url: self.metadata_url.clone(),
+                metadata_locales_url: self.metadata_locales_url.clone(),
+                metadata_mirror_urls: self.metadata_mirror_urls.clone(),
+                metadata_locales_mirror_urls: self.metadata_locales_mirror_urls.clone(),
+            });
+        }
+
+        registries.extend(self.filter_registries.iter().cloned());
+
+        registries
+    }
+
     /// Normalize locale string
     pub(crate) fn normalize_locale_string(locale: &Locale) -> Locale {
         locale.replace('-', LOCALES_DELIMITER)
@@ -141,6 +278,9 @@ impl Default for Configuration {
             metadata_url: String::new(),
             request_proxy_mode: RequestProxyMode::UseSystemProxy,
             metadata_locales_url: String::new(),
+            metadata_mirror_urls: vec![],
+            metadata_locales_mirror_urls: vec![],
+            filter_registries: vec![],
             should_ignore_expires_for_local_urls: false,
             request_timeout_ms: DEFAULT_REQUEST_TIMEOUT_MS,
             auto_lift_up_database: true,
@@ -149,6 +289,16 @@ impl Default for Configuration {
             integrity_key: None,
             filter_update_concurrency: DEFAULT_FILTER_UPDATE_CONCURRENCY,
             filter_update_dispatch_delay_ms: DEFAULT_FILTER_UPDATE_DISPATCH_DELAY_MS,
+            filter_update_max_retries: DEFAULT_FILTER_UPDATE_MAX_RETRIES,
+            filter_update_retry_delay_ms: DEFAULT_FILTER_UPDATE_RETRY_DELAY_MS,
+            filter_failure_backoff_sec: DEFAULT_FILTER_FAILURE_BACKOFF_SEC,
+            filter_update_max_concurrency_per_host: 0,
+            filter_update_host_rate_limit_per_min: 0,
+            filter_update_host_rate_burst: 1,
+            rules_storage_compression: RulesStorageCompression::None,
+            filter_versions_retention: 0,
+            http_transport: None,
+            database_encryption_key: None,
         }
     }
 }
@@ -1,4 +1,5 @@
 /// Proxy mode of operation for requests
+#[derive(Clone)]
 pub enum RequestProxyMode {
     /// System proxy will be used
     UseSystemProxy,
@@ -0,0 +1,15 @@
+//! Compression of filters contents in the database
+
+/// How rules texts of filters and bodies of their includes are stored in the database.
+///
+/// Changing this value affects only filters saved afterwards; already stored contents
+/// are read as is, so databases with mixed contents are supported.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RulesStorageCompression {
+    /// Contents are stored as plain text
+    #[default]
+    None,
+    /// Contents are compressed with zstd. Reduces the database size several times,
+    /// but requires decompression on every read
+    Zstd,
+}
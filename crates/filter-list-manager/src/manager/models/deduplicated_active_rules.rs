@@ -0,0 +1,28 @@
+//! Active rules, where each unique rule is emitted once
+use crate::{ActiveRulesInfo, ActiveRulesInfoRaw, FilterId};
+
+/// Count of duplicated rules, removed from the filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicatesByFilter {
+    /// Associated filter id
+    pub filter_id: FilterId,
+    /// Count of removed rules, which are kept in another filter
+    /// or appear earlier in the same filter
+    pub duplicates_count: i32,
+}
+
+/// Result of [`crate::FilterListManager::get_active_rules_deduplicated`]
+pub struct DeduplicatedActiveRules {
+    /// Active rules without duplicates
+    pub rules: Vec<ActiveRulesInfo>,
+    /// Removed duplicates for each filter of `rules`
+    pub duplicates: Vec<DuplicatesByFilter>,
+}
+
+/// Result of [`crate::FilterListManager::get_active_rules_raw_deduplicated`]
+pub struct DeduplicatedActiveRulesRaw {
+    /// Active rules without duplicates
+    pub rules: Vec<ActiveRulesInfoRaw>,
+    /// Removed duplicates for each filter of `rules`
+    pub duplicates: Vec<DuplicatesByFilter>,
+}
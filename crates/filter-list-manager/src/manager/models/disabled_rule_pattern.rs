@@ -0,0 +1,34 @@
+//! Patterns of disabled rules
+use crate::FilterId;
+
+/// Syntax of [`DisabledRulePattern::pattern`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisabledRulePatternKind {
+    /// `*` matches any sequence of characters, other characters are matched literally.
+    /// Pattern must match the whole rule, e.g. `*example.com*` or `*##+js(*`
+    Wildcard,
+    /// Regular expression. Rule is disabled if the expression matches any part of it,
+    /// so `^` and `$` should be used to match the whole rule
+    Regex,
+}
+
+/// Pattern, which disables all matching rules of the filter and its includes.
+///
+/// Patterns are stored apart from literal disabled rules, so literal rules,
+/// which look like patterns, are never treated as them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisabledRulePattern {
+    /// Syntax of the pattern
+    pub kind: DisabledRulePatternKind,
+    /// Pattern itself
+    pub pattern: String,
+}
+
+/// Disabled rule patterns of the filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisabledRulePatterns {
+    /// Associated filter id
+    pub filter_id: FilterId,
+    /// Patterns in the saved order
+    pub patterns: Vec<DisabledRulePattern>,
+}
@@ -0,0 +1,54 @@
+//! Models for filter linting
+
+/// How serious the [`FilterDiagnostic`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterDiagnosticSeverity {
+    /// Filter can't be compiled, or compiles not the way its author expects
+    Error,
+    /// Filter compiles, but the construction is suspicious or will be ignored
+    Warning,
+}
+
+/// Kind of problem, found by [`crate::FilterListManager::lint_filter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterDiagnosticKind {
+    /// `!#if` has no condition expression
+    EmptyIf,
+    /// `!#else` without `!#if`, or the second `!#else` for the same `!#if`
+    UnbalancedElse,
+    /// `!#endif` without `!#if`
+    UnbalancedEndIf,
+    /// `!#if` is not closed with `!#endif`
+    UnbalancedIf,
+    /// `!#if` condition can't be parsed
+    InvalidBooleanExpression,
+    /// `!#if` condition uses a constant, which is neither known to the compiler,
+    /// nor defined in [`crate::Configuration::filters_compilation_policy`].
+    /// Such constants are always `false`
+    UnknownConstant,
+    /// `!#include` has no path, or the path can't be resolved against the filter url
+    InvalidInclude,
+    /// `!#include` target has different origin than the filter
+    CrossOriginInclude,
+    /// `!#include` target is the filter itself
+    RecursiveInclude,
+    /// `! Expires` value can't be parsed, so default expiration will be used
+    MalformedExpires,
+    /// `! TimeUpdated` value is not a valid date, so download time will be used
+    MalformedTimeUpdated,
+    /// `! Checksum` doesn't match filter contents
+    ChecksumMismatch,
+}
+
+/// A problem, found in the filter body
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterDiagnostic {
+    /// Line number in the filter body. Starts from 1
+    pub line_number: u32,
+    /// How serious the problem is
+    pub severity: FilterDiagnosticSeverity,
+    /// Kind of the problem
+    pub kind: FilterDiagnosticKind,
+    /// Human-readable description
+    pub message: String,
+}
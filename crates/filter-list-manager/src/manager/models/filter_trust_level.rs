@@ -0,0 +1,30 @@
+//! Trust level of the registry filters
+
+/// Trust level of the filter list, assigned by the registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterTrustLevel {
+    /// First-party list. Only such lists are considered trusted
+    Full,
+    /// Well-known third-party list
+    High,
+    /// Community list
+    Low,
+}
+
+impl FilterTrustLevel {
+    /// Parses `trustLevel` value of the filters index.
+    /// Returns [`None`] for unknown levels
+    pub(crate) fn from_index_value(value: &str) -> Option<Self> {
+        match value {
+            "full" => Some(Self::Full),
+            "high" => Some(Self::High),
+            "low" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    /// Whether filters with this trust level are considered trusted
+    pub(crate) fn is_trusted(&self) -> bool {
+        *self == Self::Full
+    }
+}
@@ -0,0 +1,22 @@
+//! Persisted state of filter update failures
+use crate::FilterId;
+
+/// Consecutive update failures of the filter.
+/// The state is cleared after the next successful filter request
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterUpdateFailure {
+    /// ID of the filter
+    pub filter_id: FilterId,
+    /// Number of updates in a row, which have failed for this filter
+    pub consecutive_failures: i32,
+    /// Timestamp of the first failure in a row.
+    /// Tells how long the filter has been failing
+    pub first_failure_time: i64,
+    /// Timestamp of the last failure
+    pub last_failure_time: i64,
+    /// Last error converted to a string
+    pub last_error: String,
+    /// Regular updates will skip the filter until this timestamp.
+    /// See [`crate::Configuration::filter_failure_backoff_sec`]
+    pub next_retry_time: i64,
+}
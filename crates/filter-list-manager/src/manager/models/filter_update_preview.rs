@@ -0,0 +1,36 @@
+//! Model for filter update preview
+use crate::FilterId;
+
+/// Changes, which will be made by the filter update.
+/// See [`crate::FilterListManager::preview_filter_update`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterUpdatePreview {
+    /// ID of the filter
+    pub filter_id: FilterId,
+    /// Remote version has been requested via differential update
+    pub is_diff_update: bool,
+    /// Next differential update patch is not published yet, so the remote version
+    /// couldn't be checked. Other fields describe no changes then
+    pub is_patch_not_ready: bool,
+    /// Rule lines of the filter and its includes, which will appear after the update
+    pub added_rules: Vec<String>,
+    /// Rule lines of the filter and its includes, which will disappear after the update
+    pub removed_rules: Vec<String>,
+    /// Current `! Version`
+    pub old_version: String,
+    /// `! Version` after the update
+    pub new_version: String,
+    /// Current title
+    pub old_title: String,
+    /// Title after the update. Titles of index filters and user titles of custom filters are kept
+    pub new_title: String,
+    /// Current `! Expires` in seconds
+    pub old_expires: i32,
+    /// `! Expires` after the update in seconds. 0 if the remote filter has no `! Expires`
+    pub new_expires: i32,
+    /// Difference between rules count after and before the update, including includes
+    pub rules_count_delta: i32,
+    /// URL, which has served the remote version: the download URL or one of the filter mirrors.
+    /// [`None`] if the filter hasn't been requested or all URLs failed with network errors
+    pub served_url: Option<String>,
+}
@@ -0,0 +1,23 @@
+//! Previous versions of filters contents
+use crate::FilterId;
+
+/// Previous version of filter contents, which has been replaced by the update.
+/// The filter can be rolled back to it with [`crate::FilterListManager::rollback_filter`].
+/// See [`crate::Configuration::filter_versions_retention`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterVersion {
+    /// ID of this version
+    pub version_id: i64,
+    /// ID of the filter
+    pub filter_id: FilterId,
+    /// Filter version from its metadata
+    pub version: String,
+    /// Filter title at the moment of the replacement
+    pub title: String,
+    /// Filter update time from its metadata
+    pub last_update_time: i64,
+    /// Timestamp, when these contents have been replaced
+    pub saved_time: i64,
+    /// Rules count of the filter and its includes
+    pub rules_count: i32,
+}
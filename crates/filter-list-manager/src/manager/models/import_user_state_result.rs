@@ -0,0 +1,50 @@
+//! Models about user state import process
+
+use crate::manager::models::MovedFilterInfo;
+use crate::FilterId;
+
+/// Result of user state import
+#[derive(Default)]
+pub struct ImportUserStateResult {
+    /// Index filters, which state has been restored from the document
+    pub restored_filters: Vec<FilterId>,
+    /// Custom filters installed from the document.
+    /// `new_id` differs from `previous_id` if the exported id has already been taken
+    pub installed_custom_filters: Vec<MovedFilterInfo>,
+    /// Conflicts found during the import
+    pub conflicts: Vec<UserStateConflict>,
+}
+
+/// Kind of [`UserStateConflict`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserStateConflictKind {
+    /// Index filter from the document is not present in the database. Its state was skipped
+    FilterNotFound,
+    /// Filter has no downloaded rules yet, so its disabled rules were skipped
+    RulesListNotFound,
+    /// Custom filter with the same download url is already installed. Filter was skipped
+    CustomFilterAlreadyInstalled,
+    /// Id of the custom filter has already been taken. Filter was installed with a new id
+    CustomFilterIdCollision,
+}
+
+/// Describes an entry of the document, which could not be imported as is
+#[derive(Debug, Clone)]
+pub struct UserStateConflict {
+    /// Filter id from the document
+    pub filter_id: FilterId,
+    /// Conflict kind
+    pub kind: UserStateConflictKind,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl UserStateConflict {
+    pub(crate) fn new(filter_id: FilterId, kind: UserStateConflictKind, message: String) -> Self {
+        Self {
+            filter_id,
+            kind,
+            message,
+        }
+    }
+}
@@ -1,20 +1,33 @@
 //! Models associated with [`crate::FilterListManager`]
+pub mod active_rules_export;
 pub mod active_rules_info;
 pub mod active_rules_info_raw;
 pub mod configuration;
+pub mod deduplicated_active_rules;
+pub mod disabled_rule_pattern;
 pub mod disabled_rules_raw;
+pub mod filter_diagnostic;
 pub mod filter_group;
 pub mod filter_list_metadata;
 pub mod filter_list_metadata_with_body;
 pub mod filter_list_rules;
 pub mod filter_list_rules_raw;
 pub mod filter_tag;
+pub mod filter_trust_level;
+pub mod filter_update_failure;
+pub mod filter_update_preview;
+pub mod filter_version;
 pub mod flm_error;
 pub mod full_filter_list;
+pub mod import_user_state_result;
 pub mod pull_metadata_result;
+pub mod rule_provenance;
+pub mod rule_search;
 pub mod rules_count_by_filter;
 pub mod stored_filter_metadata;
+pub mod update_progress;
 pub mod update_result;
+pub mod update_scheduler;
 
 pub use self::disabled_rules_raw::DisabledRulesRaw;
 pub use self::filter_list_metadata::FilterListMetadata;
@@ -22,7 +35,7 @@ pub use self::filter_list_metadata_with_body::FilterListMetadataWithBody;
 pub use self::filter_list_rules_raw::FilterListRulesRaw;
 pub use self::flm_error::FLMError;
 pub use self::full_filter_list::FullFilterList;
-pub use self::pull_metadata_result::{MovedFilterInfo, PullMetadataResult};
+pub use self::pull_metadata_result::{FailedFilterRegistry, MovedFilterInfo, PullMetadataResult};
 pub use self::update_result::UpdateResult;
 
 /// Filter list id type alias
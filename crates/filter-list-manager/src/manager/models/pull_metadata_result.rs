@@ -10,6 +10,8 @@ pub struct PullMetadataResult {
     pub removed_filters: Vec<FilterId>,
     /// List of filters moved in the update
     pub moved_filters: Vec<MovedFilterInfo>,
+    /// Additional filter registries, which could not be synced
+    pub failed_registries: Vec<FailedFilterRegistry>,
 }
 
 impl PullMetadataResult {
@@ -19,6 +21,7 @@ impl PullMetadataResult {
             added_filters: vec![],
             removed_filters: vec![],
             moved_filters: vec![],
+            failed_registries: vec![],
         }
     }
 
@@ -31,8 +34,25 @@ impl PullMetadataResult {
             added_filters,
             removed_filters: vec![],
             moved_filters: vec![],
+            failed_registries: vec![],
         }
     }
+
+    /// Appends the result of another registry sync to this one
+    pub(crate) fn merge(&mut self, other: PullMetadataResult) {
+        self.added_filters.extend(other.added_filters);
+        self.removed_filters.extend(other.removed_filters);
+        self.moved_filters.extend(other.moved_filters);
+        self.failed_registries.extend(other.failed_registries);
+    }
+}
+
+/// Additional filter registry, which could not be synced during index metadata update
+pub struct FailedFilterRegistry {
+    /// Name of the registry
+    pub name: String,
+    /// Description of the error
+    pub message: String,
 }
 
 /// Information about filter movement during index metadata update
@@ -0,0 +1,17 @@
+//! Models for rule provenance lookup
+use crate::FilterId;
+
+/// Location of the rule, found by [`crate::FilterListManager::get_rule_provenance`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleProvenance {
+    /// Filter, which produces the rule
+    pub filter_id: FilterId,
+    /// Absolute URL of the first-level `!#include`, which contains the rule.
+    /// Nested includes are inlined into their first-level include.
+    /// [`None`] if the rule is in the filter body itself
+    pub include_url: Option<String>,
+    /// Line number in the filter body or in the include body. Starts from 1
+    pub line_number: u32,
+    /// Rule is in the list of disabled rules of the filter
+    pub is_disabled: bool,
+}
@@ -0,0 +1,31 @@
+//! Models for rules search across stored filters
+use crate::FilterId;
+
+/// Options of [`crate::FilterListManager::search_rules`]
+#[derive(Debug, Clone, Default)]
+pub struct RuleSearchOptions {
+    /// Treat query as a regular expression. Otherwise, query is a substring
+    pub is_regex: bool,
+    /// Case-sensitive matching
+    pub is_case_sensitive: bool,
+    /// Also return rules from `disabled_rules`. They will be marked with [`RuleSearchMatch::is_disabled`]
+    pub include_disabled_rules: bool,
+    /// Maximum number of matches. [`None`] means unlimited
+    pub limit: Option<u32>,
+}
+
+/// Single rule found by [`crate::FilterListManager::search_rules`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSearchMatch {
+    /// Filter, which contains the rule
+    pub filter_id: FilterId,
+    /// Absolute URL of the include, which contains the rule.
+    /// [`None`] if the rule is in the filter body itself
+    pub include_url: Option<String>,
+    /// Line number in the filter body or in the include body. Starts from 1
+    pub line_number: u32,
+    /// Rule text
+    pub rule: String,
+    /// Rule is in the list of disabled rules of the filter
+    pub is_disabled: bool,
+}
@@ -1,7 +1,7 @@
 //! lightweight analog of [`crate::FullFilterList`] without filter contents
 
 use crate::storage::entities::filter::filter_entity::FilterEntity;
-use crate::{FilterId, FilterTag};
+use crate::{FilterId, FilterTag, FilterTrustLevel};
 
 /// The lightweight analog of [`crate::FullFilterList`] without filter contents
 pub struct StoredFilterMetadata {
@@ -85,7 +85,16 @@ pub struct StoredFilterMetadata {
     /// what types of rules are allowed in the list. Comes either from the list
     /// metadata in the registry or passed by the caller in the case of a custom
     /// list.
+    ///
+    /// For registry filters it is derived from `trust_level`: lists with
+    /// [`FilterTrustLevel::High`] and [`FilterTrustLevel::Low`] are not trusted.
+    /// Lists without a known trust level stay trusted.
     pub is_trusted: bool,
+    /// Trust level of the filter list, assigned by the registry. It lets
+    /// engines and UI distinguish first-party lists from community ones.
+    ///
+    /// [`None`] for custom filter lists.
+    pub trust_level: Option<FilterTrustLevel>,
     /// Indicates whether the filter list came from a registry or was created by
     /// the caller or by the service filter lists. This field is used to
     /// distinguish between custom and registry filters when parsing the list
@@ -132,6 +141,11 @@ pub struct StoredFilterMetadata {
     /// code without locale (i.e. `en`, `zh`) or with locale (i.e. `en-GB`,
     /// etc.)
     pub languages: Vec<String>,
+    /// Name of the filter registry this filter list came from. The main
+    /// registry, configured with `metadata_url`, is named `main`.
+    ///
+    /// [`None`] for custom and service filter lists.
+    pub registry: Option<String>,
 }
 
 impl StoredFilterMetadata {
@@ -140,6 +154,7 @@ impl StoredFilterMetadata {
         entity: FilterEntity,
         tags: Vec<FilterTag>,
         languages: Vec<String>,
+        registry: Option<String>,
     ) -> Option<Self> {
         if let Some(filter_id) = entity.filter_id {
             let is_custom = entity.is_custom();
@@ -158,6 +173,7 @@ impl StoredFilterMetadata {
                 tags,
                 expires: entity.expires,
                 is_trusted: entity.is_trusted,
+                trust_level: entity.trust_level,
                 is_custom,
                 is_enabled: entity.is_enabled,
                 homepage: entity.homepage,
@@ -165,6 +181,7 @@ impl StoredFilterMetadata {
                 checksum: entity.checksum,
                 languages,
                 is_installed: entity.is_installed,
+                registry,
             });
         }
 
@@ -0,0 +1,110 @@
+//! Progress reporting and cancellation models for filters updates.
+use crate::FilterId;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Stage of filter (or index) processing, reported to [`UpdateObserver`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateProgressStage {
+    /// Filter is selected for update and waits for dispatch
+    Queued,
+    /// Contents are being downloaded
+    Downloading,
+    /// Filter has been updated via differential update
+    DiffPatched,
+    /// Filter has been downloaded and compiled
+    Compiled,
+    /// Updated data has been saved into the database
+    Saved,
+    /// Contents have not been changed since the last update
+    NotChanged,
+    /// Processing has failed. See [`UpdateProgressEvent::message`]
+    Failed,
+}
+
+/// Single progress event of `update_filters`/`pull_metadata` methods
+#[derive(Debug, Clone)]
+pub struct UpdateProgressEvent {
+    /// ID of the filter. [`None`] for index (registry) events
+    pub filter_id: Option<FilterId>,
+    /// Filter download url or index url
+    pub url: String,
+    /// Current stage
+    pub stage: UpdateProgressStage,
+    /// Error message for [`UpdateProgressStage::Failed`] stage
+    pub message: Option<String>,
+}
+
+/// Receives progress events of filters updates.
+///
+/// *NOTE:* Events are emitted from worker threads, so implementation should
+/// return as soon as possible.
+pub trait UpdateObserver: Send + Sync {
+    /// Called for every [`UpdateProgressEvent`]
+    fn on_update_progress(&self, event: UpdateProgressEvent);
+}
+
+/// Cancellation token for running `update_filters`/`pull_metadata` methods.
+///
+/// Token is shared between clones, so it can be cancelled from another thread.
+/// Cancellation applies to the running operation, or to the next one if none is running.
+/// Operations are counted, so cancellation of the finished operation doesn't affect the next one.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateCancellationToken {
+    state: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    /// Count of finished operations, i.e. generation of the current operation
+    generation: AtomicU64,
+    /// Generation of the cancelled operation plus one. `0` means nothing is cancelled
+    cancelled_generation: AtomicU64,
+}
+
+/// Marks the operation of [`UpdateCancellationToken`] as finished on drop
+#[must_use]
+pub(crate) struct UpdateOperationGuard {
+    state: Arc<CancellationState>,
+    generation: u64,
+}
+
+impl Drop for UpdateOperationGuard {
+    fn drop(&mut self) {
+        self.state
+            .generation
+            .fetch_max(self.generation + 1, Ordering::SeqCst);
+    }
+}
+
+impl UpdateCancellationToken {
+    /// Creates a new token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation of the currently running operation,
+    /// or of the next one if none is running
+    pub fn cancel(&self) {
+        let generation = self.state.generation.load(Ordering::SeqCst);
+
+        self.state
+            .cancelled_generation
+            .store(generation + 1, Ordering::SeqCst);
+    }
+
+    /// Was cancellation of the current operation requested
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled_generation.load(Ordering::SeqCst)
+            == self.state.generation.load(Ordering::SeqCst) + 1
+    }
+
+    /// Captures generation of the operation, which starts now.
+    /// Operation is finished, when the guard is dropped
+    pub(crate) fn begin(&self) -> UpdateOperationGuard {
+        UpdateOperationGuard {
+            state: Arc::clone(&self.state),
+            generation: self.state.generation.load(Ordering::SeqCst),
+        }
+    }
+}
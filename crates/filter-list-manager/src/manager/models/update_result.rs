@@ -1,4 +1,5 @@
 //! Filters update result models.
+use crate::FLMError;
 use crate::FilterId;
 use crate::FullFilterList;
 
@@ -13,6 +14,95 @@ pub struct UpdateResult {
     /// List of entities containing the filter id and a string representation of the error
     /// that occurred when processing or receiving the filter
     pub filters_errors: Vec<UpdateFilterError>,
+    /// Outcome of every filter passed to the update, including skipped ones
+    pub filters_reports: Vec<FilterUpdateReport>,
+}
+
+/// Why the filter was or wasn't updated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterUpdateOutcome {
+    /// Filter has been downloaded in full and saved
+    Updated,
+    /// Filter has been patched via differential update and saved
+    UpdatedViaDiff,
+    /// Filter is not expired yet, so it wasn't requested
+    NotExpired,
+    /// Version from the index is the same as the installed one, so filter wasn't requested
+    SameVersion,
+    /// Filter has been requested, but its contents are not changed.
+    /// This includes `304 Not Modified` responses
+    SameContent,
+    /// Filter is disabled, so it wasn't requested
+    Disabled,
+    /// Filter has no download url, so it can't be updated
+    NoDownloadUrl,
+    /// Filter hasn't been processed due to timeout or cancellation.
+    /// Such filters are counted in [`UpdateResult::remaining_filters_count`]
+    NotProcessed,
+    /// Filter couldn't be updated. See [`UpdateResult::filters_errors`] for details
+    Failed {
+        /// Failure category
+        kind: UpdateFailureKind,
+    },
+    /// Previous updates of the filter have failed, so it won't be requested
+    /// until [`crate::FilterUpdateFailure::next_retry_time`]
+    Postponed,
+    /// Filter has been rolled back and pinned, so it won't be requested
+    /// until [`crate::FilterListManager::unpin_filter`] is called
+    Pinned,
+    /// Next differential update patch of the filter is not published yet,
+    /// so its contents haven't been checked. The patch will be requested again by the next update
+    PatchNotReady,
+}
+
+/// Category of filter update failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateFailureKind {
+    /// Http request failed or server responded with unexpected status code
+    Network,
+    /// Local file couldn't be read
+    Io,
+    /// Filter checksum is invalid
+    InvalidChecksum,
+    /// Downloaded contents are likely not a filter
+    InvalidContent,
+    /// Filter contents couldn't be parsed, e.g. due to invalid directives or includes
+    Parser,
+    /// Other errors
+    Other,
+}
+
+/// Per-filter update report
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterUpdateReport {
+    /// ID of the filter
+    pub filter_id: FilterId,
+    /// Update outcome
+    pub outcome: FilterUpdateOutcome,
+    /// Number of bytes downloaded for the filter and its includes.
+    /// For differential updates only patch files are counted
+    pub downloaded_bytes: u64,
+    /// Status code of the filter response, if it has been requested via http(s)
+    pub http_status: Option<u16>,
+    /// Time spent on downloading and compiling the filter, in milliseconds
+    pub compile_duration_ms: u64,
+    /// URL, which has served the filter: its download URL or one of its mirrors from the index.
+    /// [`None`] if the filter hasn't been requested or all URLs failed with network errors
+    pub served_url: Option<String>,
+}
+
+impl FilterUpdateReport {
+    /// Creates a report for the filter, which hasn't been requested
+    pub(crate) const fn new(filter_id: FilterId, outcome: FilterUpdateOutcome) -> Self {
+        Self {
+            filter_id,
+            outcome,
+            downloaded_bytes: 0,
+            http_status: None,
+            compile_duration_ms: 0,
+            served_url: None,
+        }
+    }
 }
 
 /// Container for filter updating error
@@ -25,6 +115,13 @@ pub struct UpdateFilterError {
     pub filter_url: Option<String>,
     /// Http client error
     pub http_client_error: Option<String>,
+    /// Structured cause of the failure.
+    /// Compilation errors come as [`FLMError::ParseFilterError`],
+    /// whose inner [`crate::FilterParserError`] tells checksum mismatch, network failure,
+    /// invalid diff patch, etc. apart
+    pub error: Option<FLMError>,
+    /// Status code of the failed filter response, if there was one
+    pub http_status: Option<u16>,
 }
 
 impl UpdateFilterError {
@@ -35,6 +132,8 @@ impl UpdateFilterError {
             message,
             filter_url: None,
             http_client_error: None,
+            error: None,
+            http_status: None,
         }
     }
 }
@@ -0,0 +1,112 @@
+//! Settings and events of the built-in update scheduler.
+use crate::manager::models::{PullMetadataResult, UpdateResult};
+use crate::{FLMError, FLMResult};
+
+/// Default minimal period between scheduled filters updates. In seconds
+const DEFAULT_UPDATE_INTERVAL_SEC: i32 = 3600;
+
+/// Default period between scheduled metadata pulls. In seconds
+const DEFAULT_PULL_METADATA_INTERVAL_SEC: i32 = 604800;
+
+/// Default delay before the first retry, when the scheduled task has failed due to network. In seconds
+const DEFAULT_OFFLINE_BACKOFF_MIN_SEC: i32 = 60;
+
+/// Default upper bound of the retry delay, when the scheduled task has failed due to network. In seconds
+const DEFAULT_OFFLINE_BACKOFF_MAX_SEC: i32 = 3600;
+
+/// Settings of the scheduler, started with [`crate::FilterListManager::start_update_scheduler`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateSchedulerSettings {
+    /// Minimal period between filters updates in seconds.
+    /// Update is postponed further until at least one enabled filter is expired
+    /// or its differential update is due. Must be positive.
+    /// Default value: 3600 (1 hour)
+    pub update_interval_sec: i32,
+    /// Period between metadata pulls in seconds. Values less than 1 disable pulling.
+    /// Default value: 604800 (7 days)
+    pub pull_metadata_interval_sec: i32,
+    /// Delay before the first retry, if the task has failed because the device is offline.
+    /// Delay is doubled after every consecutive failure. Must be positive.
+    /// Default value: 60
+    pub offline_backoff_min_sec: i32,
+    /// Upper bound of the retry delay in seconds. Must not be less than
+    /// [`Self::offline_backoff_min_sec`].
+    /// Default value: 3600
+    pub offline_backoff_max_sec: i32,
+    /// `loose_timeout` argument for [`crate::FilterListManager::update_filters`] calls
+    pub loose_timeout: i32,
+}
+
+impl Default for UpdateSchedulerSettings {
+    fn default() -> Self {
+        Self {
+            update_interval_sec: DEFAULT_UPDATE_INTERVAL_SEC,
+            pull_metadata_interval_sec: DEFAULT_PULL_METADATA_INTERVAL_SEC,
+            offline_backoff_min_sec: DEFAULT_OFFLINE_BACKOFF_MIN_SEC,
+            offline_backoff_max_sec: DEFAULT_OFFLINE_BACKOFF_MAX_SEC,
+            loose_timeout: 0,
+        }
+    }
+}
+
+impl UpdateSchedulerSettings {
+    /// Checks settings consistency
+    pub(crate) fn validate(&self) -> FLMResult<()> {
+        if self.update_interval_sec < 1 {
+            return Err(FLMError::InvalidConfiguration(
+                "update_interval_sec must be positive",
+            ));
+        }
+        if self.offline_backoff_min_sec < 1 {
+            return Err(FLMError::InvalidConfiguration(
+                "offline_backoff_min_sec must be positive",
+            ));
+        }
+        if self.offline_backoff_max_sec < self.offline_backoff_min_sec {
+            return Err(FLMError::InvalidConfiguration(
+                "offline_backoff_max_sec must not be less than offline_backoff_min_sec",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Task, run by the scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateSchedulerTask {
+    /// [`crate::FilterListManager::update_filters`] call
+    UpdateFilters,
+    /// [`crate::FilterListManager::pull_metadata`] call
+    PullMetadata,
+    /// Restart of the scheduler after the configuration change. It's not scheduled
+    /// and is reported only on failure, with [`UpdateSchedulerEvent::error`] set
+    Restart,
+}
+
+/// Outcome of the scheduled task, passed to [`UpdateSchedulerObserver`]
+pub struct UpdateSchedulerEvent {
+    /// Task, which has been run
+    pub task: UpdateSchedulerTask,
+    /// Result of [`UpdateSchedulerTask::UpdateFilters`] task
+    pub update_result: Option<UpdateResult>,
+    /// Result of [`UpdateSchedulerTask::PullMetadata`] task
+    pub pull_metadata_result: Option<PullMetadataResult>,
+    /// Error, if the task has failed
+    pub error: Option<FLMError>,
+    /// Task has failed due to network, so it will be retried with backoff
+    pub is_offline: bool,
+    /// Time of the next scheduled task. Unix timestamp in seconds.
+    /// `0` for [`UpdateSchedulerTask::Restart`], because the scheduler has been stopped
+    pub next_run_time: i64,
+}
+
+/// Receives outcomes of the scheduled tasks.
+///
+/// *NOTE:* Events are emitted from the scheduler thread. Implementation should
+/// return as soon as possible and must not wait for the manager methods,
+/// because stopping the scheduler waits for the running task.
+pub trait UpdateSchedulerObserver: Send + Sync {
+    /// Called after every scheduled task
+    fn on_scheduled_task_finished(&self, event: UpdateSchedulerEvent);
+}
@@ -0,0 +1,315 @@
+use crate::manager::models::configuration::Configuration;
+use crate::utils::token_bucket::TokenBucket;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How long a worker may wait, when it is unknown when the next item becomes available
+const IDLE_WAIT: Duration = Duration::from_millis(100);
+
+/// Per-host dispatch limits
+#[derive(Clone, Copy, Default)]
+pub(crate) struct HostLimits {
+    /// Maximum number of in-flight items per host. 0 means no limit
+    pub(crate) max_concurrency: usize,
+    /// Maximum number of dispatches per minute per host. 0 means no limit
+    pub(crate) rate_limit_per_min: u32,
+    /// Token bucket capacity
+    pub(crate) rate_burst: u32,
+}
+
+impl HostLimits {
+    pub(crate) fn from_configuration(configuration: &Configuration) -> Self {
+        Self {
+            max_concurrency: configuration.filter_update_max_concurrency_per_host.max(0) as usize,
+            rate_limit_per_min: configuration.filter_update_host_rate_limit_per_min.max(0) as u32,
+            rate_burst: configuration.filter_update_host_rate_burst.max(1) as u32,
+        }
+    }
+}
+
+/// Dispatch state of the host
+struct HostState {
+    /// Number of in-flight items
+    active: usize,
+    /// Host asked not to be requested until this time
+    paused_until: Option<Instant>,
+    /// Rate limiter. [`None`] if rate is unlimited
+    bucket: Option<TokenBucket>,
+}
+
+impl HostState {
+    fn new(limits: &HostLimits, now: Instant) -> Self {
+        Self {
+            active: 0,
+            paused_until: None,
+            bucket: (limits.rate_limit_per_min > 0).then(|| {
+                TokenBucket::new(
+                    limits.rate_burst,
+                    limits.rate_limit_per_min as f64 / 60.0,
+                    now,
+                )
+            }),
+        }
+    }
+
+    /// Checks host limits and marks the host as busy, if item may be dispatched.
+    /// Otherwise, returns approximate time to wait
+    fn try_acquire(&mut self, limits: &HostLimits, now: Instant) -> Result<(), Duration> {
+        if let Some(paused_until) = self.paused_until {
+            if paused_until > now {
+                return Err(paused_until - now);
+            }
+
+            self.paused_until = None;
+        }
+
+        // Item will be completed at unknown moment
+        if limits.max_concurrency > 0 && self.active >= limits.max_concurrency {
+            return Err(IDLE_WAIT);
+        }
+
+        if let Some(ref mut bucket) = self.bucket {
+            bucket.try_acquire(now)?;
+        }
+
+        self.active += 1;
+
+        Ok(())
+    }
+}
+
+/// Result of the attempt to take next item from [`UpdateDispatchQueue`]
+pub(crate) enum Dispatch<T> {
+    /// Item is ready to be processed
+    Item(T),
+    /// Nothing can be dispatched right now. Try again after this delay or when any item is completed
+    Wait(Duration),
+    /// All items are processed
+    Done,
+}
+
+/// Queue of filter updates, which respects global dispatch delay
+/// and per-host concurrency and rate limits, and can pause hosts.
+///
+/// Items without host (e.g. local files) are limited only by global dispatch delay
+pub(crate) struct UpdateDispatchQueue<T> {
+    /// Queued items with their hosts in dispatch order
+    queue: VecDeque<(Option<String>, T)>,
+    /// States of known hosts
+    hosts: HashMap<String, HostState>,
+    /// Per-host limits
+    limits: HostLimits,
+    /// Minimum delay between any two dispatches
+    dispatch_delay: Duration,
+    /// Time of the last dispatch
+    last_dispatch: Option<Instant>,
+    /// Number of dispatched, but not completed items
+    in_flight: usize,
+}
+
+impl<T> UpdateDispatchQueue<T> {
+    pub(crate) fn new(limits: HostLimits, dispatch_delay: Duration) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            hosts: HashMap::new(),
+            limits,
+            dispatch_delay,
+            last_dispatch: None,
+            in_flight: 0,
+        }
+    }
+
+    /// Adds item to the end of the queue
+    pub(crate) fn push(&mut self, host: Option<String>, item: T) {
+        self.queue.push_back((host, item));
+    }
+
+    /// Takes first item, which host is not paused and not limited at the moment `now`
+    pub(crate) fn next(&mut self, now: Instant) -> Dispatch<T> {
+        if self.queue.is_empty() {
+            // In-flight items may be pushed back
+            return if self.in_flight == 0 {
+                Dispatch::Done
+            } else {
+                Dispatch::Wait(IDLE_WAIT)
+            };
+        }
+
+        if let Some(last_dispatch) = self.last_dispatch {
+            let elapsed = now.saturating_duration_since(last_dispatch);
+            if elapsed < self.dispatch_delay {
+                return Dispatch::Wait(self.dispatch_delay - elapsed);
+            }
+        }
+
+        let mut wait = IDLE_WAIT;
+        let mut ready_position = None;
+        for (position, (host, _)) in self.queue.iter().enumerate() {
+            let Some(host) = host else {
+                ready_position = Some(position);
+                break;
+            };
+
+            let state = self
+                .hosts
+                .entry(host.clone())
+                .or_insert_with(|| HostState::new(&self.limits, now));
+
+            match state.try_acquire(&self.limits, now) {
+                Ok(()) => {
+                    ready_position = Some(position);
+                    break;
+                }
+                Err(host_wait) => wait = wait.min(host_wait),
+            }
+        }
+
+        let Some((_, item)) = ready_position.and_then(|position| self.queue.remove(position))
+        else {
+            return Dispatch::Wait(wait);
+        };
+
+        self.last_dispatch = Some(now);
+        self.in_flight += 1;
+
+        Dispatch::Item(item)
+    }
+
+    /// Marks item of the `host` as processed
+    pub(crate) fn complete(&mut self, host: Option<&str>) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+
+        if let Some(state) = host.and_then(|host| self.hosts.get_mut(host)) {
+            state.active = state.active.saturating_sub(1);
+        }
+    }
+
+    /// Puts item back to the queue and pauses its host until `until`.
+    /// Item must be taken via [`Self::next`] before
+    pub(crate) fn reschedule(&mut self, host: Option<String>, item: T, until: Instant) {
+        self.complete(host.as_deref());
+
+        if let Some(state) = host.as_deref().and_then(|host| self.hosts.get_mut(host)) {
+            state.paused_until = state.paused_until.max(Some(until));
+        }
+
+        self.queue.push_back((host, item));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dispatch, HostLimits, UpdateDispatchQueue};
+    use std::time::{Duration, Instant};
+
+    fn host(name: &str) -> Option<String> {
+        Some(name.to_string())
+    }
+
+    fn take<T>(queue: &mut UpdateDispatchQueue<T>, now: Instant) -> Option<T> {
+        match queue.next(now) {
+            Dispatch::Item(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_host_concurrency_limit() {
+        let mut queue = UpdateDispatchQueue::new(
+            HostLimits {
+                max_concurrency: 1,
+                ..Default::default()
+            },
+            Duration::ZERO,
+        );
+        let now = Instant::now();
+
+        queue.push(host("a.com"), 1);
+        queue.push(host("a.com"), 2);
+        queue.push(host("b.com"), 3);
+        queue.push(None, 4);
+
+        assert_eq!(take(&mut queue, now), Some(1));
+        // The second item of a.com must wait for the first one
+        assert_eq!(take(&mut queue, now), Some(3));
+        assert_eq!(take(&mut queue, now), Some(4));
+        assert_eq!(take(&mut queue, now), None);
+
+        queue.complete(Some("a.com"));
+        assert_eq!(take(&mut queue, now), Some(2));
+
+        queue.complete(Some("a.com"));
+        queue.complete(Some("b.com"));
+        assert!(matches!(queue.next(now), Dispatch::Wait(_)));
+
+        queue.complete(None);
+        assert!(matches!(queue.next(now), Dispatch::Done));
+    }
+
+    #[test]
+    fn test_host_rate_limit() {
+        let mut queue = UpdateDispatchQueue::new(
+            HostLimits {
+                max_concurrency: 0,
+                rate_limit_per_min: 60,
+                rate_burst: 2,
+            },
+            Duration::ZERO,
+        );
+        let now = Instant::now();
+
+        for item in 0..3 {
+            queue.push(host("a.com"), item);
+        }
+
+        assert_eq!(take(&mut queue, now), Some(0));
+        assert_eq!(take(&mut queue, now), Some(1));
+
+        match queue.next(now) {
+            Dispatch::Wait(wait) => assert_eq!(wait, Duration::from_millis(100)),
+            _ => panic!("Rate limit must be applied"),
+        }
+
+        assert_eq!(take(&mut queue, now + Duration::from_secs(1)), Some(2));
+    }
+
+    #[test]
+    fn test_reschedule_pauses_host() {
+        let mut queue = UpdateDispatchQueue::new(HostLimits::default(), Duration::ZERO);
+        let now = Instant::now();
+
+        queue.push(host("a.com"), 1);
+        queue.push(host("a.com"), 2);
+        queue.push(host("b.com"), 3);
+
+        assert_eq!(take(&mut queue, now), Some(1));
+        queue.reschedule(host("a.com"), 1, now + Duration::from_secs(10));
+
+        // Only b.com is available
+        assert_eq!(take(&mut queue, now), Some(3));
+        match queue.next(now + Duration::from_secs(5)) {
+            Dispatch::Wait(wait) => assert!(wait <= Duration::from_secs(5)),
+            _ => panic!("Host must be paused"),
+        }
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(take(&mut queue, later), Some(2));
+        assert_eq!(take(&mut queue, later), Some(1));
+    }
+
+    #[test]
+    fn test_global_dispatch_delay() {
+        let mut queue = UpdateDispatchQueue::new(HostLimits::default(), Duration::from_secs(1));
+        let now = Instant::now();
+
+        queue.push(host("a.com"), 1);
+        queue.push(host("b.com"), 2);
+
+        assert_eq!(take(&mut queue, now), Some(1));
+        match queue.next(now + Duration::from_millis(400)) {
+            Dispatch::Wait(wait) => assert_eq!(wait, Duration::from_millis(600)),
+            _ => panic!("Dispatch delay must be applied"),
+        }
+        assert_eq!(take(&mut queue, now + Duration::from_secs(1)), Some(2));
+    }
+}
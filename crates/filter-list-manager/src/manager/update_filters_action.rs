@@ -3,41 +3,91 @@ use crate::filters::parser::diff_updates::batch_patches_container::BatchPatchesC
 use crate::filters::parser::diff_updates::process_diff_path::process_diff_path;
 use crate::filters::parser::filter_compiler::FilterCompiler;
 use crate::filters::parser::filter_contents_provider::diff_path_provider::DiffPathProvider;
+use crate::filters::parser::is_rule_detector::is_line_is_rule;
 use crate::filters::parser::metadata::parsers::expires::process_expires;
 use crate::filters::parser::metadata::KnownMetadataProperty;
 use crate::filters::parser::parser_error::FilterParserErrorContext;
 use crate::io::fetch_by_schemes::fetch_json_by_scheme;
 use crate::io::get_scheme;
 use crate::io::http::blocking_client::BlockingClient;
+use crate::io::http::cache_validators::CacheValidators;
 use crate::io::url_schemes::UrlSchemes;
 use crate::manager::filter_lists_builder::FullFilterListBuilder;
-use crate::manager::models::configuration::DEFAULT_FILTER_UPDATE_CONCURRENCY;
-use crate::manager::models::update_result::UpdateFilterError;
+use crate::manager::models::configuration::{
+    DEFAULT_FILTER_UPDATE_CONCURRENCY, MAX_FILTER_FAILURE_BACKOFF_SEC,
+    MAX_FILTER_UPDATE_RETRY_DELAY_MS, MAX_HOST_RETRY_AFTER_SEC,
+};
+use crate::manager::models::filter_update_preview::FilterUpdatePreview;
+use crate::manager::models::update_progress::UpdateProgressStage;
+use crate::manager::models::update_result::{
+    FilterUpdateOutcome, FilterUpdateReport, UpdateFailureKind, UpdateFilterError,
+};
 use crate::manager::models::UpdateResult;
+use crate::manager::update_dispatch_queue::{Dispatch, HostLimits, UpdateDispatchQueue};
+use crate::manager::update_progress_reporter::UpdateProgressReporter;
 use crate::storage::entities::diff_update_entity::DiffUpdateEntity;
 use crate::storage::entities::filter::filter_entity::FilterEntity;
 use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
+use crate::storage::entities::filter_update_failure_entity::FilterUpdateFailureEntity;
+use crate::storage::entities::http_cache_validators_entity::HttpCacheValidatorsEntity;
 use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
+use crate::storage::registry_id_namespace::RegistryIdNamespace;
 use crate::storage::repositories::diff_updates_repository::{DiffUpdateRepository, DiffUpdatesMap};
 use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
+use crate::storage::repositories::filter_mirror_urls_repository::FilterMirrorUrlsRepository;
+use crate::storage::repositories::filter_pins_repository::FilterPinsRepository;
+use crate::storage::repositories::filter_registries_repository::{
+    FilterRegistriesRepository, MapRegistryIdOnName,
+};
 use crate::storage::repositories::filter_repository::FilterRepository;
+use crate::storage::repositories::filter_update_failures_repository::FilterUpdateFailuresRepository;
+use crate::storage::repositories::filter_versions_repository::FilterVersionsRepository;
+use crate::storage::repositories::http_cache_validators_repository::HttpCacheValidatorsRepository;
 use crate::storage::repositories::rules_list_repository::{
     MapFilterIdOnRulesString, RulesListRepository,
 };
-use crate::storage::repositories::Repository;
+use crate::storage::repositories::{BulkDeleteRepository, Repository};
 use crate::storage::sql_generators::operator::SQLOperator;
 use crate::storage::with_transaction;
 use crate::storage::DbConnectionManager;
+use crate::utils::backoff::exponential_backoff;
 use crate::utils::integrity;
 use crate::utils::memory::heap;
-use crate::{Configuration, FLMError, FLMResult, FilterId, FilterParserError};
+use crate::{
+    Configuration, FLMError, FLMResult, FilterId, FilterParserError, FilterRegistry,
+    HttpClientError, MAIN_FILTER_REGISTRY_NAME,
+};
 use chrono::{DateTime, ParseError, Utc};
 use rusqlite::types::Value;
 use rusqlite::{Connection, Transaction};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
 use std::time::{Duration, Instant};
+use url::Url;
+
+/// Longest time, which idle worker waits before checking cancellation and timeout
+const MAX_WORKER_WAIT: Duration = Duration::from_millis(100);
+
+/// Filter, prepared for compilation
+struct CompilationTask<'compiler> {
+    /// ID of the filter
+    filter_id: FilterId,
+    /// Base entity
+    filter: FilterEntity,
+    /// Filter compiler with all filter data
+    compiler: FilterCompiler<'compiler>,
+    /// Filter will be updated via differential update
+    is_diff_update: bool,
+    /// Host of the download url. [`None`] for local files
+    host: Option<String>,
+    /// Number of already made retries
+    attempts: u32,
+    /// Alternate download urls from the index
+    mirror_urls: Vec<String>,
+    /// Url, which has served the filter during the last compilation
+    served_url: Option<String>,
+}
 
 /// Compiled filter entry with metadata
 struct CompilationListEntry<'compiler> {
@@ -51,6 +101,64 @@ struct CompilationListEntry<'compiler> {
     compiler: FilterCompiler<'compiler>,
     /// Result of compilation
     compilation_result: Result<String, FilterParserErrorContext>,
+    /// Download and compilation details
+    stats: CompilationStats,
+}
+
+/// Retries of filter download after transient failures
+#[derive(Clone, Copy)]
+struct RetryPolicy {
+    /// Number of extra attempts
+    max_retries: u32,
+    /// Delay before the first retry. Doubles after every attempt
+    base_delay_ms: u64,
+}
+
+impl RetryPolicy {
+    fn from_configuration(configuration: &Configuration) -> Self {
+        Self {
+            max_retries: configuration.filter_update_max_retries.max(0) as u32,
+            base_delay_ms: configuration.filter_update_retry_delay_ms.max(0) as u64,
+        }
+    }
+
+    /// Gets delay before the retry by zero-based `attempt` number
+    fn get_delay(&self, attempt: u32) -> Duration {
+        Duration::from_millis(exponential_backoff(
+            self.base_delay_ms,
+            attempt,
+            MAX_FILTER_UPDATE_RETRY_DELAY_MS,
+        ))
+    }
+}
+
+/// Details of filter download and compilation for [`FilterUpdateReport`]
+#[derive(Clone)]
+struct CompilationStats {
+    /// Filter has been requested via differential update
+    is_diff_update: bool,
+    /// Number of bytes downloaded for the filter and its includes
+    downloaded_bytes: usize,
+    /// Status code of the filter response
+    http_status: Option<u16>,
+    /// Time spent on downloading and compiling
+    duration: Duration,
+    /// Download url or one of its mirrors, which has served the filter
+    served_url: Option<String>,
+}
+
+impl CompilationStats {
+    /// Makes report of the requested filter
+    fn make_report(&self, filter_id: FilterId, outcome: FilterUpdateOutcome) -> FilterUpdateReport {
+        FilterUpdateReport {
+            filter_id,
+            outcome,
+            downloaded_bytes: self.downloaded_bytes as u64,
+            http_status: self.http_status,
+            compile_duration_ms: self.duration.as_millis() as u64,
+            served_url: self.served_url.clone(),
+        }
+    }
 }
 
 /// Tries to update passed filters
@@ -62,11 +170,17 @@ pub(super) fn update_filters_action(
     ignore_filters_status: bool,
     loose_timeout: i32,
     configuration: &Configuration,
+    progress_reporter: &UpdateProgressReporter,
 ) -> FLMResult<UpdateResult> {
+    let _operation = progress_reporter.begin();
+
     let filter_repository = FilterRepository::new();
     let rule_list_repository = RulesListRepository::new();
     let diff_updates_repository = DiffUpdateRepository::new();
     let filter_includes_repository = FilterIncludesRepository::new();
+    let http_cache_validators_repository = HttpCacheValidatorsRepository::new();
+    let filter_update_failures_repository = FilterUpdateFailuresRepository::new();
+    let filter_versions_repository = FilterVersionsRepository::new();
 
     let current_time = Utc::now().timestamp();
     let mut filter_entities: Vec<FilterEntity> = Vec::with_capacity(records.len());
@@ -77,6 +191,7 @@ pub(super) fn update_filters_action(
         updated_list: vec![],
         remaining_filters_count: 0,
         filters_errors: vec![],
+        filters_reports: Vec::with_capacity(records.len()),
     };
 
     let filter_ids = records
@@ -84,55 +199,157 @@ pub(super) fn update_filters_action(
         .filter_map(|filter| filter.filter_id)
         .collect::<Vec<FilterId>>();
 
-    let (mut diff_updates_map, mut rules_map, mut disabled_rules_map, rules_hashes, includes_map) =
-        db_connection_manager.execute_db(|conn: Connection| {
-            let diff_updates_map = diff_updates_repository
-                .select_map(&conn, &filter_ids)
-                .map_err(FLMError::from_database)?;
+    let download_urls = records
+        .iter()
+        .map(|filter| filter.download_url.as_str())
+        .filter(|download_url| !download_url.is_empty())
+        .collect::<Vec<&str>>();
+
+    let (
+        mut diff_updates_map,
+        mut rules_map,
+        mut disabled_rules_map,
+        rules_hashes,
+        includes_map,
+        mut cache_validators_map,
+        failures_map,
+        pins_map,
+        mut mirror_urls_map,
+        registry_names,
+    ) = db_connection_manager.execute_db(|conn: Connection| {
+        let diff_updates_map = diff_updates_repository
+            .select_map(&conn, &filter_ids)
+            .map_err(FLMError::from_database)?;
 
-            let (rules_map, disabled_rules_map, rules_hashes) = rule_list_repository
-                .select_rules_maps(&conn, &filter_ids)
-                .map_err(FLMError::from_database)?;
+        let (rules_map, disabled_rules_map, rules_hashes) = rule_list_repository
+            .select_rules_maps(&conn, &filter_ids)
+            .map_err(FLMError::from_database)?;
 
-            let includes_map = filter_includes_repository
-                .select_mapped(&conn, None)
-                .map_err(FLMError::from_database)?;
+        let includes_map = filter_includes_repository
+            .select_mapped(&conn, None)
+            .map_err(FLMError::from_database)?;
 
-            Ok((
-                diff_updates_map,
-                rules_map,
-                disabled_rules_map,
-                rules_hashes,
-                includes_map,
-            ))
-        })?;
+        let cache_validators_map = http_cache_validators_repository
+            .select_map(&conn, &download_urls)
+            .map_err(FLMError::from_database)?;
+
+        let failures_map = filter_update_failures_repository
+            .select_map(&conn, &filter_ids)
+            .map_err(FLMError::from_database)?;
+
+        let pins_map = FilterPinsRepository::new()
+            .select_map(&conn, &filter_ids)
+            .map_err(FLMError::from_database)?;
+
+        let mirror_urls_map = FilterMirrorUrlsRepository::new()
+            .select_map(&conn, &filter_ids)
+            .map_err(FLMError::from_database)?;
+
+        let registry_names = FilterRegistriesRepository::new()
+            .select_mapped(&conn)
+            .map_err(FLMError::from_database)?;
+
+        Ok((
+            diff_updates_map,
+            rules_map,
+            disabled_rules_map,
+            rules_hashes,
+            includes_map,
+            cache_validators_map,
+            failures_map,
+            pins_map,
+            mirror_urls_map,
+            registry_names,
+        ))
+    })?;
 
     let shared_http_client = BlockingClient::new(configuration)?;
 
-    let mut compilation_infos: Vec<(FilterId, FilterEntity, FilterCompiler)> = vec![];
-    let mut should_get_latest_filters_versions: bool = false;
+    let mut compilation_infos: Vec<CompilationTask> = vec![];
+    let mut registries_with_latest_versions: HashSet<i32> = HashSet::new();
     let batch_patches_container = BatchPatchesContainer::factory();
     for filter in records {
+        let Some(filter_id) = filter.filter_id else {
+            let message = "Cannot get filter contents from database".to_string();
+            let error = UpdateFilterError {
+                filter_id: 0,
+                message: message.clone(),
+                filter_url: Some(filter.download_url),
+                http_client_error: None,
+                error: Some(FLMError::Other(message)),
+                http_status: None,
+            };
+
+            progress_reporter.notify_update_error(&error);
+            update_result.filters_errors.push(error);
+            update_result.filters_reports.push(FilterUpdateReport::new(
+                0,
+                FilterUpdateOutcome::Failed {
+                    kind: UpdateFailureKind::Other,
+                },
+            ));
+
+            continue;
+        };
+
         if !ignore_filters_status && !filter.is_enabled {
+            update_result.filters_reports.push(FilterUpdateReport::new(
+                filter_id,
+                FilterUpdateOutcome::Disabled,
+            ));
+
             continue;
         }
 
         if filter.download_url.is_empty() {
+            update_result.filters_reports.push(FilterUpdateReport::new(
+                filter_id,
+                FilterUpdateOutcome::NoDownloadUrl,
+            ));
+
             continue;
         }
 
-        let filter_id = match filter.filter_id {
-            Some(filter_id) => filter_id,
-            None => {
-                update_result.filters_errors.push(UpdateFilterError {
-                    filter_id: 0,
-                    message: "Cannot get filter contents from database".to_string(),
-                    filter_url: Some(filter.download_url),
-                    http_client_error: None,
-                });
+        // Rolled back filters are not updated until they are unpinned
+        if pins_map.contains_key(&filter_id) {
+            update_result.filters_reports.push(FilterUpdateReport::new(
+                filter_id,
+                FilterUpdateOutcome::Pinned,
+            ));
 
-                continue;
-            }
+            continue;
+        }
+
+        // Recently failed filters are not requested until their next retry time.
+        // Forced updates request them anyway
+        if !ignore_filters_expiration
+            && failures_map
+                .get(&filter_id)
+                .is_some_and(|failure| failure.next_retry_time > current_time)
+        {
+            update_result.filters_reports.push(FilterUpdateReport::new(
+                filter_id,
+                FilterUpdateOutcome::Postponed,
+            ));
+
+            continue;
+        }
+
+        // Conditional request makes sense only for filters with already saved
+        // contents. Filters with includes are always requested in full,
+        // because their includes may change independently of the root filter
+        let can_use_cache_validators = !ignore_filters_expiration
+            && rules_map
+                .get(&filter_id)
+                .is_some_and(|rules| !rules.is_empty())
+            && includes_map
+                .get(&filter_id)
+                .is_none_or(|includes| includes.is_empty());
+
+        let cache_validators = if can_use_cache_validators {
+            cache_validators_map.remove(&filter.download_url)
+        } else {
+            None
         };
 
         let build_compiler_result = build_compiler(
@@ -145,33 +362,52 @@ pub(super) fn update_filters_action(
             &batch_patches_container,
             &filter,
             &shared_http_client,
+            cache_validators,
         );
 
-        let mut compiler = match build_compiler_result {
+        let (mut compiler, is_diff_update) = match build_compiler_result {
             // An error occurred
             Err(why) => {
-                update_result.filters_errors.push(UpdateFilterError {
+                let error = UpdateFilterError {
                     filter_id,
                     message: why.to_string(),
                     filter_url: Some(filter.download_url),
                     http_client_error: None,
-                });
+                    error: Some(why),
+                    http_status: None,
+                };
+
+                progress_reporter.notify_update_error(&error);
+                update_result.filters_errors.push(error);
+                update_result.filters_reports.push(FilterUpdateReport::new(
+                    filter_id,
+                    FilterUpdateOutcome::Failed {
+                        kind: UpdateFailureKind::Other,
+                    },
+                ));
 
                 continue;
             }
 
             // Not ready for update
             Ok((None, _)) => {
+                update_result.filters_reports.push(FilterUpdateReport::new(
+                    filter_id,
+                    FilterUpdateOutcome::NotExpired,
+                ));
+
                 continue;
             }
 
             Ok((Some(filter_compiler), filter_will_use_diff_update)) => {
-                // Should check index for new versions
+                // Should check index of the filter registry for new versions
                 if !filter_will_use_diff_update && !filter.is_custom() {
-                    should_get_latest_filters_versions = true;
+                    if let Some(namespace) = RegistryIdNamespace::of(filter_id) {
+                        registries_with_latest_versions.insert(namespace.registry_id());
+                    }
                 }
 
-                filter_compiler
+                (filter_compiler, filter_will_use_diff_update)
             }
         };
 
@@ -179,35 +415,68 @@ pub(super) fn update_filters_action(
             compiler.should_skip_checksum_validation(false);
         }
 
-        compilation_infos.push((filter_id, filter, compiler));
+        let host = Url::parse(&filter.download_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+
+        compilation_infos.push(CompilationTask {
+            filter_id,
+            filter,
+            compiler,
+            is_diff_update,
+            host,
+            attempts: 0,
+            mirror_urls: mirror_urls_map.remove(&filter_id).unwrap_or_default(),
+            served_url: None,
+        });
     }
 
     // Get latest filters versions
-    // only if at least one index filter is present,
-    // and it is not used diff update
+    // only for registries with at least one index filter,
+    // which is not used diff update
     let last_index_filter_versions = get_latest_filters_versions(
-        should_get_latest_filters_versions,
+        &registries_with_latest_versions,
+        &registry_names,
         &shared_http_client,
-        configuration.metadata_url.as_str(),
+        configuration,
     )?;
 
     // Pre-filter: skip filters with up-to-date versions
-    compilation_infos.retain(|(filter_id, filter, _)| {
-        if let Some(new_version) = last_index_filter_versions.get(filter_id) {
-            if !filter.version.is_empty() && &filter.version == new_version &&
+    compilation_infos.retain(
+        |CompilationTask {
+             filter_id, filter, ..
+         }| {
+            if let Some(new_version) = last_index_filter_versions.get(filter_id) {
+                if !filter.version.is_empty() && &filter.version == new_version &&
                 // Extra spike, 'cause we may already have metadata, but not the filters
                 // This stupid spike is here, 'cause we MIGHT fall in situation,
                 // when filter metadata.version is provided, it is up-to-date, BUT empty rules object is saved
                 rules_map.get(filter_id).map(|old_rules| !old_rules.is_empty()).unwrap_or_default()
-            {
-                return false;
+                {
+                    update_result.filters_reports.push(FilterUpdateReport::new(
+                        *filter_id,
+                        FilterUpdateOutcome::SameVersion,
+                    ));
+
+                    return false;
+                }
             }
-        }
 
-        true
-    });
+            true
+        },
+    );
     let rows_count = compilation_infos.len();
 
+    let mut queued_filter_ids: Vec<FilterId> = Vec::with_capacity(rows_count);
+    for task in compilation_infos.iter() {
+        queued_filter_ids.push(task.filter_id);
+        progress_reporter.notify(
+            Some(task.filter_id),
+            &task.filter.download_url,
+            UpdateProgressStage::Queued,
+        );
+    }
+
     // Multithreaded filter compilation
     let compile_results = compile_concurrently(
         compilation_infos,
@@ -218,6 +487,9 @@ pub(super) fn update_filters_action(
             .filter_update_concurrency
             .clamp(1, DEFAULT_FILTER_UPDATE_CONCURRENCY),
         configuration.filter_update_dispatch_delay_ms.max(0) as u64,
+        HostLimits::from_configuration(configuration),
+        RetryPolicy::from_configuration(configuration),
+        progress_reporter,
     );
 
     // Put here processed_filters
@@ -225,25 +497,103 @@ pub(super) fn update_filters_action(
     // Compilers with successful filter downloads
     let mut successful_compilers_with_result: Vec<(FilterId, FilterCompiler)> =
         Vec::with_capacity(rows_count);
+    // Filters, which were not modified on server since the last download
+    let mut not_modified_filter_ids: Vec<FilterId> = vec![];
+    // Validators of successful responses for next conditional requests
+    let mut cache_validators_entities: Vec<HttpCacheValidatorsEntity> = vec![];
+    // Download details of requested filters, which may be saved
+    let mut compilation_stats_map: HashMap<FilterId, CompilationStats> =
+        HashMap::with_capacity(compile_results.len());
+    // Failures of requested filters, which will be saved
+    let mut failure_entities: Vec<FilterUpdateFailureEntity> = vec![];
+    // Filters, which have been requested successfully, so their failures must be cleared
+    let mut recovered_filter_ids: Vec<FilterId> = vec![];
+
+    // Filters, which were not dispatched due to timeout or cancellation
+    let processed_filter_ids = compile_results
+        .iter()
+        .map(|entry| entry.filter_id)
+        .collect::<HashSet<FilterId>>();
+    for filter_id in queued_filter_ids {
+        if !processed_filter_ids.contains(&filter_id) {
+            update_result.filters_reports.push(FilterUpdateReport::new(
+                filter_id,
+                FilterUpdateOutcome::NotProcessed,
+            ));
+        }
+    }
 
     // Collect successfully compiled filters
     for compilation_entry in compile_results.into_iter() {
+        let stats = compilation_entry.stats;
+
         if let Err(err) = compilation_entry.compilation_result {
+            // NotModified means filter is up-to-date, only download time will be updated
+            if err.error == FilterParserError::NotModified {
+                not_modified_filter_ids.push(compilation_entry.filter_id);
+                recovered_filter_ids.push(compilation_entry.filter_id);
+                compilation_stats_map.insert(compilation_entry.filter_id, stats);
+
+                continue;
+            }
+
             // NoContent means update just unavailable yet
-            if err.error != FilterParserError::NoContent {
+            if err.error == FilterParserError::NoContent {
+                recovered_filter_ids.push(compilation_entry.filter_id);
+                update_result.filters_reports.push(stats.make_report(
+                    compilation_entry.filter_id,
+                    FilterUpdateOutcome::PatchNotReady,
+                ));
+            } else {
+                failure_entities.push(make_failure_entity(
+                    failures_map.get(&compilation_entry.filter_id),
+                    compilation_entry.filter_id,
+                    err.to_string(),
+                    current_time,
+                    configuration,
+                ));
+
+                update_result.filters_reports.push(stats.make_report(
+                    compilation_entry.filter_id,
+                    FilterUpdateOutcome::Failed {
+                        kind: get_failure_kind(&err.error),
+                    },
+                ));
+                let http_client_error = Some(&err.error)
+                    .filter(|e| matches!(e, FilterParserError::Network(_)))
+                    .map(|e| e.to_string());
+
                 update_result.filters_errors.push(UpdateFilterError {
                     filter_id: compilation_entry.filter_id,
                     message: err.to_string(),
                     filter_url: Some(compilation_entry.filter.download_url),
-                    http_client_error: Some(err.error)
-                        .filter(|e| matches!(e, FilterParserError::Network(_)))
-                        .map(|e| e.to_string()),
+                    http_client_error,
+                    error: Some(FLMError::ParseFilterError(err)),
+                    http_status: stats.http_status,
                 });
             }
 
             continue;
         }
 
+        // Validators of mirror response are not applicable to the download url
+        let is_served_by_download_url =
+            stats.served_url.as_ref() == Some(&compilation_entry.filter.download_url);
+
+        compilation_stats_map.insert(compilation_entry.filter_id, stats);
+        recovered_filter_ids.push(compilation_entry.filter_id);
+
+        if let Some(validators) = compilation_entry
+            .compiler
+            .take_response_cache_validators()
+            .filter(|_| is_served_by_download_url)
+        {
+            cache_validators_entities.push(HttpCacheValidatorsEntity::make(
+                compilation_entry.filter.download_url,
+                validators,
+            ));
+        }
+
         // compiler is None only on panic (already recorded as error above)
         successful_compilers_with_result
             .push((compilation_entry.filter_id, compilation_entry.compiler));
@@ -251,7 +601,9 @@ pub(super) fn update_filters_action(
 
     let successful_filter_ids = successful_compilers_with_result
         .iter()
-        .map(|entity| entity.0.into())
+        .map(|entity| entity.0)
+        .chain(not_modified_filter_ids.iter().copied())
+        .map(Value::from)
         .collect::<Vec<Value>>();
 
     update_result.updated_list = db_connection_manager.execute_db(|mut conn: Connection| {
@@ -266,15 +618,27 @@ pub(super) fn update_filters_action(
         // Gets from db second time, because filters may have changes
         for (filter_id, compiler) in successful_compilers_with_result {
             let Some(mut filter) = new_filters_map.remove(&filter_id) else {
-                update_result.filters_errors.push(UpdateFilterError {
+                let message = format!(
+                    "Filter with id \"{}\" is gone from database while updating",
+                    filter_id
+                );
+                let error = UpdateFilterError {
                     filter_id,
-                    message: format!(
-                        "Filter with id \"{}\" is gone from database while updating",
-                        filter_id
-                    ),
+                    message: message.clone(),
                     filter_url: None,
                     http_client_error: None,
-                });
+                    error: Some(FLMError::Other(message)),
+                    http_status: None,
+                };
+
+                progress_reporter.notify_update_error(&error);
+                update_result.filters_errors.push(error);
+                update_result.filters_reports.push(FilterUpdateReport::new(
+                    filter_id,
+                    FilterUpdateOutcome::Failed {
+                        kind: UpdateFailureKind::Other,
+                    },
+                ));
 
                 continue;
             };
@@ -345,6 +709,17 @@ pub(super) fn update_filters_action(
 
                                         if all_includes_are_same {
                                             // Filter is not changed
+                                            progress_reporter.notify(
+                                                Some(filter_id),
+                                                &filter.download_url,
+                                                UpdateProgressStage::NotChanged,
+                                            );
+                                            update_result.filters_reports.push(make_report(
+                                                &compilation_stats_map,
+                                                filter_id,
+                                                FilterUpdateOutcome::SameContent,
+                                            ));
+
                                             continue;
                                         }
                                     }
@@ -354,6 +729,17 @@ pub(super) fn update_filters_action(
                                 Some(old_includes) if old_includes.is_empty() => {
                                     // Current filter has no includes too
                                     if current_includes.is_empty() {
+                                        progress_reporter.notify(
+                                            Some(filter_id),
+                                            &filter.download_url,
+                                            UpdateProgressStage::NotChanged,
+                                        );
+                                        update_result.filters_reports.push(make_report(
+                                            &compilation_stats_map,
+                                            filter_id,
+                                            FilterUpdateOutcome::SameContent,
+                                        ));
+
                                         continue;
                                     }
                                 }
@@ -361,6 +747,17 @@ pub(super) fn update_filters_action(
                                 None => {
                                     // Old filter has no includes
                                     if current_includes.is_empty() {
+                                        progress_reporter.notify(
+                                            Some(filter_id),
+                                            &filter.download_url,
+                                            UpdateProgressStage::NotChanged,
+                                        );
+                                        update_result.filters_reports.push(make_report(
+                                            &compilation_stats_map,
+                                            filter_id,
+                                            FilterUpdateOutcome::SameContent,
+                                        ));
+
                                         continue;
                                     }
                                 }
@@ -376,12 +773,19 @@ pub(super) fn update_filters_action(
             if !diff_path.is_empty() {
                 match process_diff_path(filter_id, diff_path) {
                     Ok(Some(entity)) => diff_path_entities.push(entity),
-                    Err(why) => update_result.filters_errors.push(UpdateFilterError {
-                        filter_id,
-                        message: why.to_string(),
-                        filter_url: Some(filter.download_url.clone()),
-                        http_client_error: None,
-                    }),
+                    Err(why) => {
+                        let error = UpdateFilterError {
+                            filter_id,
+                            message: why.to_string(),
+                            filter_url: Some(filter.download_url.clone()),
+                            http_client_error: None,
+                            error: Some(FLMError::from_display(why)),
+                            http_status: None,
+                        };
+
+                        progress_reporter.notify_update_error(&error);
+                        update_result.filters_errors.push(error);
+                    }
                     _ => {}
                 }
             }
@@ -400,13 +804,84 @@ pub(super) fn update_filters_action(
             filter_entities.push(filter);
         }
 
+        // Not modified filters must be checked again only after expiration
+        let mut not_modified_filter_entities = Vec::with_capacity(not_modified_filter_ids.len());
+        for filter_id in not_modified_filter_ids {
+            if let Some(mut filter) = new_filters_map.remove(&filter_id) {
+                filter.last_download_time = current_time;
+
+                integrity::sign_filter_entity_if_needed(configuration, &mut filter);
+
+                not_modified_filter_entities.push(filter);
+            }
+        }
+
+        let saved_filter_ids = rules_entities
+            .iter()
+            .map(|entity| entity.filter_id)
+            .collect::<Vec<FilterId>>();
+
         with_transaction(&mut conn, |transaction: &Transaction| {
+            // Replaced contents must be kept before they are overwritten
+            if configuration.filter_versions_retention > 0 {
+                filter_versions_repository.save_current_versions(
+                    transaction,
+                    &saved_filter_ids,
+                    current_time,
+                )?;
+            }
+            filter_versions_repository.delete_outdated(
+                transaction,
+                &saved_filter_ids,
+                configuration.filter_versions_retention,
+            )?;
             filter_repository.insert(transaction, &filter_entities)?;
+            filter_repository.insert(transaction, &not_modified_filter_entities)?;
+            http_cache_validators_repository.insert(transaction, &cache_validators_entities)?;
             diff_updates_repository.insert(transaction, &diff_path_entities)?;
+            filter_update_failures_repository.bulk_delete(transaction, &recovered_filter_ids)?;
+            filter_update_failures_repository.insert(transaction, &failure_entities)?;
             rule_list_repository.insert(transaction, &rules_entities)?;
             filter_includes_repository.replace_entities_for_filters(transaction, &includes_entities)
         })?;
 
+        for filter in filter_entities.iter() {
+            progress_reporter.notify(
+                filter.filter_id,
+                &filter.download_url,
+                UpdateProgressStage::Saved,
+            );
+
+            if let Some(filter_id) = filter.filter_id {
+                let outcome = match compilation_stats_map.get(&filter_id) {
+                    Some(stats) if stats.is_diff_update => FilterUpdateOutcome::UpdatedViaDiff,
+                    _ => FilterUpdateOutcome::Updated,
+                };
+
+                update_result.filters_reports.push(make_report(
+                    &compilation_stats_map,
+                    filter_id,
+                    outcome,
+                ));
+            }
+        }
+
+        for filter in not_modified_filter_entities.iter() {
+            progress_reporter.notify(
+                filter.filter_id,
+                &filter.download_url,
+                UpdateProgressStage::NotChanged,
+            );
+
+            if let Some(filter_id) = filter.filter_id {
+                update_result.filters_reports.push(make_report(
+                    &compilation_stats_map,
+                    filter_id,
+                    FilterUpdateOutcome::SameContent,
+                ));
+            }
+        }
+
         let new_rules_map = rules_entities
             .into_iter()
             .fold(HashMap::new(), |mut acc, rule| {
@@ -446,6 +921,7 @@ fn build_compiler<'deps: 'compiler, 'compiler>(
     batch_patches_container: &Arc<Mutex<BatchPatchesContainer>>,
     filter: &FilterEntity,
     shared_http_client: &'deps BlockingClient,
+    cache_validators: Option<CacheValidators>,
 ) -> FLMResult<(Option<FilterCompiler<'compiler>>, bool)> {
     let expires_duration = configuration.resolve_right_expires_value(filter.expires) as i64;
 
@@ -456,7 +932,11 @@ fn build_compiler<'deps: 'compiler, 'compiler>(
     // We force full filter update through http or filter is ready for full update
     if ignore_filters_expiration || ready_for_full_update {
         return Ok((
-            Some(FilterCompiler::factory(configuration, shared_http_client)),
+            Some(FilterCompiler::with_cache_validators(
+                configuration,
+                shared_http_client,
+                cache_validators,
+            )),
             filter_will_use_diff_update,
         ));
     }
@@ -504,26 +984,38 @@ fn build_compiler<'deps: 'compiler, 'compiler>(
     Ok((None, filter_will_use_diff_update))
 }
 
-/// Compiles filters concurrently, using work-stealing algorithm
+/// Compiles filters concurrently, using work-stealing algorithm.
+///
+/// Dispatches respect global `dispatch_delay_ms` and per-host limits.
+/// If server asks to retry later via `Retry-After`, its host is paused,
+/// and the filter is put back to the queue
 fn compile_concurrently<'compilers>(
-    compilation_infos: Vec<(FilterId, FilterEntity, FilterCompiler<'compilers>)>,
+    compilation_infos: Vec<CompilationTask<'compilers>>,
     infos_count: usize,
     update_result: &mut UpdateResult,
     loose_timeout: u64,
     max_concurrent: usize,
     dispatch_delay_ms: u64,
+    host_limits: HostLimits,
+    retry_policy: RetryPolicy,
+    progress_reporter: &UpdateProgressReporter,
 ) -> Vec<CompilationListEntry<'compilers>> {
     let is_use_timeout = loose_timeout > 0;
     let start_time = Instant::now();
+    let deadline = is_use_timeout.then(|| start_time + Duration::from_secs(loose_timeout));
     let num_workers = max_concurrent.min(infos_count);
     let thread_errors = Mutex::new(Vec::<UpdateFilterError>::new());
 
-    let dispatch_delay = Duration::from_millis(dispatch_delay_ms);
+    let mut queue = UpdateDispatchQueue::new(host_limits, Duration::from_millis(dispatch_delay_ms));
+    for (index, task) in compilation_infos.into_iter().enumerate() {
+        queue.push(task.host.clone(), (index, task));
+    }
 
-    // Bundle the last-dispatch timestamp with the work iterator so that
-    // throttling is checked atomically under the same lock — no thread can
-    // skip the delay window.
-    let work = Mutex::new((compilation_infos.into_iter().enumerate(), Instant::now()));
+    // Throttling and host limits are checked under the same lock,
+    // so no thread can skip the delay window.
+    // Workers are woken up, when any filter is completed or rescheduled
+    let work = Mutex::new(queue);
+    let work_changed = Condvar::new();
 
     // Parallel compilation: download and compile filters concurrently
     let mut compilation_list = std::thread::scope(|s| {
@@ -532,45 +1024,104 @@ fn compile_concurrently<'compilers>(
                 s.spawn(|| {
                     let mut thread_results: Vec<CompilationListEntry> = Vec::new();
 
-                    while !is_use_timeout || start_time.elapsed().as_secs() <= loose_timeout {
+                    while !progress_reporter.is_cancelled()
+                        && (!is_use_timeout || start_time.elapsed().as_secs() <= loose_timeout)
+                    {
                         match work.lock() {
                             Ok(mut guard) => {
-                                let (ref mut tasks_list, ref mut last_dispatch) = *guard;
-
-                                // Get next task
-                                if let Some((index, (filter_id, filter, mut compiler))) =
-                                    tasks_list.next()
-                                {
-                                    // Throttle: if less than dispatch_delay has
-                                    // passed since the previous dispatch, sleep
-                                    // only the remainder.  The check runs under
-                                    // the work lock so no two threads can start
-                                    // a request within the same window.
-                                    if dispatch_delay_ms > 0 {
-                                        let elapsed = last_dispatch.elapsed();
-                                        if elapsed < dispatch_delay {
-                                            std::thread::sleep(dispatch_delay - elapsed);
-                                        }
-                                        *last_dispatch = Instant::now();
-                                    }
-
-                                    // Unlock mutex before compilation
-                                    drop(guard);
+                                let (index, mut task) = match guard.next(Instant::now()) {
+                                    Dispatch::Item(item) => item,
+                                    Dispatch::Wait(wait) => {
+                                        // Don't sleep for too long, to notice cancellation and timeout
+                                        let _ = work_changed
+                                            .wait_timeout(guard, wait.min(MAX_WORKER_WAIT));
 
-                                    // Download and compile filter
-                                    let compilation_result = compiler.compile(&filter.download_url);
-
-                                    thread_results.push(CompilationListEntry {
-                                        index,
-                                        filter_id,
-                                        filter,
-                                        compiler,
-                                        compilation_result,
-                                    });
+                                        continue;
+                                    }
+                                    Dispatch::Done => break,
+                                };
+
+                                // Unlock mutex before compilation
+                                drop(guard);
+
+                                progress_reporter.notify(
+                                    Some(task.filter_id),
+                                    &task.filter.download_url,
+                                    UpdateProgressStage::Downloading,
+                                );
+
+                                // Download and compile filter
+                                let started_at = Instant::now();
+                                let compilation_result = compile_with_retries(
+                                    &mut task,
+                                    retry_policy,
+                                    progress_reporter,
+                                );
+
+                                if let Some(pause_until) = get_host_pause_end(
+                                    &task,
+                                    &compilation_result,
+                                    retry_policy,
+                                    deadline,
+                                    progress_reporter,
+                                ) {
+                                    task.attempts += 1;
+                                    task.compiler.reset();
+
+                                    progress_reporter.notify(
+                                        Some(task.filter_id),
+                                        &task.filter.download_url,
+                                        UpdateProgressStage::Queued,
+                                    );
+
+                                    if let Ok(mut guard) = work.lock() {
+                                        guard.reschedule(
+                                            task.host.clone(),
+                                            (index, task),
+                                            pause_until,
+                                        );
+                                    }
+                                    work_changed.notify_all();
 
-                                    // Continue working
                                     continue;
                                 }
+
+                                let stats = CompilationStats {
+                                    is_diff_update: task.is_diff_update,
+                                    downloaded_bytes: task.compiler.get_downloaded_bytes(),
+                                    http_status: task.compiler.get_response_status().or_else(
+                                        || {
+                                            compilation_result.as_ref().err().and_then(|err| {
+                                                get_http_status_from_error(&err.error)
+                                            })
+                                        },
+                                    ),
+                                    duration: started_at.elapsed(),
+                                    served_url: task.served_url.clone(),
+                                };
+
+                                notify_compilation_result(
+                                    progress_reporter,
+                                    &task,
+                                    &compilation_result,
+                                );
+
+                                if let Ok(mut guard) = work.lock() {
+                                    guard.complete(task.host.as_deref());
+                                }
+                                work_changed.notify_all();
+
+                                thread_results.push(CompilationListEntry {
+                                    index,
+                                    filter_id: task.filter_id,
+                                    filter: task.filter,
+                                    compiler: task.compiler,
+                                    compilation_result,
+                                    stats,
+                                });
+
+                                // Continue working
+                                continue;
                             }
 
                             Err(why) => {
@@ -589,6 +1140,9 @@ fn compile_concurrently<'compilers>(
                         break;
                     }
 
+                    // Wake up other workers, so they can notice that work is over
+                    work_changed.notify_all();
+
                     thread_results
                 })
             })
@@ -619,120 +1173,711 @@ fn compile_concurrently<'compilers>(
     compilation_list
 }
 
-/// Gets the latest versions of index filters from the server
-fn get_latest_filters_versions(
-    should_get_latest_filters_versions: bool,
-    shared_http_client: &BlockingClient,
-    metadata_url: &str,
-) -> FLMResult<HashMap<FilterId, String>> {
-    if !should_get_latest_filters_versions {
-        return Ok(HashMap::new());
+/// Downloads and compiles filter. Transient failures are retried with exponential backoff.
+/// Failures with `Retry-After` are not retried here, see [`get_host_pause_end`].
+///
+/// If the download url is still unreachable, mirrors of the filter are requested once each, in order.
+/// Differential updates are requested from the download url only
+fn compile_with_retries(
+    task: &mut CompilationTask,
+    retry_policy: RetryPolicy,
+    progress_reporter: &UpdateProgressReporter,
+) -> Result<String, FilterParserErrorContext> {
+    task.served_url = None;
+
+    let compilation_result =
+        compile_download_url_with_retries(task, retry_policy, progress_reporter);
+    if !is_network_failure(&compilation_result) {
+        task.served_url = Some(task.filter.download_url.clone());
+
+        return compilation_result;
     }
 
-    let index = fetch_json_by_scheme::<IndexEntity>(
-        metadata_url,
-        get_scheme(metadata_url).into(),
-        shared_http_client,
-    )?;
+    if task.is_diff_update {
+        return compilation_result;
+    }
 
-    let mut last_index_filter_versions = HashMap::with_capacity(index.filters.len());
-    for entity in index.filters {
-        last_index_filter_versions.insert(entity.filterId, entity.version);
+    for mirror_url in task.mirror_urls.iter() {
+        if progress_reporter.is_cancelled() {
+            break;
+        }
+
+        progress_reporter.notify(
+            Some(task.filter_id),
+            mirror_url,
+            UpdateProgressStage::Downloading,
+        );
+
+        task.compiler.reset();
+        let mirror_result = task.compiler.compile(mirror_url);
+
+        if !is_network_failure(&mirror_result) {
+            task.served_url = Some(mirror_url.clone());
+
+            return mirror_result;
+        }
     }
 
-    Ok(last_index_filter_versions)
+    compilation_result
 }
 
-#[cfg(test)]
-mod tests {
-    use super::update_filters_action;
-    use crate::filters::parser::{
-        DIRECTIVE_ELSE, DIRECTIVE_ENDIF, DIRECTIVE_IF, DIRECTIVE_INCLUDE,
-    };
-    use crate::storage::entities::filter::filter_entity::FilterEntity;
-    use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
-    use crate::storage::entities::rules_list::rules_list_entity::{
-        RulesListEntity, RulesListEntityCallsMock,
-    };
-    use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
-    use crate::storage::repositories::filter_repository::FilterRepository;
-    use crate::storage::repositories::rules_list_repository::RulesListRepository;
-    use crate::storage::repositories::Repository;
-    use crate::storage::with_transaction;
-    use crate::storage::DbConnectionManager;
-    use crate::test_utils::tests_fixtures::TestsFixtures;
-    use crate::test_utils::tests_path;
-    use crate::{string, Configuration, FilterId, CUSTOM_FILTERS_GROUP_ID};
-    use chrono::Utc;
-    use mimicry::Mock;
-    use rusqlite::Connection;
-    use std::thread;
-    use url::Url;
+/// Checks that filter could not be downloaded due to network error
+fn is_network_failure(compilation_result: &Result<String, FilterParserErrorContext>) -> bool {
+    matches!(
+        compilation_result,
+        Err(err) if matches!(err.error, FilterParserError::Network(_))
+    )
+}
 
-    #[allow(clippy::field_reassign_with_default)]
-    #[test]
-    fn test_update_filters_action() {
-        let source = DbConnectionManager::factory_test().unwrap();
-        unsafe { source.lift_up_database().unwrap() }
+/// Compiles filter from its download url. Part of [`compile_with_retries`]
+fn compile_download_url_with_retries(
+    task: &mut CompilationTask,
+    retry_policy: RetryPolicy,
+    progress_reporter: &UpdateProgressReporter,
+) -> Result<String, FilterParserErrorContext> {
+    loop {
+        let compilation_result = task.compiler.compile(&task.filter.download_url);
+
+        match compilation_result {
+            Err(ref err)
+                if task.attempts < retry_policy.max_retries
+                    && err.error.is_transient()
+                    && get_retry_after(&err.error).is_none()
+                    && !progress_reporter.is_cancelled() =>
+            {
+                std::thread::sleep(retry_policy.get_delay(task.attempts));
 
-        let mut fixtures = TestsFixtures::new();
+                task.attempts += 1;
+                task.compiler.reset();
+            }
+            _ => return compilation_result,
+        }
+    }
+}
 
-        // This creates filter in the fixtures folder
-        let mut write_rules = |rules: RulesListEntity| -> (Url, RulesListEntity) {
-            let filter_url = fixtures.write(
-                &format!(
-                    "update_filters_action_{}_{:?}",
-                    rules.filter_id,
-                    thread::current().id()
-                ),
-                &rules.text,
-            );
+/// If server asked to retry the filter later, returns time until which its host must be paused.
+/// Returns [`None`] if filter must fail instead: retries are exhausted, or the delay is too long
+/// or won't end before the update timeout
+fn get_host_pause_end(
+    task: &CompilationTask,
+    compilation_result: &Result<String, FilterParserErrorContext>,
+    retry_policy: RetryPolicy,
+    deadline: Option<Instant>,
+    progress_reporter: &UpdateProgressReporter,
+) -> Option<Instant> {
+    let retry_after = compilation_result
+        .as_ref()
+        .err()
+        .and_then(|err| get_retry_after(&err.error))?;
+
+    if task.attempts >= retry_policy.max_retries
+        || retry_after > Duration::from_secs(MAX_HOST_RETRY_AFTER_SEC)
+        || progress_reporter.is_cancelled()
+    {
+        return None;
+    }
 
-            (filter_url, rules)
-        };
+    let pause_end = Instant::now() + retry_after;
 
-        let first_filter_id = 1;
-        let second_filter_id = 2;
-        let third_filter_id = -100011;
-        let fourth_filter_id = -100012;
-        let fifth_filter_id = 5;
+    if deadline.is_some_and(|deadline| pause_end >= deadline) {
+        return None;
+    }
 
-        let user_rules_count_new = 2;
+    Some(pause_end)
+}
 
-        let mut filter1 = FilterEntity::default();
-        filter1.filter_id = Some(first_filter_id);
-        filter1.group_id = 1;
-        filter1.is_enabled = true;
-        filter1.title = String::from("Filter1");
-        let (download_url1, rules1) = write_rules(RulesListEntity::with_disabled_text(
-            first_filter_id,
-            string!("Filter1\nNonFilter1"),
-            string!("NonFilter1"),
-            0,
-        ));
+/// Gets delay, which server asked to wait before the next request
+fn get_retry_after(error: &FilterParserError) -> Option<Duration> {
+    match error {
+        FilterParserError::Network(HttpClientError::RetryAfter(_, retry_after_sec, _)) => {
+            Some(Duration::from_secs(*retry_after_sec))
+        }
+        _ => None,
+    }
+}
 
-        filter1.download_url = download_url1.to_string();
+/// Makes entity for the next failure of the filter.
+/// The filter won't be requested by regular updates until its backoff delay is over
+fn make_failure_entity(
+    previous: Option<&FilterUpdateFailureEntity>,
+    filter_id: FilterId,
+    last_error: String,
+    current_time: i64,
+    configuration: &Configuration,
+) -> FilterUpdateFailureEntity {
+    let consecutive_failures = previous
+        .map(|entity| entity.consecutive_failures)
+        .unwrap_or_default();
+
+    let backoff_sec = exponential_backoff(
+        configuration.filter_failure_backoff_sec.max(0) as u64,
+        consecutive_failures.max(0) as u32,
+        MAX_FILTER_FAILURE_BACKOFF_SEC,
+    );
 
-        let mut filter2 = FilterEntity::default();
-        filter2.group_id = 1;
-        filter2.filter_id = Some(second_filter_id);
-        filter2.title = String::from("Filter2");
-        let (download_url2, rules2) = write_rules(RulesListEntity::with_disabled_text(
-            second_filter_id,
-            string!("Filter2\nNonFilter2"),
-            string!("NonFilter2"),
-            0,
-        ));
-        filter2.download_url = download_url2.to_string();
+    FilterUpdateFailureEntity::make_next(
+        previous,
+        filter_id,
+        last_error,
+        current_time,
+        backoff_sec as i64,
+    )
+}
 
-        let mut filter3 = FilterEntity::default();
-        filter3.filter_id = Some(third_filter_id);
-        filter3.title = String::from("Filter3");
-        filter3.is_enabled = true;
-        let (download_url3, rules3) = write_rules(RulesListEntity::with_disabled_text(
-            third_filter_id,
-            string!("Filter3\nNonFilter3"),
-            string!("NonFilter3"),
+/// Reports result of filter compilation to observer
+fn notify_compilation_result(
+    progress_reporter: &UpdateProgressReporter,
+    task: &CompilationTask,
+    compilation_result: &Result<String, FilterParserErrorContext>,
+) {
+    let url = task.filter.download_url.as_str();
+
+    match compilation_result {
+        Ok(_) if task.is_diff_update => {
+            progress_reporter.notify(Some(task.filter_id), url, UpdateProgressStage::DiffPatched)
+        }
+        Ok(_) => progress_reporter.notify(Some(task.filter_id), url, UpdateProgressStage::Compiled),
+        // Update is unavailable yet
+        Err(err) if err.error == FilterParserError::NoContent => {
+            progress_reporter.notify(Some(task.filter_id), url, UpdateProgressStage::NotChanged)
+        }
+        // Will be reported after saving
+        Err(err) if err.error == FilterParserError::NotModified => {}
+        Err(err) => progress_reporter.notify_failed(Some(task.filter_id), url, err.to_string()),
+    }
+}
+
+/// Makes report of the filter, using its download details, if there are any
+fn make_report(
+    compilation_stats_map: &HashMap<FilterId, CompilationStats>,
+    filter_id: FilterId,
+    outcome: FilterUpdateOutcome,
+) -> FilterUpdateReport {
+    match compilation_stats_map.get(&filter_id) {
+        Some(stats) => stats.make_report(filter_id, outcome),
+        None => FilterUpdateReport::new(filter_id, outcome),
+    }
+}
+
+/// Gets status code of the failed response from compilation error
+fn get_http_status_from_error(error: &FilterParserError) -> Option<u16> {
+    match error {
+        FilterParserError::NotModified => Some(304),
+        FilterParserError::Network(HttpClientError::Strict200Response(status, _))
+        | FilterParserError::Network(HttpClientError::RetryAfter(status, _, _)) => Some(*status),
+        _ => None,
+    }
+}
+
+/// Gets failure category of compilation error
+fn get_failure_kind(error: &FilterParserError) -> UpdateFailureKind {
+    match error {
+        FilterParserError::Network(_) => UpdateFailureKind::Network,
+        FilterParserError::Io(_) => UpdateFailureKind::Io,
+        FilterParserError::InvalidChecksum(_, _) => UpdateFailureKind::InvalidChecksum,
+        FilterParserError::FilterContentIsLikelyNotAFilter => UpdateFailureKind::InvalidContent,
+        FilterParserError::EmptyIf
+        | FilterParserError::UnbalancedElse
+        | FilterParserError::UnbalancedEndIf
+        | FilterParserError::UnbalancedIf
+        | FilterParserError::RecursiveInclusion
+        | FilterParserError::StackIsCorrupted
+        | FilterParserError::SchemeIsIncorrect(_)
+        | FilterParserError::InvalidBooleanExpression
+        | FilterParserError::InvalidDiffPatch(_) => UpdateFailureKind::Parser,
+        _ => UpdateFailureKind::Other,
+    }
+}
+
+/// Gets the latest versions of index filters of the `registry_ids` from the server.
+/// Failure of the main registry index is fatal, other registries are just skipped
+fn get_latest_filters_versions(
+    registry_ids: &HashSet<i32>,
+    registry_names: &MapRegistryIdOnName,
+    shared_http_client: &BlockingClient,
+    configuration: &Configuration,
+) -> FLMResult<HashMap<FilterId, String>> {
+    let mut last_index_filter_versions = HashMap::new();
+    if registry_ids.is_empty() {
+        return Ok(last_index_filter_versions);
+    }
+
+    for registry in configuration.collect_filter_registries() {
+        let Some(registry_id) = registry_ids
+            .iter()
+            .find(|id| registry_names.get(id) == Some(&registry.name))
+        else {
+            continue;
+        };
+
+        let index = match fetch_registry_index(&registry, shared_http_client) {
+            Ok(index) => index,
+            Err(why) if registry.name == MAIN_FILTER_REGISTRY_NAME => return Err(why),
+            Err(_) => continue,
+        };
+
+        let namespace = RegistryIdNamespace::new(*registry_id);
+        for entity in index.filters {
+            last_index_filter_versions
+                .insert(namespace.to_database_id(entity.filterId), entity.version);
+        }
+    }
+
+    Ok(last_index_filter_versions)
+}
+
+/// Fetches index of the `registry`.
+/// Mirrors of the index are requested in order, if the previous url fails with network error
+fn fetch_registry_index(
+    registry: &FilterRegistry,
+    shared_http_client: &BlockingClient,
+) -> FLMResult<IndexEntity> {
+    let metadata_urls =
+        std::iter::once(&registry.metadata_url).chain(registry.metadata_mirror_urls.iter());
+
+    let mut index_result = FLMError::make_err("Index url is empty");
+    for (position, metadata_url) in metadata_urls.enumerate() {
+        let result = fetch_json_by_scheme::<IndexEntity>(
+            metadata_url,
+            get_scheme(metadata_url).into(),
+            shared_http_client,
+        );
+
+        match result {
+            // The first error is the most relevant
+            Err(FLMError::Network(_)) if position > 0 => continue,
+            Err(FLMError::Network(_)) => index_result = result,
+            _ => {
+                index_result = result;
+
+                break;
+            }
+        }
+    }
+
+    index_result
+}
+
+/// Downloads and compiles the remote version of `filter` the same way as [`update_filters_action`]:
+/// the registry index is checked for the new version, transient failures are retried,
+/// `Retry-After` is waited for and mirrors are requested, if the download url is unreachable.
+/// Nothing is saved. Expiration is not respected: if the filter
+/// can't be updated via differential update right now, it will be fully downloaded
+pub(super) fn preview_filter_update_action(
+    filter: FilterEntity,
+    db_connection_manager: &DbConnectionManager,
+    configuration: &Configuration,
+) -> FLMResult<FilterUpdatePreview> {
+    let Some(filter_id) = filter.filter_id else {
+        return FLMError::make_err("Cannot get filter contents from database");
+    };
+
+    if filter.download_url.is_empty() {
+        return Err(FLMError::FieldIsEmpty("download_url"));
+    }
+
+    let (mut diff_updates_map, old_rules_list, old_includes, mirror_urls, registry_names) =
+        db_connection_manager.execute_db(|conn: Connection| {
+            let diff_updates_map = DiffUpdateRepository::new()
+                .select_map(&conn, &[filter_id])
+                .map_err(FLMError::from_database)?;
+
+            let old_rules_list = RulesListRepository::new()
+                .select_mapped(
+                    &conn,
+                    Some(SQLOperator::FieldEqualValue("filter_id", filter_id.into())),
+                )
+                .map_err(FLMError::from_database)?
+                .remove(&filter_id);
+
+            let old_includes = FilterIncludesRepository::new()
+                .select_mapped(
+                    &conn,
+                    Some(SQLOperator::FieldEqualValue("filter_id", filter_id.into())),
+                )
+                .map_err(FLMError::from_database)?
+                .remove(&filter_id)
+                .unwrap_or_default();
+
+            let mirror_urls = FilterMirrorUrlsRepository::new()
+                .select_map(&conn, &[filter_id])
+                .map_err(FLMError::from_database)?
+                .remove(&filter_id)
+                .unwrap_or_default();
+
+            let registry_names = FilterRegistriesRepository::new()
+                .select_mapped(&conn)
+                .map_err(FLMError::from_database)?;
+
+            Ok((
+                diff_updates_map,
+                old_rules_list,
+                old_includes,
+                mirror_urls,
+                registry_names,
+            ))
+        })?;
+
+    let mut rules_map = MapFilterIdOnRulesString::new();
+    if let Some(ref rules_list) = old_rules_list {
+        rules_map.insert(filter_id, rules_list.text.clone());
+    }
+
+    let shared_http_client = BlockingClient::new(configuration)?;
+    let batch_patches_container = BatchPatchesContainer::factory();
+
+    let (compiler, is_diff_update) = build_compiler(
+        false,
+        filter_id,
+        configuration,
+        &mut diff_updates_map,
+        Utc::now().timestamp(),
+        &mut rules_map,
+        &batch_patches_container,
+        &filter,
+        &shared_http_client,
+        None,
+    )?;
+
+    let mut compiler = match compiler {
+        Some(compiler) => compiler,
+        None => FilterCompiler::factory(configuration, &shared_http_client),
+    };
+
+    if !filter.is_custom() {
+        compiler.should_skip_checksum_validation(false);
+    }
+
+    let old_rules_count = old_rules_list
+        .as_ref()
+        .map(|rules_list| rules_list.rules_count)
+        .unwrap_or_default()
+        + old_includes
+            .iter()
+            .map(|include| include.rules_count)
+            .sum::<i32>();
+
+    let old_lines = collect_rule_lines(
+        old_rules_list
+            .iter()
+            .map(|rules_list| rules_list.text.as_str())
+            .chain(old_includes.iter().map(|include| include.body.as_str())),
+    );
+
+    let mut preview = FilterUpdatePreview {
+        filter_id,
+        is_diff_update,
+        is_patch_not_ready: false,
+        added_rules: vec![],
+        removed_rules: vec![],
+        old_version: filter.version.clone(),
+        new_version: filter.version.clone(),
+        old_title: filter.title.clone(),
+        new_title: filter.title.clone(),
+        old_expires: filter.expires,
+        new_expires: filter.expires,
+        rules_count_delta: 0,
+        served_url: None,
+    };
+
+    // Same version in the registry index means, that the update won't request the filter
+    if !is_diff_update && !filter.is_custom() && old_rules_count > 0 {
+        if let Some(namespace) = RegistryIdNamespace::of(filter_id) {
+            let last_index_filter_versions = get_latest_filters_versions(
+                &HashSet::from([namespace.registry_id()]),
+                &registry_names,
+                &shared_http_client,
+                configuration,
+            )?;
+
+            if !filter.version.is_empty()
+                && last_index_filter_versions.get(&filter_id) == Some(&filter.version)
+            {
+                return Ok(preview);
+            }
+        }
+    }
+
+    let host = Url::parse(&filter.download_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string));
+
+    let mut task = CompilationTask {
+        filter_id,
+        filter,
+        compiler,
+        is_diff_update,
+        host,
+        attempts: 0,
+        mirror_urls,
+        served_url: None,
+    };
+
+    let retry_policy = RetryPolicy::from_configuration(configuration);
+    let progress_reporter = UpdateProgressReporter::default();
+    let compilation_result = loop {
+        let compilation_result = compile_with_retries(&mut task, retry_policy, &progress_reporter);
+
+        // Preview is a single request, so it just waits until the host is available again
+        match get_host_pause_end(
+            &task,
+            &compilation_result,
+            retry_policy,
+            None,
+            &progress_reporter,
+        ) {
+            Some(pause_end) => {
+                std::thread::sleep(pause_end.saturating_duration_since(Instant::now()));
+
+                task.attempts += 1;
+                task.compiler.reset();
+            }
+            None => break compilation_result,
+        }
+    };
+
+    preview.served_url = task.served_url.clone();
+
+    match compilation_result {
+        Ok(_) => {}
+        // Next patch is not published yet, so the contents can't be checked
+        Err(err) if err.error == FilterParserError::NoContent => {
+            preview.is_patch_not_ready = true;
+
+            return Ok(preview);
+        }
+        Err(err) => return Err(FLMError::ParseFilterError(err)),
+    }
+
+    let CompilationTask {
+        filter, compiler, ..
+    } = task;
+
+    preview.new_version = compiler.get_metadata(KnownMetadataProperty::Version);
+    preview.new_expires = match compiler.get_metadata(KnownMetadataProperty::Expires) {
+        value if value.is_empty() => 0i32,
+        value => process_expires(value.as_str()),
+    };
+    if filter.is_custom() && !filter.is_user_title() {
+        preview.new_title = compiler.get_metadata(KnownMetadataProperty::Title);
+    }
+    preview.rules_count_delta = compiler.get_rules_count() - old_rules_count;
+
+    let compiled_filter_entities = compiler.into_entities(filter_id);
+    let new_lines = collect_rule_lines(
+        std::iter::once(compiled_filter_entities.rules_list_entity.text.as_str()).chain(
+            compiled_filter_entities
+                .filter_includes_entities
+                .iter()
+                .map(|include| include.body.as_str()),
+        ),
+    );
+
+    preview.added_rules = subtract_lines(&new_lines, &old_lines);
+    preview.removed_rules = subtract_lines(&old_lines, &new_lines);
+
+    Ok(preview)
+}
+
+/// Collects trimmed rule lines of all `contents`
+fn collect_rule_lines<'a>(contents: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    contents
+        .flat_map(str::lines)
+        .map(str::trim)
+        .filter(|line| is_line_is_rule(line))
+        .collect()
+}
+
+/// Gets lines of `left`, which are not in `right`, preserving order.
+/// Duplicates are counted, so the line, which occurs twice in `left` and once in `right`, is returned once
+fn subtract_lines(left: &[&str], right: &[&str]) -> Vec<String> {
+    let mut right_counts: HashMap<&str, usize> = HashMap::with_capacity(right.len());
+    for line in right {
+        *right_counts.entry(line).or_default() += 1;
+    }
+
+    left.iter()
+        .filter(|line| match right_counts.get_mut(*line) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{preview_filter_update_action, update_filters_action};
+    use crate::filters::parser::parser_error::FilterParserErrorContext;
+    use crate::filters::parser::{
+        DIRECTIVE_ELSE, DIRECTIVE_ENDIF, DIRECTIVE_IF, DIRECTIVE_INCLUDE,
+    };
+    use crate::manager::models::update_progress::{
+        UpdateCancellationToken, UpdateObserver, UpdateProgressEvent, UpdateProgressStage,
+    };
+    use crate::manager::models::update_result::{FilterUpdateOutcome, UpdateFailureKind};
+    use crate::manager::update_progress_reporter::UpdateProgressReporter;
+    use crate::storage::entities::diff_update_entity::DiffUpdateEntity;
+    use crate::storage::entities::filter::filter_entity::FilterEntity;
+    use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
+    use crate::storage::entities::filter_mirror_url_entity::FilterMirrorUrlEntity;
+    use crate::storage::entities::rules_list::rules_list_entity::{
+        RulesListEntity, RulesListEntityCallsMock,
+    };
+    use crate::storage::repositories::diff_updates_repository::DiffUpdateRepository;
+    use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
+    use crate::storage::repositories::filter_mirror_urls_repository::FilterMirrorUrlsRepository;
+    use crate::storage::repositories::filter_repository::FilterRepository;
+    use crate::storage::repositories::filter_update_failures_repository::FilterUpdateFailuresRepository;
+    use crate::storage::repositories::http_cache_validators_repository::HttpCacheValidatorsRepository;
+    use crate::storage::repositories::rules_list_repository::RulesListRepository;
+    use crate::storage::repositories::Repository;
+    use crate::storage::sql_generators::operator::SQLOperator;
+    use crate::storage::with_transaction;
+    use crate::storage::DbConnectionManager;
+    use crate::test_utils::tests_fixtures::TestsFixtures;
+    use crate::test_utils::tests_http_server::{TestsHttpResponse, TestsHttpServer};
+    use crate::test_utils::tests_path;
+    use crate::{
+        string, Configuration, FLMError, FilterId, FilterParserError, HttpClientError,
+        CUSTOM_FILTERS_GROUP_ID,
+    };
+    use chrono::Utc;
+    use mimicry::Mock;
+    use rusqlite::Connection;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use url::Url;
+
+    /// Collects events and cancels update after the first compiled filter, if token is passed
+    #[derive(Default)]
+    struct TestsObserver {
+        events: Mutex<Vec<UpdateProgressEvent>>,
+        cancel_after_compilation: Option<UpdateCancellationToken>,
+    }
+
+    impl TestsObserver {
+        fn stages_of(&self, filter_id: FilterId) -> Vec<UpdateProgressStage> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|event| event.filter_id == Some(filter_id))
+                .map(|event| event.stage)
+                .collect()
+        }
+    }
+
+    impl UpdateObserver for TestsObserver {
+        fn on_update_progress(&self, event: UpdateProgressEvent) {
+            if event.stage == UpdateProgressStage::Compiled {
+                if let Some(token) = self.cancel_after_compilation.as_ref() {
+                    token.cancel();
+                }
+            }
+
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    fn spawn_filters_for_progress_tests(
+        source: &DbConnectionManager,
+        server: &TestsHttpServer,
+        filter_ids: &[FilterId],
+    ) -> Vec<FilterEntity> {
+        let filters = filter_ids
+            .iter()
+            .map(|filter_id| {
+                let mut filter = FilterEntity::default();
+                filter.filter_id = Some(*filter_id);
+                filter.group_id = CUSTOM_FILTERS_GROUP_ID;
+                filter.is_enabled = true;
+                filter.download_url = server.url(&format!("/{}.txt", filter_id));
+                filter.title = format!("Filter {}", filter_id);
+                filter
+            })
+            .collect::<Vec<FilterEntity>>();
+
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    FilterRepository::new().insert(tx, &filters)
+                })
+            })
+            .unwrap();
+
+        filters
+    }
+
+    #[allow(clippy::field_reassign_with_default)]
+    #[test]
+    fn test_update_filters_action() {
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut fixtures = TestsFixtures::new();
+
+        // This creates filter in the fixtures folder
+        let mut write_rules = |rules: RulesListEntity| -> (Url, RulesListEntity) {
+            let filter_url = fixtures.write(
+                &format!(
+                    "update_filters_action_{}_{:?}",
+                    rules.filter_id,
+                    thread::current().id()
+                ),
+                &rules.text,
+            );
+
+            (filter_url, rules)
+        };
+
+        let first_filter_id = 1;
+        let second_filter_id = 2;
+        let third_filter_id = -100011;
+        let fourth_filter_id = -100012;
+        let fifth_filter_id = 5;
+
+        let user_rules_count_new = 2;
+
+        let mut filter1 = FilterEntity::default();
+        filter1.filter_id = Some(first_filter_id);
+        filter1.group_id = 1;
+        filter1.is_enabled = true;
+        filter1.title = String::from("Filter1");
+        let (download_url1, rules1) = write_rules(RulesListEntity::with_disabled_text(
+            first_filter_id,
+            string!("Filter1\nNonFilter1"),
+            string!("NonFilter1"),
+            0,
+        ));
+
+        filter1.download_url = download_url1.to_string();
+
+        let mut filter2 = FilterEntity::default();
+        filter2.group_id = 1;
+        filter2.filter_id = Some(second_filter_id);
+        filter2.title = String::from("Filter2");
+        let (download_url2, rules2) = write_rules(RulesListEntity::with_disabled_text(
+            second_filter_id,
+            string!("Filter2\nNonFilter2"),
+            string!("NonFilter2"),
+            0,
+        ));
+        filter2.download_url = download_url2.to_string();
+
+        let mut filter3 = FilterEntity::default();
+        filter3.filter_id = Some(third_filter_id);
+        filter3.title = String::from("Filter3");
+        filter3.is_enabled = true;
+        let (download_url3, rules3) = write_rules(RulesListEntity::with_disabled_text(
+            third_filter_id,
+            string!("Filter3\nNonFilter3"),
+            string!("NonFilter3"),
             0,
         ));
         filter3.download_url = download_url3.to_string();
@@ -846,8 +1991,16 @@ mod tests {
             .unwrap()
             .to_string();
 
-        let result =
-            update_filters_action(installed_filters, &source, false, false, 0, &conf).unwrap();
+        let result = update_filters_action(
+            installed_filters,
+            &source,
+            false,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
 
         let updated_ids = result
             .updated_list
@@ -1113,8 +2266,16 @@ mod tests {
             .unwrap()
             .to_string();
 
-        let result =
-            update_filters_action(installed_filters, &source, true, true, 0, &conf).unwrap();
+        let result = update_filters_action(
+            installed_filters,
+            &source,
+            true,
+            true,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
 
         let updated_ids = result
             .updated_list
@@ -1241,8 +2402,16 @@ mod tests {
             })
             .unwrap();
 
-        let updated_result =
-            update_filters_action(list, &source, true, true, 0, &Configuration::default()).unwrap();
+        let updated_result = update_filters_action(
+            list,
+            &source,
+            true,
+            true,
+            0,
+            &Configuration::default(),
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
 
         // Only and exactly one filter was updated
         assert_eq!(updated_result.updated_list.len(), 1);
@@ -1390,7 +2559,16 @@ mod tests {
 
         // Run update (files unchanged)
         let conf = Configuration::default();
-        let result = update_filters_action(vec![filter], &source, false, false, 0, &conf).unwrap();
+        let result = update_filters_action(
+            vec![filter],
+            &source,
+            false,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
 
         // Should NOT update because hash and includes are unchanged
         assert!(result.updated_list.is_empty());
@@ -1454,7 +2632,16 @@ mod tests {
 
         // Run update
         let conf = Configuration::default();
-        let result = update_filters_action(vec![filter], &source, false, false, 0, &conf).unwrap();
+        let result = update_filters_action(
+            vec![filter],
+            &source,
+            false,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
 
         // Should update because include body hash changed
         assert_eq!(result.updated_list.len(), 1);
@@ -1540,8 +2727,16 @@ mod tests {
             })
             .unwrap();
 
-        let result =
-            update_filters_action(installed_filters, &source, false, false, 0, &conf).unwrap();
+        let result = update_filters_action(
+            installed_filters,
+            &source,
+            false,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
 
         // Both local filters must be updated immediately
         let updated_ids = result
@@ -1608,11 +2803,1057 @@ mod tests {
             })
             .unwrap();
 
-        let result =
-            update_filters_action(installed_filters, &source, false, false, 0, &conf).unwrap();
+        let result = update_filters_action(
+            installed_filters,
+            &source,
+            false,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
 
         // Should NOT update because minimal expires (1 hour) applies when ignore flag is off
         assert!(result.filters_errors.is_empty());
         assert!(result.updated_list.is_empty());
     }
+
+    #[test]
+    fn test_not_modified_filter_only_bumps_download_time() {
+        const FILTER_ID: i32 = -44;
+        const ETAG: &str = "\"v1\"";
+
+        let server = TestsHttpServer::start(|request| {
+            if request.header("if-none-match") == Some(ETAG) {
+                return TestsHttpResponse::new(304, "");
+            }
+
+            TestsHttpResponse::new(200, "! Title: Conditional\n||example.org^\n")
+                .with_header("ETag", ETAG)
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filter = FilterEntity::default();
+        filter.filter_id = Some(FILTER_ID);
+        filter.group_id = CUSTOM_FILTERS_GROUP_ID;
+        filter.is_enabled = true;
+        filter.download_url = server.url("/filter.txt");
+        filter.title = string!("Conditional");
+
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    FilterRepository::new().insert(tx, &[filter.clone()])
+                })
+            })
+            .unwrap();
+
+        let conf = Configuration::default();
+
+        // First update downloads filter and saves validators
+        let result = update_filters_action(
+            vec![filter.clone()],
+            &source,
+            false,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+        assert!(result.filters_errors.is_empty());
+        assert_eq!(result.updated_list.len(), 1);
+
+        let saved_validators = source
+            .execute_db(|connection: Connection| {
+                Ok(HttpCacheValidatorsRepository::new()
+                    .select_map(&connection, &[filter.download_url.as_str()])
+                    .unwrap())
+            })
+            .unwrap();
+        assert_eq!(
+            saved_validators
+                .get(&filter.download_url)
+                .and_then(|validators| validators.etag.as_deref()),
+            Some(ETAG)
+        );
+
+        // Reset download time to see that it will be updated
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    FilterRepository::new().insert(tx, &[filter.clone()])
+                })
+            })
+            .unwrap();
+
+        // Second update is conditional, server says that filter is not modified
+        let result = update_filters_action(
+            vec![filter],
+            &source,
+            false,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+        assert!(result.filters_errors.is_empty());
+        assert!(result.updated_list.is_empty());
+        assert_eq!(result.filters_reports.len(), 1);
+        assert_eq!(
+            result.filters_reports[0].outcome,
+            FilterUpdateOutcome::SameContent
+        );
+        assert_eq!(result.filters_reports[0].http_status, Some(304));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].header("if-none-match"), None);
+        assert_eq!(requests[1].header("if-none-match"), Some(ETAG));
+
+        source
+            .execute_db(|connection: Connection| {
+                let stored_filter = FilterRepository::new()
+                    .select_mapped(
+                        &connection,
+                        Some(SQLOperator::FieldEqualValue("filter_id", FILTER_ID.into())),
+                    )
+                    .unwrap()
+                    .remove(&FILTER_ID)
+                    .unwrap();
+
+                let (mut rules_map, _, _) = RulesListRepository::new()
+                    .select_rules_maps(&connection, &[FILTER_ID])
+                    .unwrap();
+
+                // Rules are kept, download time is updated
+                assert!(rules_map
+                    .remove(&FILTER_ID)
+                    .unwrap()
+                    .contains("||example.org^"));
+                assert!(stored_filter.last_download_time > 0);
+
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_update_filters_reports_progress() {
+        const FILTER_ID: FilterId = -20001;
+
+        let server = TestsHttpServer::start(|request| {
+            if request.path == "/missing.txt" {
+                return TestsHttpResponse::new(404, "");
+            }
+
+            TestsHttpResponse::new(200, "! Title: Progress\n||example.org^\n")
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+        let mut missing_filter = filters[0].clone();
+        missing_filter.filter_id = Some(FILTER_ID - 1);
+        missing_filter.download_url = server.url("/missing.txt");
+        filters.push(missing_filter);
+
+        let observer = Arc::new(TestsObserver::default());
+        let mut reporter = UpdateProgressReporter::default();
+        reporter.set_observer(Some(observer.clone()));
+
+        let result = update_filters_action(
+            filters,
+            &source,
+            true,
+            false,
+            0,
+            &Configuration::default(),
+            &reporter,
+        )
+        .unwrap();
+
+        assert_eq!(result.updated_list.len(), 1);
+        assert_eq!(result.filters_errors.len(), 1);
+        assert_eq!(
+            observer.stages_of(FILTER_ID),
+            vec![
+                UpdateProgressStage::Queued,
+                UpdateProgressStage::Downloading,
+                UpdateProgressStage::Compiled,
+                UpdateProgressStage::Saved,
+            ]
+        );
+        assert_eq!(
+            observer.stages_of(FILTER_ID - 1),
+            vec![
+                UpdateProgressStage::Queued,
+                UpdateProgressStage::Downloading,
+                UpdateProgressStage::Failed,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_update_filters_reports_outcomes() {
+        const FILTER_ID: FilterId = -20001;
+        const BODY: &str = "! Title: Report\n||example.org^\n";
+
+        let server = TestsHttpServer::start(|request| {
+            if request.path == "/missing.txt" {
+                return TestsHttpResponse::new(404, "");
+            }
+
+            TestsHttpResponse::new(200, BODY)
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filters = spawn_filters_for_progress_tests(
+            &source,
+            &server,
+            &[
+                FILTER_ID,
+                FILTER_ID - 1,
+                FILTER_ID - 2,
+                FILTER_ID - 3,
+                FILTER_ID - 4,
+            ],
+        );
+        filters[1].download_url = server.url("/missing.txt");
+        filters[2].is_enabled = false;
+        filters[3].download_url = String::new();
+        // Downloaded just now and never expires
+        filters[4].last_download_time = Utc::now().timestamp();
+        filters[4].expires = i32::MAX / 2;
+
+        let result = update_filters_action(
+            filters,
+            &source,
+            false,
+            false,
+            0,
+            &Configuration::default(),
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+
+        let report_of = |filter_id: FilterId| {
+            result
+                .filters_reports
+                .iter()
+                .find(|report| report.filter_id == filter_id)
+                .unwrap()
+        };
+
+        assert_eq!(result.filters_reports.len(), 5);
+
+        let updated = report_of(FILTER_ID);
+        assert_eq!(updated.outcome, FilterUpdateOutcome::Updated);
+        assert_eq!(updated.http_status, Some(200));
+        assert_eq!(updated.downloaded_bytes, BODY.len() as u64);
+
+        let failed = report_of(FILTER_ID - 1);
+        assert_eq!(
+            failed.outcome,
+            FilterUpdateOutcome::Failed {
+                kind: UpdateFailureKind::Network
+            }
+        );
+        assert_eq!(failed.http_status, Some(404));
+
+        assert_eq!(result.filters_errors.len(), 1);
+        let error = &result.filters_errors[0];
+        assert_eq!(error.filter_id, FILTER_ID - 1);
+        assert_eq!(error.http_status, Some(404));
+        assert!(matches!(
+            &error.error,
+            Some(FLMError::ParseFilterError(context)) if matches!(
+                context.error,
+                FilterParserError::Network(HttpClientError::Strict200Response(404, _))
+            )
+        ));
+
+        assert_eq!(
+            report_of(FILTER_ID - 2).outcome,
+            FilterUpdateOutcome::Disabled
+        );
+        assert_eq!(
+            report_of(FILTER_ID - 3).outcome,
+            FilterUpdateOutcome::NoDownloadUrl
+        );
+
+        let not_expired = report_of(FILTER_ID - 4);
+        assert_eq!(not_expired.outcome, FilterUpdateOutcome::NotExpired);
+        assert_eq!(not_expired.downloaded_bytes, 0);
+        assert_eq!(not_expired.http_status, None);
+    }
+
+    #[test]
+    fn test_update_filters_falls_back_to_mirrors() {
+        const FILTER_ID: FilterId = -20001;
+        const BODY: &str = "! Title: Mirror\n||example.org^\n";
+
+        let server = TestsHttpServer::start(|request| match request.path.as_str() {
+            "/mirror.txt" => TestsHttpResponse::new(200, BODY).with_header("ETag", "\"mirror\""),
+            path if path.ends_with("missing.txt") => TestsHttpResponse::new(404, ""),
+            _ => TestsHttpResponse::new(200, BODY),
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filters =
+            spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID, FILTER_ID - 1]);
+        filters[0].download_url = server.url("/missing.txt");
+
+        let mirror_urls = [server.url("/also-missing.txt"), server.url("/mirror.txt")];
+        let mirror_url_entities = [FILTER_ID, FILTER_ID - 1]
+            .into_iter()
+            .flat_map(|filter_id| {
+                mirror_urls.iter().map(move |url| FilterMirrorUrlEntity {
+                    filter_id,
+                    url: url.clone(),
+                })
+            })
+            .collect::<Vec<FilterMirrorUrlEntity>>();
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    FilterMirrorUrlsRepository::new().insert(tx, &mirror_url_entities)
+                })
+            })
+            .unwrap();
+
+        let result = update_filters_action(
+            filters.clone(),
+            &source,
+            false,
+            false,
+            0,
+            &Configuration::default(),
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+
+        assert!(result.filters_errors.is_empty());
+        assert_eq!(result.updated_list.len(), 2);
+
+        let served_by_mirror = &result.filters_reports[0];
+        assert_eq!(served_by_mirror.outcome, FilterUpdateOutcome::Updated);
+        assert_eq!(served_by_mirror.http_status, Some(200));
+        assert_eq!(served_by_mirror.served_url, Some(server.url("/mirror.txt")));
+
+        // Mirrors are not requested, if the download url is reachable
+        let served_by_download_url = &result.filters_reports[1];
+        assert_eq!(served_by_download_url.outcome, FilterUpdateOutcome::Updated);
+        assert_eq!(
+            served_by_download_url.served_url,
+            Some(filters[1].download_url.clone())
+        );
+
+        assert_eq!(
+            server
+                .requests()
+                .iter()
+                .map(|request| request.path.as_str())
+                .filter(|path| *path != "/-20002.txt")
+                .collect::<Vec<&str>>(),
+            vec!["/missing.txt", "/also-missing.txt", "/mirror.txt"]
+        );
+
+        // Validators of the mirror response are not saved for the download url
+        let validators = source
+            .execute_db(|connection: Connection| {
+                Ok(HttpCacheValidatorsRepository::new()
+                    .select_map(&connection, &[filters[0].download_url.as_str()])
+                    .unwrap())
+            })
+            .unwrap();
+        assert!(validators.is_empty());
+    }
+
+    #[test]
+    fn test_update_filters_retries_transient_failures() {
+        const FILTER_ID: FilterId = -20001;
+
+        let requests_count = Arc::new(AtomicUsize::new(0));
+        let server_requests_count = Arc::clone(&requests_count);
+        let server = TestsHttpServer::start(move |_| {
+            // The first request fails
+            if server_requests_count.fetch_add(1, Ordering::SeqCst) == 0 {
+                return TestsHttpResponse::new(503, "");
+            }
+
+            TestsHttpResponse::new(200, "! Title: Retry\n||example.org^\n")
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+
+        let mut conf = Configuration::default();
+        conf.filter_update_retry_delay_ms = 1;
+
+        let result = update_filters_action(
+            filters,
+            &source,
+            true,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+
+        assert_eq!(server.requests().len(), 2);
+        assert_eq!(result.updated_list.len(), 1);
+        assert!(result.filters_errors.is_empty());
+        assert_eq!(
+            result.filters_reports[0].outcome,
+            FilterUpdateOutcome::Updated
+        );
+    }
+
+    #[test]
+    fn test_update_filters_honours_retry_after() {
+        const FIRST_FILTER_ID: FilterId = -20001;
+        const SECOND_FILTER_ID: FilterId = -20002;
+
+        let is_rate_limited = Arc::new(AtomicBool::new(true));
+        let server_is_rate_limited = Arc::clone(&is_rate_limited);
+        let server = TestsHttpServer::start(move |_| {
+            // The first request is rate limited
+            if server_is_rate_limited.swap(false, Ordering::SeqCst) {
+                return TestsHttpResponse::new(429, "").with_header("Retry-After", "1");
+            }
+
+            TestsHttpResponse::new(200, "! Title: Retry-After\n||example.org^\n")
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let filters = spawn_filters_for_progress_tests(
+            &source,
+            &server,
+            &[FIRST_FILTER_ID, SECOND_FILTER_ID],
+        );
+
+        let mut conf = Configuration::default();
+        conf.filter_update_max_concurrency_per_host = 1;
+
+        let started_at = Instant::now();
+        let result = update_filters_action(
+            filters,
+            &source,
+            true,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+
+        // Host has been paused, so the second filter has been requested after the pause too
+        assert!(started_at.elapsed() >= Duration::from_secs(1));
+        assert_eq!(
+            server
+                .requests()
+                .into_iter()
+                .map(|request| request.path)
+                .collect::<Vec<String>>(),
+            vec![
+                format!("/{}.txt", FIRST_FILTER_ID),
+                format!("/{}.txt", SECOND_FILTER_ID),
+                format!("/{}.txt", FIRST_FILTER_ID),
+            ]
+        );
+
+        assert!(result.filters_errors.is_empty());
+        assert_eq!(result.updated_list.len(), 2);
+        assert!(result
+            .filters_reports
+            .iter()
+            .all(|report| report.outcome == FilterUpdateOutcome::Updated));
+    }
+
+    #[test]
+    fn test_update_filters_fails_on_too_long_retry_after() {
+        const FILTER_ID: FilterId = -20001;
+
+        let server = TestsHttpServer::start(move |_| {
+            TestsHttpResponse::new(503, "").with_header("Retry-After", "3600")
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+
+        let result = update_filters_action(
+            filters,
+            &source,
+            true,
+            false,
+            0,
+            &Configuration::default(),
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+
+        // Neither retried, nor rescheduled
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(result.filters_errors.len(), 1);
+        assert_eq!(result.filters_errors[0].http_status, Some(503));
+        assert!(matches!(
+            result.filters_errors[0].error,
+            Some(FLMError::ParseFilterError(FilterParserErrorContext {
+                error: FilterParserError::Network(HttpClientError::RetryAfter(503, 3600, _)),
+                ..
+            }))
+        ));
+        assert_eq!(
+            result.filters_reports[0].outcome,
+            FilterUpdateOutcome::Failed {
+                kind: UpdateFailureKind::Network
+            }
+        );
+    }
+
+    #[test]
+    fn test_update_filters_postpones_failed_filters() {
+        const FILTER_ID: FilterId = -20001;
+
+        let is_broken = Arc::new(AtomicBool::new(true));
+        let server_is_broken = Arc::clone(&is_broken);
+        let server = TestsHttpServer::start(move |_| {
+            if server_is_broken.load(Ordering::SeqCst) {
+                return TestsHttpResponse::new(404, "");
+            }
+
+            TestsHttpResponse::new(200, "! Title: Postponed\n||example.org^\n")
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+        let conf = Configuration::default();
+        let reporter = UpdateProgressReporter::default();
+
+        let select_failures = || {
+            source
+                .execute_db(|conn: Connection| {
+                    FilterUpdateFailuresRepository::new()
+                        .select_map(&conn, &[FILTER_ID])
+                        .map_err(Into::into)
+                })
+                .unwrap()
+        };
+
+        // 404 is not transient, so there are no retries
+        let started_at = Utc::now().timestamp();
+        let result =
+            update_filters_action(filters.clone(), &source, false, false, 0, &conf, &reporter)
+                .unwrap();
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(result.filters_errors.len(), 1);
+
+        let failure = select_failures().remove(&FILTER_ID).unwrap();
+        assert_eq!(failure.consecutive_failures, 1);
+        assert!(failure.last_error.contains("404"));
+        assert!(failure.first_failure_time >= started_at);
+        assert_eq!(
+            failure.next_retry_time,
+            failure.last_failure_time + conf.filter_failure_backoff_sec as i64
+        );
+
+        // Regular update skips the filter
+        let result =
+            update_filters_action(filters.clone(), &source, false, false, 0, &conf, &reporter)
+                .unwrap();
+        assert_eq!(server.requests().len(), 1);
+        assert!(result.filters_errors.is_empty());
+        assert_eq!(
+            result.filters_reports[0].outcome,
+            FilterUpdateOutcome::Postponed
+        );
+
+        // Forced update requests it anyway, backoff is doubled
+        update_filters_action(filters.clone(), &source, true, false, 0, &conf, &reporter).unwrap();
+        assert_eq!(server.requests().len(), 2);
+
+        let failure = select_failures().remove(&FILTER_ID).unwrap();
+        assert_eq!(failure.consecutive_failures, 2);
+        assert!(failure.first_failure_time <= failure.last_failure_time);
+        assert_eq!(
+            failure.next_retry_time,
+            failure.last_failure_time + conf.filter_failure_backoff_sec as i64 * 2
+        );
+
+        // Successful request clears the failure
+        is_broken.store(false, Ordering::SeqCst);
+        let result =
+            update_filters_action(filters, &source, true, false, 0, &conf, &reporter).unwrap();
+        assert_eq!(result.updated_list.len(), 1);
+        assert!(select_failures().is_empty());
+    }
+
+    #[test]
+    fn test_cancelled_update_returns_partial_result() {
+        let filter_ids: [FilterId; 3] = [-20001, -20002, -20003];
+
+        let server = TestsHttpServer::start(|_| {
+            TestsHttpResponse::new(200, "! Title: Cancellation\n||example.org^\n")
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let filters = spawn_filters_for_progress_tests(&source, &server, &filter_ids);
+
+        let mut reporter = UpdateProgressReporter::default();
+        let observer = Arc::new(TestsObserver {
+            events: Mutex::default(),
+            cancel_after_compilation: Some(reporter.cancellation_token()),
+        });
+        reporter.set_observer(Some(observer.clone()));
+
+        let mut conf = Configuration::default();
+        conf.filter_update_concurrency = 1;
+
+        // Cancellation of the previous operation must not affect the next one
+        reporter.cancellation_token().cancel();
+        update_filters_action(vec![], &source, true, false, 0, &conf, &reporter).unwrap();
+
+        let result =
+            update_filters_action(filters, &source, true, false, 0, &conf, &reporter).unwrap();
+
+        // Only the first filter has been dispatched
+        assert_eq!(result.updated_list.len(), 1);
+        assert_eq!(result.remaining_filters_count, 2);
+        assert!(result.filters_errors.is_empty());
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(
+            observer.stages_of(filter_ids[1]),
+            vec![UpdateProgressStage::Queued]
+        );
+        assert_eq!(
+            result
+                .filters_reports
+                .iter()
+                .filter(|report| report.outcome == FilterUpdateOutcome::NotProcessed)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_timed_out_filter_error() {
+        const FILTER_ID: FilterId = -20001;
+
+        let server = TestsHttpServer::start(|_| {
+            thread::sleep(Duration::from_millis(1000));
+
+            TestsHttpResponse::new(200, "! Title: Slow\n||example.org^\n")
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+
+        let mut conf = Configuration::default();
+        conf.request_timeout_ms = 100;
+        conf.filter_update_max_retries = 0;
+
+        let result = update_filters_action(
+            filters,
+            &source,
+            true,
+            false,
+            0,
+            &conf,
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.filters_errors.len(), 1);
+        let error = &result.filters_errors[0];
+        assert_eq!(error.filter_id, FILTER_ID);
+        assert_eq!(error.http_status, None);
+        assert!(matches!(
+            &error.error,
+            Some(FLMError::ParseFilterError(context)) if matches!(
+                context.error,
+                FilterParserError::Network(HttpClientError::TimedOut(_))
+            )
+        ));
+    }
+
+    #[test]
+    fn test_invalid_diff_patch_filter_error() {
+        const FILTER_ID: FilterId = -20001;
+        const FILTER_TEXT: &str =
+            "! Title: Diff\n! Diff-Path: patches/v1-m-28334060-60.patch\n||example.org^\n";
+
+        let server = TestsHttpServer::start(|request| match request.path.as_str() {
+            // Deletes line, which the filter doesn't have
+            "/patches/v1-m-28334060-60.patch" => TestsHttpResponse::new(200, "d100 1\n"),
+            _ => TestsHttpResponse::new(200, FILTER_TEXT),
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+        // Not expired, so the differential update is used
+        filters[0].last_download_time = Utc::now().timestamp();
+        filters[0].expires = i32::MAX / 2;
+
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    RulesListRepository::new().insert(
+                        tx,
+                        &[RulesListEntity::make(FILTER_ID, string!(FILTER_TEXT), 1)],
+                    )?;
+                    DiffUpdateRepository::new().insert(
+                        tx,
+                        &[DiffUpdateEntity {
+                            filter_id: FILTER_ID,
+                            next_path: string!("patches/v1-m-28334060-60.patch"),
+                            next_check_time: 0,
+                        }],
+                    )
+                })
+            })
+            .unwrap();
+
+        let result = update_filters_action(
+            filters,
+            &source,
+            false,
+            false,
+            0,
+            &Configuration::default(),
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.filters_errors.len(), 1);
+        let error = &result.filters_errors[0];
+        assert_eq!(error.filter_id, FILTER_ID);
+        assert!(matches!(
+            &error.error,
+            Some(FLMError::ParseFilterError(context)) if matches!(
+                context.error,
+                FilterParserError::InvalidDiffPatch(_)
+            )
+        ));
+        assert_eq!(
+            result.filters_reports[0].outcome,
+            FilterUpdateOutcome::Failed {
+                kind: UpdateFailureKind::Parser
+            }
+        );
+    }
+
+    #[test]
+    fn test_unpublished_diff_patch_is_reported_as_not_ready() {
+        const FILTER_ID: FilterId = -20001;
+        const FILTER_TEXT: &str =
+            "! Title: Diff\n! Diff-Path: patches/v1-m-28334060-60.patch\n||example.org^\n";
+
+        let server = TestsHttpServer::start(|request| match request.path.as_str() {
+            // Empty patch file means, that the next patch is not published yet
+            "/patches/v1-m-28334060-60.patch" => TestsHttpResponse::new(200, ""),
+            _ => TestsHttpResponse::new(200, FILTER_TEXT),
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+        filters[0].last_download_time = Utc::now().timestamp();
+        filters[0].expires = i32::MAX / 2;
+
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    RulesListRepository::new().insert(
+                        tx,
+                        &[RulesListEntity::make(FILTER_ID, string!(FILTER_TEXT), 1)],
+                    )?;
+                    DiffUpdateRepository::new().insert(
+                        tx,
+                        &[DiffUpdateEntity {
+                            filter_id: FILTER_ID,
+                            next_path: string!("patches/v1-m-28334060-60.patch"),
+                            next_check_time: 0,
+                        }],
+                    )
+                })
+            })
+            .unwrap();
+
+        let result = update_filters_action(
+            filters,
+            &source,
+            false,
+            false,
+            0,
+            &Configuration::default(),
+            &UpdateProgressReporter::default(),
+        )
+        .unwrap();
+
+        assert!(result.filters_errors.is_empty());
+        assert!(result.updated_list.is_empty());
+        assert_eq!(
+            result.filters_reports[0].outcome,
+            FilterUpdateOutcome::PatchNotReady
+        );
+        // Filter itself hasn't been requested
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_update_cancelled_before_start_returns_empty_result() {
+        let filter_ids: [FilterId; 2] = [-20001, -20002];
+
+        let server = TestsHttpServer::start(|_| {
+            TestsHttpResponse::new(200, "! Title: Cancellation\n||example.org^\n")
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let filters = spawn_filters_for_progress_tests(&source, &server, &filter_ids);
+        let reporter = UpdateProgressReporter::default();
+
+        // E.g. cancelled from another thread, while the call waits for the manager
+        reporter.cancellation_token().cancel();
+
+        let conf = Configuration::default();
+        let result =
+            update_filters_action(filters.clone(), &source, true, false, 0, &conf, &reporter)
+                .unwrap();
+
+        assert!(result.updated_list.is_empty());
+        assert_eq!(result.remaining_filters_count, 2);
+        assert!(server.requests().is_empty());
+        assert!(!reporter.is_cancelled());
+
+        // Next operation is not cancelled
+        let result =
+            update_filters_action(filters, &source, true, false, 0, &conf, &reporter).unwrap();
+
+        assert_eq!(result.updated_list.len(), 2);
+    }
+
+    #[test]
+    fn test_preview_filter_update_does_not_save_changes() {
+        const FILTER_ID: i32 = -45;
+        const OLD_TEXT: &str = "! Title: Preview\n! Version: 1.0\n||a.com^\n||b.com^\n||b.com^";
+
+        let server = TestsHttpServer::start(|_| {
+            TestsHttpResponse::new(
+                200,
+                "! Title: Preview v2\n! Version: 2.0\n! Expires: 1 day\n||b.com^\n||c.com^\n||d.com^",
+            )
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filter = FilterEntity::default();
+        filter.filter_id = Some(FILTER_ID);
+        filter.group_id = CUSTOM_FILTERS_GROUP_ID;
+        filter.is_enabled = true;
+        filter.download_url = server.url("/filter.txt");
+        filter.title = string!("Preview");
+        filter.version = string!("1.0");
+        filter.last_download_time = Utc::now().timestamp();
+
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    FilterRepository::new().insert(tx, &[filter.clone()])?;
+                    RulesListRepository::new().insert(
+                        tx,
+                        &[RulesListEntity::make(FILTER_ID, string!(OLD_TEXT), 3)],
+                    )
+                })
+            })
+            .unwrap();
+
+        // Filter is not expired, but preview requests it anyway
+        let preview =
+            preview_filter_update_action(filter, &source, &Configuration::default()).unwrap();
+
+        assert_eq!(preview.filter_id, FILTER_ID);
+        assert!(!preview.is_diff_update);
+        assert_eq!(preview.added_rules, vec!["||c.com^", "||d.com^"]);
+        assert_eq!(preview.removed_rules, vec!["||a.com^", "||b.com^"]);
+        assert_eq!(preview.old_version, "1.0");
+        assert_eq!(preview.new_version, "2.0");
+        assert_eq!(preview.old_title, "Preview");
+        assert_eq!(preview.new_title, "Preview v2");
+        assert_eq!(preview.new_expires, 86400);
+        assert_eq!(preview.rules_count_delta, 0);
+        assert_eq!(preview.served_url, Some(server.url("/filter.txt")));
+
+        let saved_text = source
+            .execute_db(|connection: Connection| {
+                Ok(RulesListRepository::new()
+                    .select_rules_maps(&connection, &[FILTER_ID])
+                    .unwrap()
+                    .0)
+            })
+            .unwrap()
+            .remove(&FILTER_ID)
+            .unwrap();
+        assert_eq!(saved_text, OLD_TEXT);
+    }
+
+    #[test]
+    fn test_preview_filter_update_honours_retry_after() {
+        const FILTER_ID: FilterId = -20001;
+
+        let is_rate_limited = Arc::new(AtomicBool::new(true));
+        let server_is_rate_limited = Arc::clone(&is_rate_limited);
+        let server = TestsHttpServer::start(move |_| {
+            // The first request is rate limited
+            if server_is_rate_limited.swap(false, Ordering::SeqCst) {
+                return TestsHttpResponse::new(429, "").with_header("Retry-After", "1");
+            }
+
+            TestsHttpResponse::new(
+                200,
+                "! Title: Retry-After\n! Version: 2.0\n||example.org^\n",
+            )
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+
+        let started_at = Instant::now();
+        let preview =
+            preview_filter_update_action(filters.remove(0), &source, &Configuration::default())
+                .unwrap();
+
+        assert!(started_at.elapsed() >= Duration::from_secs(1));
+        assert_eq!(server.requests().len(), 2);
+        assert!(!preview.is_patch_not_ready);
+        assert_eq!(preview.new_version, "2.0");
+        assert_eq!(preview.added_rules, vec!["||example.org^"]);
+    }
+
+    #[test]
+    fn test_preview_filter_update_falls_back_to_mirrors() {
+        const FILTER_ID: FilterId = -20001;
+
+        let server = TestsHttpServer::start(|request| match request.path.as_str() {
+            "/mirror.txt" => TestsHttpResponse::new(200, "! Title: Mirror\n||example.org^\n"),
+            _ => TestsHttpResponse::new(404, ""),
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+        filters[0].download_url = server.url("/missing.txt");
+
+        let mirror_url_entities = [server.url("/also-missing.txt"), server.url("/mirror.txt")]
+            .into_iter()
+            .map(|url| FilterMirrorUrlEntity {
+                filter_id: FILTER_ID,
+                url,
+            })
+            .collect::<Vec<FilterMirrorUrlEntity>>();
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    FilterMirrorUrlsRepository::new().insert(tx, &mirror_url_entities)
+                })
+            })
+            .unwrap();
+
+        let preview =
+            preview_filter_update_action(filters.remove(0), &source, &Configuration::default())
+                .unwrap();
+
+        assert_eq!(preview.served_url, Some(server.url("/mirror.txt")));
+        assert_eq!(preview.added_rules, vec!["||example.org^"]);
+        assert_eq!(
+            server
+                .requests()
+                .iter()
+                .map(|request| request.path.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["/missing.txt", "/also-missing.txt", "/mirror.txt"]
+        );
+    }
+
+    #[test]
+    fn test_preview_of_unpublished_diff_patch_is_marked_as_not_ready() {
+        const FILTER_ID: FilterId = -20001;
+        const FILTER_TEXT: &str =
+            "! Title: Diff\n! Diff-Path: patches/v1-m-28334060-60.patch\n||example.org^\n";
+
+        let server = TestsHttpServer::start(|request| match request.path.as_str() {
+            // Empty patch file means, that the next patch is not published yet
+            "/patches/v1-m-28334060-60.patch" => TestsHttpResponse::new(200, ""),
+            _ => TestsHttpResponse::new(200, FILTER_TEXT),
+        });
+
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let mut filters = spawn_filters_for_progress_tests(&source, &server, &[FILTER_ID]);
+        filters[0].last_download_time = Utc::now().timestamp();
+        filters[0].expires = i32::MAX / 2;
+
+        source
+            .execute_db(|mut connection: Connection| {
+                with_transaction(&mut connection, |tx| {
+                    RulesListRepository::new().insert(
+                        tx,
+                        &[RulesListEntity::make(FILTER_ID, string!(FILTER_TEXT), 1)],
+                    )?;
+                    DiffUpdateRepository::new().insert(
+                        tx,
+                        &[DiffUpdateEntity {
+                            filter_id: FILTER_ID,
+                            next_path: string!("patches/v1-m-28334060-60.patch"),
+                            next_check_time: 0,
+                        }],
+                    )
+                })
+            })
+            .unwrap();
+
+        let preview =
+            preview_filter_update_action(filters.remove(0), &source, &Configuration::default())
+                .unwrap();
+
+        assert!(preview.is_diff_update);
+        assert!(preview.is_patch_not_ready);
+        assert!(preview.added_rules.is_empty());
+        assert!(preview.removed_rules.is_empty());
+        assert_eq!(preview.rules_count_delta, 0);
+        // Filter itself hasn't been requested
+        assert_eq!(server.requests().len(), 1);
+    }
 }
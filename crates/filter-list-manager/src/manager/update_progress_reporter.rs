@@ -0,0 +1,95 @@
+//! Internal helper for [`UpdateObserver`] notifications and cancellation checks
+use crate::manager::models::update_progress::{
+    UpdateCancellationToken, UpdateObserver, UpdateOperationGuard, UpdateProgressEvent,
+    UpdateProgressStage,
+};
+use crate::manager::models::update_result::UpdateFilterError;
+use crate::FilterId;
+use std::sync::Arc;
+
+/// Holds optional observer and cancellation token of the manager
+#[derive(Clone, Default)]
+pub(crate) struct UpdateProgressReporter {
+    observer: Option<Arc<dyn UpdateObserver>>,
+    cancellation_token: UpdateCancellationToken,
+}
+
+impl UpdateProgressReporter {
+    /// Sets or removes observer
+    pub(crate) fn set_observer(&mut self, observer: Option<Arc<dyn UpdateObserver>>) {
+        self.observer = observer;
+    }
+
+    /// Gets shared cancellation token
+    pub(crate) fn cancellation_token(&self) -> UpdateCancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    /// Makes a copy with the same observer, but its own cancellation token
+    pub(crate) fn with_cancellation_token(
+        &self,
+        cancellation_token: UpdateCancellationToken,
+    ) -> Self {
+        Self {
+            observer: self.observer.clone(),
+            cancellation_token,
+        }
+    }
+
+    /// Must be called at the start of each cancellable operation, and the guard
+    /// must be kept until its end, so cancellation of the operation won't affect the next one
+    pub(crate) fn begin(&self) -> UpdateOperationGuard {
+        self.cancellation_token.begin()
+    }
+
+    /// Was the current operation cancelled
+    #[inline]
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation_token.is_cancelled()
+    }
+
+    /// Notifies observer about filter stage
+    pub(crate) fn notify(
+        &self,
+        filter_id: Option<FilterId>,
+        url: &str,
+        stage: UpdateProgressStage,
+    ) {
+        self.emit(filter_id, url, stage, None);
+    }
+
+    /// Notifies observer about failure
+    pub(crate) fn notify_failed(&self, filter_id: Option<FilterId>, url: &str, message: String) {
+        self.emit(filter_id, url, UpdateProgressStage::Failed, Some(message));
+    }
+
+    /// Notifies observer about failure, described by [`UpdateFilterError`]
+    pub(crate) fn notify_update_error(&self, error: &UpdateFilterError) {
+        if self.observer.is_none() {
+            return;
+        }
+
+        self.notify_failed(
+            Some(error.filter_id).filter(|filter_id| *filter_id != 0),
+            error.filter_url.as_deref().unwrap_or_default(),
+            error.message.clone(),
+        );
+    }
+
+    fn emit(
+        &self,
+        filter_id: Option<FilterId>,
+        url: &str,
+        stage: UpdateProgressStage,
+        message: Option<String>,
+    ) {
+        if let Some(observer) = self.observer.as_ref() {
+            observer.on_update_progress(UpdateProgressEvent {
+                filter_id,
+                url: url.to_string(),
+                stage,
+                message,
+            });
+        }
+    }
+}
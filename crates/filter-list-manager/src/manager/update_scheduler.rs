@@ -0,0 +1,606 @@
+//! Background scheduler of filters updates and metadata pulls
+use crate::manager::filter_list_manager_impl::FilterListManagerImpl;
+use crate::manager::models::update_progress::UpdateCancellationToken;
+use crate::manager::models::update_scheduler::{
+    UpdateSchedulerEvent, UpdateSchedulerObserver, UpdateSchedulerSettings, UpdateSchedulerTask,
+};
+use crate::manager::FilterListManager;
+use crate::storage::entities::filter::filter_entity::FilterEntity;
+use crate::storage::repositories::db_metadata_repository::DBMetadataRepository;
+use crate::storage::repositories::diff_updates_repository::{DiffUpdateRepository, DiffUpdatesMap};
+use crate::storage::repositories::filter_repository::FilterRepository;
+use crate::storage::sql_generators::operator::SQLOperator;
+use crate::storage::with_transaction;
+use crate::utils::backoff::exponential_backoff;
+use crate::{
+    Configuration, FLMError, FLMResult, FilterId, FilterUpdateOutcome, UpdateFailureKind,
+    UpdateResult,
+};
+use chrono::Utc;
+use rusqlite::Connection;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Name of the scheduler thread
+const SCHEDULER_THREAD_NAME: &str = "flm-update-scheduler";
+
+/// Handle of the running scheduler thread. Thread is stopped on drop
+pub(crate) struct UpdateScheduler {
+    settings: UpdateSchedulerSettings,
+    observer: Arc<dyn UpdateSchedulerObserver>,
+    stop_signal: Arc<StopSignal>,
+    cancellation_token: UpdateCancellationToken,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl UpdateScheduler {
+    /// Spawns the scheduler thread.
+    ///
+    /// * `manager` - Copy of the manager, which runs the tasks
+    /// * `cancellation_token` - Token of the `manager` copy. Cancelled on stop
+    pub(crate) fn start(
+        manager: FilterListManagerImpl,
+        cancellation_token: UpdateCancellationToken,
+        settings: UpdateSchedulerSettings,
+        observer: Arc<dyn UpdateSchedulerObserver>,
+    ) -> FLMResult<Self> {
+        settings.validate()?;
+
+        let stop_signal = Arc::new(StopSignal::default());
+        let worker = SchedulerWorker::new(
+            manager,
+            settings.clone(),
+            Arc::clone(&observer),
+            Arc::clone(&stop_signal),
+        );
+
+        let thread = std::thread::Builder::new()
+            .name(SCHEDULER_THREAD_NAME.to_string())
+            .spawn(move || worker.run())
+            .map_err(FLMError::from_io)?;
+
+        Ok(Self {
+            settings,
+            observer,
+            stop_signal,
+            cancellation_token,
+            thread: Some(thread),
+        })
+    }
+
+    /// Stops the thread, waiting for the running task to be cancelled.
+    /// Returns settings and observer, so the scheduler can be started again
+    pub(crate) fn stop(mut self) -> (UpdateSchedulerSettings, Arc<dyn UpdateSchedulerObserver>) {
+        self.shutdown();
+
+        (self.settings.clone(), Arc::clone(&self.observer))
+    }
+
+    fn shutdown(&mut self) {
+        self.stop_signal.stop();
+        self.cancellation_token.cancel();
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+impl UpdateScheduler {
+    /// Ctor for tests. Scheduler has no thread, so its settings are not validated
+    pub(crate) fn factory_test(
+        settings: UpdateSchedulerSettings,
+        observer: Arc<dyn UpdateSchedulerObserver>,
+    ) -> Self {
+        Self {
+            settings,
+            observer,
+            stop_signal: Arc::new(StopSignal::default()),
+            cancellation_token: UpdateCancellationToken::new(),
+            thread: None,
+        }
+    }
+}
+
+impl Drop for UpdateScheduler {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Stop flag, which can be awaited with timeout
+#[derive(Default)]
+struct StopSignal {
+    is_stopped: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl StopSignal {
+    fn stop(&self) {
+        *self
+            .is_stopped
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = true;
+        self.condvar.notify_all();
+    }
+
+    fn is_stopped(&self) -> bool {
+        *self
+            .is_stopped
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Sleeps until `deadline` (unix timestamp in seconds). Returns `true` if stop was requested
+    fn wait_until(&self, deadline: i64) -> bool {
+        let mut is_stopped = self
+            .is_stopped
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        loop {
+            if *is_stopped {
+                return true;
+            }
+
+            let now = Utc::now().timestamp();
+            if now >= deadline {
+                return false;
+            }
+
+            is_stopped = self
+                .condvar
+                .wait_timeout(is_stopped, Duration::from_secs((deadline - now) as u64))
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+}
+
+/// Next task of the scheduler
+#[derive(Debug, PartialEq)]
+struct Schedule {
+    task: UpdateSchedulerTask,
+    /// Unix timestamp in seconds
+    run_time: i64,
+}
+
+impl Schedule {
+    /// Chooses the next task.
+    ///
+    /// * `last_update_time` - Last run of filters update, if any
+    /// * `last_pull_metadata_time` - Last run of metadata pull, if any
+    /// * `earliest_due_time` - See [`earliest_filter_due_time`]
+    /// * `offline_until` - Tasks won't be run before this time
+    fn plan(
+        settings: &UpdateSchedulerSettings,
+        last_update_time: Option<i64>,
+        last_pull_metadata_time: Option<i64>,
+        earliest_due_time: Option<i64>,
+        offline_until: i64,
+    ) -> Self {
+        let by_interval =
+            last_update_time.map_or(0, |time| time + settings.update_interval_sec as i64);
+
+        let mut schedule = Self {
+            task: UpdateSchedulerTask::UpdateFilters,
+            run_time: earliest_due_time.map_or(by_interval, |due| due.max(by_interval)),
+        };
+
+        // Metadata goes first, because it may change the filters to update
+        if settings.pull_metadata_interval_sec > 0 {
+            let pull_time = last_pull_metadata_time
+                .map_or(0, |time| time + settings.pull_metadata_interval_sec as i64);
+
+            if pull_time <= schedule.run_time {
+                schedule = Self {
+                    task: UpdateSchedulerTask::PullMetadata,
+                    run_time: pull_time,
+                };
+            }
+        }
+
+        schedule.run_time = schedule.run_time.max(offline_until);
+
+        schedule
+    }
+}
+
+/// Calculates the earliest time, when at least one of enabled filters
+/// becomes expired or its differential update check is due.
+/// Returns [`None`] if there are no filters, which can be updated
+fn earliest_filter_due_time(
+    filters: &[FilterEntity],
+    diff_updates: &DiffUpdatesMap,
+    configuration: &Configuration,
+) -> Option<i64> {
+    filters
+        .iter()
+        .filter(|filter| filter.is_enabled && !filter.download_url.is_empty())
+        .map(|filter| {
+            let expiration_time = filter.last_download_time
+                + configuration.resolve_right_expires_value(filter.expires) as i64;
+
+            match filter
+                .filter_id
+                .and_then(|filter_id| diff_updates.get(&filter_id))
+            {
+                Some(diff_update) => expiration_time.min(diff_update.next_check_time),
+                None => expiration_time,
+            }
+        })
+        .min()
+}
+
+/// Filters update has failed, because the device is offline:
+/// nothing has been fetched, and at least one filter has failed due to network
+fn is_offline_update_result(result: &UpdateResult) -> bool {
+    let mut has_network_failures = false;
+
+    for report in result.filters_reports.iter() {
+        match report.outcome {
+            FilterUpdateOutcome::Updated
+            | FilterUpdateOutcome::UpdatedViaDiff
+            | FilterUpdateOutcome::SameContent
+            | FilterUpdateOutcome::PatchNotReady => return false,
+            FilterUpdateOutcome::Failed {
+                kind: UpdateFailureKind::Network,
+            } => has_network_failures = true,
+            _ => {}
+        }
+    }
+
+    has_network_failures
+}
+
+/// State of the scheduler thread
+struct SchedulerWorker {
+    manager: FilterListManagerImpl,
+    settings: UpdateSchedulerSettings,
+    observer: Arc<dyn UpdateSchedulerObserver>,
+    stop_signal: Arc<StopSignal>,
+    last_update_time: Option<i64>,
+    last_pull_metadata_time: Option<i64>,
+    /// Number of consecutive tasks, failed due to network
+    offline_attempt: u32,
+    offline_until: i64,
+}
+
+impl SchedulerWorker {
+    fn new(
+        manager: FilterListManagerImpl,
+        settings: UpdateSchedulerSettings,
+        observer: Arc<dyn UpdateSchedulerObserver>,
+        stop_signal: Arc<StopSignal>,
+    ) -> Self {
+        Self {
+            manager,
+            settings,
+            observer,
+            stop_signal,
+            last_update_time: None,
+            last_pull_metadata_time: None,
+            offline_attempt: 0,
+            offline_until: 0,
+        }
+    }
+
+    fn run(mut self) {
+        if let Ok(Some(metadata)) =
+            self.manager
+                .connection_manager
+                .execute_db(|conn: Connection| {
+                    DBMetadataRepository::read(&conn).map_err(FLMError::from_database)
+                })
+        {
+            self.last_update_time = metadata.scheduler_last_update_time;
+            self.last_pull_metadata_time = metadata.scheduler_last_pull_metadata_time;
+        }
+
+        let mut schedule = self.next_schedule();
+
+        while !self.stop_signal.wait_until(schedule.run_time) {
+            let mut event = self.run_task(schedule.task);
+
+            // Task has been cancelled, so it must be run again after restart
+            if self.stop_signal.is_stopped() {
+                break;
+            }
+
+            let now = Utc::now().timestamp();
+            if event.is_offline {
+                self.offline_until = now
+                    + exponential_backoff(
+                        self.settings.offline_backoff_min_sec as u64,
+                        self.offline_attempt,
+                        self.settings.offline_backoff_max_sec as u64,
+                    ) as i64;
+                self.offline_attempt = self.offline_attempt.saturating_add(1);
+            } else {
+                self.offline_attempt = 0;
+                self.offline_until = 0;
+
+                match schedule.task {
+                    UpdateSchedulerTask::UpdateFilters => self.last_update_time = Some(now),
+                    UpdateSchedulerTask::PullMetadata => self.last_pull_metadata_time = Some(now),
+                    // Restart is not scheduled
+                    UpdateSchedulerTask::Restart => {}
+                }
+
+                if let Err(why) = self.save_last_run_times() {
+                    event.error.get_or_insert(why);
+                }
+            }
+
+            schedule = self.next_schedule();
+            event.next_run_time = schedule.run_time;
+
+            self.observer.on_scheduled_task_finished(event);
+        }
+    }
+
+    fn next_schedule(&self) -> Schedule {
+        Schedule::plan(
+            &self.settings,
+            self.last_update_time,
+            self.last_pull_metadata_time,
+            self.read_earliest_filter_due_time().unwrap_or_default(),
+            self.offline_until,
+        )
+    }
+
+    fn run_task(&self, task: UpdateSchedulerTask) -> UpdateSchedulerEvent {
+        let mut event = UpdateSchedulerEvent {
+            task,
+            update_result: None,
+            pull_metadata_result: None,
+            error: None,
+            is_offline: false,
+            next_run_time: 0,
+        };
+
+        let error = match task {
+            UpdateSchedulerTask::UpdateFilters => {
+                match self
+                    .manager
+                    .update_filters(false, self.settings.loose_timeout, false)
+                {
+                    Ok(result) => {
+                        event.is_offline = result.as_ref().is_some_and(is_offline_update_result);
+                        event.update_result = result;
+
+                        None
+                    }
+                    Err(why) => Some(why),
+                }
+            }
+            UpdateSchedulerTask::PullMetadata => match self.manager.pull_metadata() {
+                Ok(result) => {
+                    event.pull_metadata_result = Some(result);
+
+                    None
+                }
+                Err(why) => Some(why),
+            },
+            // Restart is not scheduled
+            UpdateSchedulerTask::Restart => None,
+        };
+
+        if let Some(why) = error {
+            event.is_offline = matches!(why, FLMError::Network(_));
+            event.error = Some(why);
+        }
+
+        event
+    }
+
+    fn read_earliest_filter_due_time(&self) -> FLMResult<Option<i64>> {
+        let configuration = self.manager.get_configuration();
+
+        self.manager
+            .connection_manager
+            .execute_db(|conn: Connection| {
+                let Some(filters) = FilterRepository::new()
+                    .select(
+                        &conn,
+                        Some(SQLOperator::FieldEqualValue("is_enabled", true.into())),
+                    )
+                    .map_err(FLMError::from_database)?
+                else {
+                    return Ok(None);
+                };
+
+                let ids: Vec<FilterId> = filters
+                    .iter()
+                    .filter_map(|filter| filter.filter_id)
+                    .collect();
+
+                let diff_updates = DiffUpdateRepository::new()
+                    .select_map(&conn, &ids)
+                    .map_err(FLMError::from_database)?;
+
+                Ok(earliest_filter_due_time(
+                    &filters,
+                    &diff_updates,
+                    configuration,
+                ))
+            })
+    }
+
+    fn save_last_run_times(&self) -> FLMResult<()> {
+        let last_update_time = self.last_update_time;
+        let last_pull_metadata_time = self.last_pull_metadata_time;
+
+        self.manager
+            .connection_manager
+            .execute_db(move |mut conn: Connection| {
+                with_transaction(&mut conn, |tx| {
+                    let mut metadata = DBMetadataRepository::read(tx)?.unwrap_or_default();
+                    metadata.scheduler_last_update_time = last_update_time;
+                    metadata.scheduler_last_pull_metadata_time = last_pull_metadata_time;
+
+                    DBMetadataRepository::save(tx, &metadata)
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{earliest_filter_due_time, is_offline_update_result, Schedule};
+    use crate::manager::models::update_scheduler::{UpdateSchedulerSettings, UpdateSchedulerTask};
+    use crate::storage::entities::diff_update_entity::DiffUpdateEntity;
+    use crate::storage::entities::filter::filter_entity::FilterEntity;
+    use crate::{
+        Configuration, FilterUpdateOutcome, FilterUpdateReport, UpdateFailureKind, UpdateResult,
+    };
+    use std::collections::HashMap;
+
+    fn make_filter(filter_id: i32, last_download_time: i64, expires: i32) -> FilterEntity {
+        let mut filter = FilterEntity::default();
+        filter.filter_id = Some(filter_id);
+        filter.download_url = format!("https://example.com/{}.txt", filter_id);
+        filter.is_enabled = true;
+        filter.last_download_time = last_download_time;
+        filter.expires = expires;
+
+        filter
+    }
+
+    #[test]
+    fn test_plan_schedule() {
+        let settings = UpdateSchedulerSettings {
+            update_interval_sec: 100,
+            pull_metadata_interval_sec: 1000,
+            ..UpdateSchedulerSettings::default()
+        };
+
+        // Never run: metadata goes first
+        assert_eq!(
+            Schedule::plan(&settings, None, None, None, 0),
+            Schedule {
+                task: UpdateSchedulerTask::PullMetadata,
+                run_time: 0
+            }
+        );
+
+        // Filters are not expired yet
+        assert_eq!(
+            Schedule::plan(&settings, Some(5000), Some(5000), Some(5500), 0),
+            Schedule {
+                task: UpdateSchedulerTask::UpdateFilters,
+                run_time: 5500
+            }
+        );
+
+        // Expired filters wait for the interval
+        assert_eq!(
+            Schedule::plan(&settings, Some(5000), Some(5000), Some(4000), 0),
+            Schedule {
+                task: UpdateSchedulerTask::UpdateFilters,
+                run_time: 5100
+            }
+        );
+
+        // Metadata pull is due earlier
+        assert_eq!(
+            Schedule::plan(&settings, Some(5000), Some(4050), Some(6000), 0),
+            Schedule {
+                task: UpdateSchedulerTask::PullMetadata,
+                run_time: 5050
+            }
+        );
+
+        // Offline backoff postpones the task
+        assert_eq!(
+            Schedule::plan(&settings, Some(5000), Some(5000), None, 7000),
+            Schedule {
+                task: UpdateSchedulerTask::UpdateFilters,
+                run_time: 7000
+            }
+        );
+
+        // Pulling is disabled
+        let settings = UpdateSchedulerSettings {
+            update_interval_sec: 100,
+            pull_metadata_interval_sec: 0,
+            ..UpdateSchedulerSettings::default()
+        };
+        assert_eq!(
+            Schedule::plan(&settings, Some(5000), None, None, 0),
+            Schedule {
+                task: UpdateSchedulerTask::UpdateFilters,
+                run_time: 5100
+            }
+        );
+    }
+
+    #[test]
+    fn test_earliest_filter_due_time() {
+        let configuration = Configuration::default();
+
+        let mut disabled_filter = make_filter(3, 100, 3600);
+        disabled_filter.is_enabled = false;
+
+        let mut local_filter = make_filter(4, 100, 3600);
+        local_filter.download_url.clear();
+
+        let filters = vec![
+            make_filter(1, 10000, 7200),
+            make_filter(2, 10000, 3600),
+            disabled_filter,
+            local_filter,
+        ];
+
+        assert_eq!(
+            earliest_filter_due_time(&filters, &HashMap::new(), &configuration),
+            Some(13600)
+        );
+
+        let mut diff_updates = HashMap::new();
+        diff_updates.insert(
+            1,
+            DiffUpdateEntity {
+                filter_id: 1,
+                next_path: String::from("patches/1.patch"),
+                next_check_time: 11000,
+            },
+        );
+
+        assert_eq!(
+            earliest_filter_due_time(&filters, &diff_updates, &configuration),
+            Some(11000)
+        );
+
+        assert_eq!(
+            earliest_filter_due_time(&filters[2..], &diff_updates, &configuration),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_offline_update_result() {
+        let network_failure = FilterUpdateReport::new(
+            1,
+            FilterUpdateOutcome::Failed {
+                kind: UpdateFailureKind::Network,
+            },
+        );
+
+        let mut result = UpdateResult::default();
+        assert!(!is_offline_update_result(&result));
+
+        result.filters_reports.push(network_failure.clone());
+        result
+            .filters_reports
+            .push(FilterUpdateReport::new(2, FilterUpdateOutcome::NotExpired));
+        assert!(is_offline_update_result(&result));
+
+        result
+            .filters_reports
+            .push(FilterUpdateReport::new(3, FilterUpdateOutcome::Updated));
+        assert!(!is_offline_update_result(&result));
+    }
+}
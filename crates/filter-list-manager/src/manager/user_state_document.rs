@@ -0,0 +1,146 @@
+//! Serializable document for user state export/import
+use crate::manager::models::disabled_rule_pattern::{DisabledRulePattern, DisabledRulePatternKind};
+use crate::FilterId;
+use serde::{Deserialize, Serialize};
+
+/// Current version of [`UserStateDocument`] format.
+/// Must be increased on any incompatible change of the document.
+///
+/// Version history:
+/// * `1` - initial format
+/// * `2` - disabled rule patterns
+pub(crate) const USER_STATE_DOCUMENT_VERSION: u32 = 2;
+
+/// Portable backup of the user state
+#[derive(Serialize, Deserialize)]
+pub(crate) struct UserStateDocument {
+    /// Document format version
+    pub(crate) version: u32,
+    /// Export time. Unix timestamp in seconds
+    pub(crate) exported_at: i64,
+    /// Enabled/installed index filters and their disabled rules
+    #[serde(default)]
+    pub(crate) index_filters: Vec<IndexFilterState>,
+    /// Custom filters with their contents
+    #[serde(default)]
+    pub(crate) custom_filters: Vec<CustomFilterState>,
+    /// Special filter with user rules
+    pub(crate) user_rules: UserRulesState,
+}
+
+/// State of the filter from the index
+#[derive(Serialize, Deserialize)]
+pub(crate) struct IndexFilterState {
+    pub(crate) filter_id: FilterId,
+    pub(crate) is_enabled: bool,
+    pub(crate) is_installed: bool,
+    /// Newline-separated disabled rules
+    #[serde(default)]
+    pub(crate) disabled_rules: String,
+    /// Disabled rule patterns. [`None`] for documents of version `1`,
+    /// then patterns are kept as is
+    #[serde(default)]
+    pub(crate) disabled_rule_patterns: Option<Vec<DisabledRulePatternState>>,
+}
+
+/// Custom filter with all its data
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CustomFilterState {
+    pub(crate) filter_id: FilterId,
+    pub(crate) title: String,
+    pub(crate) description: String,
+    pub(crate) is_user_title: bool,
+    pub(crate) is_user_description: bool,
+    pub(crate) download_url: String,
+    pub(crate) subscription_url: String,
+    pub(crate) version: String,
+    pub(crate) homepage: String,
+    pub(crate) license: String,
+    pub(crate) checksum: String,
+    pub(crate) expires: i32,
+    pub(crate) last_update_time: i64,
+    pub(crate) last_download_time: i64,
+    pub(crate) is_enabled: bool,
+    pub(crate) is_installed: bool,
+    pub(crate) is_trusted: bool,
+    /// Newline-separated rules
+    pub(crate) rules: String,
+    pub(crate) rules_count: i32,
+    /// Newline-separated disabled rules
+    #[serde(default)]
+    pub(crate) disabled_rules: String,
+    /// Disabled rule patterns. [`None`] for documents of version `1`
+    #[serde(default)]
+    pub(crate) disabled_rule_patterns: Option<Vec<DisabledRulePatternState>>,
+    /// Resolved includes of the filter
+    #[serde(default)]
+    pub(crate) includes: Vec<CustomFilterIncludeState>,
+}
+
+/// Resolved include of the custom filter
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CustomFilterIncludeState {
+    pub(crate) absolute_url: String,
+    pub(crate) body: String,
+    pub(crate) rules_count: i32,
+}
+
+/// State of the user rules filter
+#[derive(Serialize, Deserialize)]
+pub(crate) struct UserRulesState {
+    pub(crate) is_enabled: bool,
+    /// Newline-separated rules
+    pub(crate) rules: String,
+    /// Newline-separated disabled rules
+    #[serde(default)]
+    pub(crate) disabled_rules: String,
+    /// Disabled rule patterns. [`None`] for documents of version `1`,
+    /// then patterns are kept as is
+    #[serde(default)]
+    pub(crate) disabled_rule_patterns: Option<Vec<DisabledRulePatternState>>,
+}
+
+/// Disabled rule pattern of the filter
+#[derive(Serialize, Deserialize)]
+pub(crate) struct DisabledRulePatternState {
+    pub(crate) kind: DisabledRulePatternKindState,
+    pub(crate) pattern: String,
+}
+
+/// Syntax of the pattern. See [`DisabledRulePatternKind`]
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DisabledRulePatternKindState {
+    Wildcard,
+    Regex,
+}
+
+impl From<DisabledRulePattern> for DisabledRulePatternState {
+    fn from(value: DisabledRulePattern) -> Self {
+        Self {
+            kind: match value.kind {
+                DisabledRulePatternKind::Wildcard => DisabledRulePatternKindState::Wildcard,
+                DisabledRulePatternKind::Regex => DisabledRulePatternKindState::Regex,
+            },
+            pattern: value.pattern,
+        }
+    }
+}
+
+impl From<DisabledRulePatternKindState> for DisabledRulePatternKind {
+    fn from(value: DisabledRulePatternKindState) -> Self {
+        match value {
+            DisabledRulePatternKindState::Wildcard => DisabledRulePatternKind::Wildcard,
+            DisabledRulePatternKindState::Regex => DisabledRulePatternKind::Regex,
+        }
+    }
+}
+
+impl From<DisabledRulePatternState> for DisabledRulePattern {
+    fn from(value: DisabledRulePatternState) -> Self {
+        Self {
+            kind: value.kind.into(),
+            pattern: value.pattern,
+        }
+    }
+}
@@ -1,15 +1,21 @@
 use crate::storage::blob::BlobHandle;
+use crate::RulesStorageCompression;
 use rusqlite::{blob::Blob, Result};
 
 /// Thin wrapper over [`Blob`] implementing [`BlobHandle`].
 pub(crate) struct BlobHandleImpl<'conn> {
     inner: Blob<'conn>,
+    compression: RulesStorageCompression,
 }
 
 impl<'conn> BlobHandleImpl<'conn> {
     /// Creates a new blob handle wrapper.
-    pub(in crate::storage) fn new(blob: Blob<'conn>) -> Self {
-        Self { inner: blob }
+    /// `compression` is the compression of the blob contents
+    pub(in crate::storage) fn new(blob: Blob<'conn>, compression: RulesStorageCompression) -> Self {
+        Self {
+            inner: blob,
+            compression,
+        }
     }
 }
 
@@ -17,4 +23,8 @@ impl BlobHandle for BlobHandleImpl<'_> {
     fn read_at(&self, buf: &mut [u8], read_start: usize) -> Result<usize> {
         self.inner.read_at(buf, read_start)
     }
+
+    fn compression(&self) -> RulesStorageCompression {
+        self.compression
+    }
 }
@@ -1,13 +1,20 @@
 use crate::storage::blob::blob_reader::BlobReader;
 use crate::storage::blob::{BlobHandle, BLOB_CHUNK_SIZE};
+use crate::storage::compression::ContentsReader;
+use crate::{FLMError, FLMResult};
 use std::io::BufReader;
 
 /// Buffered blob reader used by streaming blob consumers.
+/// Compressed blobs are decompressed on the fly.
 ///
 /// The buffer capacity is aligned with [`BLOB_CHUNK_SIZE`].
-pub(crate) type BufferedBlobReader<B> = BufReader<BlobReader<B>>;
+pub(crate) type BufferedBlobReader<B> = BufReader<ContentsReader<BlobReader<B>>>;
 
 /// Creates a buffered reader for sequential blob consumption.
-pub(crate) fn create_buffered_reader<B: BlobHandle>(blob: B) -> BufferedBlobReader<B> {
-    BufReader::with_capacity(BLOB_CHUNK_SIZE, BlobReader::new(blob))
+pub(crate) fn create_buffered_reader<B: BlobHandle>(blob: B) -> FLMResult<BufferedBlobReader<B>> {
+    let compression = blob.compression();
+    let reader =
+        ContentsReader::new(BlobReader::new(blob), compression).map_err(FLMError::from_io)?;
+
+    Ok(BufReader::with_capacity(BLOB_CHUNK_SIZE, reader))
 }
@@ -1,9 +1,10 @@
+use crate::filters::disabled_rules_matcher::DisabledRulesMatcher;
 use crate::storage::blob::{
     create_buffered_reader, read_next_line, write_to_stream, BlobHandleImpl, BufferedBlobReader,
 };
 use crate::storage::repositories::filter_includes_repository::FilterIncludesRepository;
 use crate::{FLMError, FLMResult};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::io::Write;
 
 /// Stateful blob reader + writer for streaming filter rules.
@@ -17,8 +18,8 @@ pub(crate) struct FilterStream<'a, W: Write> {
     output_stream: &'a mut W,
     /// Container for single line
     line_accumulator: Vec<u8>,
-    /// Disabled rules set for skipping lines in output stream
-    disabled_rules_set: &'a HashSet<Vec<u8>>,
+    /// Disabled rules matcher for skipping lines in output stream
+    disabled_rules_matcher: &'a DisabledRulesMatcher,
     /// Include resolution
     includes_url_to_row_id: &'a HashMap<String, i64>,
     /// Storage connection handle
@@ -29,18 +30,18 @@ impl<'a, W: Write> FilterStream<'a, W> {
     pub(crate) fn new(
         blob: BlobHandleImpl<'a>,
         stream: &'a mut W,
-        disabled_rules_set: &'a HashSet<Vec<u8>>,
+        disabled_rules_matcher: &'a DisabledRulesMatcher,
         includes_url_to_row_id: &'a HashMap<String, i64>,
         conn: &'a rusqlite::Connection,
-    ) -> Self {
-        Self {
-            input_stream: create_buffered_reader(blob),
+    ) -> FLMResult<Self> {
+        Ok(Self {
+            input_stream: create_buffered_reader(blob)?,
             line_accumulator: Vec::new(),
             output_stream: stream,
-            disabled_rules_set,
+            disabled_rules_matcher,
             includes_url_to_row_id,
             conn,
-        }
+        })
     }
 
     /// Reads the next line from the blob into `line_buf`.
@@ -66,7 +67,7 @@ impl<'a, W: Write> FilterStream<'a, W> {
     /// Writes a rule line to the output stream, skipping disabled rules.
     /// If `has_newline` is true, a trailing `\n` is appended.
     pub(crate) fn write_line(&mut self, line: &[u8], has_newline: bool) -> FLMResult<()> {
-        if self.disabled_rules_set.contains(line) {
+        if self.disabled_rules_matcher.is_disabled(line) {
             // Skip
             return Ok(());
         }
@@ -102,8 +103,11 @@ impl<'a, W: Write> FilterStream<'a, W> {
             .get_include_blob_handle(self.conn, row_id)
             .map_err(FLMError::from_database)?;
 
-        let ended_with_newline =
-            write_to_stream(self.output_stream, include_blob, self.disabled_rules_set)?;
+        let ended_with_newline = write_to_stream(
+            self.output_stream,
+            include_blob,
+            self.disabled_rules_matcher,
+        )?;
 
         if !ended_with_newline {
             self.output_stream
@@ -6,9 +6,9 @@ pub(crate) mod blob_reader;
 pub(crate) mod buffered_blob_reader;
 pub(crate) mod filter_stream;
 
-use crate::{FLMError, FLMResult};
+use crate::filters::disabled_rules_matcher::DisabledRulesMatcher;
+use crate::{FLMError, FLMResult, RulesStorageCompression};
 use rusqlite::Result;
-use std::collections::HashSet;
 use std::io::{BufRead, Write};
 
 pub(crate) use blob_handle_impl::BlobHandleImpl;
@@ -23,29 +23,33 @@ pub const BLOB_CHUNK_SIZE: usize = 1024 * 1024; // 1 mb
 pub(crate) trait BlobHandle {
     /// Inherits behaviour of [`Blob::read_at`]
     fn read_at(&self, buf: &mut [u8], read_start: usize) -> Result<usize>;
+
+    /// Compression of the blob contents
+    fn compression(&self) -> RulesStorageCompression {
+        RulesStorageCompression::None
+    }
 }
 
 /// Streams a blob into a [`Write`] sink line by line.
 ///
-/// Disabled lines are skipped using exact byte matching against
-/// `disabled_rules_set`.
+/// Disabled lines are skipped using `disabled_rules_matcher`.
 ///
 /// Returns `true` when the last emitted line ended with `\n`, otherwise `false`.
 pub(crate) fn write_to_stream<W, B>(
     stream: &mut W,
     blob: B,
-    disabled_rules_set: &HashSet<Vec<u8>>,
+    disabled_rules_matcher: &DisabledRulesMatcher,
 ) -> FLMResult<bool>
 where
     W: Write,
     B: BlobHandle,
 {
-    let mut reader = create_buffered_reader(blob);
+    let mut reader = create_buffered_reader(blob)?;
     let mut line_buf = Vec::new();
     let mut ended_with_newline = false;
 
     while let Some(has_newline) = read_next_line(&mut reader, &mut line_buf)? {
-        if !disabled_rules_set.contains(&line_buf) {
+        if !disabled_rules_matcher.is_disabled(&line_buf) {
             stream.write_all(&line_buf).map_err(FLMError::from_io)?;
 
             if has_newline {
@@ -94,8 +98,8 @@ pub(crate) fn read_next_line<R: BufRead>(
 mod tests {
     use super::write_to_stream;
     use super::BlobHandle;
+    use crate::filters::disabled_rules_matcher::DisabledRulesMatcher;
     use std::cell::RefCell;
-    use std::collections::HashSet;
     use std::io::Cursor;
     use std::io::Read;
     use std::io::Seek;
@@ -123,11 +127,13 @@ mod tests {
     }
 
     /// For testing purposes
-    fn write_to_stream_test_internal(data: &str, disabled_rules_set: HashSet<Vec<u8>>) -> String {
+    fn write_to_stream_test_internal(data: &str, disabled_rules: &[&[u8]]) -> String {
         let blob = TestBlobHandle::new(data.as_bytes().to_vec());
         let mut fake_file = Cursor::new(Vec::new());
 
-        write_to_stream(&mut fake_file, blob, &disabled_rules_set).unwrap();
+        let matcher = DisabledRulesMatcher::from_literals(disabled_rules.iter().copied());
+
+        write_to_stream(&mut fake_file, blob, &matcher).unwrap();
 
         let mut test_string = String::new();
 
@@ -140,7 +146,7 @@ mod tests {
     #[test]
     fn test_write_to_stream() {
         let data = "\nHello world!\n Hello world, again and again\n00";
-        let test_string = write_to_stream_test_internal(data, HashSet::new());
+        let test_string = write_to_stream_test_internal(data, &[]);
 
         assert_eq!(test_string.get((test_string.len() - 3)..), Some("\n00"));
         assert!(test_string.starts_with("\nHello world!\n H"));
@@ -148,16 +154,16 @@ mod tests {
 
     #[test]
     fn test_write_to_stream_empty_string() {
-        let test_string = write_to_stream_test_internal("", HashSet::new());
+        let test_string = write_to_stream_test_internal("", &[]);
         assert!(test_string.is_empty())
     }
 
     #[test]
     fn test_write_to_stream_with_disabled_rules() {
         let text = "Hallo world!\n This line won't be removed; \nfewf\n\n\n123456890";
-        let disabled_rules = HashSet::from([b"Hallo world!".to_vec(), b"fewf".to_vec()]);
+        let disabled_rules: [&[u8]; 2] = [b"Hallo world!", b"fewf"];
 
-        let test_string = write_to_stream_test_internal(text, disabled_rules);
+        let test_string = write_to_stream_test_internal(text, &disabled_rules);
         assert_eq!(
             test_string.as_str(),
             " This line won't be removed; \n\n\n123456890"
@@ -167,13 +173,9 @@ mod tests {
     #[test]
     fn test_write_to_stream_all_disabled_rules() {
         let text = "Hello world!\n Hello world, again and again\n00";
-        let disabled_rules = HashSet::from([
-            b"Hello world!".to_vec(),
-            b" Hello world, again and again".to_vec(),
-            b"00".to_vec(),
-        ]);
+        let disabled_rules: [&[u8]; 3] = [b"Hello world!", b" Hello world, again and again", b"00"];
 
-        let test_string = write_to_stream_test_internal(text, disabled_rules);
+        let test_string = write_to_stream_test_internal(text, &disabled_rules);
         assert!(test_string.is_empty())
     }
 }
@@ -0,0 +1,173 @@
+//! Encoding of filters contents, which are stored in `rules_list.rules_text`
+//! and `filter_includes.body` columns, according to [`RulesStorageCompression`]
+
+use crate::RulesStorageCompression;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, Type, ValueRef};
+use rusqlite::{Error, Row, ToSql};
+use std::io::{BufReader, Read, Result as IOResult};
+use zstd::stream::read::Decoder;
+
+/// Name of the column with [`RulesStorageCompression`] in both tables
+pub(crate) const COMPRESSION_FIELD: &str = "compression";
+
+/// Default zstd level is fast enough for mobile devices and still compresses filters several times
+const ZSTD_COMPRESSION_LEVEL: i32 = zstd::DEFAULT_COMPRESSION_LEVEL;
+
+impl ToSql for RulesStorageCompression {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        let value: i64 = match self {
+            RulesStorageCompression::None => 0,
+            RulesStorageCompression::Zstd => 1,
+        };
+
+        Ok(ToSqlOutput::from(value))
+    }
+}
+
+impl FromSql for RulesStorageCompression {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_i64()? {
+            0 => Ok(RulesStorageCompression::None),
+            1 => Ok(RulesStorageCompression::Zstd),
+            other => Err(FromSqlError::OutOfRange(other)),
+        }
+    }
+}
+
+/// Filter contents, encoded for saving
+pub(crate) enum EncodedContents<'a> {
+    /// Saved as TEXT
+    Plain(&'a str),
+    /// Saved as BLOB
+    Compressed(Vec<u8>),
+}
+
+impl<'a> EncodedContents<'a> {
+    /// Encodes `contents` with `compression`
+    pub(crate) fn encode(
+        contents: &'a str,
+        compression: RulesStorageCompression,
+    ) -> rusqlite::Result<Self> {
+        match compression {
+            RulesStorageCompression::None => Ok(Self::Plain(contents)),
+            RulesStorageCompression::Zstd => {
+                zstd::encode_all(contents.as_bytes(), ZSTD_COMPRESSION_LEVEL)
+                    .map(Self::Compressed)
+                    .map_err(|why| Error::ToSqlConversionFailure(Box::new(why)))
+            }
+        }
+    }
+}
+
+impl ToSql for EncodedContents<'_> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        match self {
+            EncodedContents::Plain(contents) => contents.to_sql(),
+            EncodedContents::Compressed(bytes) => bytes.to_sql(),
+        }
+    }
+}
+
+/// Reads filter contents from the column `index`, which has been saved with `compression`
+pub(crate) fn get_contents(
+    row: &Row,
+    index: usize,
+    compression: RulesStorageCompression,
+) -> rusqlite::Result<String> {
+    if compression == RulesStorageCompression::None {
+        return row.get(index);
+    }
+
+    let value = row.get_ref(index)?;
+    let data_type = value.data_type();
+
+    let mut contents = String::new();
+    value
+        .as_bytes()
+        .map_err(|why| Error::FromSqlConversionFailure(index, data_type, Box::new(why)))
+        .and_then(|bytes| {
+            ContentsReader::new(bytes, compression)
+                .and_then(|mut reader| reader.read_to_string(&mut contents))
+                .map_err(|why| Error::FromSqlConversionFailure(index, Type::Blob, Box::new(why)))
+        })?;
+
+    Ok(contents)
+}
+
+/// Reader of stored filter contents, which yields uncompressed bytes
+pub(crate) enum ContentsReader<R: Read> {
+    Plain(R),
+    Zstd(Decoder<'static, BufReader<R>>),
+}
+
+impl<R: Read> ContentsReader<R> {
+    /// Wraps `reader` of the contents, which have been saved with `compression`
+    pub(crate) fn new(reader: R, compression: RulesStorageCompression) -> IOResult<Self> {
+        match compression {
+            RulesStorageCompression::None => Ok(Self::Plain(reader)),
+            RulesStorageCompression::Zstd => Decoder::new(reader).map(Self::Zstd),
+        }
+    }
+}
+
+impl<R: Read> Read for ContentsReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IOResult<usize> {
+        match self {
+            ContentsReader::Plain(reader) => reader.read(buf),
+            ContentsReader::Zstd(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_contents, ContentsReader, EncodedContents};
+    use crate::RulesStorageCompression;
+    use rusqlite::Connection;
+    use std::io::Read;
+
+    #[test]
+    fn test_encode_and_read_contents() {
+        let contents = "! Title: Test\n||example.org^\n".repeat(100);
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE t (contents TEXT, compression INTEGER)", [])
+            .unwrap();
+
+        for compression in [RulesStorageCompression::None, RulesStorageCompression::Zstd] {
+            let encoded = EncodedContents::encode(&contents, compression).unwrap();
+
+            match (&encoded, compression) {
+                (EncodedContents::Plain(_), RulesStorageCompression::None) => {}
+                (EncodedContents::Compressed(bytes), RulesStorageCompression::Zstd) => {
+                    assert!(bytes.len() < contents.len() / 10);
+
+                    let mut decoded = String::new();
+                    ContentsReader::new(bytes.as_slice(), compression)
+                        .unwrap()
+                        .read_to_string(&mut decoded)
+                        .unwrap();
+                    assert_eq!(decoded, contents);
+                }
+                _ => panic!("Unexpected encoding"),
+            }
+
+            conn.execute("DELETE FROM t", []).unwrap();
+            conn.execute(
+                "INSERT INTO t (contents, compression) VALUES (?, ?)",
+                (&encoded, compression),
+            )
+            .unwrap();
+
+            let (read_compression, read_contents) = conn
+                .query_row("SELECT compression, contents FROM t", [], |row| {
+                    let compression: RulesStorageCompression = row.get(0)?;
+
+                    Ok((compression, get_contents(row, 1, compression)?))
+                })
+                .unwrap();
+
+            assert_eq!(read_compression, compression);
+            assert_eq!(read_contents, contents);
+        }
+    }
+}
@@ -16,6 +16,14 @@ pub const MAXIMUM_CUSTOM_FILTER_ID: FilterId = -10000;
 /// Custom filters ids must be in range
 pub const MINIMUM_CUSTOM_FILTER_ID: FilterId = -1_000_000_000;
 
+/// Name of the filter registry, which is described by [`crate::Configuration::metadata_url`]
+pub const MAIN_FILTER_REGISTRY_NAME: &str = "main";
+
+/// Filters, groups and tags ids from the registry index must be less than this number.
+/// Each registry occupies its own range of ids in the database: index ids are shifted by
+/// `registry_id * FILTER_REGISTRY_ID_NAMESPACE_SIZE`. Main registry keeps the original ids
+pub const FILTER_REGISTRY_ID_NAMESPACE_SIZE: i32 = 1_000_000;
+
 /// Smallest possible filter id value. -2^53
 /// You can safely occupy any filter with an id lower than this number.
 /// The library is guaranteed to never create a filter with this id.
@@ -1,4 +1,5 @@
 use super::DbConnectionManager;
+use crate::storage::encryption::apply_key;
 use crate::{FLMError, FLMResult};
 use rusqlite::{Connection, OpenFlags};
 
@@ -14,6 +15,21 @@ pub(super) fn connect_with_create(
     connect_internal(
         connection_source,
         OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE,
+        connection_source.encryption_key.as_deref(),
+    )
+}
+
+/// Same as [`connect_with_create`], but ignores the encryption key.
+/// Used for encryption of the plaintext database.
+///
+/// # Failure
+///
+/// returns [`FLMError`] if an error encountered
+pub(super) fn connect_plaintext(connection_source: &DbConnectionManager) -> FLMResult<Connection> {
+    connect_internal(
+        connection_source,
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE,
+        None,
     )
 }
 
@@ -23,7 +39,11 @@ pub(super) fn connect_with_create(
 ///
 /// returns [`FLMError`] if an error encountered
 pub(super) fn connect(connection_source: &DbConnectionManager) -> FLMResult<Connection> {
-    connect_internal(connection_source, OpenFlags::SQLITE_OPEN_READ_WRITE)
+    connect_internal(
+        connection_source,
+        OpenFlags::SQLITE_OPEN_READ_WRITE,
+        connection_source.encryption_key.as_deref(),
+    )
 }
 
 /// Main connection function
@@ -31,10 +51,15 @@ pub(super) fn connect(connection_source: &DbConnectionManager) -> FLMResult<Conn
 fn connect_internal(
     connection_source: &DbConnectionManager,
     open_flags: OpenFlags,
+    encryption_key: Option<&str>,
 ) -> FLMResult<Connection> {
     let conn = Connection::open_with_flags(connection_source.get_calculated_path(), open_flags)
         .map_err(FLMError::from_database)?;
 
+    if let Some(key) = encryption_key {
+        apply_key(&conn, key)?;
+    }
+
     enable_wal_mode(&conn);
 
     Ok(conn)
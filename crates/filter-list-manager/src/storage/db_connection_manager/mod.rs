@@ -1,12 +1,16 @@
 mod connect;
 pub mod database_name;
 
-use self::connect::{connect, connect_with_create};
+use self::connect::{connect, connect_plaintext, connect_with_create};
 use crate::storage::database_name::build_database_name_for_filter_list_type;
 use crate::storage::database_status::{
     create_db_folder_if_it_does_not_exist, get_database_status, DatabaseStatus,
 };
 use crate::storage::db_bootstrap::db_bootstrap;
+use crate::storage::encryption::{
+    build_export_path, ensure_encryption_is_supported, export_database, is_plaintext_database,
+    replace_database,
+};
 use crate::storage::migrations::run_migrations;
 use crate::{Configuration, FLMError, FLMResult, FilterListType};
 use rusqlite::Connection;
@@ -19,10 +23,12 @@ const SCHEMA_STR: &str = include_str!("../../../resources/sql/schema.sql");
 
 /// Structure for database configuration. Also, used to calculate the absolute path for a database.
 /// This MUST build path in constructors.
-#[cfg_attr(test, derive(Clone))]
+#[derive(Clone)]
 pub struct DbConnectionManager {
     calculated_path: PathBuf,
     db_mutex: Arc<Mutex<()>>,
+    /// SQLCipher key of the database. [`None`] for plaintext database
+    encryption_key: Option<String>,
 }
 
 impl DbConnectionManager {
@@ -33,10 +39,15 @@ impl DbConnectionManager {
             Some(ref str) => Ok(PathBuf::from(str)),
         }?;
 
-        Ok(Self::build_with_dir(
-            calculated_dir,
-            configuration.filter_list_type,
-        ))
+        if configuration.database_encryption_key.is_some() {
+            ensure_encryption_is_supported()?;
+        }
+
+        let mut connection_manager =
+            Self::build_with_dir(calculated_dir, configuration.filter_list_type);
+        connection_manager.encryption_key = configuration.database_encryption_key.clone();
+
+        Ok(connection_manager)
     }
 
     /// Path getter
@@ -64,6 +75,14 @@ impl DbConnectionManager {
         // First of all, create folder
         create_db_folder_if_it_does_not_exist(self.get_calculated_path().to_owned())?;
 
+        // One-shot encryption of the database, created before the key has been set
+        if let Some(ref key) = self.encryption_key {
+            if is_plaintext_database(self.get_calculated_path())? {
+                let conn = connect_plaintext(self)?;
+                self.export_and_replace(conn, key)?;
+            }
+        }
+
         let mut conn = connect_with_create(self)?;
         let mut tx = conn.transaction().map_err(FLMError::from_database)?;
 
@@ -88,6 +107,39 @@ impl DbConnectionManager {
 
         tx.commit().map_err(FLMError::from_database)
     }
+
+    /// Re-encrypts the database with the new key.
+    /// [`None`] decrypts the database into plaintext.
+    /// Further connections will use the new key
+    pub(crate) fn change_encryption_key(
+        &mut self,
+        encryption_key: Option<String>,
+    ) -> FLMResult<()> {
+        ensure_encryption_is_supported()?;
+
+        let _guard = self.db_mutex.lock();
+
+        // Attached export database inherits open flags, so it must be allowed to create files
+        let conn = connect_with_create(self)?;
+        self.export_and_replace(conn, encryption_key.as_deref().unwrap_or_default())?;
+
+        self.encryption_key = encryption_key;
+
+        Ok(())
+    }
+
+    /// Exports the database, opened by `conn`, into the file encrypted with `key`
+    /// and replaces the database with it.
+    /// `conn` must be opened with `SQLITE_OPEN_CREATE` flag under the `db_mutex` lock
+    fn export_and_replace(&self, conn: Connection, key: &str) -> FLMResult<()> {
+        let export_path = build_export_path(self.get_calculated_path());
+
+        export_database(&conn, &export_path, key)?;
+        // Connection must be closed before replacement
+        drop(conn);
+
+        replace_database(self.get_calculated_path(), &export_path)
+    }
 }
 
 impl DbConnectionManager {
@@ -108,6 +160,7 @@ impl DbConnectionManager {
         Self {
             calculated_path: dir,
             db_mutex: Arc::new(Mutex::new(())),
+            encryption_key: None,
         }
     }
 }
@@ -124,6 +177,15 @@ impl DbConnectionManager {
     pub(crate) fn factory_test() -> FLMResult<DbConnectionManager> {
         Self::from_configuration(&Configuration::default())
     }
+
+    /// Same manager with another encryption key
+    #[cfg(feature = "sqlcipher")]
+    pub(crate) fn with_encryption_key(&self, encryption_key: &str) -> DbConnectionManager {
+        let mut connection_manager = self.clone();
+        connection_manager.encryption_key = Some(encryption_key.to_string());
+
+        connection_manager
+    }
 }
 
 #[cfg(test)]
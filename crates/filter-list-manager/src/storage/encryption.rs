@@ -0,0 +1,232 @@
+//! Encryption of the database at rest with SQLCipher
+use crate::{FLMError, FLMResult};
+use rusqlite::{params, Connection};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+/// Header of every plaintext SQLite database file.
+/// Encrypted databases start with random salt instead
+const PLAINTEXT_DATABASE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Alias of the attached database for [`export_database`]
+const EXPORT_DATABASE_ALIAS: &str = "flm_export";
+
+/// Returns [`FLMError::InvalidConfiguration`] if the library is built without `sqlcipher` feature
+pub(crate) fn ensure_encryption_is_supported() -> FLMResult<()> {
+    if cfg!(feature = "sqlcipher") {
+        Ok(())
+    } else {
+        Err(FLMError::InvalidConfiguration(
+            "database encryption requires the library to be built with `sqlcipher` feature",
+        ))
+    }
+}
+
+/// Checks that the database file exists and is not encrypted.
+/// Empty file is not considered as plaintext database, because it has no contents to encrypt
+pub(crate) fn is_plaintext_database(db_path: &Path) -> FLMResult<bool> {
+    let mut file = match File::open(db_path) {
+        Ok(file) => file,
+        Err(why) if why.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(why) => return Err(FLMError::from_io(why)),
+    };
+
+    let mut header = [0u8; PLAINTEXT_DATABASE_HEADER.len()];
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header == PLAINTEXT_DATABASE_HEADER),
+        Err(why) if why.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(why) => Err(FLMError::from_io(why)),
+    }
+}
+
+/// Applies the key to the connection. Must be called before any other statement.
+///
+/// Wrong key isn't detected here: the first query will fail with
+/// [`crate::storage::error::DatabaseError::NotADatabase`]
+pub(crate) fn apply_key(conn: &Connection, key: &str) -> FLMResult<()> {
+    conn.pragma_update(None, "key", key)
+        .map_err(FLMError::from_database)
+}
+
+/// Builds path of the temporary file for [`export_database`]
+pub(crate) fn build_export_path(db_path: &Path) -> PathBuf {
+    with_suffix(db_path, ".export")
+}
+
+/// Copies all contents of the database, opened by `conn`, into the new file by `target_path`,
+/// which will be encrypted with `key`. Empty `key` makes plaintext copy.
+/// Target file is removed on failure
+pub(crate) fn export_database(conn: &Connection, target_path: &Path, key: &str) -> FLMResult<()> {
+    ensure_encryption_is_supported()?;
+
+    // Leftovers of the previous failed export
+    remove_database_files(target_path)?;
+
+    conn.execute(
+        &format!("ATTACH DATABASE ?1 AS {} KEY ?2", EXPORT_DATABASE_ALIAS),
+        params![target_path.to_string_lossy(), key],
+    )
+    .map_err(FLMError::from_database)?;
+
+    let export_result = conn
+        .query_row(
+            &format!("SELECT sqlcipher_export('{}')", EXPORT_DATABASE_ALIAS),
+            [],
+            |_| Ok(()),
+        )
+        .map_err(FLMError::from_database);
+
+    let detach_result = conn
+        .execute(&format!("DETACH DATABASE {}", EXPORT_DATABASE_ALIAS), [])
+        .map(|_| ())
+        .map_err(FLMError::from_database);
+
+    if let Err(why) = export_result.and(detach_result) {
+        remove_database_files(target_path).unwrap_or(());
+
+        return Err(why);
+    }
+
+    Ok(())
+}
+
+/// Replaces the database by `db_path` with the exported one.
+/// All connections to the database must be closed before
+pub(crate) fn replace_database(db_path: &Path, exported_path: &Path) -> FLMResult<()> {
+    // WAL sidecars of the old database must not be applied to the new one
+    remove_file_if_exists(&with_suffix(db_path, "-wal"))?;
+    remove_file_if_exists(&with_suffix(db_path, "-shm"))?;
+
+    fs::rename(exported_path, db_path).map_err(FLMError::from_io)
+}
+
+/// Removes database file with its sidecars
+fn remove_database_files(db_path: &Path) -> FLMResult<()> {
+    remove_file_if_exists(db_path)?;
+    remove_file_if_exists(&with_suffix(db_path, "-journal"))?;
+    remove_file_if_exists(&with_suffix(db_path, "-wal"))?;
+    remove_file_if_exists(&with_suffix(db_path, "-shm"))
+}
+
+fn remove_file_if_exists(path: &Path) -> FLMResult<()> {
+    match fs::remove_file(path) {
+        Err(why) if why.kind() != ErrorKind::NotFound => Err(FLMError::from_io(why)),
+        _ => Ok(()),
+    }
+}
+
+/// Appends `suffix` to the file name, e.g. `agflm_standard.db` -> `agflm_standard.db-wal`
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path_string = OsString::from(path.as_os_str());
+    path_string.push(suffix);
+
+    PathBuf::from(path_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_plaintext_database, with_suffix};
+    use crate::storage::DbConnectionManager;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_is_plaintext_database() {
+        let dcm = DbConnectionManager::factory_test().unwrap();
+        let path = dcm.get_calculated_path();
+
+        assert!(!is_plaintext_database(path).unwrap());
+
+        fs::write(path, b"").unwrap();
+        assert!(!is_plaintext_database(path).unwrap());
+
+        fs::remove_file(path).unwrap();
+        unsafe { dcm.lift_up_database().unwrap() }
+        assert!(is_plaintext_database(path).unwrap());
+    }
+
+    #[test]
+    fn test_with_suffix() {
+        assert_eq!(
+            with_suffix(Path::new("/tmp/agflm_standard.db"), "-wal"),
+            Path::new("/tmp/agflm_standard.db-wal")
+        );
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn test_encrypt_plaintext_database_on_lift_up() {
+        use crate::storage::error::DatabaseError;
+        use crate::storage::repositories::filter_repository::FilterRepository;
+        use crate::FLMError;
+
+        let plaintext_dcm = DbConnectionManager::factory_test().unwrap();
+        unsafe { plaintext_dcm.lift_up_database().unwrap() }
+
+        let filters_count = plaintext_dcm
+            .execute_db(|conn| Ok(FilterRepository::new().count_all(&conn).unwrap()))
+            .unwrap();
+
+        let encrypted_dcm = plaintext_dcm.with_encryption_key("secret");
+        unsafe { encrypted_dcm.lift_up_database().unwrap() }
+
+        let path = encrypted_dcm.get_calculated_path();
+        assert!(!is_plaintext_database(path).unwrap());
+        assert!(!build_export_path_exists(path));
+
+        let encrypted_filters_count = encrypted_dcm
+            .execute_db(|conn| Ok(FilterRepository::new().count_all(&conn).unwrap()))
+            .unwrap();
+        assert_eq!(encrypted_filters_count, filters_count);
+
+        // Database can't be read without the key
+        let err = plaintext_dcm
+            .execute_db(|conn| {
+                FilterRepository::new()
+                    .count_all(&conn)
+                    .map_err(FLMError::from_database)
+            })
+            .unwrap_err();
+        assert_eq!(err, FLMError::Database(DatabaseError::NotADatabase));
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn test_change_encryption_key() {
+        use crate::storage::error::DatabaseError;
+        use crate::storage::repositories::filter_repository::FilterRepository;
+        use crate::FLMError;
+
+        let mut dcm = DbConnectionManager::factory_test()
+            .unwrap()
+            .with_encryption_key("first");
+        unsafe { dcm.lift_up_database().unwrap() }
+
+        let old_key_dcm = dcm.clone();
+        dcm.change_encryption_key(Some(String::from("second")))
+            .unwrap();
+        assert!(!build_export_path_exists(dcm.get_calculated_path()));
+
+        assert!(
+            dcm.execute_db(|conn| Ok(FilterRepository::new().count_all(&conn).unwrap()))
+                .unwrap()
+                > 0
+        );
+
+        let err = old_key_dcm
+            .execute_db(|conn| {
+                FilterRepository::new()
+                    .count_all(&conn)
+                    .map_err(FLMError::from_database)
+            })
+            .unwrap_err();
+        assert_eq!(err, FLMError::Database(DatabaseError::NotADatabase));
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    fn build_export_path_exists(db_path: &Path) -> bool {
+        fs::metadata(super::build_export_path(db_path)).is_ok()
+    }
+}
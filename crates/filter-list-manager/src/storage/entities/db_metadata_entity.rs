@@ -14,6 +14,10 @@ pub(crate) struct DBMetadataEntity {
     /// Signed count of all filter records.
     /// `None` when integrity protection is disabled or not yet computed.
     pub(crate) filter_count_signature: Option<String>,
+    /// Last time the update scheduler has run filters update. Unix timestamp in seconds
+    pub(crate) scheduler_last_update_time: Option<i64>,
+    /// Last time the update scheduler has pulled metadata. Unix timestamp in seconds
+    pub(crate) scheduler_last_pull_metadata_time: Option<i64>,
 }
 
 impl Default for DBMetadataEntity {
@@ -22,6 +26,8 @@ impl Default for DBMetadataEntity {
             version: 0,
             custom_filters_autoincrement_value: MAXIMUM_CUSTOM_FILTER_ID,
             filter_count_signature: None,
+            scheduler_last_update_time: None,
+            scheduler_last_pull_metadata_time: None,
         }
     }
 }
@@ -32,6 +38,8 @@ impl Hydrate for DBMetadataEntity {
             version: row.get(0)?,
             custom_filters_autoincrement_value: row.get(1)?,
             filter_count_signature: row.get(2)?,
+            scheduler_last_update_time: row.get(3)?,
+            scheduler_last_pull_metadata_time: row.get(4)?,
         })
     }
 }
@@ -1,5 +1,7 @@
-use rusqlite::{Result, Row};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{Result, Row, ToSql};
 
+use crate::manager::models::filter_trust_level::FilterTrustLevel;
 use crate::manager::models::FilterId;
 use crate::storage::entities::hydrate::Hydrate;
 use crate::CUSTOM_FILTERS_GROUP_ID;
@@ -31,6 +33,8 @@ pub(crate) struct FilterEntity {
     pub is_enabled: bool,
     pub is_installed: bool,
     pub is_trusted: bool,
+    /// Trust level from the registry. [`None`] for custom filters
+    pub trust_level: Option<FilterTrustLevel>,
     pub(in crate::storage) is_user_title: Option<bool>,
     pub(in crate::storage) is_user_description: Option<bool>,
     pub(in crate::storage) integrity_signature: Option<String>,
@@ -100,6 +104,7 @@ impl Default for FilterEntity {
             version: String::new(),
             display_number: 0,
             is_trusted: false,
+            trust_level: None,
             expires: 0,
             homepage: String::new(),
             license: String::new(),
@@ -135,6 +140,30 @@ impl Hydrate for FilterEntity {
             is_user_title: row.get(17)?,
             is_user_description: row.get(18)?,
             integrity_signature: row.get(19)?,
+            trust_level: row.get(20)?,
         })
     }
 }
+
+impl ToSql for FilterTrustLevel {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        let value: i64 = match self {
+            FilterTrustLevel::Full => 0,
+            FilterTrustLevel::High => 1,
+            FilterTrustLevel::Low => 2,
+        };
+
+        Ok(ToSqlOutput::from(value))
+    }
+}
+
+impl FromSql for FilterTrustLevel {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_i64()? {
+            0 => Ok(FilterTrustLevel::Full),
+            1 => Ok(FilterTrustLevel::High),
+            2 => Ok(FilterTrustLevel::Low),
+            other => Err(FromSqlError::OutOfRange(other)),
+        }
+    }
+}
@@ -1,5 +1,6 @@
+use crate::storage::compression::get_contents;
 use crate::storage::entities::hydrate::Hydrate;
-use crate::FilterId;
+use crate::{FilterId, RulesStorageCompression};
 use rusqlite::Row;
 use std::ops::Not;
 
@@ -14,6 +15,8 @@ pub(crate) struct FilterIncludeEntity {
     pub(crate) rules_count: i32,
     pub(crate) body_hash: Option<String>,
     pub(crate) integrity_signature: Option<String>,
+    /// How `body` is stored in the database
+    pub(crate) compression: RulesStorageCompression,
 }
 
 impl FilterIncludeEntity {
@@ -35,6 +38,7 @@ impl FilterIncludeEntity {
             body,
             rules_count,
             integrity_signature: None,
+            compression: RulesStorageCompression::None,
         }
     }
 
@@ -45,14 +49,17 @@ impl FilterIncludeEntity {
 
 impl Hydrate for FilterIncludeEntity {
     fn hydrate(row: &Row) -> rusqlite::Result<Self> {
+        let compression = row.get(7)?;
+
         Ok(FilterIncludeEntity {
             row_id: row.get(0)?,
             filter_id: row.get(1)?,
             absolute_url: row.get(2)?,
-            body: row.get(3)?,
+            body: get_contents(row, 3, compression)?,
             rules_count: row.get(4)?,
             body_hash: row.get(5)?,
             integrity_signature: row.get(6)?,
+            compression,
         })
     }
 }
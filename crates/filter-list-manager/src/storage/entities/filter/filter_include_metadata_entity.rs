@@ -1,4 +1,5 @@
 use crate::manager::models::FilterId;
+use crate::RulesStorageCompression;
 use rusqlite::{Result, Row};
 
 /// Lightweight metadata entity for filter_includes table.
@@ -8,6 +9,7 @@ pub(crate) struct FilterIncludeMetadataEntity {
     pub(crate) filter_id: FilterId,
     pub(crate) absolute_url: String,
     pub(crate) integrity_signature: Option<String>,
+    pub(crate) compression: RulesStorageCompression,
 }
 
 impl FilterIncludeMetadataEntity {
@@ -17,6 +19,7 @@ impl FilterIncludeMetadataEntity {
             filter_id: row.get(1)?,
             absolute_url: row.get(2)?,
             integrity_signature: row.get(3)?,
+            compression: row.get(4)?,
         })
     }
 }
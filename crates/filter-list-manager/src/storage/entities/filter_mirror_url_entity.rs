@@ -0,0 +1,21 @@
+use rusqlite::{Result, Row};
+
+use crate::manager::models::FilterId;
+
+use super::hydrate::Hydrate;
+
+/// Entity for filter_mirror_url table
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FilterMirrorUrlEntity {
+    pub(crate) filter_id: FilterId,
+    pub(crate) url: String,
+}
+
+impl Hydrate for FilterMirrorUrlEntity {
+    fn hydrate(row: &Row) -> Result<FilterMirrorUrlEntity> {
+        Ok(FilterMirrorUrlEntity {
+            filter_id: row.get(0)?,
+            url: row.get(1)?,
+        })
+    }
+}
@@ -0,0 +1,28 @@
+use rusqlite::{Result, Row};
+
+use crate::FilterId;
+
+use super::hydrate::Hydrate;
+
+/// Entity for filter_pin table.
+/// Pinned filters are skipped by updates
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(crate) struct FilterPinEntity {
+    /// Related filter entity id
+    pub(crate) filter_id: FilterId,
+    /// Filter version, which has been pinned
+    pub(crate) version: String,
+    /// Timestamp of pinning
+    pub(crate) pinned_time: i64,
+}
+
+impl Hydrate for FilterPinEntity {
+    fn hydrate(row: &Row) -> Result<FilterPinEntity> {
+        Ok(FilterPinEntity {
+            filter_id: row.get(0)?,
+            version: row.get(1)?,
+            pinned_time: row.get(2)?,
+        })
+    }
+}
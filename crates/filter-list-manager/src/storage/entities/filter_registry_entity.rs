@@ -0,0 +1,19 @@
+use rusqlite::{Result, Row};
+
+use super::hydrate::Hydrate;
+
+/// Entity for filter_registry table
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FilterRegistryEntity {
+    pub(crate) registry_id: i32,
+    pub(crate) name: String,
+}
+
+impl Hydrate for FilterRegistryEntity {
+    fn hydrate(row: &Row) -> Result<FilterRegistryEntity> {
+        Ok(FilterRegistryEntity {
+            registry_id: row.get(0)?,
+            name: row.get(1)?,
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use rusqlite::{Result, Row};
+
+use crate::{FilterId, FilterUpdateFailure};
+
+use super::hydrate::Hydrate;
+
+/// Entity for filter_update_failure table
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(crate) struct FilterUpdateFailureEntity {
+    /// Related filter entity id
+    pub(crate) filter_id: FilterId,
+    /// Number of updates in a row, which have failed for this filter
+    pub(crate) consecutive_failures: i32,
+    /// Timestamp of the first failure in a row
+    pub(crate) first_failure_time: i64,
+    /// Timestamp of the last failure
+    pub(crate) last_failure_time: i64,
+    /// Last error converted to a string
+    pub(crate) last_error: String,
+    /// Filter will not be requested by regular updates before this time
+    pub(crate) next_retry_time: i64,
+}
+
+impl FilterUpdateFailureEntity {
+    /// Makes entity for the next failure of the filter
+    ///
+    /// * `previous` - Previous failure of this filter, if there is one
+    /// * `backoff_sec` - Delay in seconds before the next retry
+    pub(crate) fn make_next(
+        previous: Option<&FilterUpdateFailureEntity>,
+        filter_id: FilterId,
+        last_error: String,
+        current_time: i64,
+        backoff_sec: i64,
+    ) -> Self {
+        Self {
+            filter_id,
+            consecutive_failures: previous
+                .map(|entity| entity.consecutive_failures)
+                .unwrap_or_default()
+                + 1,
+            first_failure_time: previous
+                .map(|entity| entity.first_failure_time)
+                .unwrap_or(current_time),
+            last_failure_time: current_time,
+            last_error,
+            next_retry_time: current_time + backoff_sec,
+        }
+    }
+}
+
+impl Hydrate for FilterUpdateFailureEntity {
+    fn hydrate(row: &Row) -> Result<FilterUpdateFailureEntity> {
+        Ok(FilterUpdateFailureEntity {
+            filter_id: row.get(0)?,
+            consecutive_failures: row.get(1)?,
+            first_failure_time: row.get(2)?,
+            last_failure_time: row.get(3)?,
+            last_error: row.get(4)?,
+            next_retry_time: row.get(5)?,
+        })
+    }
+}
+
+impl From<FilterUpdateFailureEntity> for FilterUpdateFailure {
+    fn from(value: FilterUpdateFailureEntity) -> Self {
+        Self {
+            filter_id: value.filter_id,
+            consecutive_failures: value.consecutive_failures,
+            first_failure_time: value.first_failure_time,
+            last_failure_time: value.last_failure_time,
+            last_error: value.last_error,
+            next_retry_time: value.next_retry_time,
+        }
+    }
+}
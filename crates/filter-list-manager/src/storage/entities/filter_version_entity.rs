@@ -0,0 +1,60 @@
+use rusqlite::{Result, Row};
+
+use crate::{FilterId, FilterVersion};
+
+use super::hydrate::Hydrate;
+
+/// Entity for filter_version table.
+/// Rules text and includes are not loaded into this entity
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(crate) struct FilterVersionEntity {
+    pub(crate) version_id: i64,
+    /// Related filter entity id
+    pub(crate) filter_id: FilterId,
+    /// Timestamp, when these contents have been replaced
+    pub(crate) saved_time: i64,
+    pub(crate) version: String,
+    pub(crate) title: String,
+    pub(crate) description: String,
+    pub(crate) homepage: String,
+    pub(crate) license: String,
+    pub(crate) checksum: String,
+    pub(crate) expires: i32,
+    pub(crate) last_update_time: i64,
+    /// Rules count of the filter and its includes
+    pub(crate) rules_count: i32,
+}
+
+impl Hydrate for FilterVersionEntity {
+    fn hydrate(row: &Row) -> Result<FilterVersionEntity> {
+        Ok(FilterVersionEntity {
+            version_id: row.get(0)?,
+            filter_id: row.get(1)?,
+            saved_time: row.get(2)?,
+            version: row.get(3)?,
+            title: row.get(4)?,
+            description: row.get(5)?,
+            homepage: row.get(6)?,
+            license: row.get(7)?,
+            checksum: row.get(8)?,
+            expires: row.get(9)?,
+            last_update_time: row.get(10)?,
+            rules_count: row.get(11)?,
+        })
+    }
+}
+
+impl From<FilterVersionEntity> for FilterVersion {
+    fn from(value: FilterVersionEntity) -> Self {
+        Self {
+            version_id: value.version_id,
+            filter_id: value.filter_id,
+            version: value.version,
+            title: value.title,
+            last_update_time: value.last_update_time,
+            saved_time: value.saved_time,
+            rules_count: value.rules_count,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use rusqlite::{Result, Row};
+
+use crate::io::http::cache_validators::CacheValidators;
+
+use super::hydrate::Hydrate;
+
+/// Entity for http_cache_validators table
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(crate) struct HttpCacheValidatorsEntity {
+    /// Requested url. Filter download url or index url
+    pub(crate) url: String,
+    /// `ETag` header value of the last response
+    pub(crate) etag: Option<String>,
+    /// `Last-Modified` header value of the last response
+    pub(crate) last_modified: Option<String>,
+}
+
+impl HttpCacheValidatorsEntity {
+    pub(crate) fn make(url: String, validators: CacheValidators) -> Self {
+        Self {
+            url,
+            etag: validators.etag,
+            last_modified: validators.last_modified,
+        }
+    }
+}
+
+impl From<HttpCacheValidatorsEntity> for CacheValidators {
+    fn from(value: HttpCacheValidatorsEntity) -> Self {
+        Self {
+            etag: value.etag,
+            last_modified: value.last_modified,
+        }
+    }
+}
+
+impl Hydrate for HttpCacheValidatorsEntity {
+    fn hydrate(row: &Row) -> Result<HttpCacheValidatorsEntity> {
+        Ok(HttpCacheValidatorsEntity {
+            url: row.get(0)?,
+            etag: row.get(1)?,
+            last_modified: row.get(2)?,
+        })
+    }
+}
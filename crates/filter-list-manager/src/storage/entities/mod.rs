@@ -4,7 +4,13 @@ pub(crate) mod filter;
 pub(crate) mod filter_filter_tag_entity;
 pub(crate) mod filter_group_entity;
 pub(crate) mod filter_locale_entity;
+pub(crate) mod filter_mirror_url_entity;
+pub(crate) mod filter_pin_entity;
+pub(crate) mod filter_registry_entity;
 pub(crate) mod filter_tag_entity;
+pub(crate) mod filter_update_failure_entity;
+pub(crate) mod filter_version_entity;
+pub(crate) mod http_cache_validators_entity;
 pub(crate) mod hydrate;
 pub(crate) mod localisation;
 pub(crate) mod rules_list;
@@ -0,0 +1,64 @@
+use crate::manager::models::disabled_rule_pattern::{DisabledRulePattern, DisabledRulePatternKind};
+use crate::manager::models::FilterId;
+use crate::storage::entities::hydrate::Hydrate;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{Result, Row, ToSql};
+
+/// Entity for disabled_rule_pattern table
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, Eq, PartialEq))]
+pub(crate) struct DisabledRulePatternEntity {
+    pub(crate) filter_id: FilterId,
+    pub(crate) kind: DisabledRulePatternKind,
+    pub(crate) pattern: String,
+}
+
+impl DisabledRulePatternEntity {
+    pub(crate) fn make(filter_id: FilterId, pattern: DisabledRulePattern) -> Self {
+        Self {
+            filter_id,
+            kind: pattern.kind,
+            pattern: pattern.pattern,
+        }
+    }
+}
+
+impl Hydrate for DisabledRulePatternEntity {
+    fn hydrate(row: &Row) -> Result<DisabledRulePatternEntity> {
+        Ok(DisabledRulePatternEntity {
+            filter_id: row.get(0)?,
+            kind: row.get(1)?,
+            pattern: row.get(2)?,
+        })
+    }
+}
+
+impl From<DisabledRulePatternEntity> for DisabledRulePattern {
+    fn from(value: DisabledRulePatternEntity) -> Self {
+        Self {
+            kind: value.kind,
+            pattern: value.pattern,
+        }
+    }
+}
+
+impl ToSql for DisabledRulePatternKind {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        let value: i64 = match self {
+            DisabledRulePatternKind::Wildcard => 0,
+            DisabledRulePatternKind::Regex => 1,
+        };
+
+        Ok(ToSqlOutput::from(value))
+    }
+}
+
+impl FromSql for DisabledRulePatternKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_i64()? {
+            0 => Ok(DisabledRulePatternKind::Wildcard),
+            1 => Ok(DisabledRulePatternKind::Regex),
+            other => Err(FromSqlError::OutOfRange(other)),
+        }
+    }
+}
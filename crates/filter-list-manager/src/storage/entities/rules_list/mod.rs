@@ -1,3 +1,4 @@
+pub(crate) mod disabled_rule_pattern_entity;
 pub(crate) mod disabled_rules_entity;
 pub(crate) mod rules_count_entity;
 pub(crate) mod rules_list_entity;
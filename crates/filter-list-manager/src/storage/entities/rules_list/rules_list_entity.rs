@@ -1,8 +1,9 @@
 use crate::manager::models::filter_list_rules::FilterListRules;
 use crate::manager::models::filter_list_rules_raw::FilterListRulesRaw;
 use crate::manager::models::FilterId;
+use crate::storage::compression::get_contents;
 use crate::storage::entities::hydrate::Hydrate;
-use crate::string;
+use crate::{string, RulesStorageCompression};
 use rusqlite::{Result, Row};
 use std::ops::Not;
 
@@ -20,6 +21,8 @@ pub(crate) struct RulesListEntity {
     pub(in crate::storage) has_directives: bool,
     pub(in crate::storage) text_hash: Option<String>,
     pub(crate) integrity_signature: Option<String>,
+    /// How `text` is stored in the database
+    pub(crate) compression: RulesStorageCompression,
 }
 
 impl RulesListEntity {
@@ -38,6 +41,7 @@ impl RulesListEntity {
             rules_count,
             has_directives: DEFAULT_HAS_DIRECTIVES_VALUE,
             integrity_signature: None,
+            compression: RulesStorageCompression::None,
         }
     }
 
@@ -52,14 +56,17 @@ impl RulesListEntity {
 
 impl Hydrate for RulesListEntity {
     fn hydrate(row: &Row) -> Result<RulesListEntity> {
+        let compression = row.get(7)?;
+
         Ok(RulesListEntity {
             filter_id: row.get(0)?,
-            text: row.get(1)?,
+            text: get_contents(row, 1, compression)?,
             disabled_text: row.get(2)?,
             rules_count: row.get(3)?,
             has_directives: row.get(4)?,
             text_hash: row.get(5)?,
             integrity_signature: row.get(6)?,
+            compression,
         })
     }
 }
@@ -1,4 +1,5 @@
 use crate::manager::models::FilterId;
+use crate::RulesStorageCompression;
 use rusqlite::{Result, Row};
 
 /// Lightweight metadata entity for streaming rules from rules_list table.
@@ -8,6 +9,7 @@ pub(crate) struct RulesListMetadataEntity {
     pub(crate) filter_id: FilterId,
     pub(crate) has_directives: bool,
     pub(crate) integrity_signature: Option<String>,
+    pub(crate) compression: RulesStorageCompression,
 }
 
 impl RulesListMetadataEntity {
@@ -17,6 +19,7 @@ impl RulesListMetadataEntity {
             filter_id: row.get(1)?,
             has_directives: row.get(2)?,
             integrity_signature: row.get(3)?,
+            compression: row.get(4)?,
         })
     }
 }
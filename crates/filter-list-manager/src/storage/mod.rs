@@ -4,13 +4,16 @@ use crate::{FLMError, FLMResult};
 use rusqlite::{Connection, Transaction};
 
 pub(crate) mod blob;
+pub(crate) mod compression;
 pub mod constants;
 pub(crate) mod database_status;
 pub(crate) mod db_bootstrap;
 mod db_connection_manager;
+pub(crate) mod encryption;
 pub(crate) mod entities;
 pub mod error;
 mod migrations;
+pub(crate) mod registry_id_namespace;
 pub(crate) mod repositories;
 pub(crate) mod sql_generators;
 mod utils;
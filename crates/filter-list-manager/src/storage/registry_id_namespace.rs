@@ -0,0 +1,81 @@
+//! Ranges of ids, occupied by filter registries
+use crate::FILTER_REGISTRY_ID_NAMESPACE_SIZE;
+
+/// The greatest registry id, which namespace still fits into [`i32`]
+pub(crate) const MAX_FILTER_REGISTRY_ID: i32 = i32::MAX / FILTER_REGISTRY_ID_NAMESPACE_SIZE - 1;
+
+/// Range of filters, groups and tags ids of the single filter registry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RegistryIdNamespace {
+    registry_id: i32,
+}
+
+impl RegistryIdNamespace {
+    pub(crate) const fn new(registry_id: i32) -> Self {
+        Self { registry_id }
+    }
+
+    /// Gets namespace by the database id of registry filter, group or tag.
+    /// Returns [`None`] for custom and special ones
+    pub(crate) const fn of(id: i32) -> Option<Self> {
+        if id > 0 {
+            Some(Self::new(id / FILTER_REGISTRY_ID_NAMESPACE_SIZE))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) const fn registry_id(self) -> i32 {
+        self.registry_id
+    }
+
+    /// Converts id from the registry index into the database one
+    pub(crate) const fn to_database_id(self, index_id: i32) -> i32 {
+        self.offset() + index_id
+    }
+
+    /// The smallest database id of the namespace
+    pub(crate) const fn first_id(self) -> i32 {
+        self.offset() + 1
+    }
+
+    /// The greatest database id of the namespace
+    pub(crate) const fn last_id(self) -> i32 {
+        self.offset() + FILTER_REGISTRY_ID_NAMESPACE_SIZE - 1
+    }
+
+    /// Checks that database `id` belongs to the namespace
+    pub(crate) const fn contains(self, id: i32) -> bool {
+        id >= self.first_id() && id <= self.last_id()
+    }
+
+    const fn offset(self) -> i32 {
+        self.registry_id * FILTER_REGISTRY_ID_NAMESPACE_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RegistryIdNamespace, MAX_FILTER_REGISTRY_ID};
+
+    #[test]
+    fn test_registry_id_namespace() {
+        let main = RegistryIdNamespace::new(0);
+        assert_eq!(main.to_database_id(2), 2);
+        assert!(main.contains(999_999));
+        assert!(!main.contains(0));
+
+        let second = RegistryIdNamespace::new(2);
+        assert_eq!(second.to_database_id(2), 2_000_002);
+        assert!(second.contains(2_000_002));
+        assert!(!second.contains(2));
+        assert!(!second.contains(3_000_001));
+
+        assert_eq!(RegistryIdNamespace::of(2_000_002), Some(second));
+        assert_eq!(RegistryIdNamespace::of(2), Some(main));
+        assert_eq!(RegistryIdNamespace::of(-10_001), None);
+
+        // The last namespace must not overflow
+        assert!(RegistryIdNamespace::new(MAX_FILTER_REGISTRY_ID).last_id() > 0);
+    }
+}
@@ -10,7 +10,9 @@ CREATE TABLE [metadata] (
     [rowid] INTEGER PRIMARY KEY,
     [schema_version] INTEGER NOT NULL,
     [custom_filter_increment] INTEGER NOT NULL,
-    [filter_count_signature] TEXT
+    [filter_count_signature] TEXT,
+    [scheduler_last_update_time] INTEGER,
+    [scheduler_last_pull_metadata_time] INTEGER
 );";
 
 /// Basic SQL-query with all fields
@@ -18,7 +20,9 @@ const BASIC_SELECT_SQL: &str = r"
     SELECT
         schema_version,
         custom_filter_increment,
-        filter_count_signature
+        filter_count_signature,
+        scheduler_last_update_time,
+        scheduler_last_pull_metadata_time
     FROM
         [metadata]
     WHERE
@@ -133,12 +137,16 @@ impl DBMetadataRepository {
                 rowid,
                 schema_version,
                 custom_filter_increment,
-                filter_count_signature
+                filter_count_signature,
+                scheduler_last_update_time,
+                scheduler_last_pull_metadata_time
             ) VALUES (
                 1,
                 :schema_version,
                 :custom_filter_increment,
-                :filter_count_signature
+                :filter_count_signature,
+                :scheduler_last_update_time,
+                :scheduler_last_pull_metadata_time
             )
         ",
         )?;
@@ -147,6 +155,8 @@ impl DBMetadataRepository {
             ":schema_version": entity.version,
             ":custom_filter_increment": entity.custom_filters_autoincrement_value,
             ":filter_count_signature": entity.filter_count_signature,
+            ":scheduler_last_update_time": entity.scheduler_last_update_time,
+            ":scheduler_last_pull_metadata_time": entity.scheduler_last_pull_metadata_time,
         })?;
 
         Ok(())
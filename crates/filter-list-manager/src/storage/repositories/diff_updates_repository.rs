@@ -1,6 +1,6 @@
 use crate::storage::entities::diff_update_entity::DiffUpdateEntity;
 use crate::storage::entities::hydrate::Hydrate;
-use crate::storage::repositories::Repository;
+use crate::storage::repositories::{BulkDeleteRepository, Repository};
 use crate::storage::utils::build_in_clause;
 use crate::FilterId;
 use rusqlite::{named_params, params_from_iter, Connection, Error, Transaction};
@@ -53,6 +53,10 @@ impl DiffUpdateRepository {
     }
 }
 
+impl BulkDeleteRepository<DiffUpdateEntity, FilterId> for DiffUpdateRepository {
+    const PK_FIELD: &'static str = "filter_id";
+}
+
 impl Repository<DiffUpdateEntity> for DiffUpdateRepository {
     const TABLE_NAME: &'static str = "[diff_updates]";
 
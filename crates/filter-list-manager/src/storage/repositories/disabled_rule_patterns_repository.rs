@@ -0,0 +1,118 @@
+use crate::storage::entities::hydrate::Hydrate;
+use crate::storage::entities::rules_list::disabled_rule_pattern_entity::DisabledRulePatternEntity;
+use crate::storage::repositories::{BulkDeleteRepository, Repository};
+use crate::storage::utils::build_in_clause;
+use crate::FilterId;
+use rusqlite::{named_params, params_from_iter, Connection, Error, Transaction};
+use std::collections::HashMap;
+
+pub(crate) type DisabledRulePatternsMap = HashMap<FilterId, Vec<DisabledRulePatternEntity>>;
+
+/// Repository for `disabled_rule_pattern` table.
+/// Patterns are stored apart from `rules_list.disabled_text`, so they survive filter updates
+pub(crate) struct DisabledRulePatternsRepository;
+
+impl DisabledRulePatternsRepository {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Selects patterns mapped by [`FilterId`] for provided `for_ids`.
+    /// Patterns of each filter are kept in the saved order
+    pub(crate) fn select_map(
+        &self,
+        conn: &Connection,
+        for_ids: &[FilterId],
+    ) -> rusqlite::Result<DisabledRulePatternsMap> {
+        let sql = format!(
+            r"
+            SELECT
+                filter_id,
+                kind,
+                pattern
+            FROM
+                [disabled_rule_pattern]
+            WHERE {}
+            ORDER BY
+                row_id",
+            build_in_clause("filter_id", for_ids.len())
+        );
+
+        let mut statement = conn.prepare(sql.as_str())?;
+
+        let mut rows = statement.query(params_from_iter(for_ids))?;
+
+        let mut out: DisabledRulePatternsMap = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let entity = DisabledRulePatternEntity::hydrate(row)?;
+
+            out.entry(entity.filter_id).or_default().push(entity);
+        }
+
+        Ok(out)
+    }
+
+    /// Selects patterns of the single filter
+    pub(crate) fn select_for_filter(
+        &self,
+        conn: &Connection,
+        filter_id: FilterId,
+    ) -> rusqlite::Result<Vec<DisabledRulePatternEntity>> {
+        Ok(self
+            .select_map(conn, &[filter_id])?
+            .remove(&filter_id)
+            .unwrap_or_default())
+    }
+
+    /// Replaces all patterns of the filter with `entities`
+    pub(crate) fn replace_for_filter(
+        &self,
+        tx: &Transaction,
+        filter_id: FilterId,
+        entities: &[DisabledRulePatternEntity],
+    ) -> rusqlite::Result<()> {
+        self.bulk_delete(tx, &vec![filter_id])?;
+
+        self.insert(tx, entities)
+    }
+}
+
+impl Repository<DisabledRulePatternEntity> for DisabledRulePatternsRepository {
+    const TABLE_NAME: &'static str = "[disabled_rule_pattern]";
+
+    fn insert(
+        &self,
+        conn: &Transaction<'_>,
+        entities: &[DisabledRulePatternEntity],
+    ) -> Result<(), Error> {
+        let mut statement = conn.prepare(
+            r"
+            INSERT INTO
+                [disabled_rule_pattern]
+                (
+                    filter_id,
+                    kind,
+                    pattern
+                ) VALUES (
+                    :filter_id,
+                    :kind,
+                    :pattern
+                )
+        ",
+        )?;
+
+        for entity in entities.iter() {
+            statement.execute(named_params! {
+                ":filter_id": entity.filter_id,
+                ":kind": entity.kind,
+                ":pattern": entity.pattern
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BulkDeleteRepository<DisabledRulePatternEntity, FilterId> for DisabledRulePatternsRepository {
+    const PK_FIELD: &'static str = "filter_id";
+}
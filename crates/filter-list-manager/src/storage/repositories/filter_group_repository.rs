@@ -1,7 +1,7 @@
 use crate::manager::models::configuration::Locale;
 use crate::storage::entities::filter_group_entity::FilterGroupEntity;
 use crate::storage::entities::hydrate::Hydrate;
-use crate::storage::repositories::Repository;
+use crate::storage::repositories::{RegistryScopedRepository, Repository};
 #[cfg(test)]
 use rusqlite::Row;
 use rusqlite::{named_params, Connection, Result, Transaction};
@@ -83,16 +83,6 @@ impl FilterGroupRepository {
 
         Ok(out)
     }
-
-    /// This deletes groups only from index
-    pub(crate) fn delete_index_groups(&self, transaction: &Transaction) -> Result<()> {
-        let mut statement = transaction
-            .prepare(format!("DELETE FROM {} WHERE group_id > 0", Self::TABLE_NAME).as_str())?;
-
-        statement.execute(())?;
-
-        Ok(())
-    }
 }
 
 impl Repository<FilterGroupEntity> for FilterGroupRepository {
@@ -137,3 +127,7 @@ impl Repository<FilterGroupEntity> for FilterGroupRepository {
         Err(rusqlite::Error::InvalidQuery)
     }
 }
+
+impl RegistryScopedRepository<FilterGroupEntity> for FilterGroupRepository {
+    const REGISTRY_SCOPE_FIELD: &'static str = "group_id";
+}
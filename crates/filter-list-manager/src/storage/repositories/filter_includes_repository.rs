@@ -1,4 +1,6 @@
+use crate::storage::blob::blob_reader::BlobReader;
 use crate::storage::blob::{BlobHandleImpl, BLOB_CHUNK_SIZE};
+use crate::storage::compression::{get_contents, ContentsReader, EncodedContents};
 use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
 use crate::storage::entities::filter::filter_include_metadata_entity::FilterIncludeMetadataEntity;
 use crate::storage::repositories::Repository;
@@ -8,8 +10,10 @@ use crate::storage::Hydrate;
 use crate::utils::integrity::{sign_content, verify_content};
 use crate::FilterId;
 use blake3::Hasher;
+use rusqlite::types::Type;
 use rusqlite::{named_params, params_from_iter, Connection, DatabaseName, Error, Transaction};
 use std::collections::HashMap;
+use std::io::Read;
 
 pub(crate) type MapFilterIdOnFilterIncludes = HashMap<FilterId, Vec<FilterIncludeEntity>>;
 
@@ -22,7 +26,8 @@ const BASIC_SELECT_SQL: &str = r"
         body,
         rules_count,
         body_hash,
-        integrity_signature
+        integrity_signature,
+        compression
     FROM
         [filter_includes]
 ";
@@ -120,6 +125,35 @@ impl FilterIncludesRepository {
         statement.execute(params_from_iter(ids)).map(|_| ())
     }
 
+    /// Iterates over entities by `where_clause` ordered by [`FilterId`],
+    /// without loading all include bodies into memory at once.
+    /// Iteration stops when `block` returns `false`.
+    pub(crate) fn for_each<F, E>(
+        &self,
+        conn: &Connection,
+        where_clause: Option<SQLOperator>,
+        mut block: F,
+    ) -> Result<(), E>
+    where
+        F: FnMut(FilterIncludeEntity) -> Result<bool, E>,
+        E: From<Error>,
+    {
+        let mut sql = String::from(BASIC_SELECT_SQL);
+        let params = process_where_clause(&mut sql, where_clause)?;
+        sql.push_str(" ORDER BY filter_id, row_id");
+
+        let mut statement = conn.prepare(sql.as_str())?;
+        let mut rows = statement.query(params)?;
+
+        while let Some(row) = rows.next()? {
+            if !block(FilterIncludeEntity::hydrate(row)?)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Gets entities mapped by [`FilterId`]
     pub(crate) fn select_mapped(
         &self,
@@ -160,7 +194,8 @@ impl FilterIncludesRepository {
             SELECT
                 row_id,
                 filter_id,
-                body
+                body,
+                compression
             FROM
                 [filter_includes]",
         )?;
@@ -171,7 +206,7 @@ impl FilterIncludesRepository {
         while let Some(row) = rows.next()? {
             let row_id: i64 = row.get(0)?;
             let filter_id: FilterId = row.get(1)?;
-            let body: String = row.get(2)?;
+            let body = get_contents(row, 2, row.get(3)?)?;
 
             let sig = sign_content(derived_key, filter_id, &body);
             signatures.push((row_id, sig));
@@ -193,7 +228,8 @@ impl FilterIncludesRepository {
             SELECT
                 filter_id,
                 body,
-                integrity_signature
+                integrity_signature,
+                compression
             FROM
                 [filter_includes]",
         )?;
@@ -201,7 +237,7 @@ impl FilterIncludesRepository {
         let mut rows = statement.query([])?;
         while let Some(row) = rows.next()? {
             let filter_id: FilterId = row.get(0)?;
-            let body: String = row.get(1)?;
+            let body = get_contents(row, 1, row.get(3)?)?;
             let signature: Option<String> = row.get(2)?;
 
             if let Some(ref sig) = signature {
@@ -245,7 +281,7 @@ impl FilterIncludesRepository {
 
 impl FilterIncludesRepository {
     /// Gets lightweight metadata for includes of a single filter, without loading body.
-    /// Returns Vec of (row_id, filter_id, absolute_url, integrity_signature).
+    /// Returns Vec of (row_id, filter_id, absolute_url, integrity_signature, compression).
     pub(crate) fn get_include_metadata_for_filter(
         &self,
         conn: &Connection,
@@ -257,7 +293,8 @@ impl FilterIncludesRepository {
                 row_id,
                 filter_id,
                 absolute_url,
-                integrity_signature
+                integrity_signature,
+                compression
             FROM
                 [filter_includes]
             WHERE
@@ -276,6 +313,7 @@ impl FilterIncludesRepository {
 
     /// Verifies integrity of include body by streaming the blob through blake3
     /// incremental hasher, without loading the full body into memory.
+    /// Compressed body is hashed after decompression.
     /// Returns `true` if the signature matches.
     pub(crate) fn verify_include_blob_integrity_streaming(
         &self,
@@ -299,15 +337,20 @@ impl FilterIncludesRepository {
         let mut hasher = Hasher::new_keyed(derived_key);
         hasher.update(&metadata.filter_id.to_le_bytes());
 
+        let to_sql_error = |why| Error::FromSqlConversionFailure(3, Type::Blob, Box::new(why));
+        let mut reader = ContentsReader::new(
+            BlobReader::new(BlobHandleImpl::new(blob, metadata.compression)),
+            metadata.compression,
+        )
+        .map_err(to_sql_error)?;
+
         let mut buffer = vec![0u8; BLOB_CHUNK_SIZE];
-        let mut offset = 0;
         loop {
-            let bytes_read = blob.read_at(&mut buffer, offset)?;
+            let bytes_read = reader.read(&mut buffer).map_err(to_sql_error)?;
             if bytes_read == 0 {
                 break;
             }
             hasher.update(&buffer[..bytes_read]);
-            offset += bytes_read;
         }
 
         let computed = hasher.finalize();
@@ -320,9 +363,22 @@ impl FilterIncludesRepository {
         conn: &'a Connection,
         row_id: i64,
     ) -> rusqlite::Result<BlobHandleImpl<'a>> {
+        let compression = conn.query_row(
+            r"
+            SELECT
+                compression
+            FROM
+                [filter_includes]
+            WHERE
+                row_id = ?
+        ",
+            [row_id],
+            |row| row.get(0),
+        )?;
+
         let blob = conn.blob_open(DatabaseName::Main, Self::TABLE_NAME, "body", row_id, true)?;
 
-        Ok(BlobHandleImpl::new(blob))
+        Ok(BlobHandleImpl::new(blob, compression))
     }
 }
 
@@ -345,7 +401,8 @@ impl Repository<FilterIncludeEntity> for FilterIncludesRepository {
                         body,
                         rules_count,
                         body_hash,
-                        integrity_signature
+                        integrity_signature,
+                        compression
                     )
                 VALUES
                     (
@@ -355,7 +412,8 @@ impl Repository<FilterIncludeEntity> for FilterIncludesRepository {
                         :body,
                         :rules_count,
                         :body_hash,
-                        :integrity_signature
+                        :integrity_signature,
+                        :compression
                     )
                 ",
         )?;
@@ -365,10 +423,11 @@ impl Repository<FilterIncludeEntity> for FilterIncludesRepository {
                 ":row_id": entity.row_id,
                 ":filter_id": entity.filter_id,
                 ":absolute_url": entity.absolute_url,
-                ":body": entity.body,
+                ":body": EncodedContents::encode(&entity.body, entity.compression)?,
                 ":rules_count": entity.rules_count,
                 ":body_hash": entity.body_hash,
                 ":integrity_signature": entity.integrity_signature,
+                ":compression": entity.compression,
             })?;
         }
 
@@ -1,7 +1,7 @@
 use crate::manager::models::FilterId;
 use crate::storage::entities::filter_locale_entity::FilterLocaleEntity;
 use crate::storage::entities::hydrate::Hydrate;
-use crate::storage::repositories::Repository;
+use crate::storage::repositories::{RegistryScopedRepository, Repository};
 use rusqlite::Result;
 use rusqlite::{named_params, Connection, Error, Transaction};
 use std::collections::hash_map::Entry;
@@ -83,3 +83,7 @@ impl Repository<FilterLocaleEntity> for FilterLocaleRepository {
         Ok(())
     }
 }
+
+impl RegistryScopedRepository<FilterLocaleEntity> for FilterLocaleRepository {
+    const REGISTRY_SCOPE_FIELD: &'static str = "filter_id";
+}
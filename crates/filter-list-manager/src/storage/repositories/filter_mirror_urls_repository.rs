@@ -0,0 +1,94 @@
+use crate::storage::entities::filter_mirror_url_entity::FilterMirrorUrlEntity;
+use crate::storage::entities::hydrate::Hydrate;
+use crate::storage::repositories::{BulkDeleteRepository, RegistryScopedRepository, Repository};
+use crate::storage::utils::build_in_clause;
+use crate::FilterId;
+use rusqlite::{named_params, params_from_iter, Connection, Error, Transaction};
+use std::collections::HashMap;
+
+pub(crate) type MapFilterIdOnMirrorUrls = HashMap<FilterId, Vec<String>>;
+
+/// Repository for `filter_mirror_url` table.
+/// Mirrors come from the index, so the table is fully replaced on each index sync
+pub(crate) struct FilterMirrorUrlsRepository;
+
+impl FilterMirrorUrlsRepository {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Selects mirror urls mapped by [`FilterId`] for provided `for_ids`.
+    /// Urls of each filter are kept in the index order
+    pub(crate) fn select_map(
+        &self,
+        conn: &Connection,
+        for_ids: &[FilterId],
+    ) -> rusqlite::Result<MapFilterIdOnMirrorUrls> {
+        let sql = format!(
+            r"
+            SELECT
+                filter_id,
+                url
+            FROM
+                [filter_mirror_url]
+            WHERE {}
+            ORDER BY
+                row_id",
+            build_in_clause("filter_id", for_ids.len())
+        );
+
+        let mut statement = conn.prepare(sql.as_str())?;
+
+        let mut rows = statement.query(params_from_iter(for_ids))?;
+
+        let mut out: MapFilterIdOnMirrorUrls = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let entity = FilterMirrorUrlEntity::hydrate(row)?;
+
+            out.entry(entity.filter_id).or_default().push(entity.url);
+        }
+
+        Ok(out)
+    }
+}
+
+impl Repository<FilterMirrorUrlEntity> for FilterMirrorUrlsRepository {
+    const TABLE_NAME: &'static str = "[filter_mirror_url]";
+
+    fn insert(
+        &self,
+        conn: &Transaction<'_>,
+        entities: &[FilterMirrorUrlEntity],
+    ) -> Result<(), Error> {
+        let mut statement = conn.prepare(
+            r"
+            INSERT INTO
+                [filter_mirror_url]
+                (
+                    filter_id,
+                    url
+                ) VALUES (
+                    :filter_id,
+                    :url
+                )
+        ",
+        )?;
+
+        for entity in entities.iter() {
+            statement.execute(named_params! {
+                ":filter_id": entity.filter_id,
+                ":url": entity.url
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BulkDeleteRepository<FilterMirrorUrlEntity, FilterId> for FilterMirrorUrlsRepository {
+    const PK_FIELD: &'static str = "filter_id";
+}
+
+impl RegistryScopedRepository<FilterMirrorUrlEntity> for FilterMirrorUrlsRepository {
+    const REGISTRY_SCOPE_FIELD: &'static str = "filter_id";
+}
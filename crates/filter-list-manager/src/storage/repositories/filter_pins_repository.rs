@@ -0,0 +1,87 @@
+use crate::storage::entities::filter_pin_entity::FilterPinEntity;
+use crate::storage::entities::hydrate::Hydrate;
+use crate::storage::repositories::{BulkDeleteRepository, Repository};
+use crate::storage::utils::build_in_clause;
+use crate::FilterId;
+use rusqlite::{named_params, params_from_iter, Connection, Error, Transaction};
+use std::collections::HashMap;
+
+pub(crate) type FilterPinsMap = HashMap<FilterId, FilterPinEntity>;
+
+/// Repository for `filter_pin` table.
+/// Filters, which have been rolled back to the previous version, are stored here
+pub(crate) struct FilterPinsRepository;
+
+impl FilterPinsRepository {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Selects entities mapped by [`FilterId`] for provided `for_ids`
+    pub(crate) fn select_map(
+        &self,
+        conn: &Connection,
+        for_ids: &[FilterId],
+    ) -> rusqlite::Result<FilterPinsMap> {
+        let sql = format!(
+            r"
+            SELECT
+                filter_id,
+                version,
+                pinned_time
+            FROM
+                [filter_pin]
+            WHERE {}",
+            build_in_clause("filter_id", for_ids.len())
+        );
+
+        let mut statement = conn.prepare(sql.as_str())?;
+
+        let mut rows = statement.query(params_from_iter(for_ids))?;
+
+        let mut out = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let entity = FilterPinEntity::hydrate(row)?;
+
+            out.insert(entity.filter_id, entity);
+        }
+
+        Ok(out)
+    }
+}
+
+impl Repository<FilterPinEntity> for FilterPinsRepository {
+    const TABLE_NAME: &'static str = "[filter_pin]";
+
+    fn insert(&self, conn: &Transaction<'_>, entities: &[FilterPinEntity]) -> Result<(), Error> {
+        let mut statement = conn.prepare(
+            r"
+            INSERT OR REPLACE INTO
+                [filter_pin]
+                (
+                    filter_id,
+                    version,
+                    pinned_time
+                ) VALUES (
+                    :filter_id,
+                    :version,
+                    :pinned_time
+                )
+        ",
+        )?;
+
+        for entity in entities.iter() {
+            statement.execute(named_params! {
+                ":filter_id": entity.filter_id,
+                ":version": entity.version,
+                ":pinned_time": entity.pinned_time
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BulkDeleteRepository<FilterPinEntity, FilterId> for FilterPinsRepository {
+    const PK_FIELD: &'static str = "filter_id";
+}
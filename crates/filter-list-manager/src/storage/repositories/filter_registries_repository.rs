@@ -0,0 +1,98 @@
+use crate::storage::entities::filter_registry_entity::FilterRegistryEntity;
+use crate::storage::entities::hydrate::Hydrate;
+use crate::storage::repositories::Repository;
+use rusqlite::{named_params, Connection, Error, OptionalExtension, Transaction};
+use std::collections::HashMap;
+
+/// Map of registry id on its name
+pub(crate) type MapRegistryIdOnName = HashMap<i32, String>;
+
+/// Repository for `filter_registry` table.
+/// Registry id defines the range of its filters ids, so registries are never deleted
+pub(crate) struct FilterRegistriesRepository;
+
+impl FilterRegistriesRepository {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Selects names of all known registries
+    pub(crate) fn select_mapped(&self, conn: &Connection) -> rusqlite::Result<MapRegistryIdOnName> {
+        let mut statement = conn.prepare(
+            r"
+            SELECT
+                registry_id,
+                name
+            FROM
+                [filter_registry]
+        ",
+        )?;
+
+        let rows = statement.query_map((), FilterRegistryEntity::hydrate)?;
+
+        let mut out = HashMap::new();
+        for row in rows {
+            let entity = row?;
+
+            out.insert(entity.registry_id, entity.name);
+        }
+
+        Ok(out)
+    }
+
+    /// Selects id of the registry by its `name`
+    pub(crate) fn select_id_by_name(
+        &self,
+        conn: &Connection,
+        name: &str,
+    ) -> rusqlite::Result<Option<i32>> {
+        conn.query_row(
+            "SELECT registry_id FROM [filter_registry] WHERE name = ?1",
+            [name],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Selects the greatest registry id
+    pub(crate) fn select_max_id(&self, conn: &Connection) -> rusqlite::Result<i32> {
+        conn.query_row(
+            "SELECT COALESCE(MAX(registry_id), 0) FROM [filter_registry]",
+            (),
+            |row| row.get(0),
+        )
+    }
+}
+
+impl Repository<FilterRegistryEntity> for FilterRegistriesRepository {
+    const TABLE_NAME: &'static str = "[filter_registry]";
+
+    fn insert(
+        &self,
+        conn: &Transaction<'_>,
+        entities: &[FilterRegistryEntity],
+    ) -> Result<(), Error> {
+        let mut statement = conn.prepare(
+            r"
+            INSERT INTO
+                [filter_registry]
+                (
+                    registry_id,
+                    name
+                ) VALUES (
+                    :registry_id,
+                    :name
+                )
+        ",
+        )?;
+
+        for entity in entities.iter() {
+            statement.execute(named_params! {
+                ":registry_id": entity.registry_id,
+                ":name": entity.name
+            })?;
+        }
+
+        Ok(())
+    }
+}
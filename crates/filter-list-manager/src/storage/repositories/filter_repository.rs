@@ -39,7 +39,8 @@ const BASIC_SELECT_SQL: &str = r"
         f.is_trusted,
         f.is_user_title,
         f.is_user_description,
-        f.integrity_signature
+        f.integrity_signature,
+        f.trust_level
     FROM
         [filter] f
 ";
@@ -486,7 +487,8 @@ impl FilterRepository {
                     is_trusted,
                     is_user_title,
                     is_user_description,
-                    integrity_signature
+                    integrity_signature,
+                    trust_level
                 ) VALUES (
                     :filter_id,
                     :group_id,
@@ -507,7 +509,8 @@ impl FilterRepository {
                     :is_trusted,
                     :is_user_title,
                     :is_user_description,
-                    :integrity_signature
+                    :integrity_signature,
+                    :trust_level
                 )",
         )?;
 
@@ -584,6 +587,7 @@ impl FilterRepository {
                 ":is_user_title": is_user_title,
                 ":is_user_description": is_user_description,
                 ":integrity_signature": entity.integrity_signature,
+                ":trust_level": entity.trust_level,
             })?;
         }
 
@@ -855,6 +859,7 @@ mod tests {
                 license: "".to_string(),
                 display_number: 0,
                 is_trusted: false,
+                trust_level: None,
                 expires: 0,
                 homepage: "".to_string(),
                 is_installed: false,
@@ -929,6 +934,7 @@ mod tests {
                 checksum: "".to_string(),
                 license: "".to_string(),
                 is_trusted: false,
+                trust_level: None,
                 expires: 0,
                 homepage: "".to_string(),
                 is_installed: false,
@@ -1,7 +1,7 @@
 use crate::manager::models::FilterId;
 use crate::storage::entities::filter_tag_entity::FilterTagEntity;
 use crate::storage::entities::hydrate::Hydrate;
-use crate::storage::repositories::Repository;
+use crate::storage::repositories::{RegistryScopedRepository, Repository};
 use rusqlite::{named_params, Connection, Error, Result, Row, Transaction};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
@@ -119,3 +119,7 @@ impl Repository<FilterTagEntity> for FilterTagRepository {
         Ok(())
     }
 }
+
+impl RegistryScopedRepository<FilterTagEntity> for FilterTagRepository {
+    const REGISTRY_SCOPE_FIELD: &'static str = "tag_id";
+}
@@ -0,0 +1,172 @@
+use crate::storage::entities::filter_update_failure_entity::FilterUpdateFailureEntity;
+use crate::storage::entities::hydrate::Hydrate;
+use crate::storage::repositories::{BulkDeleteRepository, Repository};
+use crate::storage::utils::build_in_clause;
+use crate::FilterId;
+use rusqlite::{named_params, params_from_iter, Connection, Error, Transaction};
+use std::collections::HashMap;
+
+pub(crate) type FilterUpdateFailuresMap = HashMap<FilterId, FilterUpdateFailureEntity>;
+
+const BASE_SELECT_SQL: &str = r"
+    SELECT
+        filter_id,
+        consecutive_failures,
+        first_failure_time,
+        last_failure_time,
+        last_error,
+        next_retry_time
+    FROM
+        [filter_update_failure]
+";
+
+/// Repository for `filter_update_failure` table.
+/// Consecutive update failures of filters are stored here
+pub(crate) struct FilterUpdateFailuresRepository;
+
+impl FilterUpdateFailuresRepository {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Selects entities mapped by [`FilterId`] for provided `for_ids`
+    pub(crate) fn select_map(
+        &self,
+        conn: &Connection,
+        for_ids: &[FilterId],
+    ) -> rusqlite::Result<FilterUpdateFailuresMap> {
+        let sql = format!(
+            "{} WHERE {}",
+            BASE_SELECT_SQL,
+            build_in_clause("filter_id", for_ids.len())
+        );
+
+        let mut statement = conn.prepare(sql.as_str())?;
+
+        let mut rows = statement.query(params_from_iter(for_ids))?;
+
+        let mut out = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let entity = FilterUpdateFailureEntity::hydrate(row)?;
+
+            out.insert(entity.filter_id, entity);
+        }
+
+        Ok(out)
+    }
+
+    /// Selects all entities ordered by [`FilterId`]
+    pub(crate) fn select_all(
+        &self,
+        conn: &Connection,
+    ) -> rusqlite::Result<Vec<FilterUpdateFailureEntity>> {
+        let sql = format!("{} ORDER BY filter_id", BASE_SELECT_SQL);
+
+        let mut statement = conn.prepare(sql.as_str())?;
+
+        let rows = statement.query_map((), FilterUpdateFailureEntity::hydrate)?;
+
+        rows.collect()
+    }
+}
+
+impl Repository<FilterUpdateFailureEntity> for FilterUpdateFailuresRepository {
+    const TABLE_NAME: &'static str = "[filter_update_failure]";
+
+    fn insert(
+        &self,
+        conn: &Transaction<'_>,
+        entities: &[FilterUpdateFailureEntity],
+    ) -> Result<(), Error> {
+        let mut statement = conn.prepare(
+            r"
+            INSERT OR REPLACE INTO
+                [filter_update_failure]
+                (
+                    filter_id,
+                    consecutive_failures,
+                    first_failure_time,
+                    last_failure_time,
+                    last_error,
+                    next_retry_time
+                ) VALUES (
+                    :filter_id,
+                    :consecutive_failures,
+                    :first_failure_time,
+                    :last_failure_time,
+                    :last_error,
+                    :next_retry_time
+                )
+        ",
+        )?;
+
+        for entity in entities.iter() {
+            statement.execute(named_params! {
+                ":filter_id": entity.filter_id,
+                ":consecutive_failures": entity.consecutive_failures,
+                ":first_failure_time": entity.first_failure_time,
+                ":last_failure_time": entity.last_failure_time,
+                ":last_error": entity.last_error,
+                ":next_retry_time": entity.next_retry_time
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BulkDeleteRepository<FilterUpdateFailureEntity, FilterId> for FilterUpdateFailuresRepository {
+    const PK_FIELD: &'static str = "filter_id";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilterUpdateFailuresRepository;
+    use crate::storage::entities::filter_update_failure_entity::FilterUpdateFailureEntity;
+    use crate::storage::repositories::{BulkDeleteRepository, Repository};
+    use crate::storage::with_transaction;
+    use crate::storage::DbConnectionManager;
+    use rusqlite::{Connection, Transaction};
+
+    #[test]
+    fn test_insert_select_and_delete() {
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let repository = FilterUpdateFailuresRepository::new();
+        let first = FilterUpdateFailureEntity::make_next(None, 1, "first".to_string(), 100, 10);
+        let second =
+            FilterUpdateFailureEntity::make_next(Some(&first), 1, "second".to_string(), 200, 20);
+
+        assert_eq!(second.consecutive_failures, 2);
+        assert_eq!(second.first_failure_time, 100);
+        assert_eq!(second.next_retry_time, 220);
+
+        let (map, all) = source
+            .execute_db(|mut conn: Connection| {
+                with_transaction(&mut conn, |tx: &Transaction| {
+                    repository.insert(
+                        tx,
+                        &[
+                            first.clone(),
+                            FilterUpdateFailureEntity::make_next(None, 2, String::new(), 100, 10),
+                        ],
+                    )?;
+
+                    // Must replace the previous failure
+                    repository.insert(tx, &[second.clone()])?;
+                    repository.bulk_delete(tx, &vec![2]).map(|_| ())
+                })?;
+
+                let map = repository.select_map(&conn, &[1, 2, 3])?;
+                let all = repository.select_all(&conn)?;
+
+                Ok((map, all))
+            })
+            .unwrap();
+
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&1), Some(&second));
+        assert_eq!(all, vec![second]);
+    }
+}
@@ -0,0 +1,455 @@
+use crate::storage::compression::get_contents;
+use crate::storage::entities::filter::filter_include_entity::FilterIncludeEntity;
+use crate::storage::entities::filter_version_entity::FilterVersionEntity;
+use crate::storage::entities::hydrate::Hydrate;
+use crate::storage::entities::rules_list::rules_list_entity::RulesListEntity;
+use crate::storage::utils::build_in_clause;
+use crate::utils::integrity::sign_content;
+use crate::FilterId;
+use rusqlite::{named_params, params_from_iter, Connection, OptionalExtension, Transaction};
+
+/// Basic SQL-query for versions without their contents.
+/// Rules count includes rules of the version includes
+const BASIC_SELECT_SQL: &str = r"
+    SELECT
+        v.version_id,
+        v.filter_id,
+        v.saved_time,
+        v.version,
+        v.title,
+        v.description,
+        v.homepage,
+        v.license,
+        v.checksum,
+        v.expires,
+        v.last_update_time,
+        v.rules_count + IFNULL(
+            (
+                SELECT
+                    SUM(i.rules_count)
+                FROM
+                    [filter_version_includes] i
+                WHERE
+                    i.version_id = v.version_id
+            ),
+            0
+        )
+    FROM
+        [filter_version] v
+";
+
+/// Repository for `filter_version` and `filter_version_includes` tables.
+/// Previous contents of filters, replaced by updates, are stored here
+pub(crate) struct FilterVersionsRepository;
+
+impl FilterVersionsRepository {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Copies current contents, includes and metadata of filters into versions.
+    /// Filters with empty rules are skipped.
+    /// Contents are copied as is, so their compression and signatures are preserved
+    pub(crate) fn save_current_versions(
+        &self,
+        tx: &Transaction<'_>,
+        filter_ids: &[FilterId],
+        saved_time: i64,
+    ) -> rusqlite::Result<()> {
+        let mut version_statement = tx.prepare(
+            r"
+            INSERT INTO
+                [filter_version]
+                (
+                    filter_id,
+                    saved_time,
+                    version,
+                    title,
+                    description,
+                    homepage,
+                    license,
+                    checksum,
+                    expires,
+                    last_update_time,
+                    rules_text,
+                    rules_count,
+                    has_directives,
+                    text_hash,
+                    integrity_signature,
+                    compression
+                )
+            SELECT
+                f.filter_id,
+                :saved_time,
+                f.version,
+                f.title,
+                f.description,
+                f.homepage,
+                f.license,
+                f.checksum,
+                f.expires,
+                f.last_update_time,
+                r.rules_text,
+                r.rules_count,
+                r.has_directives,
+                r.text_hash,
+                r.integrity_signature,
+                r.compression
+            FROM
+                [filter] f
+            INNER JOIN
+                [rules_list] r ON r.filter_id = f.filter_id
+            WHERE
+                f.filter_id = :filter_id AND LENGTH(r.rules_text) > 0
+        ",
+        )?;
+
+        let mut includes_statement = tx.prepare(
+            r"
+            INSERT INTO
+                [filter_version_includes]
+                (
+                    version_id,
+                    filter_id,
+                    absolute_url,
+                    body,
+                    rules_count,
+                    body_hash,
+                    integrity_signature,
+                    compression
+                )
+            SELECT
+                :version_id,
+                filter_id,
+                absolute_url,
+                body,
+                rules_count,
+                body_hash,
+                integrity_signature,
+                compression
+            FROM
+                [filter_includes]
+            WHERE
+                filter_id = :filter_id
+            ORDER BY
+                row_id
+        ",
+        )?;
+
+        for filter_id in filter_ids {
+            let inserted = version_statement.execute(named_params! {
+                ":filter_id": filter_id,
+                ":saved_time": saved_time,
+            })?;
+
+            if inserted == 0 {
+                continue;
+            }
+
+            includes_statement.execute(named_params! {
+                ":version_id": tx.last_insert_rowid(),
+                ":filter_id": filter_id,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes versions of filters, except `retention` latest ones.
+    /// All versions are deleted if `retention` is less than 1
+    pub(crate) fn delete_outdated(
+        &self,
+        tx: &Transaction<'_>,
+        filter_ids: &[FilterId],
+        retention: i32,
+    ) -> rusqlite::Result<()> {
+        let mut versions_statement = tx.prepare(
+            r"
+            DELETE FROM
+                [filter_version]
+            WHERE
+                filter_id = :filter_id
+                AND version_id NOT IN (
+                    SELECT
+                        version_id
+                    FROM
+                        [filter_version]
+                    WHERE
+                        filter_id = :filter_id
+                    ORDER BY
+                        version_id DESC
+                    LIMIT :retention
+                )
+        ",
+        )?;
+
+        let mut includes_statement = tx.prepare(
+            r"
+            DELETE FROM
+                [filter_version_includes]
+            WHERE
+                filter_id = :filter_id
+                AND version_id NOT IN (
+                    SELECT
+                        version_id
+                    FROM
+                        [filter_version]
+                    WHERE
+                        filter_id = :filter_id
+                )
+        ",
+        )?;
+
+        for filter_id in filter_ids {
+            versions_statement.execute(named_params! {
+                ":filter_id": filter_id,
+                ":retention": retention.max(0),
+            })?;
+
+            includes_statement.execute(named_params! {
+                ":filter_id": filter_id,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes versions by their ids
+    pub(crate) fn delete_versions(
+        &self,
+        tx: &Transaction<'_>,
+        version_ids: &[i64],
+    ) -> rusqlite::Result<()> {
+        let in_clause = build_in_clause("version_id", version_ids.len());
+
+        tx.execute(
+            format!("DELETE FROM [filter_version_includes] WHERE {}", in_clause).as_str(),
+            params_from_iter(version_ids),
+        )?;
+
+        tx.execute(
+            format!("DELETE FROM [filter_version] WHERE {}", in_clause).as_str(),
+            params_from_iter(version_ids),
+        )
+        .map(|_| ())
+    }
+
+    /// Deletes all versions of filters
+    pub(crate) fn delete_for_filters(
+        &self,
+        tx: &Transaction<'_>,
+        filter_ids: &[FilterId],
+    ) -> rusqlite::Result<()> {
+        let in_clause = build_in_clause("filter_id", filter_ids.len());
+
+        tx.execute(
+            format!("DELETE FROM [filter_version_includes] WHERE {}", in_clause).as_str(),
+            params_from_iter(filter_ids),
+        )?;
+
+        tx.execute(
+            format!("DELETE FROM [filter_version] WHERE {}", in_clause).as_str(),
+            params_from_iter(filter_ids),
+        )
+        .map(|_| ())
+    }
+
+    /// Selects versions of the filter, from the latest to the oldest
+    pub(crate) fn select_for_filter(
+        &self,
+        conn: &Connection,
+        filter_id: FilterId,
+    ) -> rusqlite::Result<Vec<FilterVersionEntity>> {
+        let sql = format!(
+            "{} WHERE v.filter_id = ? ORDER BY v.version_id DESC",
+            BASIC_SELECT_SQL
+        );
+
+        let mut statement = conn.prepare(sql.as_str())?;
+
+        let rows = statement.query_map([filter_id], FilterVersionEntity::hydrate)?;
+
+        rows.collect()
+    }
+
+    /// Selects version by its id
+    pub(crate) fn select_by_id(
+        &self,
+        conn: &Connection,
+        version_id: i64,
+    ) -> rusqlite::Result<Option<FilterVersionEntity>> {
+        let sql = format!("{} WHERE v.version_id = ?", BASIC_SELECT_SQL);
+
+        let mut statement = conn.prepare(sql.as_str())?;
+
+        statement
+            .query_row([version_id], FilterVersionEntity::hydrate)
+            .optional()
+    }
+
+    /// Selects rules of the version.
+    /// `disabled_text` of the entity is always empty
+    pub(crate) fn select_rules_list_entity(
+        &self,
+        conn: &Connection,
+        version_id: i64,
+    ) -> rusqlite::Result<RulesListEntity> {
+        let mut statement = conn.prepare(
+            r"
+            SELECT
+                filter_id,
+                rules_text,
+                '',
+                rules_count,
+                has_directives,
+                text_hash,
+                integrity_signature,
+                compression
+            FROM
+                [filter_version]
+            WHERE
+                version_id = ?
+        ",
+        )?;
+
+        statement.query_row([version_id], RulesListEntity::hydrate)
+    }
+
+    /// Selects includes of the version.
+    /// `row_id` of entities is empty, so they may be inserted as new includes
+    pub(crate) fn select_include_entities(
+        &self,
+        conn: &Connection,
+        version_id: i64,
+    ) -> rusqlite::Result<Vec<FilterIncludeEntity>> {
+        let mut statement = conn.prepare(
+            r"
+            SELECT
+                NULL,
+                filter_id,
+                absolute_url,
+                body,
+                rules_count,
+                body_hash,
+                integrity_signature,
+                compression
+            FROM
+                [filter_version_includes]
+            WHERE
+                version_id = ?
+            ORDER BY
+                row_id
+        ",
+        )?;
+
+        let rows = statement.query_map([version_id], FilterIncludeEntity::hydrate)?;
+
+        rows.collect()
+    }
+
+    /// Signs rules of all versions and collects `(version_id, signature)` pairs
+    pub(crate) fn sign_and_collect_signatures_streaming(
+        &self,
+        conn: &Connection,
+        derived_key: &[u8; 32],
+    ) -> rusqlite::Result<Vec<(i64, String)>> {
+        Self::sign_and_collect(
+            conn,
+            derived_key,
+            r"
+            SELECT
+                version_id,
+                filter_id,
+                rules_text,
+                compression
+            FROM
+                [filter_version]",
+        )
+    }
+
+    /// Signs includes of all versions and collects `(row_id, signature)` pairs
+    pub(crate) fn sign_and_collect_include_signatures_streaming(
+        &self,
+        conn: &Connection,
+        derived_key: &[u8; 32],
+    ) -> rusqlite::Result<Vec<(i64, String)>> {
+        Self::sign_and_collect(
+            conn,
+            derived_key,
+            r"
+            SELECT
+                row_id,
+                filter_id,
+                body,
+                compression
+            FROM
+                [filter_version_includes]",
+        )
+    }
+
+    /// Updates signatures of versions rules and their includes
+    pub(crate) fn batch_update_signatures(
+        &self,
+        tx: &Transaction<'_>,
+        version_signatures: &[(i64, String)],
+        include_signatures: &[(i64, String)],
+    ) -> rusqlite::Result<()> {
+        let mut statement = tx.prepare(
+            r"
+            UPDATE
+                [filter_version]
+            SET
+                integrity_signature = :sig
+            WHERE
+                version_id = :id",
+        )?;
+
+        for (version_id, sig) in version_signatures {
+            statement.execute(named_params! {
+                ":id": version_id,
+                ":sig": sig,
+            })?;
+        }
+
+        let mut statement = tx.prepare(
+            r"
+            UPDATE
+                [filter_version_includes]
+            SET
+                integrity_signature = :sig
+            WHERE
+                row_id = :id",
+        )?;
+
+        for (row_id, sig) in include_signatures {
+            statement.execute(named_params! {
+                ":id": row_id,
+                ":sig": sig,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Signs contents, selected by `sql` as `(id, filter_id, contents, compression)` rows
+    fn sign_and_collect(
+        conn: &Connection,
+        derived_key: &[u8; 32],
+        sql: &str,
+    ) -> rusqlite::Result<Vec<(i64, String)>> {
+        let mut statement = conn.prepare(sql)?;
+
+        let mut signatures = Vec::new();
+        let mut rows = statement.query([])?;
+
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let filter_id: FilterId = row.get(1)?;
+            let contents = get_contents(row, 2, row.get(3)?)?;
+
+            signatures.push((id, sign_content(derived_key, filter_id, &contents)));
+        }
+
+        Ok(signatures)
+    }
+}
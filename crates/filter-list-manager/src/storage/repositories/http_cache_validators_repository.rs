@@ -0,0 +1,161 @@
+use crate::io::http::cache_validators::CacheValidators;
+use crate::storage::entities::http_cache_validators_entity::HttpCacheValidatorsEntity;
+use crate::storage::entities::hydrate::Hydrate;
+use crate::storage::repositories::{BulkDeleteRepository, Repository};
+use crate::storage::utils::build_in_clause;
+use rusqlite::{named_params, params_from_iter, Connection, Error, Transaction};
+use std::collections::HashMap;
+
+pub(crate) type MapUrlOnCacheValidators = HashMap<String, CacheValidators>;
+
+/// Repository for `http_cache_validators` table.
+/// Validators of the last responses are stored here by requested url
+pub(crate) struct HttpCacheValidatorsRepository;
+
+impl HttpCacheValidatorsRepository {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+
+    /// Selects validators mapped by url for provided `for_urls`
+    pub(crate) fn select_map<U>(
+        &self,
+        conn: &Connection,
+        for_urls: &[U],
+    ) -> rusqlite::Result<MapUrlOnCacheValidators>
+    where
+        U: AsRef<str>,
+    {
+        let mut sql = String::from(
+            r"
+            SELECT
+                url,
+                etag,
+                last_modified
+            FROM
+                [http_cache_validators]
+            WHERE ",
+        );
+
+        sql += build_in_clause("url", for_urls.len()).as_str();
+
+        let mut statement = conn.prepare(sql.as_str())?;
+
+        let mut rows = statement.query(params_from_iter(for_urls.iter().map(AsRef::as_ref)))?;
+
+        let mut out = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let entity = HttpCacheValidatorsEntity::hydrate(row)?;
+
+            out.insert(entity.url.clone(), entity.into());
+        }
+
+        Ok(out)
+    }
+}
+
+impl BulkDeleteRepository<HttpCacheValidatorsEntity, String> for HttpCacheValidatorsRepository {
+    const PK_FIELD: &'static str = "url";
+}
+
+impl Repository<HttpCacheValidatorsEntity> for HttpCacheValidatorsRepository {
+    const TABLE_NAME: &'static str = "[http_cache_validators]";
+
+    fn insert(
+        &self,
+        conn: &Transaction<'_>,
+        entities: &[HttpCacheValidatorsEntity],
+    ) -> Result<(), Error> {
+        let mut statement = conn.prepare(
+            r"
+            INSERT OR REPLACE INTO
+                [http_cache_validators]
+                (
+                    url,
+                    etag,
+                    last_modified
+                ) VALUES (
+                    :url,
+                    :etag,
+                    :last_modified
+                )
+        ",
+        )?;
+
+        for entity in entities.iter() {
+            statement.execute(named_params! {
+                ":url": entity.url,
+                ":etag": entity.etag,
+                ":last_modified": entity.last_modified
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpCacheValidatorsRepository;
+    use crate::io::http::cache_validators::CacheValidators;
+    use crate::storage::entities::http_cache_validators_entity::HttpCacheValidatorsEntity;
+    use crate::storage::repositories::Repository;
+    use crate::storage::with_transaction;
+    use crate::storage::DbConnectionManager;
+    use rusqlite::{Connection, Transaction};
+
+    #[test]
+    fn test_insert_and_select_map() {
+        let source = DbConnectionManager::factory_test().unwrap();
+        unsafe { source.lift_up_database().unwrap() }
+
+        let repository = HttpCacheValidatorsRepository::new();
+        let first_url = "https://example.com/1.txt";
+        let second_url = "https://example.com/2.txt";
+
+        let map = source
+            .execute_db(|mut conn: Connection| {
+                with_transaction(&mut conn, |tx: &Transaction| {
+                    repository.insert(
+                        tx,
+                        &[
+                            HttpCacheValidatorsEntity {
+                                url: first_url.to_string(),
+                                etag: Some("\"old\"".to_string()),
+                                last_modified: None,
+                            },
+                            HttpCacheValidatorsEntity {
+                                url: second_url.to_string(),
+                                etag: None,
+                                last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+                            },
+                        ],
+                    )?;
+
+                    // Must replace previous validators
+                    repository.insert(
+                        tx,
+                        &[HttpCacheValidatorsEntity {
+                            url: first_url.to_string(),
+                            etag: Some("\"new\"".to_string()),
+                            last_modified: None,
+                        }],
+                    )
+                })?;
+
+                repository
+                    .select_map(&conn, &[first_url, "https://example.com/3.txt"])
+                    .map_err(Into::into)
+            })
+            .unwrap();
+
+        assert_eq!(map.len(), 1);
+        assert_eq!(
+            map.get(first_url),
+            Some(&CacheValidators {
+                etag: Some("\"new\"".to_string()),
+                last_modified: None,
+            })
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::manager::models::configuration::Locale;
 use crate::storage::entities::filter::filter_entity::FilterEntity;
 use crate::storage::entities::localisation::filter_localisation_entity::FilterLocalisationEntity;
-use crate::storage::repositories::Repository;
+use crate::storage::repositories::{RegistryScopedRepository, Repository};
 use crate::storage::utils::build_in_clause;
 use crate::FilterId;
 use rusqlite::types::Value;
@@ -137,3 +137,7 @@ impl Repository<FilterLocalisationEntity> for FilterLocalisationRepository {
         Ok(())
     }
 }
+
+impl RegistryScopedRepository<FilterLocalisationEntity> for FilterLocalisationRepository {
+    const REGISTRY_SCOPE_FIELD: &'static str = "filter_id";
+}
@@ -1,5 +1,5 @@
 use crate::storage::entities::localisation::filter_tag_localisation_entity::FilterTagLocalisationEntity;
-use crate::storage::repositories::Repository;
+use crate::storage::repositories::{RegistryScopedRepository, Repository};
 use rusqlite::{named_params, Error, Transaction};
 
 /// Repository for Tag localisation entities
@@ -51,3 +51,7 @@ impl Repository<FilterTagLocalisationEntity> for FilterTagLocalisationRepository
         Ok(())
     }
 }
+
+impl RegistryScopedRepository<FilterTagLocalisationEntity> for FilterTagLocalisationRepository {
+    const REGISTRY_SCOPE_FIELD: &'static str = "tag_id";
+}
@@ -1,5 +1,5 @@
 use crate::storage::entities::localisation::filter_group_localisation_entity::FilterGroupLocalisationEntity;
-use crate::storage::repositories::Repository;
+use crate::storage::repositories::{RegistryScopedRepository, Repository};
 use rusqlite::{named_params, Error, Transaction};
 
 /// Repository for group localisations
@@ -47,3 +47,7 @@ impl Repository<FilterGroupLocalisationEntity> for GroupLocalisationRepository {
         Ok(())
     }
 }
+
+impl RegistryScopedRepository<FilterGroupLocalisationEntity> for GroupLocalisationRepository {
+    const REGISTRY_SCOPE_FIELD: &'static str = "group_id";
+}
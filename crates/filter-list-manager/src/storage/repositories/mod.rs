@@ -1,15 +1,23 @@
+use crate::storage::registry_id_namespace::RegistryIdNamespace;
 use crate::storage::utils::build_in_clause;
-use rusqlite::{params_from_iter, ToSql, Transaction};
+use rusqlite::{params, params_from_iter, ToSql, Transaction};
 
 pub(crate) mod db_metadata_repository;
 pub(crate) mod db_schema_repository;
 pub(crate) mod diff_updates_repository;
+pub(crate) mod disabled_rule_patterns_repository;
 pub(crate) mod filter_filter_tag_repository;
 pub(crate) mod filter_group_repository;
 pub(crate) mod filter_includes_repository;
 pub(crate) mod filter_locale_repository;
+pub(crate) mod filter_mirror_urls_repository;
+pub(crate) mod filter_pins_repository;
+pub(crate) mod filter_registries_repository;
 pub(crate) mod filter_repository;
 pub(crate) mod filter_tag_repository;
+pub(crate) mod filter_update_failures_repository;
+pub(crate) mod filter_versions_repository;
+pub(crate) mod http_cache_validators_repository;
 pub(crate) mod localisation;
 pub(crate) mod rules_list_repository;
 
@@ -18,6 +26,11 @@ pub(crate) trait Repository<Entity> {
 
     fn insert(&self, conn: &Transaction<'_>, entities: &[Entity]) -> Result<(), rusqlite::Error>;
 
+    /// Deletes all rows of the table.
+    ///
+    /// Tables of [`RegistryScopedRepository`] hold rows of all registries, so replacing
+    /// the data of one registry must use [`RegistryScopedRepository::delete_for_registry`]
+    #[allow(dead_code)]
     fn clear(&self, transaction: &Transaction) -> rusqlite::Result<()> {
         let mut statement =
             transaction.prepare(format!("DELETE FROM {} WHERE 1", Self::TABLE_NAME).as_str())?;
@@ -48,6 +61,29 @@ where
     }
 }
 
+/// Repository of data from registry index, which is replaced by each registry independently
+pub(crate) trait RegistryScopedRepository<Entity>: Repository<Entity> {
+    /// Field with filter, group or tag id, which defines the registry of the row
+    const REGISTRY_SCOPE_FIELD: &'static str;
+
+    fn delete_for_registry(
+        &self,
+        transaction: &Transaction,
+        namespace: RegistryIdNamespace,
+    ) -> rusqlite::Result<usize> {
+        let mut statement = transaction.prepare(
+            format!(
+                "DELETE FROM {} WHERE {} BETWEEN ?1 AND ?2",
+                Self::TABLE_NAME,
+                Self::REGISTRY_SCOPE_FIELD
+            )
+            .as_str(),
+        )?;
+
+        statement.execute(params![namespace.first_id(), namespace.last_id()])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::storage::repositories::filter_locale_repository::FilterLocaleRepository;
@@ -1,5 +1,7 @@
 use crate::manager::models::FilterId;
+use crate::storage::blob::blob_reader::BlobReader;
 use crate::storage::blob::{BlobHandleImpl, BLOB_CHUNK_SIZE};
+use crate::storage::compression::{get_contents, ContentsReader, EncodedContents};
 use crate::storage::entities::hydrate::Hydrate;
 use crate::storage::entities::rules_list::disabled_rules_entity::DisabledRulesEntity;
 use crate::storage::entities::rules_list::rules_count_entity::RulesCountEntity;
@@ -10,11 +12,13 @@ use crate::storage::sql_generators::operator::SQLOperator;
 use crate::storage::utils::{build_in_clause, process_where_clause};
 use crate::utils::integrity::{sign_content, verify_content};
 use blake3::Hasher;
+use rusqlite::types::Type;
 use rusqlite::{
     named_params, params_from_iter, Connection, Error, OptionalExtension, Row, Transaction,
 };
 use rusqlite::{DatabaseName, Result};
 use std::collections::HashMap;
+use std::io::Read;
 
 pub(crate) type MapFilterIdOnRulesList = HashMap<FilterId, RulesListEntity>;
 
@@ -29,7 +33,8 @@ const BASIC_SELECT_SQL: &str = r"
         rules_count,
         has_directives,
         text_hash,
-        integrity_signature
+        integrity_signature,
+        compression
     FROM
         [rules_list]
 ";
@@ -131,6 +136,35 @@ impl RulesListRepository {
         statement.query_row(params, |row: &Row| row.get(0))
     }
 
+    /// Iterates over entities by `where_clause` ordered by [`FilterId`],
+    /// without loading all rules texts into memory at once.
+    /// Iteration stops when `block` returns `false`.
+    pub(crate) fn for_each<F, E>(
+        &self,
+        conn: &Connection,
+        where_clause: Option<SQLOperator>,
+        mut block: F,
+    ) -> std::result::Result<(), E>
+    where
+        F: FnMut(RulesListEntity) -> std::result::Result<bool, E>,
+        E: From<Error>,
+    {
+        let mut sql = String::from(BASIC_SELECT_SQL);
+        let params = process_where_clause(&mut sql, where_clause)?;
+        sql.push_str(" ORDER BY filter_id");
+
+        let mut statement = conn.prepare(sql.as_str())?;
+        let mut rows = statement.query(params)?;
+
+        while let Some(row) = rows.next()? {
+            if !block(RulesListEntity::hydrate(row)?)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Gets entities mapped by [`FilterId`]
     pub(crate) fn select_mapped(
         &self,
@@ -171,7 +205,8 @@ impl RulesListRepository {
                 rules_count,
                 has_directives,
                 text_hash,
-                integrity_signature
+                integrity_signature,
+                compression
             FROM
                 [rules_list]
             WHERE ",
@@ -189,7 +224,7 @@ impl RulesListRepository {
         while let Some(row) = rows.next()? {
             let id: FilterId = row.get(0)?;
 
-            rules.insert(id, row.get(1)?);
+            rules.insert(id, get_contents(row, 1, row.get(7)?)?);
             disabled_rules.insert(id, row.get(2)?);
 
             let text_hash = row.get::<usize, Option<String>>(5)?;
@@ -299,7 +334,7 @@ impl RulesListRepository {
     }
 
     /// Gets lightweight metadata for a single filter, without loading rules_text and rules_disabled_text.
-    /// Returns row_id, filter_id, has_directives, integrity_signature.
+    /// Returns row_id, filter_id, has_directives, integrity_signature, compression.
     pub(crate) fn get_metadata(
         &self,
         connection: &Connection,
@@ -311,7 +346,8 @@ impl RulesListRepository {
                 rowid,
                 filter_id,
                 has_directives,
-                integrity_signature
+                integrity_signature,
+                compression
             FROM
                 [rules_list]
             WHERE
@@ -332,7 +368,8 @@ impl RulesListRepository {
             r"
             SELECT
                 rowid,
-                CAST(disabled_rules_text AS BLOB)
+                CAST(disabled_rules_text AS BLOB),
+                compression
             FROM
                 [rules_list]
             WHERE
@@ -340,8 +377,12 @@ impl RulesListRepository {
         ",
         )?;
 
-        let (row_id, disabled_rules) = statement.query_row([filter_id], |row| {
-            Ok((row.get::<usize, i64>(0)?, row.get::<usize, Vec<u8>>(1)?))
+        let (row_id, disabled_rules, compression) = statement.query_row([filter_id], |row| {
+            Ok((
+                row.get::<usize, i64>(0)?,
+                row.get::<usize, Vec<u8>>(1)?,
+                row.get(2)?,
+            ))
         })?;
 
         let blob = connection.blob_open(
@@ -352,11 +393,12 @@ impl RulesListRepository {
             true,
         )?;
 
-        Ok((disabled_rules, BlobHandleImpl::new(blob)))
+        Ok((disabled_rules, BlobHandleImpl::new(blob, compression)))
     }
 
     /// Verifies integrity of rules_text for a single filter by streaming the blob
     /// through blake3 incremental hasher, without loading the full text into memory.
+    /// Compressed text is hashed after decompression.
     /// Returns `true` if the signature matches.
     pub(crate) fn verify_blob_integrity_streaming(
         &self,
@@ -380,15 +422,20 @@ impl RulesListRepository {
         let mut hasher = Hasher::new_keyed(derived_key);
         hasher.update(&metadata.filter_id.to_le_bytes());
 
+        let to_sql_error = |why| Error::FromSqlConversionFailure(1, Type::Blob, Box::new(why));
+        let mut reader = ContentsReader::new(
+            BlobReader::new(BlobHandleImpl::new(blob, metadata.compression)),
+            metadata.compression,
+        )
+        .map_err(to_sql_error)?;
+
         let mut buffer = vec![0u8; BLOB_CHUNK_SIZE];
-        let mut offset = 0;
         loop {
-            let bytes_read = blob.read_at(&mut buffer, offset)?;
+            let bytes_read = reader.read(&mut buffer).map_err(to_sql_error)?;
             if bytes_read == 0 {
                 break;
             }
             hasher.update(&buffer[..bytes_read]);
-            offset += bytes_read;
         }
 
         let computed = hasher.finalize();
@@ -407,7 +454,8 @@ impl RulesListRepository {
             r"
             SELECT
                 filter_id,
-                rules_text
+                rules_text,
+                compression
             FROM
                 [rules_list]",
         )?;
@@ -416,7 +464,7 @@ impl RulesListRepository {
         let mut rows = statement.query([])?;
         while let Some(row) = rows.next()? {
             let filter_id: FilterId = row.get(0)?;
-            let text: String = row.get(1)?;
+            let text = get_contents(row, 1, row.get(2)?)?;
 
             let sig = sign_content(derived_key, filter_id, &text);
             signatures.push((filter_id, sig));
@@ -438,7 +486,8 @@ impl RulesListRepository {
                 SELECT
                     filter_id,
                     rules_text,
-                    integrity_signature
+                    integrity_signature,
+                    compression
                 FROM
                     [rules_list]",
         )?;
@@ -446,7 +495,7 @@ impl RulesListRepository {
         let mut rows = statement.query([])?;
         while let Some(row) = rows.next()? {
             let filter_id: FilterId = row.get(0)?;
-            let text: String = row.get(1)?;
+            let text = get_contents(row, 1, row.get(3)?)?;
             let signature: Option<String> = row.get(2)?;
 
             if let Some(ref sig) = signature {
@@ -507,7 +556,8 @@ impl Repository<RulesListEntity> for RulesListRepository {
                         rules_count,
                         text_hash,
                         has_directives,
-                        integrity_signature
+                        integrity_signature,
+                        compression
                     )
                 VALUES
                     (
@@ -517,7 +567,8 @@ impl Repository<RulesListEntity> for RulesListRepository {
                         :rules_count,
                         :text_hash,
                         :has_directives,
-                        :integrity_signature
+                        :integrity_signature,
+                        :compression
                     )
             ",
         )?;
@@ -525,12 +576,13 @@ impl Repository<RulesListEntity> for RulesListRepository {
         for entity in entities.iter() {
             statement.execute(named_params! {
                 ":filter_id": entity.filter_id,
-                ":rules_text": entity.text,
+                ":rules_text": EncodedContents::encode(&entity.text, entity.compression)?,
                 ":disabled_rules_text": entity.disabled_text,
                 ":rules_count": entity.rules_count,
                 ":text_hash": entity.text_hash,
                 ":has_directives": entity.has_directives,
-                ":integrity_signature": entity.integrity_signature
+                ":integrity_signature": entity.integrity_signature,
+                ":compression": entity.compression
             })?;
         }
 
@@ -26,6 +26,10 @@ pub(crate) enum SQLOperator<'field> {
     And(Box<SQLOperator<'field>>, Box<SQLOperator<'field>>),
     /// filter_id IN (..,..)
     FieldIn(&'field str, Vec<Value>),
+    /// Case-sensitive substring search: instr(rules_text, [`Value`]) > 0
+    FieldContains(&'field str, Value),
+    /// rules_text LIKE [`Value`] ESCAPE '\\'
+    FieldLike(&'field str, Value),
 }
 
 impl<'a> SQLOperator<'a> {
@@ -99,6 +103,16 @@ impl<'a> SQLOperator<'a> {
 
                 build_in_clause(field, len)
             }
+            SQLOperator::FieldContains(field, value) => {
+                container.borrow_mut().push(value);
+
+                format!("instr({}, ?) > 0", field)
+            }
+            SQLOperator::FieldLike(field, value) => {
+                container.borrow_mut().push(value);
+
+                format!("{} LIKE ? ESCAPE '\\'", field)
+            }
         }
     }
 }
@@ -186,4 +200,25 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_text_search_clauses() {
+        let clause = Or(
+            heap(FieldContains("rules_text", "example".to_string().into())),
+            heap(FieldLike("body", "%example%".to_string().into())),
+        );
+
+        match SQLOperator::process(clause).unwrap() {
+            SQLOperatorsTreeResult(str, params) => {
+                assert_eq!("(instr(rules_text, ?) > 0 OR body LIKE ? ESCAPE '\\')", str);
+                assert_eq!(
+                    params,
+                    vec![
+                        Value::from("example".to_string()),
+                        Value::from("%example%".to_string())
+                    ]
+                );
+            }
+        }
+    }
 }
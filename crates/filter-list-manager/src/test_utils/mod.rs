@@ -1,6 +1,7 @@
 pub(crate) mod indexes_fixtures;
 pub(crate) mod tests_db;
 pub(crate) mod tests_fixtures;
+pub(crate) mod tests_http_server;
 
 use lazy_static::lazy_static;
 use once_cell::sync::Lazy;
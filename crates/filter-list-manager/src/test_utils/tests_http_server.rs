@@ -0,0 +1,124 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Response of [`TestsHttpServer`]
+pub(crate) struct TestsHttpResponse {
+    pub(crate) status: u16,
+    pub(crate) headers: Vec<(&'static str, String)>,
+    pub(crate) body: String,
+}
+
+impl TestsHttpResponse {
+    pub(crate) fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: body.into(),
+        }
+    }
+
+    pub(crate) fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+}
+
+/// Minimal local HTTP server for tests.
+/// Every request is answered with `responder`, which receives request path and
+/// lowercased request headers. All received requests are recorded
+pub(crate) struct TestsHttpServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<TestsHttpRequest>>>,
+}
+
+/// Request, received by [`TestsHttpServer`]
+#[derive(Clone)]
+pub(crate) struct TestsHttpRequest {
+    pub(crate) path: String,
+    pub(crate) headers: Vec<(String, String)>,
+}
+
+impl TestsHttpRequest {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl TestsHttpServer {
+    pub(crate) fn start<F>(responder: F) -> Self
+    where
+        F: Fn(&TestsHttpRequest) -> TestsHttpResponse + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let thread_requests = Arc::clone(&requests);
+
+        // Thread lives until the end of tests process
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+
+                let path = request_line
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_string();
+
+                let mut headers = vec![];
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).is_err() || line.trim().is_empty() {
+                        break;
+                    }
+
+                    if let Some((name, value)) = line.split_once(':') {
+                        headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+                    }
+                }
+
+                let request = TestsHttpRequest { path, headers };
+                let response = responder(&request);
+                thread_requests.lock().unwrap().push(request);
+
+                let mut raw = format!(
+                    "HTTP/1.1 {} Test\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    response.status,
+                    response.body.len()
+                );
+                for (name, value) in response.headers {
+                    raw += &format!("{}: {}\r\n", name, value);
+                }
+                raw += "\r\n";
+                raw += &response.body;
+
+                let _ = stream.write_all(raw.as_bytes());
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    /// Makes absolute url for `path`
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Gets copy of all received requests
+    pub(crate) fn requests(&self) -> Vec<TestsHttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
@@ -0,0 +1,26 @@
+//! Exponential backoff helpers
+
+/// Calculates exponential backoff delay: `base * 2^attempt`, but not more than `max`.
+///
+/// * `base` - Delay for the first attempt
+/// * `attempt` - Zero-based attempt number
+/// * `max` - Upper bound of the delay
+pub(crate) fn exponential_backoff(base: u64, attempt: u32, max: u64) -> u64 {
+    base.saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+        .min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exponential_backoff;
+
+    #[test]
+    fn test_exponential_backoff() {
+        assert_eq!(exponential_backoff(100, 0, 1000), 100);
+        assert_eq!(exponential_backoff(100, 1, 1000), 200);
+        assert_eq!(exponential_backoff(100, 3, 1000), 800);
+        assert_eq!(exponential_backoff(100, 4, 1000), 1000);
+        assert_eq!(exponential_backoff(100, 200, 1000), 1000);
+        assert_eq!(exponential_backoff(0, 5, 1000), 0);
+    }
+}
@@ -1,5 +1,7 @@
+pub(crate) mod backoff;
 pub mod integrity;
 pub(crate) mod iterators;
 pub(crate) mod memory;
 pub(crate) mod parsing;
 pub(crate) mod string;
+pub(crate) mod token_bucket;
@@ -0,0 +1,94 @@
+//! Token bucket rate limiter
+
+use std::time::{Duration, Instant};
+
+/// Classic token bucket: holds up to `capacity` tokens, which are refilled
+/// with constant rate. Every request takes one token.
+pub(crate) struct TokenBucket {
+    /// Maximum number of tokens, i.e. size of a burst
+    capacity: f64,
+    /// Currently available tokens
+    tokens: f64,
+    /// Number of tokens added per second
+    refill_per_sec: f64,
+    /// Last time when tokens were refilled
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Makes full bucket
+    ///
+    /// * `capacity` - Maximum number of tokens. Values < 1 will be treated as 1
+    /// * `refill_per_sec` - Number of tokens added per second
+    /// * `now` - Current time
+    pub(crate) fn new(capacity: u32, refill_per_sec: f64, now: Instant) -> Self {
+        let capacity = capacity.max(1) as f64;
+
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec,
+            last_refill: now,
+        }
+    }
+
+    /// Takes one token, if there is any.
+    /// Otherwise, returns time after which the token will be available
+    pub(crate) fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+
+            return Ok(());
+        }
+
+        if self.refill_per_sec <= 0.0 {
+            return Err(Duration::MAX);
+        }
+
+        Err(Duration::from_secs_f64(
+            (1.0 - self.tokens) / self.refill_per_sec,
+        ))
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TokenBucket;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2, 1.0, start);
+
+        // Burst
+        assert!(bucket.try_acquire(start).is_ok());
+        assert!(bucket.try_acquire(start).is_ok());
+        assert_eq!(bucket.try_acquire(start), Err(Duration::from_secs(1)));
+
+        // Half of the token has been refilled
+        let half_second_later = start + Duration::from_millis(500);
+        assert_eq!(
+            bucket.try_acquire(half_second_later),
+            Err(Duration::from_millis(500))
+        );
+
+        assert!(bucket.try_acquire(start + Duration::from_secs(1)).is_ok());
+
+        // Tokens won't exceed capacity
+        let much_later = start + Duration::from_secs(100);
+        assert!(bucket.try_acquire(much_later).is_ok());
+        assert!(bucket.try_acquire(much_later).is_ok());
+        assert!(bucket.try_acquire(much_later).is_err());
+    }
+}